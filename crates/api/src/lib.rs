@@ -6,10 +6,18 @@ use std::time::{Duration, SystemTime};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 
-pub use domain::base::Serial;
+pub use domain::base::{Rtype, Serial};
 
 pub mod dep;
 
+//----------- ACTOR_HEADER -----------------------------------------------------
+
+/// The HTTP header naming the operator on whose behalf a request is made.
+///
+/// Changes to zones caused by the request are attributed to this operator in
+/// the zone history.
+pub const ACTOR_HEADER: &str = "x-cascade-actor";
+
 //----------- ZoneName ---------------------------------------------------------
 
 /// The name of a zone.
@@ -18,8 +26,12 @@ pub type ZoneName = domain::base::Name<bytes::Bytes>;
 //----------- ZoneReview -------------------------------------------------------
 
 /// Review a version of a zone.
-#[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct ZoneReview {}
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ZoneReview {
+    /// Why the zone is approved or rejected, if given.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
 
 /// A stage for reviewing a zone.
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -67,28 +79,609 @@ impl std::fmt::Display for ZoneReviewError {
     }
 }
 
-//----------- ZoneReset --------------------------------------------------------
+//----------- ZoneReviewSummary ------------------------------------------------
+
+/// The result of a review summary request.
+pub type ZoneReviewSummaryResult = Result<ZoneReviewSummary, ZoneReviewError>;
+
+/// A summary of a version of a zone under review.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneReviewSummary {
+    /// The name of the zone.
+    pub zone: ZoneName,
+
+    /// The stage the zone is being reviewed at.
+    pub stage: ZoneReviewStage,
+
+    /// The SOA serial of the version under review.
+    pub serial: Serial,
+
+    /// The number of records in the version under review.
+    pub record_count: usize,
+
+    /// How the unsigned contents changed from the previous version, if known.
+    pub diff: Option<ZoneReviewDiff>,
+
+    /// How the DNSKEY RRset changed from the previous version.
+    ///
+    /// This is only known for signed versions.
+    pub keys: Option<ZoneReviewKeyChanges>,
+}
+
+/// Record changes in a version of a zone under review.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneReviewDiff {
+    /// The number of records added (excluding the SOA record).
+    pub added: usize,
+
+    /// The number of records removed (excluding the SOA record).
+    pub removed: usize,
+}
+
+/// DNSKEY changes in a signed version of a zone under review.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneReviewKeyChanges {
+    /// The key tags of the added DNSKEY records.
+    pub added: Vec<u16>,
+
+    /// The key tags of the removed DNSKEY records.
+    pub removed: Vec<u16>,
+}
+
+impl Display for ZoneReviewSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage = match self.stage {
+            ZoneReviewStage::Unsigned => "unsigned",
+            ZoneReviewStage::Signed => "signed",
+        };
+        write!(
+            f,
+            "{stage} zone '{}' at serial {}: {} records",
+            self.zone, self.serial, self.record_count
+        )?;
+        match &self.diff {
+            Some(diff) => write!(f, ", {} added, {} removed", diff.added, diff.removed)?,
+            None => f.write_str(", changes unknown")?,
+        }
+        if let Some(keys) = &self.keys {
+            if keys.added.is_empty() && keys.removed.is_empty() {
+                f.write_str(", DNSKEYs unchanged")?;
+            }
+            for tag in &keys.added {
+                write!(f, ", DNSKEY {tag} added")?;
+            }
+            for tag in &keys.removed {
+                write!(f, ", DNSKEY {tag} removed")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+//----------- ZoneReviewZonefile -----------------------------------------------
+
+/// The result of a request for the contents of a version under review.
+pub type ZoneReviewZonefileResult = Result<ZoneReviewZonefile, ZoneReviewError>;
+
+/// The contents of a version of a zone under review.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneReviewZonefile {
+    /// The name of the zone.
+    pub zone: ZoneName,
+
+    /// The stage the zone is being reviewed at.
+    pub stage: ZoneReviewStage,
+
+    /// The SOA serial of the version under review.
+    pub serial: Serial,
+
+    /// The records of the version under review, in zone file format.
+    ///
+    /// The SOA record comes first.
+    pub records: Vec<String>,
+}
+
+//----------- ZoneBatchReview --------------------------------------------------
+
+/// The result of a `zone approve --batch` command.
+pub type ZoneBatchReviewResult = Result<ZoneBatchReviewOutput, ZoneBatchReviewError>;
+
+/// The output of a `zone approve --batch` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneBatchReviewOutput {
+    /// The zones of the batch that were under review, and the outcome of
+    /// approving each of them.
+    pub reviewed: Vec<ZoneBatchReviewed>,
+}
+
+/// A zone version reviewed as part of a review batch.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneBatchReviewed {
+    /// The name of the zone.
+    pub zone: ZoneName,
+
+    /// The SOA serial of the reviewed version.
+    pub serial: Serial,
+
+    /// Whether the version could be approved.
+    pub result: ZoneReviewResult,
+}
+
+/// An error from a `zone approve --batch` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneBatchReviewError {
+    /// No zone belongs to the specified batch.
+    NoSuchBatch,
+}
+
+impl std::fmt::Display for ZoneBatchReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchBatch => f.write_str("No zones belong to this batch"),
+        }
+    }
+}
+
+//----------- PendingReviews ---------------------------------------------------
+
+/// The output of a `review list` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PendingReviewsResult {
+    /// The zone versions awaiting review, oldest first.
+    pub reviews: Vec<PendingReview>,
+}
+
+/// A zone version awaiting review.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PendingReview {
+    /// The name of the zone.
+    pub zone: ZoneName,
+
+    /// The stage at which the zone is being reviewed.
+    pub stage: ZoneReviewStage,
+
+    /// The SOA serial of the version under review.
+    pub serial: Serial,
+
+    /// When the review was requested.
+    pub since: SystemTime,
+
+    /// The review batch the version belongs to, if any.
+    pub batch: Option<String>,
+}
+
+//----------- ZoneReset --------------------------------------------------------
+
+/// The result of a `zone reset` command.
+pub type ZoneResetResult = Result<ZoneResetOutput, ZoneResetError>;
+
+/// The output of a `zone reset` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneResetOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneResetError {
+    NoSuchZone,
+    NotHalted,
+}
+
+impl std::fmt::Display for ZoneResetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotHalted => f.write_str("Zone is not halted"),
+        }
+    }
+}
+
+//----------- ZoneResume -------------------------------------------------------
+
+/// A `zone resume` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneResume {
+    /// Resume the zone without checking that the failing step now succeeds.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// The result of a `zone resume` command.
+pub type ZoneResumeResult = Result<ZoneResumeOutput, ZoneResumeError>;
+
+/// The output of a `zone resume` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneResumeOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneResumeError {
+    NoSuchZone,
+    NotHalted,
+    RejectedReview,
+    StillFailing(String),
+}
+
+impl std::fmt::Display for ZoneResumeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotHalted => f.write_str("Zone is not halted"),
+            Self::RejectedReview => f.write_str(
+                "Zone was halted by a rejected review; override the review or reset the zone instead",
+            ),
+            Self::StillFailing(err) => write!(f, "Zone is still failing: {err}"),
+        }
+    }
+}
+
+//----------- ZoneSetDenial ----------------------------------------------------
+
+/// A `zone set-denial` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetDenial {
+    pub denial: ZoneDenial,
+}
+
+/// The denial-of-existence type to use for a zone.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneDenial {
+    /// Use the denial-of-existence type of the zone's policy.
+    Policy,
+
+    /// Use NSEC.
+    Nsec,
+
+    /// Use NSEC3.
+    Nsec3 { opt_out: bool },
+}
+
+/// The result of a `zone set-denial` command.
+pub type ZoneSetDenialResult = Result<ZoneSetDenialOutput, ZoneSetDenialError>;
+
+/// The output of a `zone set-denial` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetDenialOutput {
+    pub zone: ZoneName,
+
+    /// Whether the denial-of-existence type of the zone changed.
+    pub changed: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneSetDenialError {
+    NoSuchZone,
+    NoPolicy,
+}
+
+impl std::fmt::Display for ZoneSetDenialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NoPolicy => f.write_str("Zone has no policy"),
+        }
+    }
+}
+
+//----------- ZoneSetDescription -----------------------------------------------
+
+/// A `zone set-description` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetDescription {
+    /// The new description, or `None` to remove it.
+    pub description: Option<String>,
+}
+
+/// The result of a `zone set-description` command.
+pub type ZoneSetDescriptionResult = Result<ZoneSetDescriptionOutput, ZoneSetDescriptionError>;
+
+/// The output of a `zone set-description` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetDescriptionOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneSetDescriptionError {
+    NoSuchZone,
+}
+
+impl std::fmt::Display for ZoneSetDescriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+        }
+    }
+}
+
+//----------- ZoneSetSerialFloor -----------------------------------------------
+
+/// A `zone set-serial-floor` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetSerialFloor {
+    /// The new serial floor, or `None` to remove it.
+    pub serial_floor: Option<Serial>,
+}
+
+/// The result of a `zone set-serial-floor` command.
+pub type ZoneSetSerialFloorResult = Result<ZoneSetSerialFloorOutput, ZoneSetSerialFloorError>;
+
+/// The output of a `zone set-serial-floor` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneSetSerialFloorOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneSetSerialFloorError {
+    NoSuchZone,
+
+    /// The zone keeps the serial of the loaded zone, which is below the floor.
+    AboveKeptSerial {
+        loaded_serial: Serial,
+    },
+}
+
+impl std::fmt::Display for ZoneSetSerialFloorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::AboveKeptSerial { loaded_serial } => write!(
+                f,
+                "The zone's serial policy is keep and the loaded zone has serial {loaded_serial}; the floor cannot be higher"
+            ),
+        }
+    }
+}
+
+//----------- ZoneRotateTsig ---------------------------------------------------
+
+/// A `zone rotate-tsig` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneRotateTsig {
+    /// Begin rotating to the given TSIG key.
+    ///
+    /// Until the rotation is finished, the new key is tried first and the old
+    /// key is used as a fallback.
+    Start { key: TsigKeyName },
+
+    /// Stop using the old TSIG key.
+    Finish,
+
+    /// Stop using the new TSIG key.
+    Abort,
+}
+
+/// The result of a `zone rotate-tsig` command.
+pub type ZoneRotateTsigResult = Result<ZoneRotateTsigOutput, ZoneRotateTsigError>;
+
+/// The output of a `zone rotate-tsig` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneRotateTsigOutput {
+    pub zone: ZoneName,
+
+    /// The TSIG key now in use.
+    pub tsig_key: Option<TsigKeyName>,
+
+    /// The TSIG key being rotated to, if any.
+    pub next_tsig_key: Option<TsigKeyName>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneRotateTsigError {
+    NoSuchZone,
+    NotLoadedFromServer,
+    NoSuchTsigKey,
+    AlreadyRotating,
+    NotRotating,
+}
+
+impl std::fmt::Display for ZoneRotateTsigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotLoadedFromServer => f.write_str("The zone is not loaded from a server"),
+            Self::NoSuchTsigKey => f.write_str("No such TSIG key"),
+            Self::AlreadyRotating => f.write_str("A TSIG key rotation is already in progress"),
+            Self::NotRotating => f.write_str("No TSIG key rotation is in progress"),
+        }
+    }
+}
+
+//----------- ZoneTestNotify ---------------------------------------------------
+
+/// The result of a `zone test-notify` command.
+pub type ZoneTestNotifyResult = Result<ZoneTestNotifyOutput, ZoneTestNotifyError>;
+
+/// The output of a `zone test-notify` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneTestNotifyOutput {
+    pub zone: ZoneName,
+
+    /// The SOA serial announced in the NOTIFY messages.
+    pub serial: Serial,
+
+    /// The outcome for each configured NOTIFY target.
+    pub targets: Vec<ZoneTestNotifyTarget>,
+}
+
+/// The outcome of sending a NOTIFY message to a single name server.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneTestNotifyTarget {
+    pub addr: SocketAddr,
+    pub tsig_key: Option<TsigKeyName>,
+    pub outcome: NotifyOutcome,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum NotifyOutcome {
+    /// The name server responded with the given RCODE.
+    Response { rcode: String },
+
+    /// No response was received.
+    Failed { error: String },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneTestNotifyError {
+    NoSuchZone,
+    NotPublished,
+}
+
+impl std::fmt::Display for ZoneTestNotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotPublished => f.write_str("The zone has not been published yet"),
+        }
+    }
+}
+
+//----------- ZonePromote ------------------------------------------------------
+
+/// The result of a `zone promote` command.
+pub type ZonePromoteResult = Result<ZonePromoteOutput, ZonePromoteError>;
+
+/// The output of a `zone promote` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZonePromoteOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZonePromoteError {
+    NoSuchZone,
+    NotStaged,
+}
+
+impl std::fmt::Display for ZonePromoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotStaged => f.write_str("The zone has no staged instance"),
+        }
+    }
+}
+
+//----------- ZoneResign -------------------------------------------------------
+
+/// A `zone resign` command.
+///
+/// The overrides only apply to this re-signing operation; later re-signing
+/// operations use the values from the zone's policy.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ZoneResign {
+    /// Override the lifetime of the new signatures.
+    #[serde(default)]
+    pub sig_validity: Option<Duration>,
+
+    /// Override how far the inception of the new signatures lies in the past.
+    #[serde(default)]
+    pub inception_offset: Option<Duration>,
+}
+
+/// The result of a `zone resign` command.
+pub type ZoneResignResult = Result<ZoneResignOutput, ZoneResignError>;
+
+/// The output of a `zone resign` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneResignOutput {
+    pub zone: ZoneName,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneResignError {
+    NoSuchZone,
+    NoPolicy,
+    NotSigned,
+    Halted(String),
+    BadOverride(String),
+}
+
+impl std::fmt::Display for ZoneResignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NoPolicy => f.write_str("The zone has no policy"),
+            Self::NotSigned => f.write_str("The zone has not been signed yet"),
+            Self::Halted(reason) => write!(f, "The zone is halted: {reason}"),
+            Self::BadOverride(reason) => write!(f, "Invalid override: {reason}"),
+        }
+    }
+}
+
+//----------- ZoneGoLive -------------------------------------------------------
+
+/// The result of a `zone go-live` command.
+pub type ZoneGoLiveResult = Result<ZoneGoLiveOutput, ZoneGoLiveError>;
+
+/// The output of a `zone go-live` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneGoLiveOutput {
+    pub zone: ZoneName,
+
+    /// Whether a held signed version of the zone was released.
+    pub released: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneGoLiveError {
+    NoSuchZone,
+    NotObserveOnly,
+}
+
+impl std::fmt::Display for ZoneGoLiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotObserveOnly => f.write_str("The zone is not observe-only"),
+        }
+    }
+}
+
+//----------- ZoneMemory -------------------------------------------------------
 
-/// The result of a `zone reset` command.
-pub type ZoneResetResult = Result<ZoneResetOutput, ZoneResetError>;
+/// The result of a `zone memory` command for a single zone.
+pub type ZoneMemoryResult = Result<ZoneMemoryReport, ZoneMemoryError>;
 
-/// The output of a `zone reset` command.
+/// An estimate of the memory used by the data of a zone, in bytes.
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub struct ZoneResetOutput {
+pub struct ZoneMemoryReport {
     pub zone: ZoneName,
+
+    /// The unsigned (loaded) instances of the zone.
+    pub unsigned: usize,
+
+    /// The records generated by signing the zone.
+    pub signed: usize,
+
+    /// The diffs kept for serving IXFR.
+    pub diffs: usize,
 }
 
+impl ZoneMemoryReport {
+    /// The total estimated memory used by the zone, in bytes.
+    pub fn total(&self) -> usize {
+        self.unsigned + self.signed + self.diffs
+    }
+}
+
+/// The output of a `zone memory` command for all zones.
 #[derive(Deserialize, Serialize, Debug, Clone)]
-pub enum ZoneResetError {
+pub struct ZonesMemoryResult {
+    pub zones: Vec<ZoneMemoryReport>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneMemoryError {
     NoSuchZone,
-    NotHalted,
 }
 
-impl std::fmt::Display for ZoneResetError {
+impl std::fmt::Display for ZoneMemoryError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::NoSuchZone => f.write_str("No such zone"),
-            Self::NotHalted => f.write_str("Zone is not halted"),
         }
     }
 }
@@ -120,7 +713,85 @@ impl std::fmt::Display for ZoneOverrideError {
     }
 }
 
-//----------- ZoneOverride -----------------------------------------------------
+//----------- ZoneAdvance ------------------------------------------------------
+
+/// A `zone advance` command.
+///
+/// This forces a zone through the pipeline, skipping any reviews on the way to
+/// the requested stage. It is intended for testing and development only.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneAdvance {
+    /// The stage to advance the zone to.
+    pub to: ZoneAdvanceStage,
+
+    /// Confirmation that reviews should be bypassed.
+    ///
+    /// The request is refused unless this is `true`.
+    #[serde(default)]
+    pub dangerous: bool,
+}
+
+/// A stage of the pipeline that a zone can be forcibly advanced to.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneAdvanceStage {
+    /// Until the zone is signed (skipping the unsigned review).
+    Signed,
+
+    /// Until the zone is published (skipping both reviews).
+    Published,
+}
+
+impl std::fmt::Display for ZoneAdvanceStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Signed => "signed",
+            Self::Published => "published",
+        })
+    }
+}
+
+/// The result of a `zone advance` command.
+pub type ZoneAdvanceResult = Result<ZoneAdvanceOutput, ZoneAdvanceError>;
+
+/// The output of a `zone advance` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneAdvanceOutput {
+    pub zone: ZoneName,
+
+    /// Whether a review was approved immediately.
+    pub approved: bool,
+
+    /// The stage up to which reviews will be skipped once the zone reaches
+    /// them, if any remain.
+    pub pending: Option<ZoneAdvanceStage>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneAdvanceError {
+    NoSuchZone,
+    NotConfirmed,
+    Halted,
+    AlreadyAtStage,
+
+    /// The pending review could not be approved.
+    Review(ZoneReviewError),
+}
+
+impl std::fmt::Display for ZoneAdvanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchZone => f.write_str("No such zone"),
+            Self::NotConfirmed => {
+                f.write_str("Advancing a zone bypasses its reviews and must be confirmed")
+            }
+            Self::Halted => f.write_str("Zone is halted"),
+            Self::AlreadyAtStage => f.write_str("Zone has already reached that stage"),
+            Self::Review(err) => write!(f, "Could not approve the pending review: {err}"),
+        }
+    }
+}
+
+//----------- ZoneMaintenanceMode ----------------------------------------------
 
 /// The result of a `zone maintenance start/stop` command.
 pub type ZoneMaintenanceModeResult = Result<ZoneMaintenanceModeOutput, ZoneMaintenanceModeError>;
@@ -316,12 +987,28 @@ pub struct ZoneAdd {
     pub source: ZoneSource,
     pub policy: String,
     pub key_imports: Vec<KeyImport>,
+
+    /// Only validate the request, without adding the zone.
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Load and sign the zone, but do not publish it until it goes live.
+    #[serde(default)]
+    pub observe_only: bool,
+
+    /// The lowest SOA serial the signer may use for the zone.
+    #[serde(default)]
+    pub serial_floor: Option<Serial>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ZoneAddResult {
     pub name: ZoneName,
     pub status: String,
+
+    /// For a dry run, what adding the zone would do.
+    #[serde(default)]
+    pub actions: Vec<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -330,18 +1017,41 @@ pub enum ZoneAddError {
     NoSuchPolicy,
     PolicyMidDeletion,
     NoSuchTsigKey,
+    KeyOwnerMismatch {
+        path: String,
+        owner: String,
+    },
+    KeyAlgorithmMismatch {
+        key: String,
+        algorithm: String,
+        expected: String,
+    },
     Other(String),
 }
 
 impl fmt::Display for ZoneAddError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Self::AlreadyExists => "a zone of this name already exists",
-            Self::NoSuchPolicy => "no policy with that name exists",
-            Self::PolicyMidDeletion => "the specified policy is being deleted",
-            Self::NoSuchTsigKey => "no TSIG key with that name exists",
-            Self::Other(reason) => reason,
-        })
+        match self {
+            Self::AlreadyExists => f.write_str("a zone of this name already exists"),
+            Self::NoSuchPolicy => f.write_str("no policy with that name exists"),
+            Self::PolicyMidDeletion => f.write_str("the specified policy is being deleted"),
+            Self::NoSuchTsigKey => f.write_str("no TSIG key with that name exists"),
+            Self::KeyOwnerMismatch { path, owner } => {
+                write!(
+                    f,
+                    "the key in '{path}' belongs to '{owner}', not to this zone"
+                )
+            }
+            Self::KeyAlgorithmMismatch {
+                key,
+                algorithm,
+                expected,
+            } => write!(
+                f,
+                "the key '{key}' uses algorithm {algorithm}, but the policy requires {expected}"
+            ),
+            Self::Other(reason) => f.write_str(reason),
+        }
     }
 }
 
@@ -388,9 +1098,28 @@ pub enum ZoneSource {
 
         /// The name of a TSIG key, if any.
         tsig_key: Option<TsigKeyName>,
+
+        /// The name of the TSIG key being rotated to, if any.
+        #[serde(default)]
+        next_tsig_key: Option<TsigKeyName>,
+
+        /// A zonefile to load if the server can't be reached, if any.
+        #[serde(default)]
+        fallback: Option<ZonefileFallback>,
     },
 }
 
+/// A zonefile to fall back to when the primary of a zone is unreachable.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZonefileFallback {
+    /// The path to the zonefile.
+    pub path: Box<Utf8Path>,
+
+    /// How long to wait for the primary to answer a SOA query before falling
+    /// back.
+    pub timeout: Duration,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub enum ZoneRefreshStatus {
     /// Refreshing according to the SOA REFRESH interval.
@@ -413,11 +1142,22 @@ impl Display for ZoneSource {
         match self {
             ZoneSource::None => f.write_str("<none>"),
             ZoneSource::Zonefile { path } => path.fmt(f),
-            ZoneSource::Server { addr, tsig_key } => {
+            ZoneSource::Server {
+                addr,
+                tsig_key,
+                next_tsig_key,
+                fallback,
+            } => {
                 write!(f, "{addr}")?;
                 if let Some(tsig_key) = &tsig_key {
                     write!(f, " with TSIG key '{tsig_key}'")?;
                 }
+                if let Some(next_tsig_key) = &next_tsig_key {
+                    write!(f, " (rotating to TSIG key '{next_tsig_key}')")?;
+                }
+                if let Some(fallback) = &fallback {
+                    write!(f, " falling back to zone file '{}'", fallback.path)?;
+                }
                 Ok(())
             }
         }
@@ -427,6 +1167,9 @@ impl Display for ZoneSource {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ZonesListResult {
     pub zones: Vec<ZoneName>,
+    /// The descriptions of those zones that have one.
+    #[serde(default)]
+    pub descriptions: Vec<(ZoneName, String)>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -478,6 +1221,44 @@ pub struct ZoneStatus {
     pub published_serial: Option<Serial>,
     pub publish_addr: Vec<SocketAddr>,
     pub halted_reason: Option<String>,
+    /// The number of consecutive failed loads.
+    #[serde(default)]
+    pub load_failure_streak: u32,
+    /// The number of consecutive failed signing operations.
+    #[serde(default)]
+    pub sign_failure_streak: u32,
+    /// The number of loads and inbound NOTIFY messages that failed TSIG
+    /// verification, per TSIG key.
+    #[serde(default)]
+    pub tsig_failures: HashMap<TsigKeyName, u64>,
+    /// The review batch the upcoming version of the zone belongs to.
+    #[serde(default)]
+    pub review_batch: Option<String>,
+    /// The operator's description of the zone.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// When the signed instance under review was staged, if it was.
+    #[serde(default)]
+    pub staged_since: Option<SystemTime>,
+    /// Whether the zone is observe-only.
+    #[serde(default)]
+    pub observe_only: bool,
+    /// The lowest SOA serial the signer may use for the zone, if any.
+    #[serde(default)]
+    pub serial_floor: Option<Serial>,
+    /// Whether automatic key rolls are paused for the zone.
+    #[serde(default)]
+    pub key_rolls_paused: bool,
+    /// When the signed instance under review was held back, if it was.
+    #[serde(default)]
+    pub held_since: Option<SystemTime>,
+    /// How long manually reported key roll steps wait for caches to expire.
+    ///
+    /// This is only given if it is known and some key roll steps are
+    /// reported by hand, i.e. automatic reporting is disabled for some roll
+    /// type or key rolls are paused.
+    #[serde(default)]
+    pub rollover_wait: Option<keyset::RolloverWait>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -494,6 +1275,7 @@ pub enum Progress {
     Waiting,
     Restoring,
     Loading,
+    LoadingFailed,
     LoadedReview,
     HaltLoaded,
     Signing,
@@ -509,6 +1291,26 @@ pub struct ZoneLoaderReport {
     pub byte_count: usize,
     pub total_byte_count: Option<usize>,
     pub record_count: usize,
+
+    /// The kind of zone transfer that loaded the zone, if any.
+    #[serde(default)]
+    pub transfer: Option<ZoneTransferType>,
+}
+
+/// The kind of zone transfer used to load a zone.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ZoneTransferType {
+    Axfr,
+    Ixfr,
+}
+
+impl Display for ZoneTransferType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Axfr => "AXFR",
+            Self::Ixfr => "IXFR",
+        })
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -540,6 +1342,38 @@ pub struct SigningQueueReport {
     pub signing_report: SigningReport,
 }
 
+//------------ SigningQueueStats --------------------------------------------
+
+/// Aggregate statistics about the signing queue.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SigningQueueStats {
+    /// The maximum number of zones that are signed concurrently.
+    pub concurrency_limit: usize,
+
+    /// The number of zones being signed.
+    pub active: usize,
+
+    /// The number of zones waiting for signing capacity.
+    pub pending: usize,
+
+    /// The estimated memory used by the zones being signed, in bytes.
+    #[serde(default)]
+    pub memory_usage: usize,
+
+    /// The memory budget for the zones being signed, in bytes, if any.
+    #[serde(default)]
+    pub memory_budget: Option<usize>,
+
+    /// How long the longest-waiting pending zone has been waiting.
+    pub oldest_wait: Option<Duration>,
+
+    /// The average time zones waited for signing capacity since startup.
+    pub average_wait: Option<Duration>,
+
+    /// The longest time a zone waited for signing capacity since startup.
+    pub max_wait: Option<Duration>,
+}
+
 //------------ SigningStageReport -------------------------------------------
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -596,6 +1430,9 @@ pub struct KeyInfo {
     pub key_type: KeyType,
     pub key_tag: u16,
     pub signer: bool,
+    /// Whether the key is retired, i.e. no longer in use but not removed yet.
+    #[serde(default)]
+    pub retired: bool,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -663,6 +1500,9 @@ pub struct HistoryItem {
     pub when: SystemTime,
     pub serial: Option<Serial>,
     pub event: HistoricalEvent,
+    /// The operator who caused the event, if known.
+    #[serde(default)]
+    pub actor: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -680,8 +1520,17 @@ pub enum HistoricalEventType {
     SignedZoneReview,
     UnsignedHookFailed,
     SignedHookFailed,
+    Staged,
+    Promoted,
+    Held,
+    WentLive,
+    ForcedAdvance,
+    Healed,
     KeySetCommand,
     KeySetError,
+    KeyTagCollision,
+    DnssecInput,
+    Halted,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -699,12 +1548,21 @@ pub enum HistoricalEvent {
     SigningFailed {
         trigger: SigningTrigger,
         reason: String,
+        /// Whether the failure is transient and will be retried.
+        #[serde(default)]
+        transient: bool,
     },
     UnsignedZoneReview {
         status: ZoneReviewStatus,
+        /// Why the reviewer made their decision, if known.
+        #[serde(default)]
+        reason: Option<String>,
     },
     SignedZoneReview {
         status: ZoneReviewStatus,
+        /// Why the reviewer made their decision, if known.
+        #[serde(default)]
+        reason: Option<String>,
     },
     UnsignedHookFailed {
         err: String,
@@ -712,6 +1570,22 @@ pub enum HistoricalEvent {
     SignedHookFailed {
         err: String,
     },
+    /// The approved signed instance was published to the staging nameservers.
+    Staged,
+    /// The staged instance was promoted to production.
+    Promoted,
+    /// The approved signed instance was held back as the zone is observe-only.
+    Held,
+    /// The zone left observe-only mode.
+    WentLive,
+    /// The zone was forcibly advanced, bypassing its reviews.
+    ForcedAdvance {
+        to: ZoneAdvanceStage,
+    },
+    /// The served data did not match the recorded state on startup.
+    Healed {
+        reason: String,
+    },
     KeySetCommand {
         cmd: String,
         warning: Option<String>,
@@ -720,11 +1594,32 @@ pub enum HistoricalEvent {
     KeySetError {
         cmd: String,
         err: String,
+        #[serde(default)]
+        stderr: Option<String>,
         elapsed: Duration,
     },
+    /// Keys of the zone share a DNSKEY key tag.
+    ///
+    /// The keys are kept; validators and operators have to tell them apart
+    /// by other means until one of them is rolled or removed.
+    KeyTagCollision {
+        key_tag: u16,
+        keys: Vec<String>,
+    },
+    /// The loaded zone contained DNSSEC records.
+    ///
+    /// They were either stripped before signing, or signing was refused.
+    DnssecInput {
+        records: usize,
+        stripped: bool,
+    },
     LoadingFailed {
         reason: String,
     },
+    /// The zone halted and will not progress until the operator intervenes.
+    Halted {
+        reason: String,
+    },
 }
 
 /// The trigger for a (re-)signing operation.
@@ -745,6 +1640,10 @@ pub struct ResigningTrigger {
 
     /// Whether signatures need to be refreshed.
     pub sigs_need_refresh: bool,
+
+    /// Whether an operator requested re-signing.
+    #[serde(default)]
+    pub manual: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -752,6 +1651,14 @@ pub enum ZoneHistoryError {
     ZoneDoesNotExist,
 }
 
+/// A `zone reload` command.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ZoneReload {
+    /// The review batch to tag the reloaded versions of the zone with.
+    #[serde(default)]
+    pub batch: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ZoneReloadResult {
     pub name: ZoneName,
@@ -776,6 +1683,137 @@ impl fmt::Display for ZoneReloadError {
     }
 }
 
+/// The result of a `zone rollback` command.
+pub type ZoneRollbackResult = Result<ZoneRollbackOutput, ZoneRollbackError>;
+
+/// The output of a `zone rollback` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneRollbackOutput {
+    pub name: ZoneName,
+
+    /// The SOA serial of the loaded version being restored.
+    pub serial: Serial,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneRollbackError {
+    ZoneDoesNotExist,
+    ZoneHalted(String),
+
+    /// The zone is being loaded, signed or reviewed.
+    ZoneBusy,
+
+    /// No previous version of the zone has been retained.
+    NoPreviousVersion,
+
+    /// The zone is transferred from a primary, which would undo the rollback
+    /// on the next refresh.
+    ServerSource,
+
+    /// The serial policy of the zone is `keep`, so the restored version
+    /// cannot be published with a newer serial.
+    SerialPolicyKeep,
+}
+
+impl fmt::Display for ZoneRollbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::ZoneDoesNotExist => "no zone with this name exist",
+            Self::ZoneHalted(reason) => {
+                return write!(f, "the zone has been halted (reason: {reason})");
+            }
+            Self::ZoneBusy => "the zone is busy, try again once it has been published",
+            Self::NoPreviousVersion => "no previous version of the zone has been retained",
+            Self::ServerSource => {
+                "the zone is transferred from a primary, whose next refresh would undo the rollback"
+            }
+            Self::SerialPolicyKeep => {
+                "the serial policy of the zone is 'keep', so the previous version cannot be republished"
+            }
+        })
+    }
+}
+
+//----------- ZoneQuery --------------------------------------------------------
+
+/// A `zone query` command.
+///
+/// This looks up records in Cascade's own copy of a zone, rather than asking
+/// a name server.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneQuery {
+    /// The owner name to look up.
+    pub name: ZoneName,
+
+    /// The record type to look up.
+    ///
+    /// Signatures covering this type are included.  `ANY` matches all types.
+    pub rtype: Rtype,
+
+    /// The version of the zone to look in.
+    pub stage: ZoneQueryStage,
+}
+
+/// A version of a zone that can be queried.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneQueryStage {
+    /// The unsigned version under review, else the published one.
+    Unsigned,
+
+    /// The signed version under review, else the published one.
+    Signed,
+
+    /// The published version.
+    Published,
+}
+
+impl std::fmt::Display for ZoneQueryStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unsigned => "unsigned",
+            Self::Signed => "signed",
+            Self::Published => "published",
+        })
+    }
+}
+
+/// The result of a `zone query` command.
+pub type ZoneQueryResult = Result<ZoneQueryOutput, ZoneQueryError>;
+
+/// The output of a `zone query` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneQueryOutput {
+    /// The SOA serial of the version that was queried.
+    pub serial: Serial,
+
+    /// Whether the version that was queried is under review.
+    pub under_review: bool,
+
+    /// The matching records, in zone file format.
+    pub records: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum ZoneQueryError {
+    NoSuchZone,
+
+    /// The queried name does not belong to the zone.
+    OutOfZone,
+
+    /// The zone has not been published yet, and no version is under review.
+    NotAvailable,
+}
+
+impl fmt::Display for ZoneQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NoSuchZone => "no zone with this name exist",
+            Self::OutOfZone => "the name does not belong to the zone",
+            Self::NotAvailable => "no version of the zone is available at this stage yet",
+        })
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Health {
     pub healthy: bool,
@@ -790,6 +1828,8 @@ pub struct Info {
 pub struct ServerStatusResult {
     pub halted_zones: Vec<(ZoneName, String)>,
     pub signing_queue: Vec<SigningQueueReport>,
+    #[serde(default)]
+    pub signing_queue_stats: SigningQueueStats,
     pub loaded_review_addrs: Vec<SocketAddr>,
     pub signed_review_addrs: Vec<SocketAddr>,
     pub server_addrs: Vec<SocketAddr>,
@@ -820,6 +1860,43 @@ pub struct KeyMsg {
     pub msg: String,
 }
 
+/// The output of a `status resign-schedule` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ResignScheduleResult {
+    /// The zones, in the order they are scheduled to be re-signed.
+    pub zones: Vec<ZoneResignSchedule>,
+}
+
+/// When a zone is scheduled to be re-signed, and why.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ZoneResignSchedule {
+    pub zone: ZoneName,
+
+    /// When the zone is next scheduled to be re-signed, if it is.
+    pub scheduled: Option<SystemTime>,
+
+    /// When the signatures of the zone were last refreshed.
+    pub last_refresh: SystemTime,
+
+    /// How often the signatures of the zone are refreshed, in seconds.
+    pub refresh_interval: u32,
+
+    /// When the earliest expiring signature of the published zone expires.
+    pub min_expiration: Option<SystemTime>,
+
+    /// How long before they expire signatures are replaced, in seconds.
+    pub sig_remain_time: u32,
+}
+
+impl ZoneResignSchedule {
+    /// When the earliest expiring signature should have been replaced.
+    pub fn deadline(&self) -> Option<SystemTime> {
+        let remain_time = Duration::from_secs(self.sig_remain_time.into());
+        self.min_expiration
+            .map(|expiration| expiration.checked_sub(remain_time).unwrap_or(expiration))
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 // Allow the large enum variant caused by TsigKeyName using Name<Array<255>>
 // to avoid the conversions that would be needed if Name<Bytes> were to be
@@ -829,6 +1906,7 @@ pub enum PolicyReloadError {
     Io(Utf8PathBuf, String),
     NoSuchTsigKey(TsigKeyName),
     BadValue(String),
+    InheritanceCycle(Vec<String>),
 }
 
 impl Display for PolicyReloadError {
@@ -837,10 +1915,21 @@ impl Display for PolicyReloadError {
             PolicyReloadError::Io(p, e) => write!(f, "{p}: {e}"),
             PolicyReloadError::NoSuchTsigKey(k) => write!(f, "no TSIG key with name '{k}' exists"),
             PolicyReloadError::BadValue(e) => write!(f, "bad value in policy variable: {e}"),
+            PolicyReloadError::InheritanceCycle(chain) => {
+                write!(f, "policy inheritance cycle: {}", chain.join(" -> "))
+            }
         }
     }
 }
 
+/// A `policy reload` command.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct PolicyReload {
+    /// Re-parse all policy files, including those that were not modified.
+    #[serde(default)]
+    pub full: bool,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PolicyChanges {
     pub changes: Vec<(String, PolicyChange)>,
@@ -864,6 +1953,17 @@ pub struct PolicyInfo {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct LoaderPolicyInfo {
     pub review: ReviewPolicyInfo,
+    #[serde(default)]
+    pub transfer: TransferLimitsPolicyInfo,
+    #[serde(default)]
+    pub soa_query_over_tcp: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct TransferLimitsPolicyInfo {
+    pub max_bytes: Option<u64>,
+    pub max_records: Option<u64>,
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -888,6 +1988,9 @@ pub struct KeyManagerPolicyInfo {
     pub default_ttl: u32,
     pub auto_remove: bool,
     pub auto_remove_delay: Duration,
+    /// The maximum per-zone reduction of key validities, in seconds.
+    #[serde(default)]
+    pub validity_jitter: u32,
     pub publication_nameservers: Vec<String>,
 }
 
@@ -929,6 +2032,10 @@ pub struct SignerPolicyInfo {
     pub key_roll_time: u32,
     pub denial: SignerDenialPolicyInfo,
     pub review: ReviewPolicyInfo,
+    #[serde(default)]
+    pub preserve_external_signatures: bool,
+    #[serde(default)]
+    pub refuse_dnssec_input: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -955,14 +2062,26 @@ pub enum Nsec3OptOutPolicyInfo {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ServerPolicyInfo {
     pub outbound: OutboundPolicyInfo,
+    #[serde(default)]
+    pub staging: StagingPolicyInfo,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct OutboundPolicyInfo {
     pub provide_xfr_to: Vec<NameserverCommsPolicyInfo>,
+    #[serde(default)]
+    pub provide_review_xfr_to: Vec<NameserverCommsPolicyInfo>,
     pub send_notify_to: Vec<NameserverCommsPolicyInfo>,
     pub max_diffs: usize,
     pub max_diffs_size: usize,
+    #[serde(default)]
+    pub removal_grace_period: Option<Duration>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct StagingPolicyInfo {
+    pub send_notify_to: Vec<NameserverCommsPolicyInfo>,
+    pub soak_time: Option<Duration>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -981,6 +2100,28 @@ pub enum PolicyInfoError {
     PolicyDoesNotExist,
 }
 
+/// The output of a `policy export` command.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PolicyExportOutput {
+    /// The policy, in the format of a policy file.
+    pub toml: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub enum PolicyExportError {
+    PolicyDoesNotExist,
+    Serialization(String),
+}
+
+impl Display for PolicyExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PolicyDoesNotExist => f.write_str("No such policy"),
+            Self::Serialization(err) => write!(f, "Could not render the policy: {err}"),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub enum PolicyChange {
     Added,
@@ -1130,6 +2271,127 @@ pub mod keyset {
         pub continue_flag: bool,
     }
 
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyImports {
+        pub key_imports: Vec<KeyImport>,
+    }
+
+    /// How long a key roll waits for caches to expire.
+    ///
+    /// This is derived from the TTLs of the published instance of a zone.  It
+    /// does not include the TTL of the DS RRset at the parent, which has to
+    /// be waited for as well when the DS records change.
+    #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+    pub struct RolloverWait {
+        /// The largest TTL in the signed zone, in seconds.
+        ///
+        /// This also bounds the TTL of negative responses.
+        pub max_ttl: u32,
+        /// The safety margin on top of the TTL, in seconds.
+        pub margin: u32,
+    }
+
+    impl RolloverWait {
+        /// The total wait, in seconds.
+        pub fn total(&self) -> u32 {
+            self.max_ttl.saturating_add(self.margin)
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyMigrate {
+        /// The ID of the KMIP server to move the keys to.
+        pub to_hsm: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyVerifyResult {
+        /// The number of keys in the key set state.
+        pub checked: usize,
+        /// Keys in the key set state that could not be loaded.
+        pub dangling: Vec<KeyVerifyProblem>,
+        /// Key files for the zone that the key set state does not refer to.
+        pub orphans: Vec<String>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyVerifyProblem {
+        /// The key, as named in the key set state.
+        pub key: String,
+        /// Why the key could not be loaded.
+        pub error: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyDnskeyResult {
+        /// The serial number of the published instance of the zone.
+        pub serial: Serial,
+        /// The published DNSKEY records.
+        pub keys: Vec<PublishedDnskey>,
+        /// The published RRSIG records covering the DNSKEY RRset.
+        pub signatures: Vec<PublishedRrsig>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeySetStateDump {
+        /// The path of the key set state file.
+        pub path: Utf8PathBuf,
+        /// The contents of the state file, as pretty-printed JSON.
+        pub state: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct PublishedDnskey {
+        /// The key tag of the key.
+        pub key_tag: u16,
+        /// The flags of the key (256 for a ZSK, 257 for a KSK or CSK).
+        pub flags: u16,
+        /// The record, in presentation format.
+        pub record: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct PublishedRrsig {
+        /// The key tag of the key that made the signature.
+        pub key_tag: u16,
+        /// The record, in presentation format.
+        pub record: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct KeyPropagationResult {
+        /// The serial number of the published instance of the zone.
+        pub serial: Serial,
+        /// The key tags of the published DNSKEY RRset.
+        pub expected: Vec<u16>,
+        /// The DNSKEY RRset seen at each publication nameserver.
+        pub nameservers: Vec<NameserverPropagation>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub struct NameserverPropagation {
+        /// The nameserver, as configured or found in the SOA MNAME field.
+        pub nameserver: String,
+        /// The address the nameserver was queried at, if it was resolved.
+        pub addr: Option<SocketAddr>,
+        pub outcome: PropagationOutcome,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone)]
+    pub enum PropagationOutcome {
+        /// The nameserver serves the published DNSKEY RRset.
+        Propagated,
+        /// The nameserver serves a different DNSKEY RRset.
+        Pending {
+            /// Published key tags the nameserver does not serve yet.
+            missing: Vec<u16>,
+            /// Key tags the nameserver still serves, but are not published.
+            unexpected: Vec<u16>,
+        },
+        /// The nameserver could not be queried.
+        Failed { error: String },
+    }
+
     #[derive(Deserialize, Serialize, Debug, Clone)]
     pub struct KeyGet {
         pub key_type: KeyGetType,
@@ -1188,3 +2450,38 @@ pub mod keyset {
         RollDone,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_summary_lists_changes_and_keys() {
+        let mut summary = ZoneReviewSummary {
+            zone: "example.org".parse().unwrap(),
+            stage: ZoneReviewStage::Signed,
+            serial: Serial(2026101701),
+            record_count: 42,
+            diff: Some(ZoneReviewDiff {
+                added: 3,
+                removed: 1,
+            }),
+            keys: Some(ZoneReviewKeyChanges {
+                added: vec![12345],
+                removed: vec![54321],
+            }),
+        };
+        assert_eq!(
+            summary.to_string(),
+            "signed zone 'example.org' at serial 2026101701: 42 records, 3 added, 1 removed, DNSKEY 12345 added, DNSKEY 54321 removed"
+        );
+
+        summary.stage = ZoneReviewStage::Unsigned;
+        summary.diff = None;
+        summary.keys = None;
+        assert_eq!(
+            summary.to_string(),
+            "unsigned zone 'example.org' at serial 2026101701: 42 records, changes unknown"
+        );
+    }
+}
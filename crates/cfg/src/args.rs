@@ -19,6 +19,9 @@ pub struct ArgsSpec {
     /// The configuration file to load.
     pub config: Option<Box<Utf8Path>>,
 
+    /// The directory of drop-in configuration files to load.
+    pub config_dir: Option<Box<Utf8Path>>,
+
     /// The minimum severity of messages to log.
     pub log_level: Option<LogLevel>,
 
@@ -27,6 +30,10 @@ pub struct ArgsSpec {
 
     /// Whether Cascade should fork on startup.
     pub daemonize: bool,
+
+    /// Whether Cascade should stay in the foreground, overriding the
+    /// configuration file.
+    pub foreground: bool,
 }
 
 impl ArgsSpec {
@@ -57,6 +64,14 @@ impl ArgsSpec {
                 ))
                 .value_hint(ValueHint::FilePath)
                 .help("The configuration file to load"),
+            Arg::new("config_dir")
+                .long("config-dir")
+                .value_name("PATH")
+                .value_parser(ValueParser::new(
+                    PathBufValueParser::new().try_map(Utf8PathBuf::try_from),
+                ))
+                .value_hint(ValueHint::DirPath)
+                .help("A directory of drop-in configuration files to merge over the configuration file"),
             Arg::new("log_level")
                 .long("log-level")
                 .value_name("LEVEL")
@@ -73,6 +88,11 @@ impl ArgsSpec {
                 .long("daemonize")
                 .action(clap::ArgAction::SetTrue)
                 .help("Whether Cascade should fork on startup"),
+            Arg::new("foreground")
+                .long("foreground")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("daemonize")
+                .help("Stay in the foreground, even if the configuration enables daemonize"),
         ])
     }
 
@@ -85,9 +105,13 @@ impl ArgsSpec {
             config: matches
                 .get_one::<Utf8PathBuf>("config")
                 .map(|p| p.as_path().into()),
+            config_dir: matches
+                .get_one::<Utf8PathBuf>("config_dir")
+                .map(|p| p.as_path().into()),
             log_level: matches.get_one::<LogLevel>("log_level").copied(),
             log_target: matches.get_one::<LogTargetSpec>("log_target").cloned(),
             daemonize: matches.get_flag("daemonize"),
+            foreground: matches.get_flag("foreground"),
         }
     }
 
@@ -98,7 +122,12 @@ impl ArgsSpec {
         daemon.logging.level.args = self.log_level;
         daemon.logging.target.args = self.log_target.map(|t| t.build());
         daemon.config_file.args = self.config;
-        daemon.daemonize.args = self.daemonize.then_some(true);
+        daemon.config_dir.args = self.config_dir.map(Some);
+        daemon.daemonize.args = if self.foreground {
+            Some(false)
+        } else {
+            self.daemonize.then_some(true)
+        };
     }
 }
 
@@ -207,3 +236,32 @@ impl ValueEnum for LogLevel {
         Some(PossibleValue::new(self.as_str()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+    use clap::Command;
+
+    use super::{ArgsSpec, Config};
+
+    /// Parse command-line arguments and merge them into a default [`Config`].
+    fn config_from_args(args: &[&str]) -> Config {
+        let cmd = ArgsSpec::setup(Command::new("cascaded"));
+        let matches = cmd.try_get_matches_from(args).unwrap();
+        let mut config = Config::default();
+        ArgsSpec::process(&matches).merge(&mut config);
+        config
+    }
+
+    #[test]
+    fn config_dir_is_optional() {
+        let config = config_from_args(&["cascaded"]);
+        assert_eq!(config.daemon.config_dir.value(), &None);
+
+        let config = config_from_args(&["cascaded", "--config-dir", "/etc/cascade/config.d"]);
+        assert_eq!(
+            config.daemon.config_dir.value().as_deref(),
+            Some(Utf8Path::new("/etc/cascade/config.d"))
+        );
+    }
+}
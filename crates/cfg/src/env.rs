@@ -17,6 +17,9 @@ pub struct EnvSpec {
     /// The configuration file to load.
     pub config: Option<Box<Utf8Path>>,
 
+    /// The directory of drop-in configuration files to load.
+    pub config_dir: Option<Box<Utf8Path>>,
+
     /// The minimum severity of messages to log.
     pub log_level: Option<LogLevel>,
 
@@ -42,6 +45,9 @@ impl EnvSpec {
         let config =
             var("CASCADE_CONFIG_PATH")?.map(|path| Utf8PathBuf::from(path).into_boxed_path());
 
+        let config_dir =
+            var("CASCADE_CONFIG_DIR")?.map(|path| Utf8PathBuf::from(path).into_boxed_path());
+
         let log_level = var("CASCADE_LOG_LEVEL")?
             .map(|value| match &*value {
                 "trace" => Ok(LogLevel::Trace),
@@ -64,6 +70,7 @@ impl EnvSpec {
         Ok(Self {
             state,
             config,
+            config_dir,
             log_level,
             log_target,
             log_trace_targets,
@@ -78,6 +85,7 @@ impl EnvSpec {
         daemon.logging.target.env = self.log_target.map(|t| t.build());
         daemon.logging.trace_targets.env = self.log_trace_targets;
         daemon.config_file.env = self.config;
+        daemon.config_dir.env = self.config_dir.map(Some);
     }
 }
 
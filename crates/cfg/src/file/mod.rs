@@ -36,6 +36,72 @@ impl Spec {
         let text = std::fs::read_to_string(path)?;
         Ok(toml::from_str(&text)?)
     }
+
+    /// Load the configuration file, merging drop-in files over it.
+    ///
+    /// The drop-in files are the `*.toml` files in `dir`; they are merged in
+    /// the lexicographic order of their names, so later files override earlier
+    /// ones.  Tables are merged key by key (recursively), while other values
+    /// (including arrays) are replaced entirely.  Drop-in files do not need to
+    /// specify a `version`.
+    ///
+    /// On failure, the path of the offending file is returned with the error.
+    pub fn load_with_drop_ins(
+        path: &Utf8Path,
+        dir: &Utf8Path,
+    ) -> Result<Self, (Box<Utf8Path>, FileError)> {
+        let mut table = read_table(path).map_err(|error| (path.into(), error))?;
+
+        let mut drop_ins = Vec::new();
+        let entries = dir
+            .read_dir_utf8()
+            .map_err(|error| (dir.into(), error.into()))?;
+        for entry in entries {
+            let entry = entry.map_err(|error| (dir.into(), error.into()))?;
+            let file_type = entry
+                .file_type()
+                .map_err(|error| (entry.path().into(), error.into()))?;
+            if !file_type.is_dir() && entry.path().extension() == Some("toml") {
+                drop_ins.push(entry.into_path());
+            }
+        }
+        drop_ins.sort();
+
+        for drop_in in drop_ins {
+            let overrides =
+                read_table(&drop_in).map_err(|error| (drop_in.as_path().into(), error))?;
+            merge_tables(&mut table, overrides);
+        }
+
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|error| (path.into(), FileError::Parse(error)))
+    }
+}
+
+/// Read a configuration file as a TOML table.
+fn read_table(path: &Utf8Path) -> Result<toml::Table, FileError> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(toml::from_str(&text)?)
+}
+
+/// Merge a TOML table over another.
+///
+/// Tables are merged key by key (recursively), while other values (including
+/// arrays) are replaced entirely.  This is used both for configuration drop-in
+/// files and for policies extending other policies.
+pub fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(inner)), toml::Value::Table(value)) => {
+                merge_tables(inner, value)
+            }
+            (Some(slot), value) => *slot = value,
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 //----------- FileError --------------------------------------------------------
@@ -91,3 +157,113 @@ impl From<toml::de::Error> for FileError {
         Self::Parse(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8Path;
+
+    use super::{Spec, merge_tables};
+
+    #[test]
+    fn merge_overrides() {
+        let mut base: toml::Table = toml::from_str(
+            r#"
+            version = "v1"
+            [signer]
+            signature-inception-offset = 3600
+            [signer.denial]
+            type = "nsec"
+            [server.outbound]
+            send-notify-to = ["192.0.2.1"]
+            "#,
+        )
+        .unwrap();
+        let overrides: toml::Table = toml::from_str(
+            r#"
+            extends = "base"
+            [signer.denial]
+            type = "nsec3"
+            [server.outbound]
+            send-notify-to = ["192.0.2.2"]
+            "#,
+        )
+        .unwrap();
+        let expected: toml::Table = toml::from_str(
+            r#"
+            version = "v1"
+            extends = "base"
+            [signer]
+            signature-inception-offset = 3600
+            [signer.denial]
+            type = "nsec3"
+            [server.outbound]
+            send-notify-to = ["192.0.2.2"]
+            "#,
+        )
+        .unwrap();
+
+        merge_tables(&mut base, overrides);
+        assert_eq!(base, expected);
+    }
+
+    /// Write a file into a test directory.
+    fn write(dir: &Utf8Path, name: &str, text: &str) {
+        std::fs::write(dir.join(name), text).unwrap();
+    }
+
+    #[test]
+    fn drop_ins_override_in_name_order() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(tmp.path()).unwrap();
+        let drop_ins = dir.join("config.d");
+        std::fs::create_dir(&drop_ins).unwrap();
+
+        write(
+            dir,
+            "config.toml",
+            r#"
+            version = "v1"
+            policy-dir = "/base/policies"
+            keys-dir = "/base/keys"
+            [signer]
+            max-failures = 1
+            "#,
+        );
+        write(
+            &drop_ins,
+            "20-late.toml",
+            r#"policy-dir = "/late/policies""#,
+        );
+        write(
+            &drop_ins,
+            "10-early.toml",
+            r#"
+            policy-dir = "/early/policies"
+            [signer]
+            expiry-warning = 3600
+            "#,
+        );
+        // Files without the '.toml' extension are ignored.
+        write(&drop_ins, "30-ignored.toml.bak", r#"keys-dir = "/ignored""#);
+
+        let Spec::V1(spec) = Spec::load_with_drop_ins(&dir.join("config.toml"), &drop_ins).unwrap();
+        assert_eq!(&*spec.policy_dir, "/late/policies");
+        assert_eq!(&*spec.keys_dir, "/base/keys");
+        assert_eq!(spec.signer.max_failures, Some(1));
+        assert_eq!(spec.signer.expiry_warning, Some(3600));
+    }
+
+    #[test]
+    fn drop_in_errors_name_the_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(tmp.path()).unwrap();
+        let drop_ins = dir.join("config.d");
+        std::fs::create_dir(&drop_ins).unwrap();
+
+        write(dir, "config.toml", r#"version = "v1""#);
+        write(&drop_ins, "10-broken.toml", "policy-dir = ");
+
+        let (path, _) = Spec::load_with_drop_ins(&dir.join("config.toml"), &drop_ins).unwrap_err();
+        assert_eq!(&*path, drop_ins.join("10-broken.toml"));
+    }
+}
@@ -1,13 +1,18 @@
 //! Version 1 of the configuration file.
 
-use std::{fmt, net::SocketAddr, num::IntErrorKind, str::FromStr};
+use std::{
+    fmt,
+    net::SocketAddr,
+    num::{IntErrorKind, NonZeroUsize},
+    str::FromStr,
+};
 
 use camino::Utf8Path;
 use serde::Deserialize;
 
 use crate::{
     Config, DaemonConfig, GroupId, KeyManagerConfig, LoaderConfig, LogLevel, LogTarget,
-    RemoteControlConfig, ReviewConfig, ServerConfig, SignerConfig, SocketConfig, UserId,
+    RemoteControlConfig, ReviewConfig, RrlConfig, ServerConfig, SignerConfig, SocketConfig, UserId,
 };
 
 //----------- Spec -------------------------------------------------------------
@@ -155,6 +160,12 @@ pub struct RemoteControlSpec {
     /// both IPv4 and IPv6 more than one address can be provided if needed.
     #[serde(default = "RemoteControlSpec::servers_default")]
     pub servers: Vec<SocketAddr>,
+
+    /// A Unix domain socket to serve our HTTP API from, if any.
+    pub unix_socket: Option<Box<Utf8Path>>,
+
+    /// Whether to serve the debugging endpoints of the HTTP API.
+    pub debug_endpoints: bool,
 }
 
 //--- Conversion
@@ -163,6 +174,8 @@ impl RemoteControlSpec {
     /// Parse from this specification.
     pub fn parse_into(self, config: &mut RemoteControlConfig) {
         config.servers = self.servers.clone();
+        config.unix_socket = self.unix_socket;
+        config.debug_endpoints = self.debug_endpoints;
     }
 }
 
@@ -172,6 +185,8 @@ impl Default for RemoteControlSpec {
     fn default() -> Self {
         Self {
             servers: Self::servers_default(),
+            unix_socket: None,
+            debug_endpoints: false,
         }
     }
 }
@@ -203,6 +218,12 @@ pub struct DaemonSpec {
 
     /// The identity to assume after startup.
     pub identity: Option<IdentitySpec>,
+
+    /// Whether to set `SO_REUSEPORT` on the sockets Cascade binds itself.
+    pub reuse_port: bool,
+
+    /// How many zone state files to read in parallel on startup.
+    pub startup_parallelism: Option<NonZeroUsize>,
 }
 
 //--- Conversion
@@ -215,6 +236,8 @@ impl DaemonSpec {
         config.daemonize.file = self.daemonize;
         config.pid_file = self.pid_file;
         config.identity = self.identity.map(|v| v.parse());
+        config.reuse_port = self.reuse_port;
+        config.startup_parallelism = self.startup_parallelism;
     }
 }
 
@@ -436,6 +459,9 @@ impl GroupIdSpec {
 pub struct LoaderSpec {
     /// Configuring whether and how loaded zones are reviewed.
     pub review: ReviewSpec,
+
+    /// How many times in a row loading a zone may fail.
+    pub max_failures: Option<u32>,
 }
 
 //--- Conversion
@@ -444,6 +470,7 @@ impl LoaderSpec {
     /// Parse from this specification.
     pub fn parse_into(self, config: &mut LoaderConfig) {
         self.review.parse_into(&mut config.review);
+        config.max_failures = self.max_failures;
     }
 }
 
@@ -455,6 +482,24 @@ impl LoaderSpec {
 pub struct SignerSpec {
     /// Configuring whether and how signed zones are reviewed.
     pub review: ReviewSpec,
+
+    /// How many times in a row signing a zone may fail.
+    pub max_failures: Option<u32>,
+
+    /// How long before signatures expire to warn about them, in seconds.
+    pub expiry_warning: Option<u32>,
+
+    /// How many zones may be signed concurrently.
+    pub max_concurrent_operations: Option<NonZeroUsize>,
+
+    /// How much memory concurrent signing operations may use, in megabytes.
+    pub memory_budget: Option<usize>,
+
+    /// How many records to sign in a single batch.
+    pub batch_size: Option<NonZeroUsize>,
+
+    /// The directory to record signing statistics in.
+    pub statistics_dir: Option<Box<Utf8Path>>,
 }
 
 //--- Conversion
@@ -463,6 +508,14 @@ impl SignerSpec {
     /// Parse from this specification.
     pub fn parse_into(self, config: &mut SignerConfig) {
         self.review.parse_into(&mut config.review);
+        config.max_failures = self.max_failures;
+        config.expiry_warning = self.expiry_warning;
+        config.max_concurrent_operations = self.max_concurrent_operations;
+        config.memory_budget = self
+            .memory_budget
+            .map(|megabytes| megabytes.saturating_mul(1_000_000));
+        config.batch_size = self.batch_size;
+        config.statistics_dir = self.statistics_dir;
     }
 }
 
@@ -507,11 +560,20 @@ impl KeyManagerSpec {
 //----------- ServerSpec -------------------------------------------------------
 
 /// Configuring how zones are published.
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
 pub struct ServerSpec {
     /// Where to serve zones.
     pub servers: Vec<SocketSpec>,
+
+    /// The maximum size of responses over UDP, in bytes.
+    pub udp_payload_size: Option<u16>,
+
+    /// Whether to support DNS Cookies.
+    pub cookies: bool,
+
+    /// Response rate limiting.
+    pub rrl: Option<RrlSpec>,
 }
 
 //--- Conversion
@@ -523,6 +585,74 @@ impl ServerSpec {
         config
             .servers
             .extend(self.servers.into_iter().map(|v| v.parse()));
+        config.udp_payload_size = self.udp_payload_size;
+        config.cookies = self.cookies;
+        config.rrl = self.rrl.map(RrlSpec::parse);
+    }
+}
+
+//--- Defaults
+
+impl Default for ServerSpec {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            udp_payload_size: None,
+            cookies: true,
+            rrl: None,
+        }
+    }
+}
+
+//----------- RrlSpec ----------------------------------------------------------
+
+/// Configuring response rate limiting.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RrlSpec {
+    /// How many responses per second to send to a client network.
+    pub responses_per_second: u32,
+
+    /// How often to send a truncated response instead of dropping one.
+    #[serde(default = "RrlSpec::slip_default")]
+    pub slip: u32,
+
+    /// The prefix length of the IPv4 client networks.
+    #[serde(default = "RrlSpec::ipv4_prefix_length_default")]
+    pub ipv4_prefix_length: u8,
+
+    /// The prefix length of the IPv6 client networks.
+    #[serde(default = "RrlSpec::ipv6_prefix_length_default")]
+    pub ipv6_prefix_length: u8,
+}
+
+//--- Conversion
+
+impl RrlSpec {
+    /// Parse from this specification.
+    pub fn parse(self) -> RrlConfig {
+        RrlConfig {
+            responses_per_second: self.responses_per_second,
+            slip: self.slip,
+            ipv4_prefix_len: self.ipv4_prefix_length.min(32),
+            ipv6_prefix_len: self.ipv6_prefix_length.min(128),
+        }
+    }
+}
+
+//--- Defaults
+
+impl RrlSpec {
+    fn slip_default() -> u32 {
+        2
+    }
+
+    fn ipv4_prefix_length_default() -> u8 {
+        24
+    }
+
+    fn ipv6_prefix_length_default() -> u8 {
+        56
     }
 }
 
@@ -571,18 +701,30 @@ pub enum ComplexSocketSpec {
     UDP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        #[serde(rename = "v6-only", default = "ComplexSocketSpec::v6_only_default")]
+        v6_only: bool,
     },
 
     /// Listen exclusively over TCP.
     TCP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        #[serde(rename = "v6-only", default = "ComplexSocketSpec::v6_only_default")]
+        v6_only: bool,
     },
 
     /// Listen over both TCP and UDP.
     TCPUDP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        #[serde(rename = "v6-only", default = "ComplexSocketSpec::v6_only_default")]
+        v6_only: bool,
     },
     //
     // TODO: TLS
@@ -639,9 +781,18 @@ impl SimpleSocketSpec {
     /// Parse from this specification.
     pub fn parse(self) -> SocketConfig {
         match self {
-            Self::UDP { addr } => SocketConfig::UDP { addr },
-            Self::TCP { addr } => SocketConfig::TCP { addr },
-            Self::TCPUDP { addr } => SocketConfig::TCPUDP { addr },
+            Self::UDP { addr } => SocketConfig::UDP {
+                addr,
+                v6_only: true,
+            },
+            Self::TCP { addr } => SocketConfig::TCP {
+                addr,
+                v6_only: true,
+            },
+            Self::TCPUDP { addr } => SocketConfig::TCPUDP {
+                addr,
+                v6_only: true,
+            },
         }
     }
 }
@@ -650,11 +801,19 @@ impl ComplexSocketSpec {
     /// Parse from this specification.
     pub fn parse(self) -> SocketConfig {
         match self {
-            Self::UDP { addr } => SocketConfig::UDP { addr },
-            Self::TCP { addr } => SocketConfig::TCP { addr },
-            Self::TCPUDP { addr } => SocketConfig::TCPUDP { addr },
+            Self::UDP { addr, v6_only } => SocketConfig::UDP { addr, v6_only },
+            Self::TCP { addr, v6_only } => SocketConfig::TCP { addr, v6_only },
+            Self::TCPUDP { addr, v6_only } => SocketConfig::TCPUDP { addr, v6_only },
         }
     }
+
+    /// The default value for `v6_only`.
+    ///
+    /// IPv6 sockets are IPv6-only unless requested otherwise, so that `[::]`
+    /// and `0.0.0.0` can be listed side by side regardless of OS defaults.
+    fn v6_only_default() -> bool {
+        true
+    }
 }
 
 //----------- ParseIdentityError -----------------------------------------------
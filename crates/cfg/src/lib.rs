@@ -8,6 +8,7 @@ use std::{
     fmt,
     hash::{Hash, Hasher},
     net::SocketAddr,
+    num::NonZeroUsize,
 };
 
 use camino::Utf8Path;
@@ -109,15 +110,15 @@ impl Config {
         // Based on the combined data, find the config file.
         let path = this.daemon.config_file.value();
 
-        // Load the config file and integrate its data.
-        let spec = match file::Spec::load(path) {
+        // Load the config file and any drop-in files, and integrate their
+        // data.
+        let spec = match this.daemon.config_dir.value() {
+            Some(dir) => file::Spec::load_with_drop_ins(path, dir),
+            None => file::Spec::load(path).map_err(|error| (path.clone(), error)),
+        };
+        let spec = match spec {
             Ok(spec) => spec,
-            Err(error) => {
-                return Err(ConfigError::File {
-                    path: path.clone(),
-                    error,
-                });
-            }
+            Err((path, error)) => return Err(ConfigError::File { path, error }),
         };
         spec.parse_into(&mut this);
 
@@ -135,12 +136,22 @@ pub struct RemoteControlConfig {
     /// To support systems where it is not possible to bind simultaneously to
     /// both IPv4 and IPv6 more than one address can be provided if needed.
     pub servers: Vec<SocketAddr>,
+
+    /// A Unix domain socket to serve our HTTP API from, if any.
+    pub unix_socket: Option<Box<Utf8Path>>,
+
+    /// Whether to serve the debugging endpoints of the HTTP API.
+    ///
+    /// These expose internal state, e.g. the raw key set state of a zone.
+    pub debug_endpoints: bool,
 }
 
 impl Default for RemoteControlConfig {
     fn default() -> Self {
         Self {
             servers: vec![SocketAddr::from(([127, 0, 0, 1], 4539))],
+            unix_socket: None,
+            debug_endpoints: false,
         }
     }
 }
@@ -159,6 +170,12 @@ pub struct DaemonConfig {
     /// The location of the configuration file.
     pub config_file: Setting<Box<Utf8Path>>,
 
+    /// The directory of drop-in configuration files, if any.
+    ///
+    /// The `*.toml` files in this directory are merged over the configuration
+    /// file, in the lexicographic order of their names.
+    pub config_dir: Setting<Option<Box<Utf8Path>>>,
+
     /// Whether Cascade should fork on startup.
     pub daemonize: Setting<bool>,
 
@@ -170,6 +187,14 @@ pub struct DaemonConfig {
 
     /// The identity to assume after startup.
     pub identity: Option<(UserId, GroupId)>,
+
+    /// Whether to set `SO_REUSEPORT` on the sockets Cascade binds itself.
+    pub reuse_port: bool,
+
+    /// How many zone state files to read in parallel on startup.
+    ///
+    /// If `None`, the available parallelism of the system is used.
+    pub startup_parallelism: Option<NonZeroUsize>,
 }
 
 impl Default for DaemonConfig {
@@ -178,10 +203,13 @@ impl Default for DaemonConfig {
             state_file: Setting::new("/var/lib/cascade/state.db".into()),
             logging: LoggingConfig::default(),
             config_file: Setting::new("/etc/cascade/config.toml".into()),
+            config_dir: Setting::new(None),
             daemonize: Setting::new(false),
             pid_file: None,
             chroot: None,
             identity: None,
+            reuse_port: false,
+            startup_parallelism: None,
         }
     }
 }
@@ -260,6 +288,12 @@ impl std::fmt::Display for GroupId {
 pub struct LoaderConfig {
     /// Configuration for reviewing loaded zones.
     pub review: ReviewConfig,
+
+    /// How many times in a row loading a zone may fail.
+    ///
+    /// When exceeded, automatic refreshes of the zone are disabled.  If
+    /// [`None`], failed loads are retried indefinitely.
+    pub max_failures: Option<u32>,
 }
 
 //----------- SignerConfig -----------------------------------------------------
@@ -269,6 +303,45 @@ pub struct LoaderConfig {
 pub struct SignerConfig {
     /// Configuration for reviewing signed zones.
     pub review: ReviewConfig,
+
+    /// How many times in a row signing a zone may fail.
+    ///
+    /// When exceeded, transient failures are no longer retried and the zone
+    /// stays halted.  If [`None`], transient failures are retried
+    /// indefinitely.
+    pub max_failures: Option<u32>,
+
+    /// How long before signatures expire to warn about them, in seconds.
+    ///
+    /// If [`None`], no warnings are emitted.
+    pub expiry_warning: Option<u32>,
+
+    /// How many zones may be signed concurrently.
+    ///
+    /// If [`None`], one zone is signed at a time.
+    pub max_concurrent_operations: Option<NonZeroUsize>,
+
+    /// How much memory concurrent signing operations may use, in bytes.
+    ///
+    /// Signing of further zones is deferred while the estimated memory used
+    /// by the zones being signed would exceed this.  If [`None`], only
+    /// [`Self::max_concurrent_operations`] limits signing.
+    pub memory_budget: Option<usize>,
+
+    /// How many records to sign in a single batch.
+    ///
+    /// When a zone is signed on multiple threads, its records are split into
+    /// batches of roughly this size, which are handed out to the threads as
+    /// they become available.  If [`None`], the records are split into one
+    /// batch per thread.
+    pub batch_size: Option<NonZeroUsize>,
+
+    /// The directory to record signing statistics in.
+    ///
+    /// After every successful signing operation, a line of JSON describing it
+    /// is appended to a per-zone file in this directory.  If [`None`], no
+    /// statistics are recorded.
+    pub statistics_dir: Option<Box<Utf8Path>>,
 }
 
 //----------- ReviewConfig -----------------------------------------------------
@@ -289,10 +362,59 @@ pub struct KeyManagerConfig {}
 //----------- ServerConfig -----------------------------------------------------
 
 /// Configuration for the zone server.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ServerConfig {
     /// Where to serve zones.
     pub servers: Vec<SocketConfig>,
+
+    /// The maximum size of responses over UDP, in bytes.
+    ///
+    /// Larger responses are truncated, signalling the client to retry over
+    /// TCP.  If [`None`], the EDNS buffer size recommended by DNS Flag Day
+    /// 2020 (1232 bytes) is used.
+    pub udp_payload_size: Option<u16>,
+
+    /// Whether to support DNS Cookies (RFC 7873).
+    pub cookies: bool,
+
+    /// Response rate limiting, if enabled.
+    pub rrl: Option<RrlConfig>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            udp_payload_size: None,
+            cookies: true,
+            rrl: None,
+        }
+    }
+}
+
+//----------- RrlConfig --------------------------------------------------------
+
+/// Configuration for response rate limiting.
+///
+/// Responses over UDP are limited per client network, to prevent the zone
+/// server from being abused for reflection and amplification attacks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RrlConfig {
+    /// How many responses per second to send to a client network.
+    pub responses_per_second: u32,
+
+    /// How often to send a truncated response instead of dropping one.
+    ///
+    /// Every `slip`-th response over the limit is sent back truncated, so that
+    /// legitimate clients retry over TCP.  If zero, all such responses are
+    /// dropped.
+    pub slip: u32,
+
+    /// The prefix length of the IPv4 client networks.
+    pub ipv4_prefix_len: u8,
+
+    /// The prefix length of the IPv6 client networks.
+    pub ipv6_prefix_len: u8,
 }
 
 //----------- RuntimeConfig ----------------------------------------------------
@@ -316,18 +438,36 @@ pub enum SocketConfig {
     UDP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        ///
+        /// If false, an unspecified IPv6 address (`[::]`) also accepts IPv4
+        /// traffic (as IPv4-mapped IPv6 addresses).  Ignored for IPv4.
+        v6_only: bool,
     },
 
     /// Listen exclusively over TCP.
     TCP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        ///
+        /// If false, an unspecified IPv6 address (`[::]`) also accepts IPv4
+        /// traffic (as IPv4-mapped IPv6 addresses).  Ignored for IPv4.
+        v6_only: bool,
     },
 
     /// Listen over both TCP and UDP.
     TCPUDP {
         /// The socket address to listen on.
         addr: SocketAddr,
+
+        /// Whether an IPv6 socket only accepts IPv6 traffic.
+        ///
+        /// If false, an unspecified IPv6 address (`[::]`) also accepts IPv4
+        /// traffic (as IPv4-mapped IPv6 addresses).  Ignored for IPv4.
+        v6_only: bool,
     },
     //
     // TODO: TLS
@@ -336,9 +476,17 @@ pub enum SocketConfig {
 impl SocketConfig {
     pub fn addr(&self) -> SocketAddr {
         match self {
-            SocketConfig::UDP { addr } => *addr,
-            SocketConfig::TCP { addr } => *addr,
-            SocketConfig::TCPUDP { addr } => *addr,
+            SocketConfig::UDP { addr, .. } => *addr,
+            SocketConfig::TCP { addr, .. } => *addr,
+            SocketConfig::TCPUDP { addr, .. } => *addr,
+        }
+    }
+
+    pub fn v6_only(&self) -> bool {
+        match self {
+            SocketConfig::UDP { v6_only, .. } => *v6_only,
+            SocketConfig::TCP { v6_only, .. } => *v6_only,
+            SocketConfig::TCPUDP { v6_only, .. } => *v6_only,
         }
     }
 }
@@ -516,7 +664,7 @@ pub enum ConfigError {
 
     /// An error occurred regarding the configuration file.
     File {
-        /// The location of the config file.
+        /// The location of the config file (or drop-in file).
         path: Box<Utf8Path>,
 
         /// The error that occurred.
@@ -1,12 +1,15 @@
 use std::fmt;
 use std::net::SocketAddr;
+use std::time::Duration;
 
+use camino::Utf8PathBuf;
 use clap::Parser;
 use clap::builder::PossibleValue;
 use tracing::level_filters::LevelFilter;
 
 use super::client::CascadeApiClient;
 use super::commands::Command;
+use super::commands::hsm::parse_duration;
 
 #[derive(Clone, Debug, Parser)]
 #[command(
@@ -39,6 +42,32 @@ pub struct Args {
     )]
     pub server: SocketAddr,
 
+    /// The Unix socket of the cascade server instance to connect to
+    ///
+    /// If set, this takes precedence over '--server'.
+    #[arg(
+        long = "socket",
+        value_name = "PATH",
+        env = "CASCADE_SOCKET",
+        global = true
+    )]
+    pub socket: Option<Utf8PathBuf>,
+
+    /// Keep retrying for this long if the server cannot be reached
+    ///
+    /// This is useful in scripts, e.g. to ride out a restart of the server.
+    #[arg(long = "wait", value_name = "DURATION", value_parser = parse_duration, global = true)]
+    pub wait: Option<Duration>,
+
+    /// Who to attribute changes to in the zone history
+    #[arg(
+        long = "actor",
+        value_name = "NAME",
+        env = "CASCADE_ACTOR",
+        global = true
+    )]
+    pub actor: Option<String>,
+
     /// The minimum severity of messages to log
     #[arg(
         long = "log-level",
@@ -54,7 +83,12 @@ pub struct Args {
 
 impl Args {
     pub async fn execute(self) -> Result<(), String> {
-        let client = CascadeApiClient::new(format!("http://{}", self.server));
+        let client = match self.socket {
+            Some(path) => CascadeApiClient::with_unix_socket(path),
+            None => CascadeApiClient::new(format!("http://{}", self.server)),
+        }
+        .with_wait(self.wait)
+        .with_actor(self.actor);
         self.command.execute(client).await
     }
 }
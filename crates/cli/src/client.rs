@@ -1,40 +1,83 @@
 use std::error::Error;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use reqwest::{IntoUrl, Method, RequestBuilder};
+use camino::Utf8PathBuf;
+use reqwest::{IntoUrl, Method, RequestBuilder, Response};
 use tracing::{debug, warn};
 use url::Url;
 
+use crate::api::ACTOR_HEADER;
 use crate::api::dep::serde::Serialize;
 use crate::api::dep::serde::de::DeserializeOwned;
 
 const HTTP_CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 #[derive(Clone)]
 pub struct CascadeApiClient {
     base_uri: Url,
+    unix_socket: Option<Utf8PathBuf>,
+    wait: Option<Duration>,
+    actor: Option<String>,
 }
 
 impl CascadeApiClient {
     pub fn new(base_uri: impl IntoUrl) -> Self {
         CascadeApiClient {
             base_uri: base_uri.into_url().unwrap(),
+            unix_socket: None,
+            wait: None,
+            actor: None,
         }
     }
 
+    /// Connect to the daemon over a Unix domain socket.
+    pub fn with_unix_socket(path: Utf8PathBuf) -> Self {
+        CascadeApiClient {
+            // The host is ignored, but a valid base URI is still needed.
+            base_uri: Url::parse("http://localhost/").unwrap(),
+            unix_socket: Some(path),
+            wait: None,
+            actor: None,
+        }
+    }
+
+    /// Keep retrying to connect to the daemon for up to `wait`.
+    ///
+    /// Only failures to connect are retried, so a request is never delivered
+    /// to the daemon more than once.
+    pub fn with_wait(self, wait: Option<Duration>) -> Self {
+        Self { wait, ..self }
+    }
+
+    /// Attribute the changes made by requests to `actor`.
+    pub fn with_actor(self, actor: Option<String>) -> Self {
+        Self { actor, ..self }
+    }
+
     pub fn request(&self, method: Method, s: &str) -> RequestBuilder {
         let path = self.base_uri.join(s).unwrap();
 
-        let client = reqwest::ClientBuilder::new()
+        let mut builder = reqwest::ClientBuilder::new()
             .user_agent(APP_USER_AGENT)
-            .timeout(HTTP_CLIENT_TIMEOUT)
-            .build()
-            .unwrap();
+            .timeout(HTTP_CLIENT_TIMEOUT);
+        if let Some(socket) = &self.unix_socket {
+            builder = builder.unix_socket(socket.clone().into_std_path_buf());
+        }
+        let client = builder.build().unwrap();
 
-        debug!("Sending HTTP {method} request to '{path}'");
+        match &self.unix_socket {
+            Some(socket) => debug!("Sending HTTP {method} request to '{path}' via '{socket}'"),
+            None => debug!("Sending HTTP {method} request to '{path}'"),
+        }
 
-        client.request(method, path)
+        let request = client.request(method, path);
+        match &self.actor {
+            Some(actor) => request.header(ACTOR_HEADER, actor),
+            None => request,
+        }
     }
 
     #[expect(dead_code)]
@@ -53,7 +96,8 @@ impl CascadeApiClient {
         T: DeserializeOwned,
         P: Serialize,
     {
-        send_format_decode(self.request(Method::GET, s).json(payload)).await
+        self.send_format_decode(self.request(Method::GET, s).json(payload))
+            .await
     }
 
     pub async fn post_json_with<T, P>(&self, s: &str, payload: &P) -> Result<T, String>
@@ -61,36 +105,65 @@ impl CascadeApiClient {
         T: DeserializeOwned,
         P: Serialize,
     {
-        send_format_decode(self.request(Method::POST, s).json(payload)).await
+        self.send_format_decode(self.request(Method::POST, s).json(payload))
+            .await
     }
 
     pub async fn get_json<T>(&self, s: &str) -> Result<T, String>
     where
         T: DeserializeOwned,
     {
-        send_format_decode(self.request(Method::GET, s)).await
+        self.send_format_decode(self.request(Method::GET, s)).await
     }
 
     pub async fn post_json<T>(&self, s: &str) -> Result<T, String>
     where
         T: DeserializeOwned,
     {
-        send_format_decode(self.request(Method::POST, s)).await
+        self.send_format_decode(self.request(Method::POST, s)).await
     }
-}
 
-pub async fn send_format_decode<T>(req: RequestBuilder) -> Result<T, String>
-where
-    T: DeserializeOwned,
-{
-    req.send()
-        .await
-        .map_err(format_http_error)? // Format connection errors
-        .error_for_status()
-        .map_err(format_http_error)? // Format status code errors
-        .json()
-        .await
-        .map_err(format_http_error) // Format decoding errors
+    async fn send_format_decode<T>(&self, req: RequestBuilder) -> Result<T, String>
+    where
+        T: DeserializeOwned,
+    {
+        self.send(req)
+            .await
+            .map_err(format_http_error)? // Format connection errors
+            .error_for_status()
+            .map_err(format_http_error)? // Format status code errors
+            .json()
+            .await
+            .map_err(format_http_error) // Format decoding errors
+    }
+
+    /// Send a request, retrying with backoff while the daemon is unreachable.
+    async fn send(&self, req: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let Some(wait) = self.wait else {
+            return req.send().await;
+        };
+
+        let deadline = Instant::now() + wait;
+        let mut delay = RETRY_INITIAL_DELAY;
+        loop {
+            // Request bodies are always JSON values, never streams, so they
+            // can always be cloned.
+            let attempt = req.try_clone().expect("request bodies are cloneable");
+            match attempt.send().await {
+                Err(err) if err.is_connect() => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(err);
+                    }
+                    let delay_now = delay.min(deadline - now);
+                    debug!("Daemon is not reachable; retrying in {delay_now:?}");
+                    tokio::time::sleep(delay_now).await;
+                    delay = (delay * 2).min(RETRY_MAX_DELAY);
+                }
+                res => return res,
+            }
+        }
+    }
 }
 
 /// Format HTTP errors with message based on error type, and chain error
@@ -0,0 +1,21 @@
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::args::Args;
+use crate::client::CascadeApiClient;
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Completions {
+    /// The shell to generate completions for
+    #[arg(value_name = "SHELL")]
+    shell: Shell,
+}
+
+impl Completions {
+    pub async fn execute(self, _client: CascadeApiClient) -> Result<(), String> {
+        let mut command = Args::command();
+        let name = command.get_name().to_string();
+        clap_complete::generate(self.shell, &mut command, name, &mut std::io::stdout());
+        Ok(())
+    }
+}
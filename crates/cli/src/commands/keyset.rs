@@ -1,6 +1,9 @@
-use crate::api::ZoneName;
+use camino::Utf8PathBuf;
+
 use crate::api::keyset as api;
+use crate::api::{KeyImport, KeyType, ZoneName};
 use crate::client::CascadeApiClient;
+use crate::commands::zone::{key_file_imports, kmip_imports};
 use crate::println;
 
 #[derive(Clone, Debug, clap::Args)]
@@ -52,12 +55,73 @@ enum KeySetCommand {
         key: String,
     },
 
+    /// Remove all retired keys from the key set.
+    PurgeRetired,
+
+    /// Pause or resume automatic key rolls for the zone.
+    Auto {
+        #[command(subcommand)]
+        action: AutoAction,
+    },
+
+    /// Import additional keys into the key set.
+    Import {
+        #[arg(long = "public-key")]
+        public_key: Vec<Utf8PathBuf>,
+
+        #[arg(long = "ksk-file")]
+        ksk_file: Vec<Utf8PathBuf>,
+
+        #[arg(long = "zsk-file")]
+        zsk_file: Vec<Utf8PathBuf>,
+
+        #[arg(long = "csk-file")]
+        csk_file: Vec<Utf8PathBuf>,
+
+        #[arg(long = "ksk-kmip", value_names = ["server", "public_id", "private_id", "algorithm", "flags"])]
+        ksk_kmip: Vec<String>,
+
+        #[arg(long = "zsk-kmip", value_names = ["server", "public_id", "private_id", "algorithm", "flags"])]
+        zsk_kmip: Vec<String>,
+
+        #[arg(long = "csk-kmip", value_names = ["server", "public_id", "private_id", "algorithm", "flags"])]
+        csk_kmip: Vec<String>,
+    },
+
+    /// Move the keys of the zone to an HSM, using an algorithm roll.
+    MigrateKeys {
+        /// The ID of the HSM (KMIP server) to move the keys to.
+        #[arg(long = "to-hsm", value_name = "SERVER_ID")]
+        to_hsm: String,
+    },
+
+    /// Check that the key set state and the key files agree.
+    Verify,
+
     /// Get the zones key(s).
     Get {
         /// Which key RRset to print.
         #[arg(ignore_case = true)]
         rr: KeyGetType,
     },
+
+    /// Show the published DNSKEY RRset and its signatures.
+    Dnskey,
+
+    /// Show which publication nameservers serve the published DNSKEY RRset.
+    Propagation,
+
+    /// Print the raw key set state of the zone, for debugging.
+    #[command(name = "dump-state", hide = true)]
+    DumpState,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+enum AutoAction {
+    /// Stop taking automatic key roll steps, overriding the policy.
+    Pause,
+    /// Take automatic key roll steps as configured in the policy again.
+    Resume,
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -142,8 +206,42 @@ impl KeySet {
                 force,
                 continue_flag,
             } => remove_key_command(&client, self.zone, key, force, continue_flag).await,
+            KeySetCommand::PurgeRetired => purge_retired_command(&client, self.zone).await,
+            KeySetCommand::Auto { action } => auto_command(&client, self.zone, action).await,
+
+            KeySetCommand::Import {
+                public_key,
+                ksk_file,
+                zsk_file,
+                csk_file,
+                ksk_kmip,
+                zsk_kmip,
+                csk_kmip,
+            } => {
+                let key_imports = public_key
+                    .into_iter()
+                    .map(KeyImport::PublicKey)
+                    .chain(key_file_imports(ksk_file, KeyType::Ksk)?)
+                    .chain(key_file_imports(csk_file, KeyType::Csk)?)
+                    .chain(key_file_imports(zsk_file, KeyType::Zsk)?)
+                    .chain(kmip_imports(KeyType::Ksk, &ksk_kmip))
+                    .chain(kmip_imports(KeyType::Csk, &csk_kmip))
+                    .chain(kmip_imports(KeyType::Zsk, &zsk_kmip))
+                    .collect();
+                import_command(&client, self.zone, key_imports).await
+            }
+
+            KeySetCommand::MigrateKeys { to_hsm } => {
+                migrate_keys_command(&client, self.zone, to_hsm).await
+            }
+
+            KeySetCommand::Verify => verify_command(&client, self.zone).await,
 
             KeySetCommand::Get { rr } => get_key_command(&client, self.zone, rr).await,
+
+            KeySetCommand::Dnskey => dnskey_command(&client, self.zone).await,
+            KeySetCommand::Propagation => propagation_command(&client, self.zone).await,
+            KeySetCommand::DumpState => dump_state_command(&client, self.zone).await,
         }?;
         Ok(())
     }
@@ -183,6 +281,103 @@ right now, you can use:
     }
 }
 
+async fn dump_state_command(client: &CascadeApiClient, zone: ZoneName) -> Result<(), String> {
+    let res: Result<api::KeySetStateDump, String> =
+        client.get_json(&format!("key/{zone}/state")).await?;
+    let res = res.map_err(|err| format!("Failed to get the key set state of {zone}: {err}"))?;
+
+    // The path goes to stderr, so that stdout can be fed to e.g. `jq`.
+    #[allow(clippy::disallowed_macros, reason = "we're not printing in color")]
+    eprintln!("; from {}", res.path);
+    println!("{}", res.state);
+    Ok(())
+}
+
+async fn propagation_command(client: &CascadeApiClient, zone: ZoneName) -> Result<(), String> {
+    let res: Result<api::KeyPropagationResult, String> =
+        client.get_json(&format!("key/{zone}/propagation")).await?;
+    let res =
+        res.map_err(|err| format!("Failed to check the DNSKEY propagation of {zone}: {err}"))?;
+
+    let tags = |tags: &[u16]| {
+        tags.iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    println!(
+        "DNSKEY RRset of {zone} published at serial {}: key tags {}",
+        res.serial,
+        tags(&res.expected)
+    );
+    let mut propagated = 0;
+    for ns in &res.nameservers {
+        let nameserver = match ns.addr {
+            Some(addr) if addr.to_string() != ns.nameserver => {
+                format!("{} ({addr})", ns.nameserver)
+            }
+            _ => ns.nameserver.clone(),
+        };
+        match &ns.outcome {
+            api::PropagationOutcome::Propagated => {
+                propagated += 1;
+                println!("- {nameserver}: propagated");
+            }
+            api::PropagationOutcome::Pending {
+                missing,
+                unexpected,
+            } => {
+                let mut details = Vec::new();
+                if !missing.is_empty() {
+                    details.push(format!("missing key tags {}", tags(missing)));
+                }
+                if !unexpected.is_empty() {
+                    details.push(format!("still serving key tags {}", tags(unexpected)));
+                }
+                println!("- {nameserver}: pending ({})", details.join("; "));
+            }
+            api::PropagationOutcome::Failed { error } => {
+                println!("- {nameserver}: failed: {error}");
+            }
+        }
+    }
+    println!(
+        "{propagated} of {} nameservers serve the published DNSKEY RRset",
+        res.nameservers.len()
+    );
+    Ok(())
+}
+
+async fn dnskey_command(client: &CascadeApiClient, zone: ZoneName) -> Result<(), String> {
+    let res: Result<api::KeyDnskeyResult, String> =
+        client.get_json(&format!("key/{zone}/dnskey")).await?;
+    let res = res.map_err(|err| format!("Failed to get the DNSKEY RRset of {zone}: {err}"))?;
+
+    // Print in zone file format, with the details as comments, so that the
+    // output can be fed to other tools.
+    println!(
+        "; DNSKEY RRset of {zone} published at serial {}",
+        res.serial
+    );
+    for key in &res.keys {
+        let mut kind = String::new();
+        if key.flags & 0x0001 != 0 {
+            kind.push_str(", SEP");
+        }
+        if key.flags & 0x0080 != 0 {
+            kind.push_str(", REVOKE");
+        }
+        println!("; key tag {}, flags {}{kind}", key.key_tag, key.flags);
+        println!("{}", key.record);
+    }
+    for sig in &res.signatures {
+        println!("; signed by key tag {}", sig.key_tag);
+        println!("{}", sig.record);
+    }
+    Ok(())
+}
+
 async fn roll_command(
     client: &CascadeApiClient,
     zone: ZoneName,
@@ -208,6 +403,128 @@ async fn roll_command(
     }
 }
 
+async fn import_command(
+    client: &CascadeApiClient,
+    zone: ZoneName,
+    key_imports: Vec<KeyImport>,
+) -> Result<(), String> {
+    if key_imports.is_empty() {
+        return Err("No keys to import were specified".into());
+    }
+
+    let count = key_imports.len();
+    let res: Result<(), String> = client
+        .post_json_with(
+            &format!("key/{zone}/import"),
+            &api::KeyImports { key_imports },
+        )
+        .await?;
+
+    match res {
+        Ok(_) => {
+            println!("Imported {count} key(s) into zone {zone}");
+            Ok(())
+        }
+        Err(err) => Err(format!("Failed to import keys into {zone}: {err}")),
+    }
+}
+
+async fn migrate_keys_command(
+    client: &CascadeApiClient,
+    zone: ZoneName,
+    to_hsm: String,
+) -> Result<(), String> {
+    let res: Result<(), String> = client
+        .post_json_with(
+            &format!("key/{zone}/migrate"),
+            &api::KeyMigrate {
+                to_hsm: to_hsm.clone(),
+            },
+        )
+        .await?;
+
+    match res {
+        Ok(_) => {
+            println!("Started moving the keys of zone {zone} to HSM '{to_hsm}'");
+            println!(
+                "New keys are generated on the HSM, and an algorithm roll replaces the old keys."
+            );
+            println!("Follow its progress with `cascade zone status --detailed {zone}`;");
+            println!(
+                "if the key roll is not automatic, advance it with `cascade keyset {zone} algorithm`."
+            );
+            Ok(())
+        }
+        Err(err) => Err(format!("Failed to move the keys of {zone}: {err}")),
+    }
+}
+
+async fn verify_command(client: &CascadeApiClient, zone: ZoneName) -> Result<(), String> {
+    let res: Result<api::KeyVerifyResult, String> =
+        client.get_json(&format!("key/{zone}/verify")).await?;
+    let res = res.map_err(|err| format!("Failed to verify keys of {zone}: {err}"))?;
+
+    for problem in &res.dangling {
+        println!("Unusable key {}: {}", problem.key, problem.error);
+    }
+    for orphan in &res.orphans {
+        println!("Unreferenced key file {orphan}");
+    }
+
+    if res.dangling.is_empty() && res.orphans.is_empty() {
+        println!("All {} key(s) of zone {zone} are usable", res.checked);
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} unusable key(s) and {} unreferenced key file(s) for zone {zone}",
+            res.dangling.len(),
+            res.orphans.len()
+        ))
+    }
+}
+
+async fn purge_retired_command(client: &CascadeApiClient, zone: ZoneName) -> Result<(), String> {
+    let res: Result<Vec<String>, String> = client
+        .post_json(&format!("key/{zone}/purge-retired"))
+        .await?;
+
+    match res {
+        Ok(removed) if removed.is_empty() => {
+            println!("Zone {zone} has no retired keys");
+            Ok(())
+        }
+        Ok(removed) => {
+            for key in removed {
+                println!("Removed retired key {key} from zone {zone}");
+            }
+            Ok(())
+        }
+        Err(err) => Err(format!("Failed to purge retired keys of {zone}: {err}")),
+    }
+}
+
+async fn auto_command(
+    client: &CascadeApiClient,
+    zone: ZoneName,
+    action: AutoAction,
+) -> Result<(), String> {
+    let (path, done) = match action {
+        AutoAction::Pause => ("pause", "Paused"),
+        AutoAction::Resume => ("resume", "Resumed"),
+    };
+    let res: Result<(), String> = client.post_json(&format!("key/{zone}/auto/{path}")).await?;
+
+    match res {
+        Ok(()) => {
+            println!("{done} automatic key rolls for zone {zone}");
+            Ok(())
+        }
+        Err(err) => Err(format!(
+            "Failed to {path} automatic key rolls of {zone}: {err}"
+        )),
+    }
+}
+
 async fn remove_key_command(
     client: &CascadeApiClient,
     zone: ZoneName,
@@ -1,9 +1,11 @@
 //! The commands of _cascade_.
 
+pub mod completions;
 pub mod debug;
 pub mod hsm;
 pub mod keyset;
 pub mod policy;
+pub mod review;
 pub mod status;
 pub mod template;
 pub mod tsig;
@@ -39,6 +41,10 @@ pub enum Command {
     #[command(name = "policy")]
     Policy(self::policy::Policy),
 
+    /// Work with pending zone reviews
+    #[command(name = "review")]
+    Review(self::review::Review),
+
     /// Execute manual key roll or key removal commands
     #[command(name = "keyset")]
     KeySet(self::keyset::KeySet),
@@ -63,6 +69,10 @@ pub enum Command {
     /// Print example config or policy files
     #[command(name = "template")]
     Template(self::template::Template),
+
+    /// Print shell completions
+    #[command(name = "completions", hide = true)]
+    Completions(self::completions::Completions),
 }
 
 impl Command {
@@ -87,10 +97,12 @@ impl Command {
             Self::Zone(zone) => zone.execute(client).await,
             Self::Status(status) => status.execute(client).await,
             Self::Policy(policy) => policy.execute(client).await,
+            Self::Review(review) => review.execute(client).await,
             Self::KeySet(keyset) => keyset.execute(client).await,
             Self::Hsm(hsm) => hsm.execute(client).await,
             Self::Tsig(tsig) => tsig.execute(client).await,
             Self::Template(template) => template.execute(client).await,
+            Self::Completions(completions) => completions.execute(client).await,
         }
     }
 }
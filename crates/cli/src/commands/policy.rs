@@ -2,15 +2,15 @@ use std::fmt::Display;
 
 use cascade_api::{
     AutoConfigPolicyInfo, KeyManagerPolicyInfo, LoaderPolicyInfo, ReviewPolicyMode,
-    ServerPolicyInfo, SignerPolicyInfo,
+    ServerPolicyInfo, SignerPolicyInfo, TransferLimitsPolicyInfo,
 };
 
 use crate::{
     ansi,
     api::{
-        NameserverCommsPolicyInfo, PolicyChange, PolicyChanges, PolicyInfo, PolicyInfoError,
-        PolicyListResult, PolicyReloadError, ReviewPolicyInfo, SignerDenialPolicyInfo,
-        SignerSerialPolicyInfo,
+        NameserverCommsPolicyInfo, PolicyChange, PolicyChanges, PolicyExportError,
+        PolicyExportOutput, PolicyInfo, PolicyInfoError, PolicyListResult, PolicyReload,
+        PolicyReloadError, ReviewPolicyInfo, SignerDenialPolicyInfo, SignerSerialPolicyInfo,
     },
     client::CascadeApiClient,
     eprintln, println,
@@ -32,9 +32,23 @@ pub enum PolicyCommand {
     #[command(name = "show")]
     Show { name: String },
 
-    /// Reload all the policies from the files
+    /// Print a policy as it would be written in a policy file
+    ///
+    /// This renders the policy Cascade is currently using, which may differ
+    /// from the policy file if it was modified but not reloaded.
+    #[command(name = "export")]
+    Export { name: String },
+
+    /// Reload the policies from the files
+    ///
+    /// Only files that were modified since they were last loaded are parsed,
+    /// unless `--full` is given.
     #[command(name = "reload")]
-    Reload,
+    Reload {
+        /// Parse all policy files, including unmodified ones
+        #[arg(long = "full")]
+        full: bool,
+    },
 }
 
 impl Policy {
@@ -64,9 +78,19 @@ impl Policy {
 
                 print_policy(&p);
             }
-            PolicyCommand::Reload => {
-                let res: Result<PolicyChanges, PolicyReloadError> =
-                    client.post_json("policy/reload").await?;
+            PolicyCommand::Export { name } => {
+                let res: Result<PolicyExportOutput, PolicyExportError> =
+                    client.get_json(&format!("policy/{name}/export")).await?;
+
+                match res {
+                    Ok(PolicyExportOutput { toml }) => println!("{}", toml.trim_end()),
+                    Err(err) => return Err(format!("Could not export policy '{name}': {err}")),
+                }
+            }
+            PolicyCommand::Reload { full } => {
+                let res: Result<PolicyChanges, PolicyReloadError> = client
+                    .post_json_with("policy/reload", &PolicyReload { full })
+                    .await?;
 
                 let res = match res {
                     Ok(res) => res,
@@ -142,9 +166,29 @@ fn or_none(x: &Option<impl Display>) -> String {
         .unwrap_or("<none>".into())
 }
 
-fn print_loader_policy(LoaderPolicyInfo { review }: &LoaderPolicyInfo) {
+fn print_loader_policy(
+    LoaderPolicyInfo {
+        review,
+        transfer,
+        soa_query_over_tcp,
+    }: &LoaderPolicyInfo,
+) {
     println!("  loader:");
     print_review(review);
+
+    let TransferLimitsPolicyInfo {
+        max_bytes,
+        max_records,
+        timeout,
+    } = transfer;
+    println!("    transfer:");
+    println!("      max bytes: {}", or_none(max_bytes));
+    println!("      max records: {}", or_none(max_records));
+    match timeout {
+        Some(timeout) => println!("      timeout: {}s", timeout.as_secs()),
+        None => println!("      timeout: <none>"),
+    }
+    println!("    SOA query over TCP: {soa_query_over_tcp}");
 }
 
 fn print_key_manager_policy(
@@ -169,6 +213,7 @@ fn print_key_manager_policy(
         default_ttl,
         auto_remove,
         auto_remove_delay,
+        validity_jitter,
         publication_nameservers,
     }: &KeyManagerPolicyInfo,
 ) {
@@ -185,6 +230,7 @@ fn print_key_manager_policy(
     } else {
         println!("    auto-remove: false",);
     }
+    println!("    validity jitter: {validity_jitter}s");
     println!("    algorithm: {algorithm}");
     print_auto_flags(auto_algorithm);
     if *use_csk {
@@ -252,6 +298,8 @@ fn print_signer_policy(
         signature_refresh_interval,
         key_roll_time,
         denial,
+        preserve_external_signatures,
+        refuse_dnssec_input,
     }: &SignerPolicyInfo,
 ) {
     let serial_policy = match serial_policy {
@@ -277,6 +325,8 @@ fn print_signer_policy(
     println!("    signature refresh interval: {signature_refresh_interval}s");
     println!("    key roll time: {key_roll_time}s");
     println!("    denial: {denial}");
+    println!("    preserve external signatures: {preserve_external_signatures}");
+    println!("    refuse DNSSEC input: {refuse_dnssec_input}");
     print_review(review);
 }
 
@@ -285,19 +335,42 @@ fn print_server_policy(
         outbound:
             cascade_api::OutboundPolicyInfo {
                 provide_xfr_to,
+                provide_review_xfr_to,
                 send_notify_to,
                 max_diffs,
                 max_diffs_size,
+                removal_grace_period,
+            },
+        staging:
+            cascade_api::StagingPolicyInfo {
+                send_notify_to: staging_send_notify_to,
+                soak_time,
             },
     }: &ServerPolicyInfo,
 ) {
     println!("  server:");
     println!("    outbound:");
     print_nameserver_comms_policy("provide XFR to", provide_xfr_to);
+    print_nameserver_comms_policy("provide review XFR to", provide_review_xfr_to);
     print_nameserver_comms_policy("send NOTIFY to", send_notify_to);
     println!(
         "      max diffs: {max_diffs} totaling less than {max_diffs_size}% of the published record count"
     );
+    match removal_grace_period {
+        Some(period) => println!("      removal grace period: {}s", period.as_secs()),
+        None => println!("      removal grace period: <none>"),
+    }
+    print!("    staging:");
+    if staging_send_notify_to.is_empty() {
+        println!(" off");
+        return;
+    }
+    println!();
+    print_nameserver_comms_policy("send NOTIFY to", staging_send_notify_to);
+    match soak_time {
+        Some(soak_time) => println!("      soak time: {}s", soak_time.as_secs()),
+        None => println!("      soak time: <none>"),
+    }
 }
 
 fn print_review(ReviewPolicyInfo { mode, on_reject }: &ReviewPolicyInfo) {
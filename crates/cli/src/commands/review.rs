@@ -0,0 +1,100 @@
+use std::time::SystemTime;
+
+use crate::api::*;
+use crate::client::CascadeApiClient;
+use crate::{eprintln, println};
+
+#[derive(Clone, Debug, clap::Args)]
+pub struct Review {
+    #[command(subcommand)]
+    command: ReviewCommand,
+}
+
+#[derive(Clone, Debug, clap::Subcommand)]
+pub enum ReviewCommand {
+    /// List the zone versions awaiting review
+    #[command(name = "list")]
+    List,
+}
+
+impl Review {
+    pub async fn execute(self, client: CascadeApiClient) -> Result<(), String> {
+        match self.command {
+            ReviewCommand::List => {
+                let response: PendingReviewsResult = client.get_json("review/pending").await?;
+
+                if response.reviews.is_empty() {
+                    eprintln!("No zones are awaiting review");
+                }
+
+                let now = jiff::Zoned::now().round(jiff::Unit::Second).unwrap();
+                for review in response.reviews {
+                    println!("{}", format_review(&review, &now));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Format a pending review as a line of the review list.
+fn format_review(review: &PendingReview, now: &jiff::Zoned) -> String {
+    let stage = match review.stage {
+        ZoneReviewStage::Unsigned => "unsigned",
+        ZoneReviewStage::Signed => "signed",
+    };
+    let mut line = format!(
+        "{}  {stage}  {}  (waiting for {})",
+        review.zone,
+        review.serial,
+        format_age(review.since, now)
+    );
+    if let Some(batch) = &review.batch {
+        line.push_str(&format!("  batch: {batch}"));
+    }
+    line
+}
+
+/// Format the time elapsed since `since`, e.g. `1h 5m 3s`.
+fn format_age(since: SystemTime, now: &jiff::Zoned) -> String {
+    let since = jiff::Timestamp::try_from(since).unwrap();
+    let span = since
+        .until(now.clone())
+        .unwrap()
+        .round(
+            jiff::SpanRound::new()
+                .relative(now)
+                .largest(jiff::Unit::Day)
+                .smallest(jiff::Unit::Second),
+        )
+        .unwrap();
+    format!("{span:#}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::{PendingReview, ZoneReviewStage, format_review};
+
+    #[test]
+    fn pending_reviews_show_their_age_and_batch() {
+        let now = jiff::Timestamp::from_second(1_800_000_000)
+            .unwrap()
+            .to_zoned(jiff::tz::TimeZone::UTC);
+        let mut review = PendingReview {
+            zone: "example.org".parse().unwrap(),
+            stage: ZoneReviewStage::Signed,
+            serial: 2026101701.into(),
+            since: SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000 - 3665),
+            batch: None,
+        };
+        assert_eq!(
+            format_review(&review, &now),
+            "example.org  signed  2026101701  (waiting for 1h 1m 5s)"
+        );
+
+        review.batch = Some("weekly".into());
+        assert!(format_review(&review, &now).ends_with("  batch: weekly"));
+    }
+}
@@ -1,8 +1,13 @@
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use crate::ansi;
-use crate::api::{KeyMsg, KeyStatusResult, KeysPerZone, ServerStatusResult, SigningStageReport};
+use crate::api::{
+    KeyMsg, KeyStatusResult, KeysPerZone, ResignScheduleResult, ServerStatusResult,
+    SigningStageReport,
+};
 use crate::client::CascadeApiClient;
+use crate::commands::zone::{format_size, to_rfc3339};
 use crate::println;
 
 #[derive(Clone, Debug, clap::Args)]
@@ -16,6 +21,10 @@ pub enum StatusCommand {
     /// Show status of DNSSEC keys
     #[command(name = "keys")]
     Keys,
+
+    /// Show when each zone is scheduled to be re-signed
+    #[command(name = "resign-schedule")]
+    ResignSchedule,
 }
 
 // From discussion in August 2025
@@ -58,6 +67,54 @@ impl Status {
                     }
                 }
             }
+            Some(StatusCommand::ResignSchedule) => {
+                let response: ResignScheduleResult =
+                    client.get_json("/status/resign-schedule").await?;
+
+                if response.zones.is_empty() {
+                    println!("No zones are signed");
+                }
+
+                let fmt_secs = |secs: u32| {
+                    format!(
+                        "{:#}",
+                        jiff::SignedDuration::from_secs(secs.into())
+                            .round(jiff::Unit::Second)
+                            .unwrap()
+                    )
+                };
+
+                for zone in &response.zones {
+                    println!(" - {}", zone.zone);
+                    match zone.scheduled {
+                        Some(scheduled) => println!("   Next re-sign: {}", to_rfc3339(scheduled)),
+                        None => println!("   Next re-sign: <not scheduled>"),
+                    }
+                    println!(
+                        "   Last refresh: {} (refreshed every {})",
+                        to_rfc3339(zone.last_refresh),
+                        fmt_secs(zone.refresh_interval)
+                    );
+                    match zone.min_expiration {
+                        Some(expiration) => println!(
+                            "   First signature expires: {} (replaced {} before)",
+                            to_rfc3339(expiration),
+                            fmt_secs(zone.sig_remain_time)
+                        ),
+                        None => println!("   First signature expires: <not yet known>"),
+                    }
+                    if let (Some(scheduled), Some(deadline)) = (zone.scheduled, zone.deadline())
+                        && scheduled > deadline
+                    {
+                        println!(
+                            "   {}WARNING: re-signing is scheduled after {}{}",
+                            ansi::YELLOW,
+                            to_rfc3339(deadline),
+                            ansi::RESET
+                        );
+                    }
+                }
+            }
             None => {
                 let response: ServerStatusResult = client.get_json("/status").await?;
 
@@ -90,6 +147,35 @@ impl Status {
                 }
 
                 println!("Signing queue:");
+                let stats = &response.signing_queue_stats;
+                let fmt_wait = |wait: Option<Duration>| match wait {
+                    Some(wait) => format!(
+                        "{:#}",
+                        jiff::SignedDuration::try_from(wait)
+                            .unwrap()
+                            .round(jiff::Unit::Second)
+                            .unwrap()
+                    ),
+                    None => "n/a".into(),
+                };
+                println!(
+                    "  Signing {} of at most {} zones, {} waiting",
+                    stats.active, stats.concurrency_limit, stats.pending
+                );
+                let memory_usage = format_size(stats.memory_usage, " ", "B");
+                match stats.memory_budget {
+                    Some(budget) => println!(
+                        "  Estimated memory in use: {memory_usage} of a {} budget",
+                        format_size(budget, " ", "B")
+                    ),
+                    None => println!("  Estimated memory in use: {memory_usage} (no budget)"),
+                }
+                println!(
+                    "  Wait times: oldest pending {}, average {}, max {}",
+                    fmt_wait(stats.oldest_wait),
+                    fmt_wait(stats.average_wait),
+                    fmt_wait(stats.max_wait)
+                );
                 if response.signing_queue.is_empty() {
                     println!("  The signing queue is currently empty.");
                 } else {
@@ -7,6 +7,7 @@ use camino::{Utf8Path, Utf8PathBuf};
 use crate::ansi;
 use crate::api::*;
 use crate::client::CascadeApiClient;
+use crate::commands::hsm::parse_duration;
 use crate::{eprintln, println};
 
 #[derive(Clone, Debug, clap::Args)]
@@ -54,6 +55,32 @@ pub enum ZoneCommand {
 
         #[arg(long = "import-csk-kmip", value_names = ["server", "public_id", "private_id", "algorithm", "flags"])]
         import_csk_kmip: Vec<String>,
+
+        /// Only check that the zone could be added, without adding it
+        #[arg(long = "dry-run")]
+        dry_run: bool,
+
+        /// Load and sign the zone, but don't publish it until `zone go-live`
+        #[arg(long = "observe")]
+        observe: bool,
+
+        /// Never sign the zone with a SOA serial below this one
+        #[arg(long = "serial-floor", value_name = "SERIAL")]
+        serial_floor: Option<u32>,
+
+        /// Load the zone from this zone file if the primary can't be reached
+        #[arg(long = "fallback-zonefile", value_name = "PATH")]
+        fallback_zonefile: Option<Utf8PathBuf>,
+
+        /// How long to wait for the primary before using the fallback zone file
+        #[arg(
+            long = "fallback-timeout",
+            value_name = "DURATION",
+            value_parser = parse_duration,
+            default_value = "30s",
+            requires = "fallback_zonefile"
+        )]
+        fallback_timeout: Duration,
     },
 
     /// Remove a zone
@@ -64,9 +91,25 @@ pub enum ZoneCommand {
     #[command(name = "list")]
     List,
 
-    /// Reload a zone
+    /// Reload one or more zones
     #[command(name = "reload")]
-    Reload { zone: ZoneName },
+    Reload {
+        /// The names of the zones.
+        #[arg(required = true)]
+        zones: Vec<ZoneName>,
+
+        /// Tag the reloaded versions with a review batch, so that they can be
+        /// approved together using `cascade zone approve --batch`.
+        #[arg(long = "batch", value_name = "ID")]
+        batch: Option<String>,
+    },
+
+    /// Roll a zone back to its previous version
+    #[command(name = "rollback")]
+    Rollback {
+        /// The name of the zone.
+        zone: ZoneName,
+    },
 
     /// Approve a zone being reviewed.
     #[command(name = "approve")]
@@ -75,11 +118,21 @@ pub enum ZoneCommand {
         #[command(flatten)]
         review_stage: ZoneReviewStage,
 
+        /// Approve all zones of this review batch that are under review.
+        #[arg(long = "batch", value_name = "ID", conflicts_with_all = ["name", "serial"])]
+        batch: Option<String>,
+
         /// The name of the zone.
-        name: ZoneName,
+        #[arg(required_unless_present = "batch")]
+        name: Option<ZoneName>,
 
         /// The serial number of the zone.
-        serial: u32,
+        #[arg(required_unless_present = "batch")]
+        serial: Option<u32>,
+
+        /// Why the zone is approved, to be recorded in its history.
+        #[arg(long = "reason", value_name = "TEXT")]
+        reason: Option<String>,
     },
 
     /// Override a previous rejection
@@ -100,6 +153,126 @@ pub enum ZoneCommand {
         zone: ZoneName,
     },
 
+    /// Resume a halted zone, if the step that failed now succeeds
+    #[command(name = "resume")]
+    Resume {
+        /// The name of the zone
+        zone: ZoneName,
+
+        /// Resume the zone without checking the step that failed
+        #[arg(long = "force")]
+        force: bool,
+    },
+
+    /// Override the denial-of-existence type of a zone and re-sign it
+    #[command(name = "set-denial")]
+    SetDenial {
+        /// The name of the zone
+        zone: ZoneName,
+
+        /// The type of denial of existence to use, or `policy` to remove the
+        /// override
+        denial: DenialType,
+
+        /// Enable NSEC3 Opt-Out
+        #[arg(long = "opt-out")]
+        opt_out: bool,
+    },
+
+    /// Set a human-readable description of a zone
+    #[command(name = "set-description")]
+    SetDescription {
+        /// The name of the zone
+        zone: ZoneName,
+
+        /// The description, or nothing to remove an existing one
+        description: Option<String>,
+    },
+
+    /// Set the lowest SOA serial the signer may use for a zone
+    #[command(name = "set-serial-floor")]
+    SetSerialFloor {
+        /// The name of the zone
+        zone: ZoneName,
+
+        /// The serial floor, or nothing to remove an existing one
+        serial_floor: Option<u32>,
+    },
+
+    /// Rotate the TSIG key used to load a zone from its primary
+    ///
+    /// While a rotation is in progress, transfers are attempted with the new
+    /// key first, falling back to the old key.
+    #[command(name = "rotate-tsig")]
+    RotateTsig {
+        /// The name of the zone
+        zone: ZoneName,
+
+        #[command(flatten)]
+        action: TsigRotationAction,
+    },
+
+    /// Promote the staged version of a zone to production
+    #[command(name = "promote")]
+    Promote {
+        /// The name of the zone
+        zone: ZoneName,
+    },
+
+    /// Re-sign a zone now, regenerating all of its signatures
+    ///
+    /// This does not wait for signatures to near expiration. The re-signed
+    /// zone gets a new serial and goes through review as usual. The signature
+    /// lifetime and inception offset can be overridden for this re-sign only;
+    /// later automatic re-signs use the values from the policy again.
+    #[command(name = "resign")]
+    Resign {
+        /// The name of the zone
+        zone: ZoneName,
+
+        /// Override the lifetime of the new signatures
+        #[arg(long = "sig-validity", value_name = "DURATION", value_parser = parse_duration)]
+        sig_validity: Option<Duration>,
+
+        /// Override how far the inception of the new signatures lies in the past
+        #[arg(long = "inception-offset", value_name = "DURATION", value_parser = parse_duration)]
+        inception_offset: Option<Duration>,
+    },
+
+    /// Start publishing an observe-only zone
+    #[command(name = "go-live")]
+    GoLive {
+        /// The name of the zone
+        zone: ZoneName,
+    },
+
+    /// Send a NOTIFY to each downstream name server and report the responses
+    ///
+    /// The NOTIFY announces the published version of the zone; the zone
+    /// itself is not changed.
+    #[command(name = "test-notify")]
+    TestNotify {
+        /// The name of the zone
+        zone: ZoneName,
+    },
+
+    /// Forcibly advance a zone through the pipeline, skipping reviews
+    ///
+    /// This is intended for testing only, and requires `--dangerous`.
+    #[command(name = "advance")]
+    Advance {
+        /// The name of the zone.
+        zone: ZoneName,
+
+        /// The stage to advance the zone to.
+        #[arg(long = "to")]
+        to: AdvanceStage,
+
+        /// Confirm that reviews should be bypassed.
+        #[arg(long = "dangerous")]
+        dangerous: bool,
+    },
+
     /// Reject a zone being reviewed.
     #[command(name = "reject")]
     Reject {
@@ -112,6 +285,24 @@ pub enum ZoneCommand {
 
         /// The serial number of the zone.
         serial: u32,
+
+        /// Why the zone is rejected, to be recorded in its history.
+        #[arg(long = "reason", value_name = "TEXT")]
+        reason: Option<String>,
+    },
+
+    /// Print the contents of a zone being reviewed
+    #[command(name = "fetch-review")]
+    FetchReview {
+        /// Whether to fetch an unsigned or signed version of the zone.
+        #[command(flatten)]
+        review_stage: ZoneReviewStage,
+
+        /// The name of the zone.
+        name: ZoneName,
+
+        /// The serial number of the zone.
+        serial: u32,
     },
 
     /// Get the status of a single zone
@@ -132,6 +323,30 @@ pub enum ZoneCommand {
         zone: ZoneName,
     },
 
+    /// Estimate the memory used by the data of one or all zones
+    #[command(name = "memory")]
+    Memory {
+        /// The zone to report on, or all zones if omitted.
+        zone: Option<ZoneName>,
+    },
+
+    /// Look up records in Cascade's own copy of a zone
+    #[command(name = "query")]
+    Query {
+        /// The name of the zone.
+        zone: ZoneName,
+
+        /// The owner name to look up.
+        name: ZoneName,
+
+        /// The record type to look up, or `ANY` for all types.
+        rtype: Rtype,
+
+        /// The version of the zone to look in.
+        #[arg(long = "stage", default_value = "published")]
+        stage: QueryStage,
+    },
+
     /// Resume a paused zone pipeline
     #[command(name = "maintenance")]
     Maintenance {
@@ -162,6 +377,78 @@ pub struct ZoneReviewStage {
     signed: bool,
 }
 
+/// What to do with the TSIG key rotation of a zone.
+#[derive(Clone, Debug, clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct TsigRotationAction {
+    /// Begin rotating to this TSIG key.
+    #[arg(long = "to", value_name = "TSIG_KEY_NAME")]
+    to: Option<String>,
+
+    /// Stop using the old TSIG key.
+    #[arg(long = "finish")]
+    finish: bool,
+
+    /// Stop using the new TSIG key.
+    #[arg(long = "abort")]
+    abort: bool,
+}
+
+/// The stage to forcibly advance a zone to.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum AdvanceStage {
+    /// Skip the unsigned review.
+    Signed,
+
+    /// Skip both the unsigned and signed reviews.
+    Published,
+}
+
+impl From<AdvanceStage> for ZoneAdvanceStage {
+    fn from(stage: AdvanceStage) -> Self {
+        match stage {
+            AdvanceStage::Signed => Self::Signed,
+            AdvanceStage::Published => Self::Published,
+        }
+    }
+}
+
+/// The version of a zone to query.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum QueryStage {
+    /// The unsigned version under review, else that of the published version.
+    Unsigned,
+
+    /// The signed version under review, else the published version.
+    Signed,
+
+    /// The published version.
+    Published,
+}
+
+impl From<QueryStage> for ZoneQueryStage {
+    fn from(stage: QueryStage) -> Self {
+        match stage {
+            QueryStage::Unsigned => Self::Unsigned,
+            QueryStage::Signed => Self::Signed,
+            QueryStage::Published => Self::Published,
+        }
+    }
+}
+
+/// The denial-of-existence type to use for a zone.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub enum DenialType {
+    /// Use the denial-of-existence type of the zone's policy.
+    Policy,
+
+    /// Use NSEC.
+    Nsec,
+
+    /// Use NSEC3.
+    Nsec3,
+}
+
 // From brainstorm in beginning of April 2025
 // - Command: reload a zone immediately
 // - Command: register a new zone
@@ -190,6 +477,11 @@ impl Zone {
                 import_ksk_kmip,
                 import_zsk_kmip,
                 import_csk_kmip,
+                dry_run,
+                observe,
+                serial_floor,
+                fallback_zonefile,
+                fallback_timeout,
             } => {
                 let import_public_key = import_public_key.into_iter().map(KeyImport::PublicKey);
                 let import_ksk_file = key_file_imports(import_ksk_file, KeyType::Ksk)?;
@@ -209,13 +501,20 @@ impl Zone {
                     .collect();
 
                 if let ZoneSource::Zonefile { path } = &mut source {
-                    let canonicalized_path = path.canonicalize().map_err(|err| {
-                        format!("Failed to canonicalize zonefile path '{}': {err}", path)
-                    })?;
-                    let path_str = canonicalized_path.to_str().ok_or_else(|| {
-                        format!("Failed to convert path '{}'", canonicalized_path.display())
-                    })?;
-                    *path = Utf8PathBuf::from(path_str).into_boxed_path();
+                    *path = canonicalize_zonefile_path(path)?;
+                }
+
+                let mut source: cascade_api::ZoneSource = source.try_into()?;
+                if let Some(path) = fallback_zonefile {
+                    let cascade_api::ZoneSource::Server { fallback, .. } = &mut source else {
+                        return Err(
+                            "A fallback zone file can only be used with a server as source".into(),
+                        );
+                    };
+                    *fallback = Some(ZonefileFallback {
+                        path: canonicalize_zonefile_path(&path)?,
+                        timeout: fallback_timeout,
+                    });
                 }
 
                 let res: Result<ZoneAddResult, ZoneAddError> = client
@@ -223,14 +522,24 @@ impl Zone {
                         "zone/add",
                         &ZoneAdd {
                             name,
-                            source: source.try_into()?,
+                            source,
                             policy,
                             key_imports,
+                            dry_run,
+                            observe_only: observe,
+                            serial_floor: serial_floor.map(Serial::from),
                         },
                     )
                     .await?;
 
                 match res {
+                    Ok(res) if dry_run => {
+                        println!("Zone {} can be added; doing so would:", res.name);
+                        for action in res.actions {
+                            println!("- {action}");
+                        }
+                        Ok(())
+                    }
                     Ok(res) => {
                         println!(
                             "Zone {} scheduled for loading, use 'cascade zone status {}' to see the status.",
@@ -261,20 +570,39 @@ impl Zone {
                 }
 
                 for zone_name in response.zones {
-                    println!("{}", zone_name);
+                    match response.descriptions.iter().find(|(n, _)| *n == zone_name) {
+                        Some((_, description)) => println!("{zone_name}  ({description})"),
+                        None => println!("{}", zone_name),
+                    }
                 }
                 Ok(())
             }
-            ZoneCommand::Reload { zone } => {
-                let url = format!("zone/{zone}/reload");
-                let res: Result<ZoneReloadResult, ZoneReloadError> = client.post_json(&url).await?;
+            ZoneCommand::Reload { zones, batch } => {
+                let reload = ZoneReload { batch };
+                for zone in zones {
+                    let url = format!("zone/{zone}/reload");
+                    let res: Result<ZoneReloadResult, ZoneReloadError> =
+                        client.post_json_with(&url, &reload).await?;
+
+                    match res {
+                        Ok(res) => {
+                            println!("Success: Sent zone reload command for {}", res.name);
+                        }
+                        Err(e) => return Err(format!("Failed to reload zone '{zone}': {e}")),
+                    }
+                }
+                Ok(())
+            }
+            ZoneCommand::Rollback { zone } => {
+                let url = format!("zone/{zone}/rollback");
+                let result: ZoneRollbackResult = client.post_json(&url).await?;
 
-                match res {
-                    Ok(res) => {
-                        println!("Success: Sent zone reload command for {}", res.name);
+                match result {
+                    Ok(ZoneRollbackOutput { name, serial }) => {
+                        println!("Rolling back zone '{name}' to loaded serial {serial}");
                         Ok(())
                     }
-                    Err(e) => Err(format!("Failed to reload zone: {e}")),
+                    Err(err) => Err(format!("Could not roll back zone '{zone}': {err}")),
                 }
             }
             ZoneCommand::Reset { zone } => {
@@ -289,6 +617,301 @@ impl Zone {
                     Err(err) => Err(format!("Could not reset zone '{zone}': {err}")),
                 }
             }
+            ZoneCommand::SetDenial {
+                zone,
+                denial,
+                opt_out,
+            } => {
+                let denial = match denial {
+                    DenialType::Nsec3 => ZoneDenial::Nsec3 { opt_out },
+                    _ if opt_out => {
+                        return Err("--opt-out can only be used with nsec3".into());
+                    }
+                    DenialType::Policy => ZoneDenial::Policy,
+                    DenialType::Nsec => ZoneDenial::Nsec,
+                };
+
+                let url = format!("zone/{zone}/set-denial");
+                let result: ZoneSetDenialResult = client
+                    .post_json_with(&url, &ZoneSetDenial { denial })
+                    .await?;
+
+                match result {
+                    Ok(ZoneSetDenialOutput {
+                        zone,
+                        changed: true,
+                    }) => {
+                        println!("Changed the denial type of zone '{zone}'; re-signing it");
+                        Ok(())
+                    }
+                    Ok(ZoneSetDenialOutput {
+                        zone,
+                        changed: false,
+                    }) => {
+                        println!("Zone '{zone}' already uses this denial type");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!(
+                        "Could not set the denial type of zone '{zone}': {err}"
+                    )),
+                }
+            }
+            ZoneCommand::SetDescription { zone, description } => {
+                let removed = description.is_none();
+                let url = format!("zone/{zone}/set-description");
+                let result: ZoneSetDescriptionResult = client
+                    .post_json_with(&url, &ZoneSetDescription { description })
+                    .await?;
+
+                match result {
+                    Ok(ZoneSetDescriptionOutput { zone }) if removed => {
+                        println!("Removed the description of zone '{zone}'");
+                        Ok(())
+                    }
+                    Ok(ZoneSetDescriptionOutput { zone }) => {
+                        println!("Set the description of zone '{zone}'");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!(
+                        "Could not set the description of zone '{zone}': {err}"
+                    )),
+                }
+            }
+            ZoneCommand::SetSerialFloor { zone, serial_floor } => {
+                let url = format!("zone/{zone}/set-serial-floor");
+                let result: ZoneSetSerialFloorResult = client
+                    .post_json_with(
+                        &url,
+                        &ZoneSetSerialFloor {
+                            serial_floor: serial_floor.map(Serial::from),
+                        },
+                    )
+                    .await?;
+
+                match result {
+                    Ok(ZoneSetSerialFloorOutput { zone }) => match serial_floor {
+                        Some(serial_floor) => {
+                            println!(
+                                "Set the serial floor of zone '{zone}' to {serial_floor}; it applies from the next signing"
+                            );
+                            Ok(())
+                        }
+                        None => {
+                            println!("Removed the serial floor of zone '{zone}'");
+                            Ok(())
+                        }
+                    },
+                    Err(err) => Err(format!(
+                        "Could not set the serial floor of zone '{zone}': {err}"
+                    )),
+                }
+            }
+            ZoneCommand::RotateTsig { zone, action } => {
+                let rotation = match action {
+                    TsigRotationAction { to: Some(key), .. } => ZoneRotateTsig::Start {
+                        key: TsigKeyName::from_str(&key).map_err(|err| {
+                            format!("TSIG key name '{key}' is not a valid domain name: {err}")
+                        })?,
+                    },
+                    TsigRotationAction { finish: true, .. } => ZoneRotateTsig::Finish,
+                    TsigRotationAction { .. } => ZoneRotateTsig::Abort,
+                };
+
+                let url = format!("zone/{zone}/rotate-tsig");
+                let result: ZoneRotateTsigResult = client.post_json_with(&url, &rotation).await?;
+
+                match result {
+                    Ok(ZoneRotateTsigOutput {
+                        zone,
+                        tsig_key,
+                        next_tsig_key,
+                    }) => {
+                        let key = |key: Option<TsigKeyName>| {
+                            key.map_or("no TSIG key".into(), |key| format!("TSIG key '{key}'"))
+                        };
+                        match next_tsig_key {
+                            Some(next_tsig_key) => println!(
+                                "Rotating zone '{zone}' from {} to TSIG key '{next_tsig_key}'; run 'cascade zone rotate-tsig {zone} --finish' once the primary uses the new key",
+                                key(tsig_key)
+                            ),
+                            None => println!("Zone '{zone}' now uses {}", key(tsig_key)),
+                        }
+                        Ok(())
+                    }
+                    Err(err) => Err(format!(
+                        "Could not rotate the TSIG key of zone '{zone}': {err}"
+                    )),
+                }
+            }
+            ZoneCommand::Promote { zone } => {
+                let url = format!("zone/{zone}/promote");
+                let result: ZonePromoteResult = client.post_json(&url).await?;
+
+                match result {
+                    Ok(ZonePromoteOutput { zone }) => {
+                        println!("Promoted the staged version of zone '{zone}'; publishing it");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not promote zone '{zone}': {err}")),
+                }
+            }
+            ZoneCommand::Resign {
+                zone,
+                sig_validity,
+                inception_offset,
+            } => {
+                let url = format!("zone/{zone}/resign");
+                let result: ZoneResignResult = client
+                    .post_json_with(
+                        &url,
+                        &ZoneResign {
+                            sig_validity,
+                            inception_offset,
+                        },
+                    )
+                    .await?;
+
+                match result {
+                    Ok(ZoneResignOutput { zone }) => {
+                        println!("Requested re-signing of zone '{zone}'");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not re-sign zone '{zone}': {err}")),
+                }
+            }
+            ZoneCommand::GoLive { zone } => {
+                let url = format!("zone/{zone}/go-live");
+                let result: ZoneGoLiveResult = client.post_json(&url).await?;
+
+                match result {
+                    Ok(ZoneGoLiveOutput {
+                        zone,
+                        released: true,
+                    }) => {
+                        println!("Zone '{zone}' is live; publishing its held signed version");
+                        Ok(())
+                    }
+                    Ok(ZoneGoLiveOutput {
+                        zone,
+                        released: false,
+                    }) => {
+                        println!(
+                            "Zone '{zone}' is live; its next signed version will be published"
+                        );
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not take zone '{zone}' live: {err}")),
+                }
+            }
+            ZoneCommand::TestNotify { zone } => {
+                let url = format!("zone/{zone}/test-notify");
+                let result: ZoneTestNotifyResult = client.post_json(&url).await?;
+
+                let ZoneTestNotifyOutput {
+                    zone,
+                    serial,
+                    targets,
+                } = result
+                    .map_err(|err| format!("Could not test NOTIFY for zone '{zone}': {err}"))?;
+
+                if targets.is_empty() {
+                    println!("No downstream name servers are configured for zone '{zone}'");
+                    return Ok(());
+                }
+
+                println!("Sent NOTIFY for zone '{zone}' with serial {serial}:");
+                let mut failures = 0;
+                for ZoneTestNotifyTarget {
+                    addr,
+                    tsig_key,
+                    outcome,
+                } in &targets
+                {
+                    let target = match tsig_key {
+                        Some(key) => format!("{addr} (TSIG key '{key}')"),
+                        None => addr.to_string(),
+                    };
+                    match outcome {
+                        NotifyOutcome::Response { rcode } if rcode == "NOERROR" => {
+                            println!("  {target}: {rcode}");
+                        }
+                        NotifyOutcome::Response { rcode } => {
+                            failures += 1;
+                            println!("  {target}: {}{rcode}{}", ansi::RED, ansi::RESET);
+                        }
+                        NotifyOutcome::Failed { error } => {
+                            failures += 1;
+                            println!(
+                                "  {target}: {}no response: {error}{}",
+                                ansi::RED,
+                                ansi::RESET
+                            );
+                        }
+                    }
+                }
+
+                if failures > 0 {
+                    return Err(format!(
+                        "{failures} of {} name servers did not accept the NOTIFY",
+                        targets.len()
+                    ));
+                }
+                Ok(())
+            }
+            ZoneCommand::Resume { zone, force } => {
+                let url = format!("zone/{zone}/resume");
+                let result: ZoneResumeResult =
+                    client.post_json_with(&url, &ZoneResume { force }).await?;
+
+                match result {
+                    Ok(ZoneResumeOutput { zone }) => {
+                        println!("Resumed zone '{zone}'");
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not resume zone '{zone}': {err}")),
+                }
+            }
+            ZoneCommand::Advance {
+                zone,
+                to,
+                dangerous,
+            } => {
+                if !dangerous {
+                    return Err(
+                        "Advancing a zone bypasses its reviews; pass --dangerous to confirm".into(),
+                    );
+                }
+
+                let url = format!("zone/{zone}/advance");
+                let result: ZoneAdvanceResult = client
+                    .post_json_with(
+                        &url,
+                        &ZoneAdvance {
+                            to: to.into(),
+                            dangerous,
+                        },
+                    )
+                    .await?;
+
+                match result {
+                    Ok(ZoneAdvanceOutput {
+                        zone,
+                        approved,
+                        pending,
+                    }) => {
+                        if approved {
+                            println!("Approved the pending review of zone '{zone}'");
+                        }
+                        if let Some(stage) = pending {
+                            println!(
+                                "Upcoming reviews of zone '{zone}' up to the {stage} stage will be skipped"
+                            );
+                        }
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not advance zone '{zone}': {err}")),
+                }
+            }
             ZoneCommand::Override { name, review_stage } => {
                 let stage = match review_stage {
                     ZoneReviewStage {
@@ -320,8 +943,10 @@ impl Zone {
             }
             ZoneCommand::Approve {
                 review_stage,
+                batch,
                 name,
                 serial,
+                reason,
             } => {
                 let stage = match review_stage {
                     ZoneReviewStage {
@@ -335,8 +960,29 @@ impl Zone {
                     _ => unreachable!(),
                 };
 
+                if let Some(batch) = batch {
+                    let url = format!("/batch/{batch}/{stage}/approve");
+                    let result: ZoneBatchReviewResult =
+                        client.post_json_with(&url, &ZoneReview { reason }).await?;
+
+                    return match result {
+                        Ok(ZoneBatchReviewOutput { reviewed }) => {
+                            report_batch_review(stage, &batch, reviewed)
+                        }
+                        Err(ZoneBatchReviewError::NoSuchBatch) => {
+                            Err(format!("No zones belong to batch '{batch}'"))
+                        }
+                    };
+                }
+
+                // Clap ensures these are present without '--batch'.
+                let (Some(name), Some(serial)) = (name, serial) else {
+                    unreachable!()
+                };
+
                 let url = format!("/zone/{name}/{stage}/{serial}/approve");
-                let result: ZoneReviewResult = client.post_json(&url).await?;
+                let result: ZoneReviewResult =
+                    client.post_json_with(&url, &ZoneReview { reason }).await?;
 
                 match result {
                     Ok(ZoneReviewOutput {}) => {
@@ -355,6 +1001,7 @@ impl Zone {
                 review_stage,
                 name,
                 serial,
+                reason,
             } => {
                 let stage = match review_stage {
                     ZoneReviewStage {
@@ -369,7 +1016,8 @@ impl Zone {
                 };
 
                 let url = format!("/zone/{name}/{stage}/{serial}/reject");
-                let result: ZoneReviewResult = client.post_json(&url).await?;
+                let result: ZoneReviewResult =
+                    client.post_json_with(&url, &ZoneReview { reason }).await?;
 
                 match result {
                     Ok(ZoneReviewOutput {}) => {
@@ -384,6 +1032,42 @@ impl Zone {
                     )),
                 }
             }
+            ZoneCommand::FetchReview {
+                review_stage,
+                name,
+                serial,
+            } => {
+                let stage = match review_stage {
+                    ZoneReviewStage {
+                        unsigned: true,
+                        signed: false,
+                    } => "unsigned",
+                    ZoneReviewStage {
+                        unsigned: false,
+                        signed: true,
+                    } => "signed",
+                    _ => unreachable!(),
+                };
+
+                let url = format!("/zone/{name}/{stage}/{serial}/zonefile");
+                let result: ZoneReviewZonefileResult = client.get_json(&url).await?;
+
+                match result {
+                    Ok(ZoneReviewZonefile { records, .. }) => {
+                        eprintln!("; {stage} version of zone '{name}' (serial {serial})");
+                        for record in records {
+                            println!("{record}");
+                        }
+                        Ok(())
+                    }
+                    Err(ZoneReviewError::NoSuchZone) => {
+                        Err(format!("Zone '{name}' could not be found"))
+                    }
+                    Err(ZoneReviewError::NotUnderReview) => Err(format!(
+                        "The {stage} zone '{name}' with serial number {serial} is not being reviewed right now"
+                    )),
+                }
+            }
             ZoneCommand::Status { zone, detailed } => {
                 let url = format!("zone/{}/status", zone);
                 let response: Result<ZoneStatus, ZoneStatusError> = client.get_json(&url).await?;
@@ -395,6 +1079,46 @@ impl Zone {
                     }
                 }
             }
+            ZoneCommand::Query {
+                zone,
+                name,
+                rtype,
+                stage,
+            } => {
+                let url = format!("zone/{zone}/query");
+                let query = ZoneQuery {
+                    name: name.clone(),
+                    rtype,
+                    stage: stage.into(),
+                };
+                let result: ZoneQueryResult = client.post_json_with(&url, &query).await?;
+
+                match result {
+                    Ok(ZoneQueryOutput {
+                        serial,
+                        under_review,
+                        records,
+                    }) => {
+                        let version = if under_review {
+                            "under review"
+                        } else {
+                            "published"
+                        };
+                        eprintln!(
+                            "; {} version of zone '{zone}' ({version}, serial {serial})",
+                            ZoneQueryStage::from(stage)
+                        );
+                        if records.is_empty() {
+                            eprintln!("; no {rtype} records found for '{name}'");
+                        }
+                        for record in records {
+                            println!("{record}");
+                        }
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not query zone '{zone}': {err}")),
+                }
+            }
             ZoneCommand::History { zone } => {
                 let url = format!("zone/{}/history", zone);
                 let response: Result<ZoneHistory, ZoneHistoryError> = client.get_json(&url).await?;
@@ -424,65 +1148,94 @@ impl Zone {
                                         "Signing succeeded (triggered by {})",
                                         match trigger {
                                             SigningTrigger::Load => "loading a new instance",
+                                            SigningTrigger::Resign(ResigningTrigger {
+                                                manual: true,
+                                                ..
+                                            }) => "an operator request",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: true,
                                                 sigs_need_refresh: false,
+                                                ..
                                             }) => "a change in signing keys",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: false,
                                                 sigs_need_refresh: true,
+                                                ..
                                             }) => "signatures needing refresh",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: true,
                                                 sigs_need_refresh: true,
+                                                ..
                                             }) =>
                                                 "a change in signing keys and signatures needing refresh",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: false,
                                                 sigs_need_refresh: false,
+                                                ..
                                             }) => "<unknown>",
                                         }
                                     )
                                 }
-                                HistoricalEvent::SigningFailed { trigger, reason } => {
+                                HistoricalEvent::SigningFailed {
+                                    trigger,
+                                    reason,
+                                    transient,
+                                } => {
                                     format!(
-                                        "Signing failed (triggered by {}): {reason}",
+                                        "Signing failed (triggered by {}): {reason}{}",
                                         match trigger {
                                             SigningTrigger::Load => "loading a new instance",
+                                            SigningTrigger::Resign(ResigningTrigger {
+                                                manual: true,
+                                                ..
+                                            }) => "an operator request",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: true,
                                                 sigs_need_refresh: false,
+                                                ..
                                             }) => "a change in signing keys",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: false,
                                                 sigs_need_refresh: true,
+                                                ..
                                             }) => "signatures nearing expiration",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: true,
                                                 sigs_need_refresh: true,
+                                                ..
                                             }) =>
                                                 "a change in signing keys and signatures nearing expiration",
                                             SigningTrigger::Resign(ResigningTrigger {
                                                 keys_changed: false,
                                                 sigs_need_refresh: false,
+                                                ..
                                             }) => "<unknown>",
-                                        }
+                                        },
+                                        if *transient { " (will be retried)" } else { "" }
                                     )
                                 }
-                                HistoricalEvent::UnsignedZoneReview { status, .. } => format!(
-                                    "Unsigned zone review {}",
+                                HistoricalEvent::UnsignedZoneReview { status, reason } => format!(
+                                    "Unsigned zone review {}{}",
                                     match status {
                                         ZoneReviewStatus::Pending => "pending",
                                         ZoneReviewStatus::Approved => "approved",
                                         ZoneReviewStatus::Rejected => "rejected",
+                                    },
+                                    match reason {
+                                        Some(reason) => format!(": {reason}"),
+                                        None => String::new(),
                                     }
                                 ),
-                                HistoricalEvent::SignedZoneReview { status, .. } => format!(
-                                    "Signed zone review {}",
+                                HistoricalEvent::SignedZoneReview { status, reason } => format!(
+                                    "Signed zone review {}{}",
                                     match status {
                                         ZoneReviewStatus::Pending => "pending",
                                         ZoneReviewStatus::Approved => "approved",
                                         ZoneReviewStatus::Rejected => "rejected",
+                                    },
+                                    match reason {
+                                        Some(reason) => format!(": {reason}"),
+                                        None => String::new(),
                                     }
                                 ),
                                 HistoricalEvent::UnsignedHookFailed { err, .. } => {
@@ -491,6 +1244,22 @@ impl Zone {
                                 HistoricalEvent::SignedHookFailed { err, .. } => {
                                     format!("Could not execute signed review hook: {err}",)
                                 }
+                                HistoricalEvent::Staged => {
+                                    "Signed zone published to staging".into()
+                                }
+                                HistoricalEvent::Promoted => {
+                                    "Staged zone promoted to production".into()
+                                }
+                                HistoricalEvent::Held => {
+                                    "Signed zone held back (observe-only)".into()
+                                }
+                                HistoricalEvent::WentLive => "Zone went live".into(),
+                                HistoricalEvent::ForcedAdvance { to } => {
+                                    format!("Zone forcibly advanced to {to}, bypassing reviews")
+                                }
+                                HistoricalEvent::Healed { reason } => {
+                                    format!("Zone healed on startup: {reason}")
+                                }
                                 HistoricalEvent::KeySetCommand {
                                     cmd,
                                     elapsed,
@@ -511,15 +1280,52 @@ impl Zone {
                                         elapsed.as_secs()
                                     )
                                 }
-                                HistoricalEvent::KeySetError { cmd, err, elapsed } => {
-                                    format!(
+                                HistoricalEvent::KeySetError {
+                                    cmd,
+                                    err,
+                                    stderr,
+                                    elapsed,
+                                } => {
+                                    let mut what = format!(
                                         "Keyset command '{cmd}' failed in {}s with error: {err}",
                                         elapsed.as_secs()
+                                    );
+                                    if let Some(stderr) = stderr {
+                                        what.push_str(&format!("\n  stderr: {stderr}"));
+                                    }
+                                    what
+                                }
+                                HistoricalEvent::KeyTagCollision { key_tag, keys } => {
+                                    format!(
+                                        "WARNING: keys {} share key tag {key_tag}",
+                                        keys.join(", ")
+                                    )
+                                }
+                                HistoricalEvent::DnssecInput {
+                                    records,
+                                    stripped: true,
+                                } => {
+                                    format!(
+                                        "WARNING: stripped {records} DNSSEC records from the loaded zone"
+                                    )
+                                }
+                                HistoricalEvent::DnssecInput {
+                                    records,
+                                    stripped: false,
+                                } => {
+                                    format!(
+                                        "Refused to sign: the loaded zone contains {records} DNSSEC records"
                                     )
                                 }
                                 HistoricalEvent::LoadingFailed { reason } => reason.clone(),
+                                HistoricalEvent::Halted { reason } => {
+                                    format!("Zone halted: {reason}")
+                                }
                             };
-                            println!("{when} {serial:10} {what}");
+                            match &history_item.actor {
+                                Some(actor) => println!("{when} {serial:10} {what} (by {actor})"),
+                                None => println!("{when} {serial:10} {what}"),
+                            }
                         }
                         Ok(())
                     }
@@ -528,6 +1334,29 @@ impl Zone {
                     }
                 }
             }
+            ZoneCommand::Memory { zone: Some(zone) } => {
+                let url = format!("zone/{zone}/memory");
+                let response: ZoneMemoryResult = client.get_json(&url).await?;
+
+                match response {
+                    Ok(report) => {
+                        print_memory_reports(&[report]);
+                        Ok(())
+                    }
+                    Err(err) => Err(format!("Could not report on zone '{zone}': {err}")),
+                }
+            }
+            ZoneCommand::Memory { zone: None } => {
+                let response: ZonesMemoryResult = client.get_json("zone/memory").await?;
+
+                if response.zones.is_empty() {
+                    eprintln!("No zones to show");
+                    return Ok(());
+                }
+
+                print_memory_reports(&response.zones);
+                Ok(())
+            }
             ZoneCommand::Maintenance { maintenance } => {
                 let (name, state) = match &maintenance {
                     Maintenance::Enable { zone } => (zone, "enable"),
@@ -598,6 +1427,22 @@ impl Zone {
         println!("zone:   {}", zone.name);
         println!("policy: {}", zone.policy);
         println!("source: {}", zone.source);
+        for (key, count) in &zone.tsig_failures {
+            println!(
+                "{}TSIG verification with key '{key}' failed {count} times{}",
+                ansi::YELLOW,
+                ansi::RESET
+            );
+        }
+        if let Some(description) = &zone.description {
+            println!("description: {description}");
+        }
+        if let Some(serial_floor) = zone.serial_floor {
+            println!("serial floor: {serial_floor}");
+        }
+        if let Some(reason) = &zone.halted_reason {
+            println!("{}halted: {reason}{}", ansi::RED, ansi::RESET);
+        }
 
         let loader_review = match &policy.loader.review.mode {
             ReviewPolicyMode::Off => "off",
@@ -615,6 +1460,9 @@ impl Zone {
         println!("review");
         println!("  loaded: {loader_review}");
         println!("  signed: {signer_review}");
+        if let Some(batch) = &zone.review_batch {
+            println!("  batch:  {batch}");
+        }
         println!("");
 
         println!("last published");
@@ -647,6 +1495,18 @@ impl Zone {
             println!("");
             println!("An error occurred during the last operation:");
             println!("  {}ERROR: {error}{}", ansi::RED, ansi::RESET);
+            if zone.load_failure_streak > 1 {
+                println!(
+                    "  Loading has failed {} times in a row.",
+                    zone.load_failure_streak
+                );
+            }
+            if zone.sign_failure_streak > 1 {
+                println!(
+                    "  Signing has failed {} times in a row.",
+                    zone.sign_failure_streak
+                );
+            }
             println!(
                 "  Run {}`cascade zone history {}`{} for more information.",
                 ansi::BLUE,
@@ -671,6 +1531,38 @@ impl Zone {
             );
         }
 
+        if zone.key_rolls_paused {
+            println!("");
+            println!(
+                "{}WARNING: Automatic key rolls are paused for this zone{}",
+                ansi::YELLOW,
+                ansi::RESET
+            );
+            println!("  Cascade will not automatically start or progress key rolls");
+            println!(
+                "  Run {}`cascade keyset {} auto resume`{} to resume automatic key rolls",
+                ansi::BLUE,
+                zone.name,
+                ansi::RESET
+            );
+        }
+
+        if zone.observe_only {
+            println!("");
+            println!(
+                "{}WARNING: This zone is observe-only{}",
+                ansi::YELLOW,
+                ansi::RESET
+            );
+            println!("  Cascade loads and signs the zone, but does not publish it");
+            println!(
+                "  Run {}`cascade zone go-live {}`{} to start publishing",
+                ansi::BLUE,
+                zone.name,
+                ansi::RESET
+            );
+        }
+
         if detailed {
             println!("");
             println!("DNSSEC keys:");
@@ -685,6 +1577,17 @@ impl Zone {
                 if key.signer {
                     println!("    Actively used for signing");
                 }
+                if key.retired {
+                    println!("    Retired, no longer used");
+                }
+            }
+            if let Some(wait) = zone.rollover_wait {
+                println!(
+                    "  Key roll cache expiry wait: {} (maximum TTL {}s, margin {}s; add the DS TTL of the parent if the DS records change)",
+                    format_duration(Duration::from_secs(wait.total().into())),
+                    wait.max_ttl,
+                    wait.margin
+                );
             }
             println!("  Details:");
             for line in zone.key_status.lines() {
@@ -703,6 +1606,7 @@ pub fn print_status(zone: &ZoneStatus, policy: &PolicyInfo) {
         Progress::Restoring => "restoring",
         Progress::Waiting => "idle",
         Progress::Loading => "loading",
+        Progress::LoadingFailed => "loading failed",
         Progress::LoadedReview => "waiting for loaded review",
         Progress::HaltLoaded => "halted after loaded review",
         Progress::Signing => "signing",
@@ -717,7 +1621,12 @@ pub fn print_status(zone: &ZoneStatus, policy: &PolicyInfo) {
         return;
     }
 
-    print_load_phase(current, zone.unsigned_serial, &zone.receipt_report);
+    print_load_phase(
+        &zone.name,
+        current,
+        zone.unsigned_serial,
+        &zone.receipt_report,
+    );
     print_loaded_review_phase(
         &zone.name,
         zone.unsigned_serial,
@@ -737,20 +1646,42 @@ pub fn print_status(zone: &ZoneStatus, policy: &PolicyInfo) {
         policy,
         current,
         &zone.signed_review_addr,
+        zone.staged_since,
+        zone.held_since,
     );
     print_publish_phase();
 }
 
 fn print_load_phase(
+    zone: &ZoneName,
     current: Progress,
     unsigned_serial: Option<Serial>,
     receipt_report: &Option<ZoneLoaderReport>,
 ) {
+    use ansi::{BLUE, RED, RESET};
+
     if current < Progress::Loading {
         println!("  {Pending} load");
+    } else if current == Progress::LoadingFailed {
+        println!("  {Error} load");
+        println!("  |   {RED}ERROR: loading failed too many times in a row{RESET}");
+        println!("  |   possible actions:");
+        println!("  |     {BLUE}cascade zone resume {zone}{RESET}");
+        println!("  |     {BLUE}cascade zone reset {zone}{RESET}");
+        println!("  |");
     } else if current > Progress::Loading {
         let unsigned_serial = serial_to_string(unsigned_serial);
-        println!("  {Done} load (serial: {unsigned_serial})");
+        let transfer = receipt_report
+            .as_ref()
+            .and_then(|r| Some((r.transfer?, r)))
+            .map_or("".into(), |(transfer, r)| {
+                format!(
+                    ", via {transfer}: {} records, {}",
+                    r.record_count,
+                    format_size(r.byte_count, " ", "B")
+                )
+            });
+        println!("  {Done} load (serial: {unsigned_serial}{transfer})");
     } else {
         let short_serial = if let Some(unsigned_serial) = unsigned_serial {
             format!(" (serial: {unsigned_serial})")
@@ -889,9 +1820,49 @@ fn print_signed_review_phase(
     policy: &PolicyInfo,
     current: Progress,
     addrs: &[SocketAddr],
+    staged_since: Option<SystemTime>,
+    held_since: Option<SystemTime>,
 ) {
     use ansi::{BLUE, DIM, RED, RESET, YELLOW};
 
+    if current == Progress::SignedReview
+        && let Some(held_since) = held_since
+    {
+        let serial = signed_serial.map_or_else(|| "<SERIAL>".into(), |s| s.to_string());
+        println!("  {Stopped} hold signed zone");
+        println!("  |   {YELLOW}zone is observe-only and not published{RESET}");
+        println!("  |   held at: {}", to_rfc3339_ago(Some(held_since), ""));
+        println!("  |   possible actions:");
+        println!("  |     {BLUE}cascade zone fetch-review --signed {zone} {serial}{RESET}");
+        println!("  |     {BLUE}cascade zone go-live {zone}{RESET}");
+        println!("  |     {BLUE}cascade zone reject --signed {zone} {serial}{RESET}");
+        println!("  |");
+        return;
+    }
+
+    if current == Progress::SignedReview
+        && let Some(staged_since) = staged_since
+    {
+        let serial = signed_serial.map_or_else(|| "<SERIAL>".into(), |s| s.to_string());
+        println!("  {Stopped} stage signed zone");
+        println!("  |   {YELLOW}zone is published to staging{RESET}");
+        println!(
+            "  |   staged at: {}",
+            to_rfc3339_ago(Some(staged_since), "")
+        );
+        if let Some(soak_time) = policy.server.staging.soak_time {
+            println!(
+                "  |   promoted automatically after {}s",
+                soak_time.as_secs()
+            );
+        }
+        println!("  |   possible actions:");
+        println!("  |     {BLUE}cascade zone promote {zone}{RESET}");
+        println!("  |     {BLUE}cascade zone reject --signed {zone} {serial}{RESET}");
+        println!("  |");
+        return;
+    }
+
     if let ReviewPolicyMode::Off = policy.signer.review.mode {
         println!("  {Pending} {DIM}review signed zone (disabled){RESET}");
         return;
@@ -967,11 +1938,92 @@ impl std::fmt::Display for Icon {
     }
 }
 
+/// Print a table of estimated memory usage, followed by a total
+fn print_memory_reports(reports: &[ZoneMemoryReport]) {
+    let size = |v| format_size(v, " ", "B");
+    let width = reports
+        .iter()
+        .map(|r| r.zone.to_string().len())
+        .chain(["(total)".len()])
+        .max()
+        .unwrap_or_default();
+
+    println!(
+        "{:width$}  {:>8}  {:>8}  {:>8}  {:>8}",
+        "Zone", "Unsigned", "Signed", "Diffs", "Total"
+    );
+    for report in reports {
+        println!(
+            "{:width$}  {:>8}  {:>8}  {:>8}  {:>8}",
+            report.zone.to_string(),
+            size(report.unsigned),
+            size(report.signed),
+            size(report.diffs),
+            size(report.total()),
+        );
+    }
+
+    if reports.len() > 1 {
+        let sum = |f: fn(&ZoneMemoryReport) -> usize| reports.iter().map(f).sum::<usize>();
+        println!(
+            "{:width$}  {:>8}  {:>8}  {:>8}  {:>8}",
+            "(total)",
+            size(sum(|r| r.unsigned)),
+            size(sum(|r| r.signed)),
+            size(sum(|r| r.diffs)),
+            size(sum(ZoneMemoryReport::total)),
+        );
+    }
+    println!("Sizes are estimates of the record data held in memory.");
+}
+
+/// Print the outcome of approving a review batch.
+///
+/// Fails if any zone of the batch could not be approved.
+fn report_batch_review(
+    stage: &str,
+    batch: &str,
+    reviewed: Vec<ZoneBatchReviewed>,
+) -> Result<(), String> {
+    if reviewed.is_empty() {
+        println!("No {stage} zones of batch '{batch}' are being reviewed");
+        return Ok(());
+    }
+
+    let total = reviewed.len();
+    let mut failures = 0;
+    for ZoneBatchReviewed {
+        zone,
+        serial,
+        result,
+    } in reviewed
+    {
+        match result {
+            Ok(ZoneReviewOutput {}) => {
+                println!("Approved {stage} zone '{zone}' with serial number {serial}")
+            }
+            Err(err) => {
+                failures += 1;
+                eprintln!(
+                    "Could not approve {stage} zone '{zone}' with serial number {serial}: {err}"
+                );
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!(
+            "{failures} of {total} {stage} zones of batch '{batch}' could not be approved"
+        ));
+    }
+    Ok(())
+}
+
 /// Format a size in a human-readable way
 ///
 /// Shows one decimal point if the size is small enough for that to make sense
 /// (e.g. below 10KB).
-fn format_size(v: usize, spacer: &str, suffix: &str) -> String {
+pub(crate) fn format_size(v: usize, spacer: &str, suffix: &str) -> String {
     let v = v as f64;
 
     const M: f64 = 1_000_000.0;
@@ -988,7 +2040,10 @@ fn format_size(v: usize, spacer: &str, suffix: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::format_size;
+    use super::{
+        ZoneBatchReviewed, ZoneName, ZoneReviewError, ZoneReviewOutput, format_size,
+        report_batch_review,
+    };
 
     #[test]
     fn test_format_size() {
@@ -1000,6 +2055,36 @@ mod tests {
         assert_eq!(format_size(94500000, " ", "B"), "94 MB");
         assert_eq!(format_size(945000000, " ", "B"), "945 MB");
     }
+
+    #[test]
+    fn batch_review_reports_partial_failures() {
+        let reviewed = |zone: &str, result| ZoneBatchReviewed {
+            zone: zone.parse::<ZoneName>().unwrap(),
+            serial: 1.into(),
+            result,
+        };
+
+        assert!(report_batch_review("unsigned", "b", vec![]).is_ok());
+        assert!(
+            report_batch_review(
+                "unsigned",
+                "b",
+                vec![reviewed("a.example", Ok(ZoneReviewOutput {}))]
+            )
+            .is_ok()
+        );
+
+        let err = report_batch_review(
+            "unsigned",
+            "b",
+            vec![
+                reviewed("a.example", Ok(ZoneReviewOutput {})),
+                reviewed("b.example", Err(ZoneReviewError::NotUnderReview)),
+            ],
+        )
+        .unwrap_err();
+        assert!(err.starts_with("1 of 2 "));
+    }
 }
 
 fn serial_to_string(serial: Option<Serial>) -> String {
@@ -1030,7 +2115,7 @@ fn to_rfc3339_ago(v: Option<SystemTime>, default: &str) -> String {
     }
 }
 
-fn to_rfc3339(v: SystemTime) -> String {
+pub(crate) fn to_rfc3339(v: SystemTime) -> String {
     jiff::Timestamp::try_from(v)
         .unwrap()
         .round(jiff::Unit::Second)
@@ -1038,7 +2123,6 @@ fn to_rfc3339(v: SystemTime) -> String {
         .to_string()
 }
 
-#[expect(dead_code)]
 fn format_duration(duration: Duration) -> String {
     format!(
         "{:#}",
@@ -1053,7 +2137,7 @@ fn format_duration(duration: Duration) -> String {
     )
 }
 
-fn key_file_imports(
+pub fn key_file_imports(
     key_paths: Vec<Utf8PathBuf>,
     key_type: KeyType,
 ) -> Result<Vec<KeyImport>, String> {
@@ -1097,7 +2181,7 @@ fn expand_key_path(key_file_path: Utf8PathBuf, key_type: KeyType) -> Result<File
     })
 }
 
-fn kmip_imports(key_type: KeyType, x: &[String]) -> Vec<KeyImport> {
+pub fn kmip_imports(key_type: KeyType, x: &[String]) -> Vec<KeyImport> {
     let (chunks, remainder) = x.as_chunks::<5>();
 
     // If this fails then clap is not doing what we expect.
@@ -1118,6 +2202,17 @@ fn kmip_imports(key_type: KeyType, x: &[String]) -> Vec<KeyImport> {
         .collect()
 }
 
+/// Canonicalize the path to a zone file, as it is used by the daemon.
+fn canonicalize_zonefile_path(path: &Utf8Path) -> Result<Box<Utf8Path>, String> {
+    let canonicalized_path = path
+        .canonicalize()
+        .map_err(|err| format!("Failed to canonicalize zonefile path '{}': {err}", path))?;
+    let path_str = canonicalized_path
+        .to_str()
+        .ok_or_else(|| format!("Failed to convert path '{}'", canonicalized_path.display()))?;
+    Ok(Utf8PathBuf::from(path_str).into_boxed_path())
+}
+
 //------------ ZoneSource ----------------------------------------------------
 
 const DEFAULT_NS_PORT: u16 = 53;
@@ -1191,7 +2286,12 @@ impl TryFrom<ZoneSource> for cascade_api::ZoneSource {
                 } else {
                     None
                 };
-                cascade_api::ZoneSource::Server { addr, tsig_key }
+                cascade_api::ZoneSource::Server {
+                    addr,
+                    tsig_key,
+                    next_tsig_key: None,
+                    fallback: None,
+                }
             }
         })
     }
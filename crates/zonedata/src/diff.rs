@@ -61,6 +61,20 @@ impl DiffData {
         }
     }
 
+    /// An estimate of the memory used by this diff, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        let soas = [&self.removed_soa, &self.added_soa]
+            .into_iter()
+            .flatten()
+            .map(SoaRecord::memory_usage);
+        let records = self
+            .removed_records
+            .iter()
+            .chain(&self.added_records)
+            .map(RegularRecord::memory_usage);
+        size_of::<Self>() + soas.sum::<usize>() + records.sum::<usize>()
+    }
+
     /// Whether this diff is empty.
     pub const fn is_empty(&self) -> bool {
         self.removed_soa.is_none()
@@ -226,6 +226,12 @@ pub fn is_signing(rtype: RType, at_apex: impl FnOnce() -> bool) -> bool {
         ) && (at_apex)())
 }
 
+/// The class of all stored zones.
+///
+/// Cascade only supports zones of the IN class.  Records of other classes are
+/// rejected when zones are loaded, rather than being silently mixed in.
+pub const ZONE_CLASS: Class = Class::IN;
+
 //----------- Record -----------------------------------------------------------
 
 /// A DNS record.
@@ -251,6 +257,14 @@ impl RegularRecord {
     pub fn class(&self) -> Class {
         self.0.rclass
     }
+
+    /// An estimate of the memory used by this record, in bytes.
+    ///
+    /// The heap-allocated owner name and record data are approximated by the
+    /// size of the record in the wire format.
+    pub fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.0.built_bytes_size()
+    }
 }
 
 impl PartialOrd for RegularRecord {
@@ -346,6 +360,15 @@ impl Ord for SoaRecord {
     }
 }
 
+impl SoaRecord {
+    /// An estimate of the memory used by this record, in bytes.
+    ///
+    /// See [`RegularRecord::memory_usage()`].
+    pub fn memory_usage(&self) -> usize {
+        size_of::<Self>() + self.0.built_bytes_size()
+    }
+}
+
 impl Deref for SoaRecord {
     type Target = domain::new::base::Record<Box<RevName>, Soa<Box<Name>>>;
 
@@ -58,6 +58,16 @@ impl<'d> LoadedZoneReader<'d> {
         self.instance.records.as_slice()
     }
 
+    /// An estimate of the memory used by this instance, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.soa().memory_usage()
+            + self
+                .regular_records()
+                .iter()
+                .map(RegularRecord::memory_usage)
+                .sum::<usize>()
+    }
+
     /// The unsigned records in the zone.
     ///
     /// DNSSEC related records that would be produced by Cascade's signer (e.g.
@@ -142,6 +152,18 @@ impl<'d> SignedZoneReader<'d> {
         self.signed_instance.records.as_slice()
     }
 
+    /// An estimate of the memory used by the signed component, in bytes.
+    ///
+    /// The underlying loaded instance is not included.
+    pub fn memory_usage(&self) -> usize {
+        self.soa().memory_usage()
+            + self
+                .generated_records()
+                .iter()
+                .map(RegularRecord::memory_usage)
+                .sum::<usize>()
+    }
+
     /// The underlying loaded instance.
     pub const fn loaded(&self) -> LoadedZoneReader<'d> {
         LoadedZoneReader::new(self.loaded_instance)
@@ -12,11 +12,12 @@
 
 use std::{fmt, sync::Arc};
 
-use domain::new::base::RType;
+use domain::new::base::{RClass, RType};
 use rayon::slice::ParallelSliceMut;
 
 use crate::{
-    DiffData, InstanceData, LoadedZoneReader, RegularRecord, SignedZoneReader, SoaRecord, merge,
+    DiffData, InstanceData, LoadedZoneReader, RegularRecord, SignedZoneReader, SoaRecord,
+    ZONE_CLASS, merge,
 };
 
 //----------- LoadedZoneReplacer -----------------------------------------------
@@ -68,6 +69,9 @@ impl<'d> LoadedZoneReplacer<'d> {
     ///
     /// A copy of the record **must** be added as a regular record too.
     pub fn set_soa(&mut self, soa: SoaRecord) -> Result<(), ReplaceError> {
+        if soa.rclass != ZONE_CLASS {
+            return Err(ReplaceError::UnsupportedClass(soa.rclass));
+        }
         if self.next.soa.is_some() {
             return Err(ReplaceError::MultipleSoas);
         }
@@ -78,6 +82,9 @@ impl<'d> LoadedZoneReplacer<'d> {
 
     /// Add a regular record.
     pub fn add(&mut self, record: RegularRecord) -> Result<(), ReplaceError> {
+        if record.rclass != ZONE_CLASS {
+            return Err(ReplaceError::UnsupportedClass(record.rclass));
+        }
         self.next.records.push(record);
         Ok(())
     }
@@ -188,6 +195,9 @@ impl<'d> LoadedZonePatcher<'d> {
     ///
     /// A copy of the record **must** be added as a regular record too.
     pub fn add_soa(&mut self, soa: SoaRecord) -> Result<(), PatchError> {
+        if soa.rclass != ZONE_CLASS {
+            return Err(PatchError::UnsupportedClass(soa.rclass));
+        }
         if self.immediate.added_soa.is_some() {
             return Err(PatchError::MultipleSoasAdded);
         }
@@ -204,6 +214,9 @@ impl<'d> LoadedZonePatcher<'d> {
 
     /// Add a new regular record.
     pub fn add(&mut self, record: RegularRecord) -> Result<(), PatchError> {
+        if record.rclass != ZONE_CLASS {
+            return Err(PatchError::UnsupportedClass(record.rclass));
+        }
         self.immediate.added_records.push(record);
         Ok(())
     }
@@ -816,6 +829,9 @@ pub enum ReplaceError {
 
     /// The built instance contains multiple SOA records.
     MultipleSoas,
+
+    /// A record of a class other than [`ZONE_CLASS`] was provided.
+    UnsupportedClass(RClass),
 }
 
 impl std::error::Error for ReplaceError {}
@@ -825,6 +841,11 @@ impl fmt::Display for ReplaceError {
         match self {
             ReplaceError::MissingSoa => f.write_str("a SOA record was not provided"),
             ReplaceError::MultipleSoas => f.write_str("multiple SOA records were provided"),
+            ReplaceError::UnsupportedClass(class) => write!(
+                f,
+                "a record of unsupported class {} was provided",
+                u16::from(class.code)
+            ),
         }
     }
 }
@@ -845,6 +866,9 @@ pub enum PatchError {
 
     /// An inconsistency was detected.
     Inconsistency,
+
+    /// A record of a class other than [`ZONE_CLASS`] was added.
+    UnsupportedClass(RClass),
 }
 
 impl std::error::Error for PatchError {}
@@ -856,6 +880,11 @@ impl fmt::Display for PatchError {
             PatchError::MissingSoaChange => f.write_str("a patchset did not change the SOA record"),
             PatchError::MultipleSoasAdded => f.write_str("a patchset added multiple SOA records"),
             PatchError::Inconsistency => f.write_str("a patchset could not be applied"),
+            PatchError::UnsupportedClass(class) => write!(
+                f,
+                "a record of unsupported class {} was added",
+                u16::from(class.code)
+            ),
         }
     }
 }
@@ -255,6 +255,7 @@ man_pages = [
     ('man/cascade-hsm', 'cascade-hsm', 'Manage HSMs', author, 1),
     ('man/cascade-keyset', 'cascade-keyset', 'Execute manual key roll or key removal commands', author, 1),
     ('man/cascade-policy', 'cascade-policy', 'Manage policies', author, 1),
+    ('man/cascade-review', 'cascade-review', 'Work with pending zone reviews', author, 1),
     ('man/cascade-status', 'cascade-status', 'Show the status of Cascade', author, 1),
     ('man/cascade-template', 'cascade-template', 'Print example config or policy files', author, 1),
     ('man/cascade-zone', 'cascade-zone', 'Manage zones', author, 1),
@@ -0,0 +1,79 @@
+#!/bin/bash
+#
+# Check the NSEC3 chain of a zone signed with NSEC3 opt-out.
+#
+# Usage: check-nsec3-opt-out.sh <axfr-output> <zone> <kind>:<name>...
+#
+# The AXFR output is expected in the format printed by dig. Each <kind>:<name>
+# argument names a delegation relative to <zone>, where <kind> is 'secure'
+# (the delegation has a DS RRset and must be covered by an NSEC3 record with
+# NS and DS in its type bitmap) or 'insecure' (the delegation has no DS RRset
+# and must not have an NSEC3 record).
+set -eo pipefail
+export LC_ALL=C
+
+axfr=$1
+zone=${2%.}
+shift 2
+
+lower() {
+	tr '[:upper:]' '[:lower:]'
+}
+
+# Determine the NSEC3 parameters in use.
+read -r alg flags iterations salt < <(awk '$4 == "NSEC3PARAM" { print $5, $6, $7, $8 }' "$axfr")
+if [[ -z "$alg" ]]; then
+	echo "::error:: no NSEC3PARAM record found"
+	exit 1
+fi
+[[ "$salt" == "-" ]] && salt=""
+
+# Every NSEC3 record must have the opt-out flag set.
+if awk '$4 == "NSEC3" && $6 != 1 { found = 1 } END { exit !found }' "$axfr"; then
+	echo "::error:: found NSEC3 records without the opt-out flag"
+	exit 1
+fi
+
+status=0
+for arg in "$@"; do
+	kind=${arg%%:*}
+	name=${arg#*:}
+	hash=$(ldns-nsec3-hash -a "$alg" -t "$iterations" -s "$salt" "${name}.${zone}." | cut -d. -f1 | lower)
+	owner="${hash}.${zone}."
+
+	# The type bitmap of the NSEC3 record for this name, if it exists.
+	if bitmap=$(lower < "$axfr" | awk -v owner="$owner" '
+		$1 == owner && $4 == "nsec3" {
+			for (i = 10; i <= NF; i++) printf " %s", $i
+			found = 1
+		}
+		END { exit !found }'); then
+		present=1
+	else
+		present=0
+	fi
+
+	case "$kind" in
+	secure)
+		if [[ $present == 0 ]]; then
+			echo "::error:: secure delegation '${name}' is missing from the NSEC3 chain"
+			status=1
+		elif [[ "$bitmap " != *" ns "* || "$bitmap " != *" ds "* ]]; then
+			echo "::error:: NSEC3 record for secure delegation '${name}' has type bitmap '${bitmap}'"
+			status=1
+		fi
+	;;
+	insecure)
+		if [[ $present == 1 ]]; then
+			echo "::error:: insecure delegation '${name}' is in the NSEC3 chain despite opt-out"
+			status=1
+		fi
+	;;
+	*)
+		echo "::error:: unknown delegation kind '${kind}'"
+		exit 2
+	;;
+	esac
+done
+
+exit $status
@@ -1,24 +1,31 @@
 //! Cascade's central command.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{
     fmt, io,
+    net::SocketAddr,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 
 use bytes::Bytes;
+use camino::Utf8Path;
 use domain::base::Name;
+use domain::base::Serial;
+use domain::base::iana::SecurityAlgorithm;
 use domain::dnssec::sign::keys::keyset::UnixTime;
 use tracing::{debug, error, info, trace};
 
 use crate::api::{self, KeyImport, TsigAddError, TsigAddResult};
+use crate::common::store::StateStore;
 use crate::config::RuntimeConfig;
 use crate::loader::Loader;
 use crate::loader::zone::LoaderZoneHandle;
 use crate::metrics::Metrics;
 use crate::persistence::{Compacter, Persister, Restorer};
+use crate::policy::KeyParameters;
 use crate::server::{LoadedReviewServer, PublicationServer, SignedReviewServer};
+use crate::signer::keys::KeyPair;
 use crate::state::PolicySpec;
 use crate::tsig::ImportError;
 use crate::units::key_manager::KeyManager;
@@ -46,6 +53,9 @@ pub struct Center {
     /// The configuration.
     pub config: Config,
 
+    /// Storage for the global and per-zone state files.
+    pub state_store: Arc<dyn StateStore>,
+
     /// The logger.
     pub logger: Logger,
 
@@ -89,7 +99,17 @@ pub async fn add_zone(
     policy_name: Box<str>,
     api_source: api::ZoneSource,
     key_imports: Vec<KeyImport>,
+    observe_only: bool,
+    serial_floor: Option<Serial>,
 ) -> Result<(), ZoneAddError> {
+    // Catch unusable key imports now, rather than at first signing.  The key
+    // files are read without holding the global state lock.
+    let algorithm = {
+        let state = center.state.lock().unwrap();
+        validate_zone_add(&state, &name, &policy_name, &api_source)?
+    };
+    check_key_imports(&name, &algorithm, &key_imports).await?;
+
     // Create and insert the zone.
     let zone;
     let source;
@@ -98,21 +118,8 @@ pub async fn add_zone(
         // Lock the global state to check consistency and insert the zone.
         let mut state = center.state.lock().unwrap();
 
-        // Prioritize 'AlreadyExists' over other kinds of errors.
-        if state.zones.contains(&name) {
-            return Err(ZoneAddError::AlreadyExists);
-        }
-
-        // Look up the requested policy.
-        {
-            let policy = state
-                .policies
-                .get(&policy_name)
-                .ok_or(ZoneAddError::NoSuchPolicy)?;
-            if policy.mid_deletion {
-                return Err(ZoneAddError::PolicyMidDeletion);
-            }
-        }
+        // The global state may have changed while the keys were checked.
+        validate_zone_add(&state, &name, &policy_name, &api_source)?;
 
         // Create the zone and initialize its state.
         zone = Arc::new(Zone::new(name, &center.metrics));
@@ -120,7 +127,12 @@ pub async fn add_zone(
         source = match api_source {
             api::ZoneSource::None => crate::loader::Source::None,
             api::ZoneSource::Zonefile { path } => crate::loader::Source::Zonefile { path },
-            api::ZoneSource::Server { addr, tsig_key } => {
+            api::ZoneSource::Server {
+                addr,
+                tsig_key,
+                fallback,
+                ..
+            } => {
                 let tsig_key = if let Some(key_name) = tsig_key {
                     // Lookup the key in the TSIG key store.
                     let key = state
@@ -141,7 +153,15 @@ pub async fn add_zone(
                     None
                 };
 
-                crate::loader::Source::Server { addr, tsig_key }
+                crate::loader::Source::Server {
+                    addr,
+                    tsig_key,
+                    next_tsig_key: None,
+                    fallback: fallback.map(|f| crate::loader::Fallback {
+                        path: f.path,
+                        timeout: f.timeout,
+                    }),
+                }
             }
         };
 
@@ -152,7 +172,9 @@ pub async fn add_zone(
                 .policies
                 .get_mut(&policy_name)
                 .ok_or(ZoneAddError::NoSuchPolicy)?;
-            zone_state.policy = Some(policy.latest.clone());
+            zone_state.set_policy(policy.latest.clone());
+            zone_state.observe_only = observe_only;
+            zone_state.serial_floor = serial_floor;
             policy.zones.insert(zone.name.clone());
 
             // Don't try to restore zone data, since it's a completely new zone.
@@ -175,11 +197,13 @@ pub async fn add_zone(
             handle.storage().on_passive();
         }
 
-        // Insert the zone in the global set.
+        // Insert the zone in the global set.  A removed zone of the same name
+        // is no longer served, so its removal is complete.
         assert!(
             state.zones.insert(ZoneByName(zone.clone())),
             "Already checked that 'state.zones' does not contain 'name'"
         );
+        state.removing_zones.remove(&zone.name);
         state.mark_dirty(center);
     }
 
@@ -244,6 +268,273 @@ pub async fn add_zone(
     Ok(())
 }
 
+/// Check whether a zone could be added, without adding it.
+///
+/// This performs the validation of [`add_zone()`] without changing any state:
+/// the zone source is checked to be reachable, and key imports are checked to
+/// exist and to parse.  On success, a description of what adding the zone
+/// would do is returned.
+pub async fn check_zone_add(
+    center: &Arc<Center>,
+    name: &Name<Bytes>,
+    policy_name: &str,
+    api_source: &api::ZoneSource,
+    key_imports: &[KeyImport],
+) -> Result<Vec<String>, ZoneAddError> {
+    let mut actions = Vec::new();
+
+    let algorithm = {
+        let state = center.state.lock().unwrap();
+        validate_zone_add(&state, name, policy_name, api_source)?
+    };
+    actions.push(format!("Add zone '{name}' with policy '{policy_name}'"));
+
+    match api_source {
+        api::ZoneSource::None => actions.push("Do not load the zone".into()),
+        api::ZoneSource::Zonefile { path } => {
+            probe_zone_file(path).await.map_err(|err| {
+                ZoneAddError::Other(format!("Cannot read zone file '{path}': {err}"))
+            })?;
+            actions.push(format!("Load the zone from zone file '{path}'"));
+        }
+        api::ZoneSource::Server {
+            addr,
+            tsig_key,
+            fallback,
+            ..
+        } => {
+            probe_zone_source(*addr)
+                .await
+                .map_err(ZoneAddError::Other)?;
+            match tsig_key {
+                Some(key_name) => actions.push(format!(
+                    "Transfer the zone from {addr} using TSIG key '{key_name}'"
+                )),
+                None => actions.push(format!("Transfer the zone from {addr}")),
+            }
+            if let Some(api::ZonefileFallback { path, timeout }) = fallback {
+                probe_zone_file(path).await.map_err(|err| {
+                    ZoneAddError::Other(format!("Cannot read fallback zone file '{path}': {err}"))
+                })?;
+                actions.push(format!(
+                    "Load the zone from zone file '{path}' if {addr} can't be reached within {} seconds",
+                    timeout.as_secs()
+                ));
+            }
+        }
+    }
+
+    check_key_imports(name, &algorithm, key_imports).await?;
+
+    for key_import in key_imports {
+        match key_import {
+            KeyImport::PublicKey(path) => {
+                actions.push(format!("Import public key '{path}'"));
+            }
+            KeyImport::File(api::FileKeyImport {
+                key_type,
+                public_key_path,
+                private_key_path,
+            }) => {
+                actions.push(format!(
+                    "Import {key_type} from '{public_key_path}' and '{private_key_path}'"
+                ));
+            }
+            KeyImport::Kmip(api::KmipKeyImport {
+                key_type,
+                server,
+                public_id,
+                private_id,
+                ..
+            }) => {
+                let path = center.config.kmip_server_state_dir.join(server);
+                if !path.exists() {
+                    return Err(ZoneAddError::Other(format!(
+                        "No KMIP server '{server}' is known"
+                    )));
+                }
+                actions.push(format!(
+                    "Import {key_type} '{public_id}'/'{private_id}' from KMIP server '{server}'"
+                ));
+            }
+        }
+    }
+
+    Ok(actions)
+}
+
+/// How long to wait for a zone source to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Check that a zone source accepts TCP connections.
+pub async fn probe_zone_source(addr: SocketAddr) -> Result<(), String> {
+    match tokio::time::timeout(CONNECT_TIMEOUT, tokio::net::TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(format!("Cannot connect to zone source {addr}: {err}")),
+        Err(_) => Err(format!("Timed out connecting to zone source {addr}")),
+    }
+}
+
+/// Check that a zone file can be opened for reading.
+pub async fn probe_zone_file(path: &Utf8Path) -> io::Result<()> {
+    tokio::fs::File::open(path).await.map(|_| ())
+}
+
+/// Check that a zone can be added, given the global state.
+///
+/// This is shared by [`add_zone()`] and [`check_zone_add()`]: the zone must not
+/// exist yet, its policy must exist and not be mid-deletion, and the TSIG key
+/// of its source (if any) must be known.  On success, the key parameters of
+/// the policy are returned, for checking key imports against.
+fn validate_zone_add(
+    state: &State,
+    name: &Name<Bytes>,
+    policy_name: &str,
+    api_source: &api::ZoneSource,
+) -> Result<KeyParameters, ZoneAddError> {
+    // Prioritize 'AlreadyExists' over other kinds of errors.
+    if state.zones.contains(name) {
+        return Err(ZoneAddError::AlreadyExists);
+    }
+
+    // Look up the requested policy.
+    let policy = state
+        .policies
+        .get(policy_name)
+        .ok_or(ZoneAddError::NoSuchPolicy)?;
+    if policy.mid_deletion {
+        return Err(ZoneAddError::PolicyMidDeletion);
+    }
+
+    if let api::ZoneSource::Server {
+        tsig_key: Some(key_name),
+        ..
+    } = api_source
+    {
+        state
+            .tsig_store
+            .get(key_name)
+            .ok_or(ZoneAddError::NoSuchTsigKey)?;
+    }
+
+    Ok(policy.latest.key_manager.algorithm.clone())
+}
+
+/// Check that imported keys fit a zone and its policy.
+///
+/// Imported public keys must be owned by the zone apex, and all imported keys
+/// must use the algorithm configured in the policy.  The key files are read
+/// on a blocking thread.
+pub async fn check_key_imports(
+    name: &Name<Bytes>,
+    algorithm: &KeyParameters,
+    key_imports: &[KeyImport],
+) -> Result<(), ZoneAddError> {
+    let name = name.clone();
+    let algorithm = algorithm.clone();
+    let key_imports = key_imports.to_vec();
+    tokio::task::spawn_blocking(move || read_key_imports(&name, &algorithm, &key_imports))
+        .await
+        .expect("checking key imports should not panic")
+}
+
+/// Read imported keys and check that they fit a zone and its policy.
+///
+/// See [`check_key_imports()`].
+fn read_key_imports(
+    name: &Name<Bytes>,
+    algorithm: &KeyParameters,
+    key_imports: &[KeyImport],
+) -> Result<(), ZoneAddError> {
+    let expected = algorithm.algorithm();
+
+    // Importing the same key twice would fail halfway through the import.
+    let mut seen = HashSet::new();
+    for key_import in key_imports {
+        let key = match key_import {
+            KeyImport::PublicKey(path)
+            | KeyImport::File(api::FileKeyImport {
+                public_key_path: path,
+                ..
+            }) => path.to_string(),
+            KeyImport::Kmip(api::KmipKeyImport {
+                server, public_id, ..
+            }) => format!("{public_id} on KMIP server '{server}'"),
+        };
+        if !seen.insert(key.clone()) {
+            return Err(ZoneAddError::Other(format!(
+                "key '{key}' is imported more than once"
+            )));
+        }
+    }
+
+    let check_public = |path: &Utf8Path| {
+        let key = KeyPair::load_pub_from_file(path)
+            .map_err(|err| ZoneAddError::Other(err.to_string()))?;
+        if key.owner() != name {
+            return Err(ZoneAddError::KeyOwnerMismatch {
+                path: path.to_string(),
+                owner: key.owner().to_string(),
+            });
+        }
+        if key.data().algorithm() != expected {
+            return Err(ZoneAddError::KeyAlgorithmMismatch {
+                key: path.to_string(),
+                algorithm: key.data().algorithm().to_string(),
+                expected: algorithm.to_string(),
+            });
+        }
+        Ok(())
+    };
+
+    for key_import in key_imports {
+        match key_import {
+            KeyImport::PublicKey(path) => check_public(path)?,
+            KeyImport::File(api::FileKeyImport {
+                public_key_path,
+                private_key_path,
+                ..
+            }) => {
+                check_public(public_key_path)?;
+                let key = KeyPair::load_priv_from_file(private_key_path)
+                    .map_err(|err| ZoneAddError::Other(err.to_string()))?;
+                if key.algorithm() != expected {
+                    return Err(ZoneAddError::KeyAlgorithmMismatch {
+                        key: private_key_path.to_string(),
+                        algorithm: key.algorithm().to_string(),
+                        expected: algorithm.to_string(),
+                    });
+                }
+            }
+            KeyImport::Kmip(api::KmipKeyImport {
+                public_id,
+                algorithm: kmip_algorithm,
+                ..
+            }) => {
+                // The algorithm may be given as a number or a mnemonic.
+                let parsed = match kmip_algorithm.parse::<u8>() {
+                    Ok(number) => Some(SecurityAlgorithm::from_int(number)),
+                    Err(_) => SecurityAlgorithm::from_mnemonic(kmip_algorithm.as_bytes()),
+                };
+                let Some(parsed) = parsed else {
+                    return Err(ZoneAddError::Other(format!(
+                        "unknown algorithm '{kmip_algorithm}' for key '{public_id}'"
+                    )));
+                };
+                if parsed != expected {
+                    return Err(ZoneAddError::KeyAlgorithmMismatch {
+                        key: public_id.clone(),
+                        algorithm: kmip_algorithm.clone(),
+                        expected: algorithm.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 async fn register_zone(
     center: &Arc<Center>,
     name: Name<Bytes>,
@@ -273,13 +564,46 @@ pub fn remove_zone(center: &Arc<Center>, name: Name<Bytes>) -> Result<(), ZoneRe
         .take(&name)
         .expect("the zone was found just above");
 
+    // A published zone may still be served for a while, so that secondaries
+    // relying on it are not cut off abruptly.  If the removal was interrupted
+    // by a restart, only the remainder of its grace period is left.
+    let now = SystemTime::now();
+    let (grace_period, removal_deadline) = {
+        let zone_state = zone.read();
+        match zone_state.removal_deadline {
+            Some(deadline) => {
+                let remainder = deadline
+                    .duration_since(now)
+                    .ok()
+                    .filter(|period| !period.is_zero());
+                (remainder, remainder.map(|_| deadline))
+            }
+            None => {
+                let grace_period = zone_state
+                    .policy
+                    .as_ref()
+                    .filter(|_| zone_state.instances.current.is_some())
+                    .and_then(|policy| policy.server.outbound.removal_grace_period)
+                    .filter(|period| !period.is_zero());
+                (grace_period, grace_period.map(|period| now + period))
+            }
+        }
+    };
+    let changed = match removal_deadline {
+        Some(_) => state.removing_zones.insert(name.clone()),
+        None => state.removing_zones.remove(&name),
+    };
+    if changed {
+        state.mark_dirty(center);
+    }
+
     // Remove the zone from all the places it might be stored.
     // The zone might not have made it to these places, but that's not an issue
     // so we just ignore any errors.
 
     LoadedReviewServer::remove_zone(center, &zone);
     SignedReviewServer::remove_zone(center, &zone);
-    PublicationServer::remove_zone(center, &zone);
+    PublicationServer::remove_zone(center, &zone, grace_period);
 
     let mut zone_state = zone.state.write_cleanly();
 
@@ -291,8 +615,13 @@ pub fn remove_zone(center: &Arc<Center>, name: Name<Bytes>) -> Result<(), ZoneRe
     .loader()
     .prep_removal();
 
-    // Update the policy's referenced zones.
-    if let Some(policy) = zone_state.policy.take() {
+    // Update the policy's referenced zones.  While the zone is still served,
+    // it keeps its policy, so that its access control remains in effect.
+    let policy = match grace_period {
+        Some(_) => zone_state.policy.clone(),
+        None => zone_state.policy.take(),
+    };
+    if let Some(policy) = policy {
         let policy = state
             .policies
             .get_mut(&policy.name)
@@ -302,15 +631,11 @@ pub fn remove_zone(center: &Arc<Center>, name: Name<Bytes>) -> Result<(), ZoneRe
         state.mark_dirty(center);
     }
 
-    // Update the TSIG key's referenced zones.
-    if let crate::loader::Source::Server {
-        tsig_key: Some(key_name),
-        ..
-    } = &zone_state.loader.source
-    {
+    // Update the TSIG keys' referenced zones.
+    for key in zone_state.loader.source.tsig_keys() {
         state
             .tsig_store
-            .get_mut(key_name.name())
+            .get_mut(key.name())
             .unwrap()
             .zones
             .remove(&ZoneByPtr(zone.clone()));
@@ -318,6 +643,7 @@ pub fn remove_zone(center: &Arc<Center>, name: Name<Bytes>) -> Result<(), ZoneRe
     }
 
     // Persist the state file one last time.
+    zone_state.removal_deadline = removal_deadline;
     zone_state.record_event(HistoricalEvent::Removed, None);
     std::mem::drop(zone_state);
     crate::zone::save_state_now(center, &zone);
@@ -328,6 +654,86 @@ pub fn remove_zone(center: &Arc<Center>, name: Name<Bytes>) -> Result<(), ZoneRe
     Ok(())
 }
 
+/// Rotate the TSIG key used to load a zone from its primary.
+pub fn rotate_zone_tsig_key(
+    center: &Arc<Center>,
+    name: &Name<Bytes>,
+    rotation: api::ZoneRotateTsig,
+) -> api::ZoneRotateTsigResult {
+    use api::ZoneRotateTsigError as Error;
+
+    let mut state = center.state.lock().unwrap();
+    let ZoneByName(zone) = state.zones.get(name).ok_or(Error::NoSuchZone)?;
+    let zone = zone.clone();
+    let mut zone_state = zone.write(center);
+
+    let crate::loader::Source::Server {
+        tsig_key,
+        next_tsig_key,
+        ..
+    } = &mut zone_state.loader.source
+    else {
+        return Err(Error::NotLoadedFromServer);
+    };
+
+    // The key that is no longer used by the zone, if any.
+    let dropped = match rotation {
+        api::ZoneRotateTsig::Start { key } => {
+            if next_tsig_key.is_some() {
+                return Err(Error::AlreadyRotating);
+            }
+            let key = state.tsig_store.get_mut(&key).ok_or(Error::NoSuchTsigKey)?;
+            key.zones.insert(ZoneByPtr(zone.clone()));
+            *next_tsig_key = Some(key.inner.clone());
+            info!(
+                "Rotating the TSIG key of zone '{name}' to '{}'",
+                key.inner.name()
+            );
+            None
+        }
+        api::ZoneRotateTsig::Finish => {
+            let next = next_tsig_key.take().ok_or(Error::NotRotating)?;
+            info!(
+                "Finished rotating the TSIG key of zone '{name}' to '{}'",
+                next.name()
+            );
+            std::mem::replace(tsig_key, Some(next))
+        }
+        api::ZoneRotateTsig::Abort => {
+            let next = next_tsig_key.take().ok_or(Error::NotRotating)?;
+            info!(
+                "Aborted rotating the TSIG key of zone '{name}' to '{}'",
+                next.name()
+            );
+            Some(next)
+        }
+    };
+
+    let output = api::ZoneRotateTsigOutput {
+        zone: name.clone(),
+        tsig_key: tsig_key.as_ref().map(|key| key.name().clone()),
+        next_tsig_key: next_tsig_key.as_ref().map(|key| key.name().clone()),
+    };
+
+    // Forget that the zone uses the dropped key, unless it still does (e.g.
+    // when rotating to the same key).
+    if let Some(dropped) = dropped
+        && !zone_state
+            .loader
+            .source
+            .tsig_keys()
+            .any(|key| key.name() == dropped.name())
+        && let Some(key) = state.tsig_store.get_mut(dropped.name())
+    {
+        key.zones.remove(&ZoneByPtr(zone.clone()));
+    }
+
+    zone_state.record_event(HistoricalEvent::SourceChanged, None);
+    state.tsig_store.mark_dirty(center);
+
+    Ok(output)
+}
+
 pub fn get_zone(center: &Arc<Center>, name: &Name<Bytes>) -> Option<Arc<Zone>> {
     let state = center.state.lock().unwrap();
     state.zones.get(name).map(|zone| zone.0.clone())
@@ -382,6 +788,13 @@ pub struct State {
     /// authenticating incoming requests for zones.
     pub tsig_store: TsigStore,
 
+    /// Removed zones that are still within their removal grace period.
+    ///
+    /// These zones are no longer in [`Self::zones`], but are still served.
+    /// Their names are persisted so that they can be restored (and their
+    /// removal resumed) after a restart.
+    pub removing_zones: foldhash::HashSet<Name<Bytes>>,
+
     /// An enqueued save of this state.
     ///
     /// The enqueued save operation will persist the current state in a short
@@ -400,11 +813,12 @@ impl State {
     /// file, that need to be parsed and inserted in the state.
     pub fn init_from_file(
         config: &Config,
+        store: &dyn StateStore,
         zones: &mut foldhash::HashSet<Name<Bytes>>,
         policies: &mut foldhash::HashMap<Box<str>, PolicySpec>,
     ) -> io::Result<Self> {
         let path = config.daemon.state_file.value();
-        let spec = crate::state::Spec::load(path)?;
+        let spec = crate::state::Spec::load(store, path)?;
 
         Ok(spec.parse(zones, policies))
     }
@@ -441,7 +855,7 @@ impl State {
             }
 
             // Save the global state.
-            match spec.save(&path) {
+            match spec.save(&*center.state_store, &path) {
                 Ok(()) => debug!("Saved global state (to '{path}')"),
                 Err(err) => {
                     error!("Could not save global state to '{path}': {err}");
@@ -465,6 +879,22 @@ pub enum ZoneAddError {
     PolicyMidDeletion,
     /// No TSIG key with that name exists.
     NoSuchTsigKey,
+    /// An imported public key is not owned by the zone apex.
+    KeyOwnerMismatch {
+        /// The path of the key file.
+        path: String,
+        /// The owner name of the key.
+        owner: String,
+    },
+    /// An imported key does not use the algorithm from the policy.
+    KeyAlgorithmMismatch {
+        /// The path or identifier of the key.
+        key: String,
+        /// The algorithm of the key.
+        algorithm: String,
+        /// The algorithm required by the policy.
+        expected: String,
+    },
     /// Some other error occurred.
     Other(String),
 }
@@ -473,13 +903,27 @@ impl std::error::Error for ZoneAddError {}
 
 impl fmt::Display for ZoneAddError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str(match self {
-            Self::AlreadyExists => "a zone of this name already exists",
-            Self::NoSuchPolicy => "no policy with that name exists",
-            Self::PolicyMidDeletion => "the specified policy is being deleted",
-            Self::NoSuchTsigKey => "no TSIG key with that name exists",
-            Self::Other(reason) => reason,
-        })
+        match self {
+            Self::AlreadyExists => f.write_str("a zone of this name already exists"),
+            Self::NoSuchPolicy => f.write_str("no policy with that name exists"),
+            Self::PolicyMidDeletion => f.write_str("the specified policy is being deleted"),
+            Self::NoSuchTsigKey => f.write_str("no TSIG key with that name exists"),
+            Self::KeyOwnerMismatch { path, owner } => {
+                write!(
+                    f,
+                    "the key in '{path}' belongs to '{owner}', not to this zone"
+                )
+            }
+            Self::KeyAlgorithmMismatch {
+                key,
+                algorithm,
+                expected,
+            } => write!(
+                f,
+                "the key '{key}' uses algorithm {algorithm}, but the policy requires {expected}"
+            ),
+            Self::Other(reason) => f.write_str(reason),
+        }
     }
 }
 
@@ -490,6 +934,18 @@ impl From<ZoneAddError> for api::ZoneAddError {
             ZoneAddError::NoSuchPolicy => Self::NoSuchPolicy,
             ZoneAddError::PolicyMidDeletion => Self::PolicyMidDeletion,
             ZoneAddError::NoSuchTsigKey => Self::NoSuchTsigKey,
+            ZoneAddError::KeyOwnerMismatch { path, owner } => {
+                Self::KeyOwnerMismatch { path, owner }
+            }
+            ZoneAddError::KeyAlgorithmMismatch {
+                key,
+                algorithm,
+                expected,
+            } => Self::KeyAlgorithmMismatch {
+                key,
+                algorithm,
+                expected,
+            },
             ZoneAddError::Other(reason) => Self::Other(reason),
         }
     }
@@ -526,3 +982,69 @@ impl From<ZoneRemoveError> for api::ZoneRemoveError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use domain::base::Name;
+
+    use super::{ZoneAddError, read_key_imports};
+    use crate::api::{self, KeyImport};
+    use crate::policy::KeyParameters;
+
+    fn kmip(public_id: &str, algorithm: &str) -> KeyImport {
+        KeyImport::Kmip(api::KmipKeyImport {
+            key_type: api::KeyType::Ksk,
+            server: "hsm".into(),
+            public_id: public_id.into(),
+            private_id: format!("{public_id}-priv"),
+            algorithm: algorithm.into(),
+            flags: "257".into(),
+        })
+    }
+
+    #[test]
+    fn duplicate_key_imports_are_rejected() {
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+        let algorithm = KeyParameters::EcdsaP256Sha256;
+
+        let imports = [kmip("a", "13"), kmip("b", "13"), kmip("a", "13")];
+        assert!(matches!(
+            read_key_imports(&name, &algorithm, &imports),
+            Err(ZoneAddError::Other(_))
+        ));
+
+        // Public key paths are checked before any file is read.
+        let imports = [
+            KeyImport::PublicKey("/nonexistent/Kexample.org.key".into()),
+            KeyImport::File(api::FileKeyImport {
+                key_type: api::KeyType::Zsk,
+                public_key_path: "/nonexistent/Kexample.org.key".into(),
+                private_key_path: "/nonexistent/Kexample.org.private".into(),
+            }),
+        ];
+        let Err(ZoneAddError::Other(err)) = read_key_imports(&name, &algorithm, &imports) else {
+            panic!("duplicate key imports should be rejected");
+        };
+        assert!(err.contains("more than once"), "{err}");
+    }
+
+    #[test]
+    fn kmip_key_algorithms_are_checked_up_front() {
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+        let algorithm = KeyParameters::EcdsaP256Sha256;
+
+        assert!(read_key_imports(&name, &algorithm, &[kmip("a", "13")]).is_ok());
+        assert!(read_key_imports(&name, &algorithm, &[kmip("a", "ECDSAP256SHA256")]).is_ok());
+
+        // A later bad entry fails the whole list, before anything is imported.
+        assert!(matches!(
+            read_key_imports(&name, &algorithm, &[kmip("a", "13"), kmip("b", "8")]),
+            Err(ZoneAddError::KeyAlgorithmMismatch { .. })
+        ));
+        assert!(matches!(
+            read_key_imports(&name, &algorithm, &[kmip("a", "13"), kmip("b", "bogus")]),
+            Err(ZoneAddError::Other(_))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod datetime;
 pub(crate) mod net;
 pub mod scheduler;
+pub(crate) mod store;
@@ -0,0 +1,37 @@
+//--- State storage traits ---------------------------------------------------
+//
+// These traits enable us to swap out the file system for an in-memory store
+// when testing.
+
+use std::{fmt, fs, io};
+
+use camino::Utf8Path;
+
+/// Storage for Cascade's state files.
+///
+/// The global state file and the per-zone state files are read and written
+/// through this trait, so that tests can avoid touching the disk.
+pub trait StateStore: fmt::Debug + Send + Sync {
+    /// Read the entire contents of the file at `path`.
+    ///
+    /// If no such file exists, an error of kind [`io::ErrorKind::NotFound`]
+    /// is returned.
+    fn read(&self, path: &Utf8Path) -> io::Result<Vec<u8>>;
+
+    /// Replace the contents of the file at `path`.
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> io::Result<()>;
+}
+
+/// A [`StateStore`] backed by the file system.
+#[derive(Debug, Default)]
+pub struct FileStateStore;
+
+impl StateStore for FileStateStore {
+    fn read(&self, path: &Utf8Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> io::Result<()> {
+        crate::util::write_file(path, contents)
+    }
+}
@@ -8,13 +8,16 @@
 use std::{
     collections::BTreeMap,
     fmt::Write,
+    fs::{File, OpenOptions, TryLockError},
+    io::{self, Read, Seek, SeekFrom},
     net::{SocketAddr, TcpListener, UdpSocket},
     sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
 };
 
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 use daemonbase::process::{EnvSockets, EnvSocketsError, Process};
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::config::{DaemonConfig, GroupId, UserId};
 
@@ -186,6 +189,150 @@ fn into_daemon_path(p: Box<Utf8Path>) -> daemonbase::config::ConfigPath {
     daemonbase::config::ConfigPath::from(p)
 }
 
+//------------ Service manager notification ----------------------------------
+
+/// Notify the service manager (i.e. systemd) of a state change.
+///
+/// This implements the `sd_notify(3)` protocol: `state` (e.g. `READY=1`) is
+/// sent as a datagram to the socket named by `$NOTIFY_SOCKET`. If the variable
+/// is not set, Cascade is not running under a service manager that wants to
+/// be notified, and nothing happens.
+pub fn notify_service_manager(state: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(socket_path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let result = (|| {
+        let socket = UnixDatagram::unbound()?;
+        let path = socket_path.as_encoded_bytes();
+        if let Some(name) = path.strip_prefix(b"@") {
+            // A socket in the abstract namespace.
+            #[cfg(target_os = "linux")]
+            {
+                use std::os::linux::net::SocketAddrExt;
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &addr)?;
+            }
+            #[cfg(not(target_os = "linux"))]
+            {
+                let _ = name;
+                return Err(io::Error::from(io::ErrorKind::Unsupported));
+            }
+        } else {
+            socket.send_to(state.as_bytes(), &socket_path)?;
+        }
+        io::Result::Ok(())
+    })();
+
+    match result {
+        Ok(()) => debug!("Notified the service manager: {state}"),
+        Err(err) => warn!("Failed to notify the service manager ({state}): {err}"),
+    }
+}
+
+/// The interval at which the service manager expects watchdog keep-alives.
+///
+/// This is derived from `$WATCHDOG_USEC` (as set by systemd's `WatchdogSec`),
+/// halved to leave room for scheduling delays. `None` is returned if no
+/// watchdog is configured for this process.
+pub fn watchdog_interval() -> Option<Duration> {
+    // If 'WATCHDOG_PID' is set, the watchdog is meant for that process only.
+    if let Ok(pid) = std::env::var("WATCHDOG_PID")
+        && pid.parse::<u32>().ok() != Some(std::process::id())
+    {
+        return None;
+    }
+
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec) / 2)
+}
+
+//------------ InstanceLock --------------------------------------------------
+
+/// An exclusive lock on Cascade's state.
+///
+/// Two instances of Cascade operating on the same state files would silently
+/// overwrite each other's changes. The lock is held for as long as this value
+/// lives, and is released by the OS if the process dies.
+#[derive(Debug)]
+pub struct InstanceLock {
+    /// The locked file.
+    file: File,
+
+    /// The path of the locked file.
+    path: Utf8PathBuf,
+}
+
+impl InstanceLock {
+    /// The name of the lock file, in the same directory as the state file.
+    const FILE_NAME: &str = "cascaded.lock";
+
+    /// Acquire the lock, alongside the given global state file.
+    ///
+    /// If another process holds the lock, this fails (the error message
+    /// includes the PID of that process, if known), or, if `wait` is set,
+    /// blocks until that process releases it.
+    pub fn acquire(state_file: &Utf8Path, wait: bool) -> Result<Self, String> {
+        let dir = state_file
+            .parent()
+            .ok_or_else(|| format!("State file '{state_file}' has no parent directory"))?;
+        std::fs::create_dir_all(dir)
+            .map_err(|err| format!("Unable to create directory '{dir}': {err}"))?;
+        let path = dir.join(Self::FILE_NAME);
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .map_err(|err| format!("Unable to open lock file '{path}': {err}"))?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut pid = String::new();
+                let _ = file.read_to_string(&mut pid);
+                let holder = match pid.trim() {
+                    "" => "another process".to_string(),
+                    pid => format!("another process (PID {pid})"),
+                };
+                if !wait {
+                    return Err(format!(
+                        "Lock file '{path}' is held by {holder}; is another instance of Cascade using the same state?"
+                    ));
+                }
+                info!("Lock file '{path}' is held by {holder}; waiting for it to exit");
+                file.lock()
+                    .map_err(|err| format!("Unable to lock '{path}': {err}"))?;
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(format!("Unable to lock '{path}': {err}"));
+            }
+        }
+
+        let mut lock = Self { file, path };
+        lock.record_pid()
+            .map_err(|err| format!("Unable to write to lock file '{}': {err}", lock.path))?;
+        Ok(lock)
+    }
+
+    /// Record the PID of the current process in the lock file.
+    ///
+    /// This should be called again after daemonizing, as forking changes the
+    /// PID (the lock itself is inherited).
+    pub fn record_pid(&mut self) -> io::Result<()> {
+        use std::io::Write;
+
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        writeln!(self.file, "{}", std::process::id())?;
+        self.file.flush()
+    }
+}
+
 //------------ SocketType ----------------------------------------------------
 
 /// The type of a socket.
@@ -269,6 +416,12 @@ pub struct SocketProvider {
 
     /// Directly bound TCP sockets, if any.
     own_tcp_listeners: BTreeMap<SocketAddr, TcpListener>,
+
+    /// Whether to set `SO_REUSEPORT` on directly bound sockets.
+    ///
+    /// This allows a new instance of Cascade to bind the same addresses while
+    /// the old instance is still serving, for zero-downtime restarts.
+    reuse_port: bool,
 }
 
 impl SocketProvider {
@@ -282,6 +435,15 @@ impl SocketProvider {
         Default::default()
     }
 
+    /// Set `SO_REUSEPORT` on sockets bound by [`Self::pre_bind_udp()`] and
+    /// [`Self::pre_bind_tcp()`].
+    ///
+    /// Sockets received from systemd are not affected; their options are
+    /// determined by the socket unit (see `ReusePort=`).
+    pub fn set_reuse_port(&mut self, reuse_port: bool) {
+        self.reuse_port = reuse_port;
+    }
+
     /// Capture socket file descriptors from environment variables.
     ///
     /// Uses the following environment variables per [`sd_listen_fds()`]:
@@ -322,12 +484,17 @@ impl SocketProvider {
     /// already been bound, either by the application or systemd. This allows
     /// an application to attempt to bind to the port but not do so (as it
     /// would fail if attempted) if the port was already bound by systemd.
+    ///
+    /// For IPv6 addresses, `v6_only` determines whether `IPV6_V6ONLY` is set.
+    /// It is always set explicitly, so the OS default is never relied upon.
     //
     // TODO: Should we also support being passed existing bound sockets?
-    pub fn pre_bind_udp(&mut self, addr: SocketAddr) -> Result<(), PreBindError> {
+    pub fn pre_bind_udp(&mut self, addr: SocketAddr, v6_only: bool) -> Result<(), PreBindError> {
         if !self.env_sockets.has_udp(&addr) {
-            let socket = UdpSocket::bind(addr).map_err(|err| PreBindError::udp(addr, err))?;
-            let _ = self.own_udp_sockets.insert(addr, socket);
+            let socket = self
+                .bind(addr, v6_only, socket2::Type::DGRAM)
+                .map_err(|err| PreBindError::udp(addr, err))?;
+            let _ = self.own_udp_sockets.insert(addr, socket.into());
         }
         Ok(())
     }
@@ -338,16 +505,48 @@ impl SocketProvider {
     /// already been bound, either by the application or systemd. This allows
     /// an application to attempt to bind to the port but not do so (as it
     /// would fail if attempted) if the port was already bound by systemd.
+    ///
+    /// For IPv6 addresses, `v6_only` determines whether `IPV6_V6ONLY` is set.
+    /// It is always set explicitly, so the OS default is never relied upon.
     //
     // TODO: Should we also support being passed existing bound sockets?
-    pub fn pre_bind_tcp(&mut self, addr: SocketAddr) -> Result<(), PreBindError> {
+    pub fn pre_bind_tcp(&mut self, addr: SocketAddr, v6_only: bool) -> Result<(), PreBindError> {
         if !self.env_sockets.has_tcp(&addr) {
-            let listener = TcpListener::bind(addr).map_err(|err| PreBindError::tcp(addr, err))?;
-            let _ = self.own_tcp_listeners.insert(addr, listener);
+            let listener = self
+                .bind(addr, v6_only, socket2::Type::STREAM)
+                .and_then(|socket| {
+                    socket.listen(1024)?;
+                    Ok(socket)
+                })
+                .map_err(|err| PreBindError::tcp(addr, err))?;
+            let _ = self.own_tcp_listeners.insert(addr, listener.into());
         }
         Ok(())
     }
 
+    /// Create a socket of the given type and bind it to an address.
+    fn bind(
+        &self,
+        addr: SocketAddr,
+        v6_only: bool,
+        ty: socket2::Type,
+    ) -> io::Result<socket2::Socket> {
+        let socket = socket2::Socket::new(socket2::Domain::for_address(addr), ty, None)?;
+        if addr.is_ipv6() {
+            socket.set_only_v6(v6_only)?;
+        }
+        if ty == socket2::Type::STREAM {
+            // Match the standard library, so restarts don't have to wait for
+            // TIME_WAIT connections to expire.
+            socket.set_reuse_address(true)?;
+        }
+        if self.reuse_port {
+            socket.set_reuse_port(true)?;
+        }
+        socket.bind(&addr.into())?;
+        Ok(socket)
+    }
+
     /// Returns a UDP socket that was pre-bound to the specified local
     /// address, whether supplied via the environment or bound directly, if
     /// available.
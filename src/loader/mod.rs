@@ -8,24 +8,26 @@ use std::{
     fmt,
     net::SocketAddr,
     sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{self, AtomicUsize},
     },
     time::{Duration, Instant, SystemTime},
 };
 
 use camino::Utf8Path;
-use domain::tsig;
-use tracing::{debug, error, info};
+use domain::{base::Serial, tsig};
+use tracing::{debug, error, info, warn};
 
 use crate::{
-    api::ZoneReloadError,
+    api::{ZoneAdvanceStage, ZoneReloadError, ZoneRollbackError},
     center::{Center, State},
     common::scheduler::Scheduler,
     loader::zone::EnqueuedRefresh,
+    metrics::XfrType,
+    policy::{LoaderPolicy, SignerSerialPolicy},
     util::AbortOnDrop,
-    zone::{HistoricalEvent, Zone, ZoneByName, ZoneByPtr},
-    zonedata::LoadedZoneBuilder,
+    zone::{HistoricalEvent, Zone, ZoneByName, ZoneByPtr, ZoneState, machine::ZoneStateMachine},
+    zonedata::{DiffData, LoadedZoneBuilder, PatchError},
 };
 
 mod server;
@@ -83,6 +85,7 @@ impl Loader {
 
     pub fn on_refresh_zone(&self, center: &Arc<Center>, zone: &Arc<Zone>) {
         zone.write_handle(center).loader().enqueue_refresh(false);
+        Self::check_superseded(center, zone);
     }
 
     pub fn on_reload_zone(
@@ -98,8 +101,125 @@ impl Loader {
             return Err(ZoneReloadError::ZoneWithoutSource);
         }
         handle.loader().enqueue_refresh(true);
+        drop(handle);
+        Self::check_superseded(center, zone);
         Ok(())
     }
+
+    /// Supersede a pending review if the primary has a newer version.
+    ///
+    /// A zone cannot be loaded while an upcoming instance of it is under
+    /// review, so an enqueued refresh has to wait for the review to finish.
+    /// If the primary of the zone already serves a newer version, the review
+    /// is pointless and is superseded, so that the newer version is loaded in
+    /// its place.
+    ///
+    /// The primary is queried directly (with the configured TSIG key), like
+    /// a refresh would, so that serials announced by (unauthenticated) NOTIFY
+    /// messages cannot cause reviews to be rejected.
+    fn check_superseded(center: &Arc<Center>, zone: &Arc<Zone>) {
+        let (addr, tsig_key, over_tcp) = {
+            let state = zone.read();
+            if !matches!(
+                state.machine,
+                ZoneStateMachine::LoadedReview(_) | ZoneStateMachine::SignedReview(_)
+            ) {
+                return;
+            }
+            let Source::Server {
+                addr,
+                tsig_key,
+                next_tsig_key,
+                ..
+            } = &state.loader.source
+            else {
+                return;
+            };
+            let over_tcp = state
+                .policy
+                .as_ref()
+                .is_some_and(|policy| policy.loader.soa_query_over_tcp);
+            (*addr, next_tsig_key.clone().or(tsig_key.clone()), over_tcp)
+        };
+
+        let center = center.clone();
+        let zone = zone.clone();
+        tokio::spawn(async move {
+            let tsig_key = tsig_key.as_deref().cloned();
+            match server::query_soa(&zone, &addr, tsig_key, over_tcp).await {
+                Ok(soa) => {
+                    let serial = Serial(soa.rdata.serial.into());
+                    zone.write_handle(&center).loader().supersede_review(serial);
+                }
+                Err(error) => {
+                    debug!(
+                        "Could not check whether the pending review of zone '{}' is superseded: {error}",
+                        zone.name
+                    );
+                }
+            }
+        });
+    }
+
+    /// Roll a zone back to its previous version.
+    ///
+    /// On success, the SOA serial of the restored loaded version is returned.
+    pub fn on_rollback_zone(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+    ) -> Result<Serial, ZoneRollbackError> {
+        let mut handle = zone.write_handle(center);
+        let serial = rollback_serial(&handle.state)?;
+
+        if !handle.loader().start_rollback() {
+            return Err(ZoneRollbackError::ZoneBusy);
+        }
+
+        // The restored version was approved before; it only needs to be
+        // reviewed again once it has been signed anew.
+        if handle.state.forced_advance.is_none() {
+            handle.state.forced_advance = Some(ZoneAdvanceStage::Signed);
+        }
+        Ok(serial)
+    }
+}
+
+/// Check whether a zone can be rolled back to its previous version.
+///
+/// On success, the SOA serial of the previous loaded version is returned.
+fn rollback_serial(state: &ZoneState) -> Result<Serial, ZoneRollbackError> {
+    if let Some(reason) = state.halted_reason() {
+        return Err(ZoneRollbackError::ZoneHalted(reason));
+    }
+
+    // Zones transferred from a primary are refreshed automatically, which
+    // would load the newer version again right away.  Zonefiles are only
+    // reloaded on request, so a rollback sticks until then.
+    if let Source::Server { .. } = state.loader.source {
+        return Err(ZoneRollbackError::ServerSource);
+    }
+
+    // The restored version must be published with a newer serial than the
+    // current one, or secondaries would ignore it.
+    if let Some(policy) = &state.policy
+        && let SignerSerialPolicy::Keep = policy.signer.serial_policy
+    {
+        return Err(ZoneRollbackError::SerialPolicyKeep);
+    }
+
+    let diff = previous_loaded_diff(state).ok_or(ZoneRollbackError::NoPreviousVersion)?;
+    diff.removed_soa
+        .as_ref()
+        .map(|soa| Serial(soa.rdata.serial.into()))
+        .ok_or(ZoneRollbackError::NoPreviousVersion)
+}
+
+/// The diff from the previous to the current loaded instance of a zone, if
+/// it has been retained.
+fn previous_loaded_diff(state: &ZoneState) -> Option<Arc<DiffData>> {
+    let current = state.instances.current.as_ref()?;
+    state.storage.diffs.loaded_diff_to(current.loaded.serial())
 }
 
 //----------- refresh() --------------------------------------------------------
@@ -123,8 +243,24 @@ async fn refresh(
 
     let start = Instant::now();
 
+    // Whether the zone was loaded from the fallback zonefile of its source.
+    let mut used_fallback = false;
+
+    // During a TSIG key rotation, the old key is tried last; a verification
+    // failure is only reported if it fails too.
+    let tsig_key_name = match &source {
+        Source::Server {
+            tsig_key: Some(key),
+            ..
+        } => Some(key.name().clone()),
+        _ => None,
+    };
+
     // Perform the source-specific reload into the zone contents.
     let result = match source {
+        _ if refresh == EnqueuedRefresh::Rollback => rollback(&zone, &mut builder)
+            .map(|()| true)
+            .map_err(RefreshError::Rollback),
         Source::None => Ok(false),
         Source::Zonefile { path } => {
             // Zonefile loading is a synchronous process, so it is executing on
@@ -141,16 +277,132 @@ async fn refresh(
             .unwrap();
             result.map(|()| true).map_err(Into::into)
         }
-        Source::Server { addr, tsig_key } if force => {
+        Source::Server {
+            addr,
+            tsig_key,
+            next_tsig_key,
+            fallback,
+        } => {
             let tsig_key = tsig_key.as_deref().cloned();
-            server::axfr(&zone, &addr, tsig_key, &mut builder, &metrics)
-                .await
-                .map(|()| true)
-                .map_err(Into::into)
-        }
-        Source::Server { addr, tsig_key } => {
-            let tsig_key = tsig_key.as_deref().cloned();
-            server::refresh(&zone, &addr, tsig_key, &mut builder, &metrics).await
+            let (policy, on_fallback) = {
+                let state = zone.read();
+                let policy = state
+                    .policy
+                    .as_ref()
+                    .map(|policy| policy.loader.clone())
+                    .unwrap_or_default();
+                (policy, state.loader.on_fallback)
+            };
+
+            // The fallback zonefile need not match the primary's version of
+            // the zone, so re-sync from the primary in full.
+            let force = force || on_fallback;
+
+            // With a fallback zonefile, the primary is given at most the
+            // fallback timeout to respond to a SOA query.  Once it has, the
+            // transfer itself is only limited by the policy.
+            let unreachable = match &fallback {
+                Some(fallback) => {
+                    let probe_key = next_tsig_key.as_deref().or(tsig_key.as_ref()).cloned();
+                    probe_primary(&zone, &addr, probe_key, &policy, fallback.timeout)
+                        .await
+                        .err()
+                }
+                None => None,
+            };
+
+            if let (Some(error), Some(fallback)) = (unreachable, fallback) {
+                warn!(
+                    "The primary {addr} of zone '{}' could not be reached ({error}); loading the fallback zone file '{}'",
+                    zone.name, fallback.path
+                );
+                let result;
+                (builder, result) =
+                    load_fallback(&zone, fallback.path.clone(), builder, &metrics).await;
+                match result {
+                    Ok(true) => {
+                        warn!(
+                            "Zone '{}' is now served from the fallback zone file '{}'",
+                            zone.name, fallback.path
+                        );
+                        used_fallback = true;
+                        Ok(true)
+                    }
+                    Ok(false) => {
+                        info!(
+                            "The fallback zone file '{}' is not newer than the loaded zone '{}'; keeping the latter",
+                            fallback.path, zone.name
+                        );
+                        Err(error)
+                    }
+                    Err(fallback_error) => {
+                        error!(
+                            "The fallback zone file '{}' of zone '{}' could not be loaded: {fallback_error}",
+                            fallback.path, zone.name
+                        );
+                        Err(error)
+                    }
+                }
+            } else {
+                let transfer = async {
+                    let Some(next_tsig_key) = next_tsig_key else {
+                        return transfer_from_server(
+                            &zone,
+                            &addr,
+                            tsig_key,
+                            force,
+                            &policy,
+                            &mut builder,
+                            &metrics,
+                        )
+                        .await;
+                    };
+
+                    // A TSIG key rotation is in progress; the server might not
+                    // have switched to the new key yet.
+                    let result = transfer_from_server(
+                        &zone,
+                        &addr,
+                        Some((*next_tsig_key).clone()),
+                        force,
+                        &policy,
+                        &mut builder,
+                        &metrics,
+                    )
+                    .await;
+                    // Only a rejected key suggests that the server is still on
+                    // the old key; other failures would recur with it too.
+                    match result {
+                        Err(error) if error.is_tsig_failure() => {
+                            warn!(
+                                "Refreshing zone '{}' with the new TSIG key '{}' failed ({error}); retrying with the old key",
+                                zone.name,
+                                next_tsig_key.name()
+                            );
+                            transfer_from_server(
+                                &zone,
+                                &addr,
+                                tsig_key,
+                                force,
+                                &policy,
+                                &mut builder,
+                                &metrics,
+                            )
+                            .await
+                        }
+                        result => result,
+                    }
+                };
+
+                // A transfer that is cut short leaves the current instance of
+                // the zone untouched.
+                match policy.transfer.timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, transfer).await.unwrap_or(Err(
+                        RefreshError::Limit(server::TransferLimitError::Timeout(timeout)),
+                    )),
+                    None => transfer.await,
+                }
+            }
         }
     };
 
@@ -174,6 +426,38 @@ async fn refresh(
             .zone_loaded_last_records(loader_metrics.num_loaded_records as i64);
     }
 
+    // Track consecutive failures, and halt the zone once the configured
+    // maximum is reached.
+    handle
+        .state
+        .loader
+        .record_load_result(result.is_ok(), center.config.loader.max_failures);
+
+    // Track whether the zone is served from its fallback zonefile.
+    if used_fallback {
+        handle.state.loader.on_fallback = true;
+    } else if result.is_ok() && handle.state.loader.on_fallback {
+        info!(
+            "The primary of zone '{}' is reachable again; the zone was re-synced from it",
+            zone.name
+        );
+        handle.state.loader.on_fallback = false;
+    }
+
+    // Count TSIG verification failures, which point to a misconfigured key
+    // or to an attack.
+    if let Err(error) = &result
+        && error.is_tsig_failure()
+        && let Some(key) = tsig_key_name
+    {
+        warn!(
+            "Response for zone '{}' failed verification with TSIG key '{key}'",
+            zone.name
+        );
+        zone.metrics.inc_tsig_verification_failures(&key);
+        *handle.state.loader.tsig_failures.entry(key).or_default() += 1;
+    }
+
     // Update the SOA refresh timer state.
     //
     // NOTE: Zonefiles don't use the SOA refresh timers. They are only
@@ -186,10 +470,14 @@ async fn refresh(
             builder.curr().map(|r| r.soa().clone())
         };
 
+        let retries_exhausted = handle.state.loader.retries_exhausted;
         let refresh_timer = &mut handle.state.loader.refresh_timer;
         let refresh_monitor = &center.loader.refresh_scheduler;
-        if result.is_ok() {
+        // While on the fallback zonefile, keep retrying the primary.
+        if result.is_ok() && !used_fallback {
             refresh_timer.schedule_refresh(&zone, start_time, soa.as_ref(), refresh_monitor);
+        } else if retries_exhausted {
+            refresh_timer.disable(&zone, refresh_monitor);
         } else {
             refresh_timer.schedule_retry(&zone, start_time, soa.as_ref(), refresh_monitor);
         }
@@ -219,6 +507,9 @@ async fn refresh(
 
             // Cancel the load
             handle.get().abandon_load(builder);
+
+            // There is no new version to review along with a batch.
+            handle.state.review_batch = None;
         }
 
         Ok(true) => {
@@ -254,15 +545,26 @@ async fn refresh(
                 "Could not load the zone: {err}"
             );
 
-            // Cancel the load
-            handle.get().abandon_load(builder);
-
+            handle.state.review_batch = None;
             handle.state.record_event(
                 HistoricalEvent::LoadingFailed {
                     reason: err.to_string(),
                 },
                 None,
             );
+
+            // Cancel the load, halting the zone if it failed too often.
+            if handle.state.loader.retries_exhausted {
+                let streak = handle.state.loader.failure_streak;
+                error!(
+                    zone = %zone.name,
+                    "Loading failed {streak} times in a row; halting the zone"
+                );
+                handle.get().loading_failed(builder);
+                handle.state.record_halt();
+            } else {
+                handle.get().abandon_load(builder);
+            }
         }
     }
 }
@@ -303,25 +605,79 @@ pub enum Source {
 
         /// The TSIG key for communicating with the server, if any.
         tsig_key: Option<Arc<tsig::Key>>,
+
+        /// The TSIG key being rotated to, if any.
+        ///
+        /// While a rotation is in progress, the server might or might not have
+        /// switched to the new key yet. Transfers are attempted with this key
+        /// first, falling back to `tsig_key`.
+        next_tsig_key: Option<Arc<tsig::Key>>,
+
+        /// A zonefile to load if the server can't be reached, if any.
+        fallback: Option<Fallback>,
     },
 }
 
+impl Source {
+    /// The TSIG keys used by this source.
+    pub fn tsig_keys(&self) -> impl Iterator<Item = &Arc<tsig::Key>> {
+        let (tsig_key, next_tsig_key) = match self {
+            Source::Server {
+                tsig_key,
+                next_tsig_key,
+                ..
+            } => (tsig_key.as_ref(), next_tsig_key.as_ref()),
+            _ => (None, None),
+        };
+        tsig_key.into_iter().chain(next_tsig_key)
+    }
+}
+
 impl std::fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Source::None => f.write_str("none"),
             Source::Zonefile { path } => write!(f, "zone file '{path}'"),
-            Source::Server { addr, tsig_key } => {
+            Source::Server {
+                addr,
+                tsig_key,
+                next_tsig_key,
+                fallback,
+            } => {
                 write!(f, "{addr}")?;
                 if let Some(tsig_key) = &tsig_key {
                     write!(f, " with TSIG key '{}'", tsig_key.name())?;
                 }
+                if let Some(next_tsig_key) = &next_tsig_key {
+                    write!(f, " (rotating to TSIG key '{}')", next_tsig_key.name())?;
+                }
+                if let Some(fallback) = &fallback {
+                    write!(f, " falling back to zone file '{}'", fallback.path)?;
+                }
                 Ok(())
             }
         }
     }
 }
 
+//----------- Fallback ---------------------------------------------------------
+
+/// A zonefile to fall back to when the primary of a zone is unreachable.
+///
+/// The zonefile is only loaded if it is newer than the loaded instance of the
+/// zone (or if there is none), so that the zone keeps being served during an
+/// outage of the primary.  Once the primary can be reached again, the zone is
+/// transferred from it in full.
+#[derive(Clone, Debug)]
+pub struct Fallback {
+    /// The path to the zonefile.
+    pub path: Box<Utf8Path>,
+
+    /// How long to wait for the primary to answer a SOA query before falling
+    /// back.
+    pub timeout: Duration,
+}
+
 //============ Metrics =========================================================
 
 //----------- LoadMetrics ------------------------------------------------------
@@ -373,6 +729,13 @@ pub struct LoadMetrics {
     /// should be treated as a measure of effort, not information about the new
     /// instance of the zone being built.
     pub num_loaded_records: usize,
+
+    /// The kind of zone transfer that built the new instance of the zone.
+    ///
+    /// This is [`None`] if the zone was loaded from a zonefile, was found to
+    /// be up-to-date, or could not be loaded. An IXFR request answered with
+    /// the whole zone counts as an AXFR.
+    pub transfer: Option<XfrType>,
 }
 
 //----------- ActiveLoadMetrics ------------------------------------------------
@@ -409,6 +772,11 @@ pub struct ActiveLoadMetrics {
     ///
     /// See [`LoadMetrics::num_loaded_records`].
     pub num_loaded_records: AtomicUsize,
+
+    /// The kind of zone transfer that built the new instance of the zone.
+    ///
+    /// See [`LoadMetrics::transfer`].
+    pub transfer: Mutex<Option<XfrType>>,
 }
 
 impl ActiveLoadMetrics {
@@ -420,6 +788,7 @@ impl ActiveLoadMetrics {
             num_loaded_bytes: AtomicUsize::new(0),
             num_total_bytes: AtomicUsize::new(0),
             num_loaded_records: AtomicUsize::new(0),
+            transfer: Mutex::new(None),
         }
     }
 
@@ -440,12 +809,121 @@ impl ActiveLoadMetrics {
             source: self.source.clone(),
             num_loaded_bytes: self.num_loaded_bytes.load(atomic::Ordering::Relaxed),
             num_loaded_records: self.num_loaded_records.load(atomic::Ordering::Relaxed),
+            transfer: *self.transfer.lock().unwrap(),
         }
     }
 }
 
 //============ Errors ==========================================================
 
+//----------- load_fallback() --------------------------------------------------
+
+/// Load a zone from its fallback zonefile.
+///
+/// The zonefile is only used if its SOA serial is newer than that of the
+/// loaded instance of the zone, if any; otherwise, `false` is returned.
+async fn load_fallback(
+    zone: &Arc<Zone>,
+    path: Box<Utf8Path>,
+    mut builder: LoadedZoneBuilder,
+    metrics: &Arc<ActiveLoadMetrics>,
+) -> (LoadedZoneBuilder, Result<bool, RefreshError>) {
+    // Zonefile loading is a synchronous process, so it is executing on its own
+    // blocking task. It cannot borrow 'builder', so 'builder' is moved and
+    // returned by value.
+    let zone = zone.clone();
+    let metrics = metrics.clone();
+    tokio::task::spawn_blocking(move || {
+        let result = zonefile::load(&zone, &path, &mut builder, &metrics)
+            .map_err(RefreshError::from)
+            .map(|()| {
+                let next = Serial::from(builder.next().unwrap().soa().rdata.serial.0.get());
+                builder
+                    .curr()
+                    .is_none_or(|curr| next > Serial::from(curr.soa().rdata.serial.0.get()))
+            });
+        (builder, result)
+    })
+    .await
+    .unwrap()
+}
+
+//----------- probe_primary() --------------------------------------------------
+
+/// Check that the primary of a zone can be reached.
+///
+/// The primary is sent a SOA query, which must be answered within `timeout`.
+/// Only failures to reach the primary are reported; other failures (e.g. of
+/// TSIG verification) are left for the following transfer to report.
+async fn probe_primary(
+    zone: &Arc<Zone>,
+    addr: &SocketAddr,
+    tsig_key: Option<tsig::Key>,
+    policy: &LoaderPolicy,
+    timeout: Duration,
+) -> Result<(), RefreshError> {
+    let query = server::query_soa(zone, addr, tsig_key, policy.soa_query_over_tcp);
+    match tokio::time::timeout(timeout, query).await {
+        Ok(Err(error)) if error.is_unreachable() => Err(RefreshError::QuerySoa(error)),
+        Ok(_) => Ok(()),
+        Err(_) => Err(RefreshError::QuerySoa(server::QuerySoaError::Timeout(
+            timeout,
+        ))),
+    }
+}
+
+//----------- transfer_from_server() -------------------------------------------
+
+/// Transfer a zone from a DNS server, using a particular TSIG key.
+///
+/// If `force` is set, the whole zone is transferred; otherwise, the zone is
+/// only transferred if it has changed, and incrementally if possible.
+async fn transfer_from_server(
+    zone: &Arc<Zone>,
+    addr: &SocketAddr,
+    tsig_key: Option<tsig::Key>,
+    force: bool,
+    policy: &LoaderPolicy,
+    builder: &mut LoadedZoneBuilder,
+    metrics: &ActiveLoadMetrics,
+) -> Result<bool, RefreshError> {
+    if force {
+        server::axfr(zone, addr, tsig_key, &policy.transfer, builder, metrics)
+            .await
+            .map(|()| true)
+            .map_err(Into::into)
+    } else {
+        server::refresh(zone, addr, tsig_key, policy, builder, metrics).await
+    }
+}
+
+//----------- rollback() -------------------------------------------------------
+
+/// Restore the previous version of a zone.
+///
+/// The last approved change to the loaded zone is reverted, by applying its
+/// diff in reverse to the current loaded instance.
+fn rollback(zone: &Zone, builder: &mut LoadedZoneBuilder) -> Result<(), PatchError> {
+    let diff = previous_loaded_diff(&zone.read()).ok_or(PatchError::Empty)?;
+    let (Some(removed_soa), Some(added_soa)) = (&diff.removed_soa, &diff.added_soa) else {
+        return Err(PatchError::MissingSoaChange);
+    };
+
+    let mut writer = builder.patch().ok_or(PatchError::Inconsistency)?;
+
+    // The regular records of the diff include copies of the SOA records.
+    writer.remove_soa(added_soa.clone())?;
+    for record in &diff.added_records {
+        writer.remove(record.clone())?;
+    }
+    writer.add_soa(removed_soa.clone())?;
+    for record in &diff.removed_records {
+        writer.add(record.clone())?;
+    }
+
+    writer.apply()
+}
+
 //----------- RefreshError -----------------------------------------------------
 
 /// An error when refreshing a zone.
@@ -462,6 +940,24 @@ pub enum RefreshError {
 
     /// The zonefile could not be loaded.
     Zonefile(zonefile::Error),
+
+    /// The previous version of the zone could not be restored.
+    Rollback(PatchError),
+
+    /// A limit on the transfer was exceeded.
+    Limit(server::TransferLimitError),
+}
+
+impl RefreshError {
+    /// Whether a response from the server failed TSIG verification.
+    pub fn is_tsig_failure(&self) -> bool {
+        match self {
+            Self::QuerySoa(error) => error.is_tsig_failure(),
+            Self::Ixfr(error) => error.is_tsig_failure(),
+            Self::Axfr(error) => error.is_tsig_failure(),
+            _ => false,
+        }
+    }
 }
 
 impl std::error::Error for RefreshError {
@@ -471,6 +967,8 @@ impl std::error::Error for RefreshError {
             Self::Ixfr(error) => Some(error),
             Self::Axfr(error) => Some(error),
             Self::Zonefile(error) => Some(error),
+            Self::Rollback(error) => Some(error),
+            Self::Limit(error) => Some(error),
         }
     }
 }
@@ -490,6 +988,10 @@ impl fmt::Display for RefreshError {
             RefreshError::Zonefile(error) => {
                 write!(f, "the zonefile could not be loaded: {error}")
             }
+            RefreshError::Rollback(error) => {
+                write!(f, "the previous version could not be restored: {error}")
+            }
+            RefreshError::Limit(error) => write!(f, "{error}"),
         }
     }
 }
@@ -519,3 +1021,80 @@ impl From<zonefile::Error> for RefreshError {
         Self::Zonefile(v)
     }
 }
+
+//============ Tests ===========================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use camino::Utf8Path;
+    use domain::{net::client::request, tsig};
+
+    use super::server::{QuerySoaError, TransferLimitError};
+    use super::{RefreshError, Source, rollback_serial, zone::LoaderState};
+    use crate::api::ZoneRollbackError;
+    use crate::policy::{SignerSerialPolicy, file::v1};
+    use crate::zone::ZoneState;
+    use crate::zone::machine::{HaltLoaded, ZoneStateMachine};
+
+    #[test]
+    fn rollback_requires_a_previous_version() {
+        let state = ZoneState::default();
+        assert!(matches!(
+            rollback_serial(&state),
+            Err(ZoneRollbackError::NoPreviousVersion)
+        ));
+    }
+
+    #[test]
+    fn rollback_is_refused_when_it_would_not_stick() {
+        let mut state = ZoneState {
+            loader: LoaderState {
+                source: Source::Server {
+                    addr: "192.0.2.1:53".parse().unwrap(),
+                    tsig_key: None,
+                    next_tsig_key: None,
+                    fallback: None,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            rollback_serial(&state),
+            Err(ZoneRollbackError::ServerSource)
+        ));
+
+        state.loader.source = Source::Zonefile {
+            path: Utf8Path::new("/zones/example.org").into(),
+        };
+        let mut policy = v1::Spec::default().parse("default");
+        policy.signer.serial_policy = SignerSerialPolicy::Keep;
+        state.set_policy(Arc::new(policy));
+        assert!(matches!(
+            rollback_serial(&state),
+            Err(ZoneRollbackError::SerialPolicyKeep)
+        ));
+
+        state.machine = ZoneStateMachine::HaltLoaded(HaltLoaded {});
+        assert!(matches!(
+            rollback_serial(&state),
+            Err(ZoneRollbackError::ZoneHalted(_))
+        ));
+    }
+
+    #[test]
+    fn only_rejected_keys_are_tsig_failures() {
+        let rejected = RefreshError::QuerySoa(QuerySoaError::Client(
+            request::Error::Authentication(tsig::ValidationError::BadSig),
+        ));
+        assert!(rejected.is_tsig_failure());
+
+        // Retrying with the old key would not help with these.
+        let timeout = RefreshError::QuerySoa(QuerySoaError::Timeout(Duration::from_secs(10)));
+        assert!(!timeout.is_tsig_failure());
+        let limit = RefreshError::Limit(TransferLimitError::Bytes(1000));
+        assert!(!limit.is_tsig_failure());
+    }
+}
@@ -4,11 +4,13 @@ use std::{
     fmt,
     net::SocketAddr,
     sync::{Arc, atomic::Ordering::Relaxed},
+    time::Duration,
 };
 
 use bytes::Bytes;
 use domain::base::MessageBuilder as OldBaseMessageBuilder;
 use domain::base::Rtype;
+use domain::base::Serial as OldBaseSerial;
 use domain::{
     base::{iana::Rcode, wire::FormError},
     net::{
@@ -23,8 +25,7 @@ use domain::{
     },
     new::{
         base::{
-            HeaderFlags, Message, MessageItem, QClass, QType, Question, RClass, RType, Record,
-            Serial,
+            HeaderFlags, Message, MessageItem, QClass, QType, Question, RType, Record, Serial,
             build::MessageBuilder,
             name::{Name, NameCompressor, RevNameBuf},
             wire::{AsBytes, ParseBytes, ParseBytesZC, ParseError},
@@ -37,15 +38,16 @@ use domain::{
     zonetree::types::ZoneUpdate,
 };
 use tokio::net::TcpStream;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 use crate::{
     loader::ActiveLoadMetrics,
     metrics::XfrType,
+    policy::{LoaderPolicy, TransferLimitsPolicy},
     zone::Zone,
     zonedata::{
         LoadedZoneBuilder, LoadedZonePatcher, LoadedZoneReplacer, OldRecord, PatchError,
-        ReplaceError, SoaRecord,
+        ReplaceError, SoaRecord, ZONE_CLASS,
     },
 };
 
@@ -70,30 +72,68 @@ pub async fn refresh(
     zone: &Arc<Zone>,
     addr: &SocketAddr,
     tsig_key: Option<tsig::Key>,
+    policy: &LoaderPolicy,
     builder: &mut LoadedZoneBuilder,
     metrics: &ActiveLoadMetrics,
 ) -> Result<bool, RefreshError> {
     debug!("Refreshing {:?} from server {addr:?}", zone.name);
 
+    let limits = &policy.transfer;
+
     if let Some(curr) = builder.curr() {
         // Check the SOA record upfront.
-        let new_soa = query_soa(zone, addr, tsig_key.clone()).await?;
+        let new_soa = query_soa(zone, addr, tsig_key.clone(), policy.soa_query_over_tcp).await?;
 
         if *curr.soa() == new_soa {
             // The local copy of the zone appears to be up-to-date.
             return Ok(false);
         }
+
+        let local_serial = OldBaseSerial::from(curr.soa().rdata.serial.0.get());
+        let remote_serial = OldBaseSerial::from(new_soa.rdata.serial.0.get());
+        if serials_are_ambiguous(local_serial, remote_serial) {
+            // The server cannot tell which changes we are missing; fetch the
+            // whole zone instead of relying on an IXFR.
+            warn!(
+                "Serial {remote_serial} of zone '{}' at {addr} cannot be ordered relative to local serial {local_serial}; falling back to AXFR",
+                zone.name
+            );
+            axfr(zone, addr, tsig_key, limits, builder, metrics).await?;
+
+            return Ok(true);
+        }
     }
 
     if builder.curr().is_none() {
         // Fetch the whole zone.
-        axfr(zone, addr, tsig_key, builder, metrics).await?;
+        axfr(zone, addr, tsig_key, limits, builder, metrics).await?;
 
         return Ok(true);
     };
 
     // Fetch the zone relative to the latest local copy.
-    Ok(ixfr(zone, addr, tsig_key, builder, metrics).await?)
+    match ixfr(zone, addr, tsig_key.clone(), limits, builder, metrics).await {
+        Err(error) if error.is_inconsistent() => {
+            // The diff does not apply to the local copy; fetch the whole zone
+            // instead, so the zone does not get stuck on the broken diff.
+            warn!(
+                "IXFR of zone '{}' from {addr} was inconsistent with the local copy ({error}); falling back to AXFR",
+                zone.name
+            );
+            axfr(zone, addr, tsig_key, limits, builder, metrics).await?;
+
+            Ok(true)
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Whether two SOA serials cannot be ordered.
+///
+/// Under RFC 1982 serial number arithmetic, serials that are exactly 2^31
+/// apart are neither greater nor less than each other.
+fn serials_are_ambiguous(local: OldBaseSerial, remote: OldBaseSerial) -> bool {
+    local != remote && local.partial_cmp(&remote).is_none()
 }
 
 //----------- ixfr() -----------------------------------------------------------
@@ -115,6 +155,7 @@ pub async fn ixfr(
     zone: &Arc<Zone>,
     addr: &SocketAddr,
     tsig_key: Option<tsig::Key>,
+    limits: &TransferLimitsPolicy,
     builder: &mut LoadedZoneBuilder,
     metrics: &ActiveLoadMetrics,
 ) -> Result<bool, IxfrError> {
@@ -146,7 +187,9 @@ pub async fn ixfr(
                 qname: zone_name,
                 // TODO: 'QType::IXFR'.
                 qtype: QType { code: 251.into() },
-                qclass: QClass::IN,
+                qclass: QClass {
+                    code: ZONE_CLASS.code,
+                },
             })
             .unwrap();
         msgbuilder.push_authority(&local_soa).unwrap();
@@ -195,7 +238,7 @@ pub async fn ixfr(
     if initial.header().rcode() == Rcode::NOTIMP {
         trace!("The server does not support IXFR, falling back to AXFR");
 
-        axfr(zone, addr, tsig_key, builder, metrics).await?;
+        axfr(zone, addr, tsig_key, limits, builder, metrics).await?;
         return Ok(true);
     }
 
@@ -231,7 +274,9 @@ pub async fn ixfr(
         }
     }
 
+    let mut budget = TransferBudget::new(limits, metrics);
     let mut bytes = initial.as_slice().len();
+    budget.received(initial.as_slice().len())?;
     let mut updates = interpreter.interpret_response(initial)?;
 
     match updates.next().unwrap()? {
@@ -241,7 +286,9 @@ pub async fn ixfr(
 
             // Process the response messages.
             let soa = loop {
-                if let Some(soa) = process_axfr(&mut writer, updates, metrics)? {
+                let soa = process_axfr(&mut writer, updates, metrics)?;
+                budget.processed(metrics)?;
+                if let Some(soa) = soa {
                     break soa;
                 } else {
                     // Retrieve the next message.
@@ -250,6 +297,7 @@ pub async fn ixfr(
                         .await?
                         .ok_or(IxfrError::IncompleteResponse)?;
                     bytes += message.as_slice().len();
+                    budget.received(message.as_slice().len())?;
                     updates = interpreter.interpret_response(message)?;
                 }
             };
@@ -259,6 +307,8 @@ pub async fn ixfr(
             writer.set_soa(soa)?;
             writer.apply()?;
             metrics.num_loaded_bytes.fetch_add(bytes, Relaxed);
+            // The server sent the whole zone rather than a diff.
+            *metrics.transfer.lock().unwrap() = Some(XfrType::Axfr);
 
             zone.metrics
                 .inc_xfr_requests_to_upstream_succeeded(XfrType::Ixfr);
@@ -279,6 +329,7 @@ pub async fn ixfr(
             // Process the response messages.
             loop {
                 process_ixfr(&mut writer, updates, metrics)?;
+                budget.processed(metrics)?;
 
                 if interpreter.is_finished() {
                     break;
@@ -289,6 +340,7 @@ pub async fn ixfr(
                         .await?
                         .ok_or(IxfrError::IncompleteResponse)?;
                     bytes += message.as_slice().len();
+                    budget.received(message.as_slice().len())?;
                     updates = interpreter.interpret_response(message)?;
                 }
             }
@@ -297,6 +349,7 @@ pub async fn ixfr(
             assert!(interpreter.is_finished());
 
             writer.apply()?;
+            *metrics.transfer.lock().unwrap() = Some(XfrType::Ixfr);
 
             zone.metrics
                 .inc_xfr_requests_to_upstream_succeeded(XfrType::Ixfr);
@@ -362,6 +415,7 @@ pub async fn axfr(
     zone: &Arc<Zone>,
     addr: &SocketAddr,
     tsig_key: Option<tsig::Key>,
+    limits: &TransferLimitsPolicy,
     builder: &mut LoadedZoneBuilder,
     metrics: &ActiveLoadMetrics,
 ) -> Result<(), AxfrError> {
@@ -383,7 +437,9 @@ pub async fn axfr(
             qname: zone_name,
             // TODO: 'QType::AXFR'.
             qtype: QType { code: 252.into() },
-            qclass: QClass::IN,
+            qclass: QClass {
+                code: ZONE_CLASS.code,
+            },
         })
         .unwrap();
     let message = Bytes::copy_from_slice(msgbuilder.finish().as_bytes());
@@ -426,9 +482,11 @@ pub async fn axfr(
         .await?
         .ok_or(AxfrError::IncompleteResponse)?;
 
+    let mut budget = TransferBudget::new(limits, metrics);
     metrics
         .num_loaded_bytes
         .fetch_add(initial.as_slice().len(), Relaxed);
+    budget.received(initial.as_slice().len())?;
     let mut updates = interpreter.interpret_response(initial)?;
 
     assert!(updates.next().unwrap()? == ZoneUpdate::DeleteAllRecords);
@@ -436,7 +494,9 @@ pub async fn axfr(
 
     // Process the response messages.
     let soa = loop {
-        if let Some(soa) = process_axfr(&mut writer, updates, metrics)? {
+        let soa = process_axfr(&mut writer, updates, metrics)?;
+        budget.processed(metrics)?;
+        if let Some(soa) = soa {
             break soa;
         } else {
             // Retrieve the next message.
@@ -447,6 +507,7 @@ pub async fn axfr(
             metrics
                 .num_loaded_bytes
                 .fetch_add(message.as_slice().len(), Relaxed);
+            budget.received(message.as_slice().len())?;
             updates = interpreter.interpret_response(message)?;
         }
     };
@@ -455,6 +516,7 @@ pub async fn axfr(
     writer.add(soa.clone().into())?;
     writer.set_soa(soa)?;
     writer.apply()?;
+    *metrics.transfer.lock().unwrap() = Some(XfrType::Axfr);
 
     zone.metrics
         .inc_xfr_requests_to_upstream_succeeded(XfrType::Axfr);
@@ -487,13 +549,64 @@ fn process_axfr(
     Ok(None)
 }
 
+//----------- TransferBudget ---------------------------------------------------
+
+/// Tracks a single zone transfer against its [`TransferLimitsPolicy`].
+struct TransferBudget<'a> {
+    /// The limits to enforce.
+    limits: &'a TransferLimitsPolicy,
+
+    /// The number of bytes received thus far.
+    bytes: u64,
+
+    /// The number of records loaded before this transfer began.
+    ///
+    /// [`ActiveLoadMetrics::num_loaded_records`] includes the records of
+    /// earlier transfers of the same load (e.g. an IXFR before falling back to
+    /// AXFR); they do not count towards the limits of this transfer.
+    start_records: usize,
+}
+
+impl<'a> TransferBudget<'a> {
+    /// Begin tracking a new transfer.
+    fn new(limits: &'a TransferLimitsPolicy, metrics: &ActiveLoadMetrics) -> Self {
+        Self {
+            limits,
+            bytes: 0,
+            start_records: metrics.num_loaded_records.load(Relaxed),
+        }
+    }
+
+    /// Account for a received message.
+    fn received(&mut self, len: usize) -> Result<(), TransferLimitError> {
+        self.bytes += len as u64;
+        match self.limits.max_bytes {
+            Some(max) if self.bytes > max => Err(TransferLimitError::Bytes(max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the number of records processed thus far.
+    fn processed(&self, metrics: &ActiveLoadMetrics) -> Result<(), TransferLimitError> {
+        let records = metrics.num_loaded_records.load(Relaxed) - self.start_records;
+        match self.limits.max_records {
+            Some(max) if records as u64 > max => Err(TransferLimitError::Records(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
 //----------- query_soa() ------------------------------------------------------
 
 /// Query a DNS server for the SOA record of a zone.
+///
+/// If `over_tcp` is set, the query is only sent over TCP; otherwise, it is
+/// sent over UDP and retried over TCP if the response is truncated.
 pub async fn query_soa(
     zone: &Arc<Zone>,
     addr: &SocketAddr,
     tsig_key: Option<tsig::Key>,
+    over_tcp: bool,
 ) -> Result<SoaRecord, QuerySoaError> {
     let zone_name: RevNameBuf = ParseBytes::parse_bytes(zone.name.as_slice()).unwrap();
 
@@ -510,14 +623,46 @@ pub async fn query_soa(
         .push_question(&Question {
             qname: &zone_name,
             qtype: QType::SOA,
-            qclass: QClass::IN,
+            qclass: QClass {
+                code: ZONE_CLASS.code,
+            },
         })
         .unwrap();
     let message = Bytes::copy_from_slice(builder.finish().as_bytes());
     let message =
         domain::base::Message::from_octets(message).expect("'Message' is at least 12 bytes long");
 
-    let response = if let Some(tsig_key) = tsig_key {
+    let response = if over_tcp {
+        // Responses over TCP cannot be spoofed by off-path attackers.
+        let tcp_conn = TcpStream::connect(*addr)
+            .await
+            .map_err(QuerySoaError::Connection)?;
+        if let Some(tsig_key) = tsig_key {
+            let (client, transport) = client::stream::Connection::<
+                client::tsig::RequestMessage<RequestMessage<Bytes>, Arc<tsig::Key>>,
+                RequestMessageMulti<Bytes>,
+            >::new(tcp_conn);
+            tokio::task::spawn(transport.run());
+
+            let client = client::tsig::Connection::new(Arc::new(tsig_key), client);
+
+            // Send the query.
+            let request = RequestMessage::new(message.clone()).unwrap();
+            SendRequest::send_request(&client, request)
+                .get_response()
+                .await?
+        } else {
+            let (client, transport) = client::stream::Connection::<
+                RequestMessage<Bytes>,
+                RequestMessageMulti<Bytes>,
+            >::new(tcp_conn);
+            tokio::task::spawn(transport.run());
+
+            // Send the query.
+            let request = RequestMessage::new(message.clone()).unwrap();
+            client.send_request(request).get_response().await?
+        }
+    } else if let Some(tsig_key) = tsig_key {
         let udp_conn = client::protocol::UdpConnect::new(*addr);
         let tcp_conn = client::protocol::TcpConnect::new(*addr);
         let (client, transport) = client::dgram_stream::Connection::new(udp_conn, tcp_conn);
@@ -553,25 +698,25 @@ pub async fn query_soa(
     let Some(MessageItem::Question(Question {
         qname,
         qtype: QType::SOA,
-        qclass: QClass::IN,
+        qclass,
     })) = parser.next().transpose()?
     else {
         return Err(QuerySoaError::MismatchedResponse);
     };
-    if qname != zone_name {
+    if qname != zone_name || qclass.code != ZONE_CLASS.code {
         return Err(QuerySoaError::MismatchedResponse);
     }
     let Some(MessageItem::Answer(Record {
         rname,
         rtype: rtype @ RType::SOA,
-        rclass: rclass @ RClass::IN,
+        rclass,
         ttl,
         rdata: RecordData::Soa(rdata),
     })) = parser.next().transpose()?
     else {
         return Err(QuerySoaError::MismatchedResponse);
     };
-    if rname != zone_name {
+    if rname != zone_name || rclass != ZONE_CLASS {
         return Err(QuerySoaError::MismatchedResponse);
     }
 
@@ -619,6 +764,36 @@ pub enum IxfrError {
 
     /// The zone data could not be written.
     Write(PatchError),
+
+    /// A limit on the transfer was exceeded.
+    Limit(TransferLimitError),
+}
+
+impl IxfrError {
+    /// Whether the IXFR was inconsistent with the local copy of the zone.
+    ///
+    /// Such failures can be recovered from by transferring the whole zone.
+    pub fn is_inconsistent(&self) -> bool {
+        matches!(
+            self,
+            Self::InconsistentUpToDate
+                | Self::Write(
+                    PatchError::Inconsistency
+                        | PatchError::MissingSoaChange
+                        | PatchError::MultipleSoasAdded
+                )
+        )
+    }
+
+    /// Whether a response failed TSIG verification.
+    pub fn is_tsig_failure(&self) -> bool {
+        match self {
+            Self::Client(error) => is_tsig_failure(error),
+            Self::QuerySoa(error) => error.is_tsig_failure(),
+            Self::Axfr(error) => error.is_tsig_failure(),
+            _ => false,
+        }
+    }
 }
 
 impl std::error::Error for IxfrError {
@@ -633,6 +808,7 @@ impl std::error::Error for IxfrError {
             IxfrError::QuerySoa(error) => Some(error),
             IxfrError::Axfr(error) => Some(error),
             IxfrError::Write(error) => Some(error),
+            IxfrError::Limit(error) => Some(error),
         }
     }
 }
@@ -662,6 +838,7 @@ impl fmt::Display for IxfrError {
             IxfrError::Write(error) => {
                 write!(f, "could not write the zone data: {error}")
             }
+            IxfrError::Limit(error) => write!(f, "{error}"),
         }
     }
 }
@@ -704,6 +881,12 @@ impl From<PatchError> for IxfrError {
     }
 }
 
+impl From<TransferLimitError> for IxfrError {
+    fn from(error: TransferLimitError) -> Self {
+        Self::Limit(error)
+    }
+}
+
 impl From<ReplaceError> for IxfrError {
     fn from(error: ReplaceError) -> Self {
         Self::Axfr(AxfrError::Write(error))
@@ -732,6 +915,19 @@ pub enum AxfrError {
 
     /// The zone data could not be written.
     Write(ReplaceError),
+
+    /// A limit on the transfer was exceeded.
+    Limit(TransferLimitError),
+}
+
+impl AxfrError {
+    /// Whether a response failed TSIG verification.
+    pub fn is_tsig_failure(&self) -> bool {
+        match self {
+            Self::Client(error) => is_tsig_failure(error),
+            _ => false,
+        }
+    }
 }
 
 impl std::error::Error for AxfrError {
@@ -743,6 +939,7 @@ impl std::error::Error for AxfrError {
             AxfrError::XfrIter(_) => None,
             AxfrError::IncompleteResponse => None,
             AxfrError::Write(error) => Some(error),
+            AxfrError::Limit(error) => Some(error),
         }
     }
 }
@@ -768,6 +965,7 @@ impl fmt::Display for AxfrError {
             AxfrError::Write(error) => {
                 write!(f, "could not write the zone data: {error}")
             }
+            AxfrError::Limit(error) => write!(f, "{error}"),
         }
     }
 }
@@ -798,11 +996,83 @@ impl From<ReplaceError> for AxfrError {
     }
 }
 
+impl From<TransferLimitError> for AxfrError {
+    fn from(error: TransferLimitError) -> Self {
+        Self::Limit(error)
+    }
+}
+
+/// Whether a DNS client error is due to a failed TSIG verification.
+fn is_tsig_failure(error: &client::request::Error) -> bool {
+    matches!(error, client::request::Error::Authentication(_))
+}
+
+/// Whether a request failed because the server could not be reached.
+///
+/// Only failures to connect to the server, of the connection itself, and to
+/// receive a response in time are included.  Responses that were received
+/// but are unusable (e.g. malformed ones) do not count.
+fn is_unreachable(error: &client::request::Error) -> bool {
+    use client::request::Error;
+
+    matches!(
+        error,
+        Error::ConnectionClosed
+            | Error::NoTransportAvailable
+            | Error::Dgram(_)
+            | Error::StreamIdleTimeout
+            | Error::StreamReadError(_)
+            | Error::StreamReadTimeout
+            | Error::StreamWriteError(_)
+            | Error::StreamUnexpectedEndOfData
+    )
+}
+
+//----------- TransferLimitError -----------------------------------------------
+
+/// A limit on an inbound zone transfer was exceeded.
+#[derive(Clone, Debug)]
+pub enum TransferLimitError {
+    /// The transfer was larger than the given number of bytes.
+    Bytes(u64),
+
+    /// The transfer contained more than the given number of records.
+    Records(u64),
+
+    /// The transfer did not finish within the given time.
+    Timeout(Duration),
+}
+
+impl std::error::Error for TransferLimitError {}
+
+impl fmt::Display for TransferLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferLimitError::Bytes(max) => {
+                write!(f, "the transfer exceeded the limit of {max} bytes")
+            }
+            TransferLimitError::Records(max) => {
+                write!(f, "the transfer exceeded the limit of {max} records")
+            }
+            TransferLimitError::Timeout(timeout) => {
+                write!(
+                    f,
+                    "the transfer did not finish within {} seconds",
+                    timeout.as_secs()
+                )
+            }
+        }
+    }
+}
+
 //----------- QuerySoaError ----------------------------------------------------
 
 /// An error when querying a DNS server for a SOA record.
 #[derive(Debug)]
 pub enum QuerySoaError {
+    /// Could not connect to the server.
+    Connection(std::io::Error),
+
     /// A DNS client error occurred.
     Client(client::request::Error),
 
@@ -811,14 +1081,38 @@ pub enum QuerySoaError {
 
     /// The response did not match the query.
     MismatchedResponse,
+
+    /// No response was received within the given time.
+    Timeout(Duration),
+}
+
+impl QuerySoaError {
+    /// Whether the response failed TSIG verification.
+    pub fn is_tsig_failure(&self) -> bool {
+        match self {
+            Self::Client(error) => is_tsig_failure(error),
+            _ => false,
+        }
+    }
+
+    /// Whether the server could not be reached, or did not respond in time.
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            Self::Client(error) => is_unreachable(error),
+            Self::Connection(_) | Self::Timeout(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl std::error::Error for QuerySoaError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
+            QuerySoaError::Connection(error) => Some(error),
             QuerySoaError::Client(error) => Some(error),
             QuerySoaError::Parse(_) => None,
             QuerySoaError::MismatchedResponse => None,
+            QuerySoaError::Timeout(_) => None,
         }
     }
 }
@@ -826,6 +1120,9 @@ impl std::error::Error for QuerySoaError {
 impl fmt::Display for QuerySoaError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            QuerySoaError::Connection(error) => {
+                write!(f, "could not connect to the server: {error}")
+            }
             QuerySoaError::Client(error) => {
                 write!(f, "could not communicate with the server: {error}")
             }
@@ -833,6 +1130,13 @@ impl fmt::Display for QuerySoaError {
             QuerySoaError::MismatchedResponse => {
                 write!(f, "the server's response did not match the query")
             }
+            QuerySoaError::Timeout(timeout) => {
+                write!(
+                    f,
+                    "the server did not respond within {}s",
+                    timeout.as_secs()
+                )
+            }
         }
     }
 }
@@ -850,3 +1154,99 @@ impl From<ParseError> for QuerySoaError {
         Self::Parse(v)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering::Relaxed;
+    use std::time::Duration;
+
+    use domain::base::Serial;
+    use domain::net::client::request;
+    use domain::tsig;
+
+    use super::{QuerySoaError, TransferBudget, TransferLimitError, serials_are_ambiguous};
+    use crate::loader::{ActiveLoadMetrics, Source};
+    use crate::policy::TransferLimitsPolicy;
+
+    #[test]
+    fn serials_near_wraparound() {
+        // Serials that wrap around are ordered.
+        assert!(!serials_are_ambiguous(Serial(u32::MAX), Serial(0)));
+        assert!(!serials_are_ambiguous(Serial(u32::MAX - 5), Serial(10)));
+
+        // The largest possible increment is still ordered.
+        assert!(!serials_are_ambiguous(Serial(0), Serial((1 << 31) - 1)));
+        assert!(!serials_are_ambiguous(
+            Serial(u32::MAX),
+            Serial((1 << 31) - 2)
+        ));
+
+        // Serials exactly 2^31 apart cannot be ordered.
+        assert!(serials_are_ambiguous(Serial(0), Serial(1 << 31)));
+        assert!(serials_are_ambiguous(Serial(1 << 31), Serial(0)));
+        assert!(serials_are_ambiguous(
+            Serial(u32::MAX),
+            Serial((1 << 31) - 1)
+        ));
+
+        // Equal serials are never ambiguous.
+        assert!(!serials_are_ambiguous(Serial(1 << 31), Serial(1 << 31)));
+    }
+
+    #[test]
+    fn transfer_budget_enforces_limits() {
+        let limits = TransferLimitsPolicy {
+            max_bytes: Some(1000),
+            max_records: Some(10),
+            timeout: None,
+        };
+        let metrics = ActiveLoadMetrics::begin(Source::None);
+
+        // Records loaded by an earlier transfer of the same load don't count.
+        metrics.num_loaded_records.store(100, Relaxed);
+        let mut budget = TransferBudget::new(&limits, &metrics);
+
+        assert!(budget.received(600).is_ok());
+        assert!(matches!(
+            budget.received(600),
+            Err(TransferLimitError::Bytes(1000))
+        ));
+
+        metrics.num_loaded_records.store(110, Relaxed);
+        assert!(budget.processed(&metrics).is_ok());
+        metrics.num_loaded_records.store(111, Relaxed);
+        assert!(matches!(
+            budget.processed(&metrics),
+            Err(TransferLimitError::Records(10))
+        ));
+    }
+
+    #[test]
+    fn transfer_budget_is_unlimited_by_default() {
+        let limits = TransferLimitsPolicy::default();
+        let metrics = ActiveLoadMetrics::begin(Source::None);
+        let mut budget = TransferBudget::new(&limits, &metrics);
+
+        assert!(budget.received(usize::MAX / 2).is_ok());
+        metrics.num_loaded_records.store(usize::MAX / 2, Relaxed);
+        assert!(budget.processed(&metrics).is_ok());
+    }
+
+    #[test]
+    fn only_connection_failures_make_the_primary_unreachable() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(QuerySoaError::Connection(refused).is_unreachable());
+        assert!(QuerySoaError::Timeout(Duration::from_secs(30)).is_unreachable());
+        assert!(QuerySoaError::Client(request::Error::ConnectionClosed).is_unreachable());
+        assert!(QuerySoaError::Client(request::Error::StreamReadTimeout).is_unreachable());
+
+        // The primary answered, so the fallback zone file is not needed.
+        assert!(!QuerySoaError::MismatchedResponse.is_unreachable());
+        assert!(
+            !QuerySoaError::Client(request::Error::Authentication(
+                tsig::ValidationError::BadSig
+            ))
+            .is_unreachable()
+        );
+    }
+}
@@ -1,17 +1,21 @@
 //! Zone-specific loader state.
 
 use std::{
+    collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use domain::{base::Serial, tsig};
 use tracing::{debug, info};
 
 use crate::{
     center::Center,
     common::scheduler::Scheduler,
+    metrics::ReviewOutcome,
+    policy::OnReject,
     util::AbortOnDrop,
-    zone::{HistoricalEvent, Zone, ZoneByPtr, ZoneHandle, ZoneState},
+    zone::{HistoricalEvent, Zone, ZoneByPtr, ZoneHandle, ZoneState, machine::ZoneStateMachine},
     zonedata::{LoadedZoneBuilder, SoaRecord},
 };
 
@@ -109,6 +113,81 @@ impl LoaderZoneHandle<'_> {
         }
     }
 
+    /// Supersede a pending review of this zone.
+    ///
+    /// A new load cannot begin while an upcoming instance of the zone is under
+    /// review.  When the source of the zone is known to serve a newer version,
+    /// the pending review is pointless; it is (softly) rejected, so that an
+    /// enqueued refresh can load the newer version in its place.
+    ///
+    /// `newer` is the SOA serial served by the source of the zone.  If it is
+    /// not newer than the instance under review, the review is left alone.
+    /// Reviews whose rejection would halt the zone (as configured by the
+    /// policy), and approved instances that are staged or held back, are never
+    /// superseded.
+    pub fn supersede_review(&mut self, newer: Serial) {
+        let Some(pending) = match &self.state.instances.upcoming {
+            Some(upcoming) => upcoming.loaded.as_ref(),
+            None => None,
+        }
+        .or(self.state.instances.current.as_ref().map(|c| &c.loaded))
+        .map(|loaded| Serial(loaded.serial().get())) else {
+            return;
+        };
+
+        if newer <= pending {
+            return;
+        }
+
+        if !may_supersede_review(self.state) {
+            info!(
+                "A newer version (serial {newer}) of zone '{}' is available, but its pending review cannot be superseded",
+                self.zone.name
+            );
+            return;
+        }
+
+        let reason = format!("superseded by a newer version (serial {newer})");
+
+        match self.state.machine {
+            ZoneStateMachine::LoadedReview(_) => {
+                info!(
+                    "Rejecting the loaded review of zone '{}': {reason}",
+                    self.zone.name
+                );
+                self.zone()
+                    .soft_reject_loaded(Some(reason), ReviewOutcome::Superseded);
+            }
+            ZoneStateMachine::SignedReview(_) => {
+                info!(
+                    "Rejecting the signed review of zone '{}': {reason}",
+                    self.zone.name
+                );
+                self.zone()
+                    .soft_reject_signed(Some(reason), ReviewOutcome::Superseded);
+            }
+            _ => {}
+        }
+    }
+
+    /// Start rolling back to the previous version of this zone.
+    ///
+    /// Instead of contacting the source of the zone, the new loaded instance
+    /// is built by reverting the last approved change to the loaded zone.  It
+    /// then goes through the rest of the pipeline (review, signing, and
+    /// publication) like any other loaded instance.
+    ///
+    /// Returns `false` if the zone is busy, in which case nothing is done.
+    pub fn start_rollback(&mut self) -> bool {
+        let Some(builder) = self.zone().try_start_load() else {
+            return false;
+        };
+
+        info!("Rolling back zone '{}'", self.zone.name);
+        self.start(EnqueuedRefresh::Rollback, builder);
+        true
+    }
+
     /// Start a pending enqueued refresh.
     ///
     /// This should be called when the zone data storage is in the passive
@@ -188,6 +267,40 @@ pub struct LoaderState {
     //
     // TODO: Make part of zone history?
     pub last_load_metrics: Option<LoadMetrics>,
+
+    /// The number of consecutive failed loads.
+    pub failure_streak: u32,
+
+    /// Whether the zone was halted after too many consecutive failures.
+    pub retries_exhausted: bool,
+
+    /// The number of loads and inbound NOTIFY messages that failed TSIG
+    /// verification, per TSIG key.
+    pub tsig_failures: HashMap<tsig::KeyName, u64>,
+
+    /// Whether the zone was last loaded from the fallback zonefile of its
+    /// source, rather than from the primary.
+    pub on_fallback: bool,
+}
+
+impl LoaderState {
+    /// Track the outcome of a load in the failure streak.
+    ///
+    /// A successful load resets the streak.  Retries are exhausted once the
+    /// streak reaches `max_failures`, if set.
+    pub fn record_load_result(&mut self, success: bool, max_failures: Option<u32>) {
+        if success {
+            self.failure_streak = 0;
+            self.retries_exhausted = false;
+        } else {
+            self.failure_streak += 1;
+            if let Some(max) = max_failures
+                && self.failure_streak >= max
+            {
+                self.retries_exhausted = true;
+            }
+        }
+    }
 }
 
 //----------- RefreshTimerState ------------------------------------------------
@@ -353,4 +466,110 @@ pub enum EnqueuedRefresh {
 
     /// An enqueued reload.
     Reload,
+
+    /// A rollback to the previous version of the zone.
+    ///
+    /// Rollbacks are never actually enqueued; see
+    /// [`LoaderZoneHandle::start_rollback()`].
+    Rollback,
+}
+
+/// Whether the pending review of a zone may be superseded.
+///
+/// Reviews whose rejection would halt the zone (as configured by the policy),
+/// and approved instances that are staged or held back, are never superseded.
+fn may_supersede_review(state: &ZoneState) -> bool {
+    if state.staged_since.is_some() || state.held_since.is_some() {
+        return false;
+    }
+
+    let Some(policy) = &state.policy else {
+        return false;
+    };
+    let on_reject = match state.machine {
+        ZoneStateMachine::LoadedReview(_) => &policy.loader.review.on_reject,
+        ZoneStateMachine::SignedReview(_) => &policy.signer.review.on_reject,
+        _ => return false,
+    };
+    *on_reject != OnReject::Halt
+}
+
+//============ Tests ===========================================================
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    use super::{LoaderState, may_supersede_review};
+    use crate::policy::{OnReject, file::v1};
+    use crate::zone::ZoneState;
+    use crate::zone::machine::{LoadedReview, SignedReview, Waiting, ZoneStateMachine};
+
+    #[test]
+    fn failure_streak_exhausts_retries() {
+        let mut state = LoaderState::default();
+        state.record_load_result(false, Some(3));
+        state.record_load_result(false, Some(3));
+        assert_eq!(state.failure_streak, 2);
+        assert!(!state.retries_exhausted);
+
+        state.record_load_result(false, Some(3));
+        assert_eq!(state.failure_streak, 3);
+        assert!(state.retries_exhausted);
+    }
+
+    #[test]
+    fn failure_streak_is_unlimited_by_default() {
+        let mut state = LoaderState::default();
+        for _ in 0..100 {
+            state.record_load_result(false, None);
+        }
+        assert_eq!(state.failure_streak, 100);
+        assert!(!state.retries_exhausted);
+    }
+
+    #[test]
+    fn successful_load_resets_failure_streak() {
+        let mut state = LoaderState::default();
+        state.record_load_result(false, Some(1));
+        assert!(state.retries_exhausted);
+
+        state.record_load_result(true, Some(1));
+        assert_eq!(state.failure_streak, 0);
+        assert!(!state.retries_exhausted);
+    }
+
+    #[test]
+    fn only_discardable_reviews_are_superseded() {
+        let mut policy = v1::Spec::default().parse("default");
+        policy.loader.review.on_reject = OnReject::Discard;
+        policy.signer.review.on_reject = OnReject::Halt;
+
+        let mut state = ZoneState::default();
+        state.set_policy(Arc::new(policy));
+
+        state.machine = ZoneStateMachine::LoadedReview(LoadedReview {});
+        assert!(may_supersede_review(&state));
+
+        // Rejecting the signed review would halt the zone.
+        state.machine = ZoneStateMachine::SignedReview(SignedReview {});
+        assert!(!may_supersede_review(&state));
+
+        // There is no review to supersede.
+        state.machine = ZoneStateMachine::Waiting(Waiting {});
+        assert!(!may_supersede_review(&state));
+    }
+
+    #[test]
+    fn staged_instances_are_not_superseded() {
+        let mut policy = v1::Spec::default().parse("default");
+        policy.loader.review.on_reject = OnReject::Discard;
+
+        let mut state = ZoneState::default();
+        state.set_policy(Arc::new(policy));
+        state.machine = ZoneStateMachine::LoadedReview(LoadedReview {});
+        state.staged_since = Some(SystemTime::now());
+        assert!(!may_supersede_review(&state));
+    }
 }
@@ -21,7 +21,7 @@ use domain::{
 use crate::{
     loader::ActiveLoadMetrics,
     zone::Zone,
-    zonedata::{LoadedZoneBuilder, RegularRecord, ReplaceError, SoaRecord},
+    zonedata::{LoadedZoneBuilder, RegularRecord, ReplaceError, SoaRecord, ZONE_CLASS},
 };
 
 //----------- load() -----------------------------------------------------------
@@ -84,7 +84,7 @@ fn make_reader(
 
     let mut reader = zone_file.into_inner();
     reader.set_origin(zone.name.clone());
-    reader.set_default_class(Class::IN);
+    reader.set_default_class(Class::from(u16::from(ZONE_CLASS.code)));
 
     Ok(reader)
 }
@@ -194,6 +194,11 @@ impl fmt::Display for Error {
             Error::Write(ReplaceError::MultipleSoas) => {
                 write!(f, "the zonefile contains multiple SOA records")
             }
+            Error::Write(ReplaceError::UnsupportedClass(class)) => write!(
+                f,
+                "the zonefile contains records of class {}, but only IN is supported",
+                u16::from(class.code)
+            ),
         }
     }
 }
@@ -5,19 +5,23 @@ use crate::{
 
 use self::{
     center::Center,
+    common::store::{FileStateStore, StateStore},
     config::{Config, SocketConfig},
-    daemon::{PreBindError, SocketProvider, daemonize},
+    daemon::{InstanceLock, PreBindError, SocketProvider, daemonize, notify_service_manager},
     loader::Loader,
     manager::Manager,
     server::{LoadedReviewServer, PublicationServer, SignedReviewServer},
     units::{key_manager::KeyManager, zone_signer::ZoneSigner},
-    zone::{Zone, ZoneByName},
+    zone::{Zone, ZoneByName, state as zone_state},
 };
+use bytes::Bytes;
 use clap::{crate_authors, crate_description};
 use daemonbase::process::exit_signalled;
+use domain::base::Name;
 use std::{collections::HashMap, fs::create_dir_all};
 use std::{
     io,
+    num::NonZeroUsize,
     process::ExitCode,
     sync::{Arc, Mutex},
 };
@@ -100,166 +104,208 @@ fn main() -> ExitCode {
         return ExitCode::FAILURE;
     }
 
+    // With 'reuse-port', bind to listen addresses before taking the lock, so
+    // that a running instance can be replaced without refusing traffic: the
+    // kernel queues it on our sockets until the old instance has exited.
+    let mut socket_provider = None;
+    if config.daemon.reuse_port {
+        let Ok(provider) = bind_to_listen_sockets_as_needed(&config) else {
+            return ExitCode::FAILURE;
+        };
+        socket_provider = Some(provider);
+    }
+
+    // Make sure no other instance of Cascade is using the same state.  With
+    // 'reuse-port', wait for the old instance to hand over instead.
+    let wait = config.daemon.reuse_port;
+    let mut instance_lock = match InstanceLock::acquire(config.daemon.state_file.value(), wait) {
+        Ok(lock) => lock,
+        Err(err) => {
+            error!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
     // Load the global state file or build one from scratch.
     let mut zones = Default::default();
     let mut policies = Default::default();
     let metrics = Metrics::new();
-    let state = match center::State::init_from_file(&config, &mut zones, &mut policies) {
-        Ok(mut state) => {
-            info!(
-                "Loaded the global state file (from '{}')",
-                config.daemon.state_file.value()
-            );
+    let state_store: Arc<dyn StateStore> = Arc::new(FileStateStore);
+    let state =
+        match center::State::init_from_file(&config, &*state_store, &mut zones, &mut policies) {
+            Ok(mut state) => {
+                info!(
+                    "Loaded the global state file (from '{}')",
+                    config.daemon.state_file.value()
+                );
 
-            // Load the TSIG store file.
-            match state.tsig_store.init_from_file(&config) {
-                Ok(()) => debug!("Loaded the TSIG store (from '{}')", config.tsig_store_path),
-                Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                    debug!(
-                        "TSIG store file '{}' did not exist; it will be created",
-                        config.tsig_store_path
-                    );
+                // Load the TSIG store file.
+                match state.tsig_store.init_from_file(&config) {
+                    Ok(()) => debug!("Loaded the TSIG store (from '{}')", config.tsig_store_path),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        debug!(
+                            "TSIG store file '{}' did not exist; it will be created",
+                            config.tsig_store_path
+                        );
+                    }
+                    Err(err) => {
+                        error!(
+                            "TSIG store file '{}' could not be read: {err}",
+                            config.tsig_store_path
+                        );
+                        return ExitCode::FAILURE;
+                    }
                 }
-                Err(err) => {
-                    error!(
-                        "TSIG store file '{}' could not be read: {err}",
-                        config.tsig_store_path
+
+                // Restore pending policies.
+                state
+                    .policies
+                    .extend(policies.into_iter().map(|(name, spec)| {
+                        let policy = spec.parse(&name);
+                        (name, policy)
+                    }));
+
+                // Restore pending zones.  Reading their state files is done
+                // in parallel; restoring them touches the global state, and
+                // is done one by one.
+                let specs = read_zone_states(&config, &*state_store, zones);
+                let mut failed = false;
+                for (name, spec) in specs {
+                    assert!(
+                        !state.zones.contains(&name),
+                        "Zone '{name}' was encountered twice"
                     );
+                    let zone = spec.and_then(|spec| {
+                        Zone::restore_from(
+                            name.clone(),
+                            spec,
+                            &mut state.policies,
+                            &state.tsig_store,
+                            &metrics,
+                        )
+                    });
+                    match zone {
+                        Ok(zone) => {
+                            // A zone in its removal grace period is only
+                            // served; keep it from being loaded or re-signed
+                            // until its removal is resumed.
+                            if state.removing_zones.contains(&name) {
+                                zone.state.write_cleanly().maintenance_mode = true;
+                            }
+                            state.zones.insert(ZoneByName(Arc::new(zone)));
+                        }
+                        Err(err) => {
+                            error!("Unable to restore zone '{name}': {err}");
+                            failed = true;
+                        }
+                    }
+                }
+                if failed {
                     return ExitCode::FAILURE;
                 }
-            }
 
-            // Restore pending policies.
-            state
-                .policies
-                .extend(policies.into_iter().map(|(name, spec)| {
-                    let policy = spec.parse(&name);
-                    (name, policy)
-                }));
-
-            // Restore pending zones.
-            for name in zones {
-                assert!(
-                    !state.zones.contains(&name),
-                    "Zone '{name}' was encountered twice"
-                );
-                let zone = match Zone::restore(
-                    &config,
-                    name.clone(),
-                    &mut state.policies,
-                    &state.tsig_store,
-                    &metrics,
-                ) {
-                    Ok(zone) => zone,
-                    Err(err) => {
-                        error!("Unable to restore zone '{name}': {err}");
-                        return ExitCode::FAILURE;
+                // Update policy.zones
+                for ZoneByName(zone) in &state.zones {
+                    if let Some(ref policy) = zone.read().policy {
+                        let pol = state
+                            .policies
+                            .get_mut(&policy.name)
+                            .expect("zone policy should exist");
+                        pol.zones.insert(zone.name.clone());
                     }
-                };
-                state.zones.insert(ZoneByName(Arc::new(zone)));
-            }
-
-            // Update policy.zones
-            for ZoneByName(zone) in &state.zones {
-                if let Some(ref policy) = zone.read().policy {
-                    let pol = state
-                        .policies
-                        .get_mut(&policy.name)
-                        .expect("zone policy should exist");
-                    pol.zones.insert(zone.name.clone());
                 }
+
+                state
             }
 
-            state
-        }
+            Err(err) => {
+                if err.kind() != io::ErrorKind::NotFound {
+                    error!(
+                        "State file '{}' could not be read: {err}",
+                        config.daemon.state_file.value()
+                    );
+                    return ExitCode::FAILURE;
+                }
 
-        Err(err) => {
-            if err.kind() != io::ErrorKind::NotFound {
-                error!(
-                    "State file '{}' could not be read: {err}",
+                info!(
+                    "State file '{}' did not exist; starting from scratch",
                     config.daemon.state_file.value()
                 );
-                return ExitCode::FAILURE;
-            }
 
-            info!(
-                "State file '{}' did not exist; starting from scratch",
-                config.daemon.state_file.value()
-            );
-
-            // Create required subdirectories (and their parents) if they don't
-            // exist. This is only needed for directories to which we write files
-            // without using util::write_file() as that function creates the
-            // directory (and parent directories) if missing. However, do it for
-            // all state directories now so that we don't discover only later that
-            // we can't create the directory.
-            // TODO: Once we implement live config reloading, this should move
-            // somewhere else to also create the directories as specified in a the
-            // reloaded config.
-            for dir in [
-                &*config.keys_dir,
-                config.kmip_credentials_store_path.parent().unwrap(),
-                &*config.kmip_server_state_dir,
-                &*config.policy_dir,
-                &*config.zone_state_dir,
-            ] {
-                if let Err(e) = create_dir_all(dir) {
-                    error!("Unable to create directory '{dir}': {e}",);
-                    return ExitCode::FAILURE;
-                };
-            }
+                // Create required subdirectories (and their parents) if they don't
+                // exist. This is only needed for directories to which we write files
+                // without using util::write_file() as that function creates the
+                // directory (and parent directories) if missing. However, do it for
+                // all state directories now so that we don't discover only later that
+                // we can't create the directory.
+                // TODO: Once we implement live config reloading, this should move
+                // somewhere else to also create the directories as specified in a the
+                // reloaded config.
+                for dir in [
+                    &*config.keys_dir,
+                    config.kmip_credentials_store_path.parent().unwrap(),
+                    &*config.kmip_server_state_dir,
+                    &*config.policy_dir,
+                    &*config.zone_state_dir,
+                ] {
+                    if let Err(e) = create_dir_all(dir) {
+                        error!("Unable to create directory '{dir}': {e}",);
+                        return ExitCode::FAILURE;
+                    };
+                }
 
-            let mut state = center::State::default();
+                let mut state = center::State::default();
 
-            // Load the TSIG store file.
-            match state.tsig_store.init_from_file(&config) {
-                Ok(()) => debug!("Loaded the TSIG store"),
-                Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                    debug!("No TSIG store found; will create one");
-                }
-                Err(err) => {
-                    error!("Failed to load the TSIG store: {err}");
-                    return ExitCode::FAILURE;
+                // Load the TSIG store file.
+                match state.tsig_store.init_from_file(&config) {
+                    Ok(()) => debug!("Loaded the TSIG store"),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        debug!("No TSIG store found; will create one");
+                    }
+                    Err(err) => {
+                        error!("Failed to load the TSIG store: {err}");
+                        return ExitCode::FAILURE;
+                    }
                 }
-            }
 
-            // Load all policies.
-            let mut updates = Vec::new();
-            let res = policy::reload_all(
-                &mut state.policies,
-                &config,
-                &state.tsig_store,
-                |name, _| {
-                    updates.push(name.clone());
-                },
-            );
+                // Load all policies.
+                let mut updates = Vec::new();
+                let res = policy::reload_all(
+                    &mut state.policies,
+                    &config,
+                    &state.tsig_store,
+                    true,
+                    |name, _| {
+                        updates.push(name.clone());
+                    },
+                );
 
-            if let Err(err) = res {
-                error!("Cascade couldn't load all policies: {err}");
-                return ExitCode::FAILURE;
-            }
+                if let Err(err) = res {
+                    error!("Cascade couldn't load all policies: {err}");
+                    return ExitCode::FAILURE;
+                }
 
-            for name in updates {
-                let pol = state
-                    .policies
-                    .get(&name)
-                    .expect("we just reloaded these policies");
+                for name in updates {
+                    let pol = state
+                        .policies
+                        .get(&name)
+                        .expect("we just reloaded these policies");
 
-                for zone_name in &pol.zones {
-                    let ZoneByName(zone) = state
-                        .zones
-                        .get(zone_name)
-                        .expect("zones and policies are consistent");
+                    for zone_name in &pol.zones {
+                        let ZoneByName(zone) = state
+                            .zones
+                            .get(zone_name)
+                            .expect("zones and policies are consistent");
 
-                    // TODO: Mark these zones dirty.
-                    zone.state.write_cleanly().policy = Some(pol.latest.clone());
+                        // TODO: Mark these zones dirty.
+                        zone.state.write_cleanly().set_policy(pol.latest.clone());
+                    }
                 }
-            }
 
-            // TODO: Fail if any zone state files exist.
-            state
-        }
-    };
+                // TODO: Fail if any zone state files exist.
+                state
+            }
+        };
 
     if config.loader.review.servers.is_empty() {
         warn!(
@@ -274,19 +320,31 @@ fn main() -> ExitCode {
     }
 
     // Bind to listen addresses before daemonizing.
-    let Ok(socket_provider) = bind_to_listen_sockets_as_needed(&config) else {
-        return ExitCode::FAILURE;
+    let socket_provider = match socket_provider {
+        Some(provider) => provider,
+        None => {
+            let Ok(provider) = bind_to_listen_sockets_as_needed(&config) else {
+                return ExitCode::FAILURE;
+            };
+            provider
+        }
     };
 
     if let Err(err) = daemonize(&config.daemon) {
         error!("Failed to daemonize: {err}");
         return ExitCode::FAILURE;
     }
+    if let Err(err) = instance_lock.record_pid() {
+        warn!("Unable to record the PID in the lock file: {err}");
+    }
 
     // Prepare Cascade.
+    let publication_server = PublicationServer::new(&config.server);
+    let signer = ZoneSigner::new(&config.signer);
     let center = Arc::new(Center {
         state: Mutex::new(state),
         config,
+        state_store,
         metrics,
         logger,
         loader: Loader::new(),
@@ -296,8 +354,8 @@ fn main() -> ExitCode {
         compacter: Compacter::new(),
         loaded_review_server: LoadedReviewServer::new(),
         signed_review_server: SignedReviewServer::new(),
-        publication_server: PublicationServer::new(),
-        signer: ZoneSigner::new(),
+        publication_server,
+        signer,
         resign_busy: Mutex::new(HashMap::new()),
     });
 
@@ -332,6 +390,7 @@ fn main() -> ExitCode {
         };
 
         info!("Cascade is fully initialized.");
+        notify_service_manager("READY=1");
 
         let res = match exit_signalled().await {
             Ok(_) => ExitCode::SUCCESS,
@@ -341,6 +400,7 @@ fn main() -> ExitCode {
             }
         };
         info!("Shutting down");
+        notify_service_manager("STOPPING=1");
 
         // All of Cascade's units have AbortOnDrop's in Manager, so all
         // background tasks will be stopped when Manager is dropped.
@@ -361,9 +421,51 @@ fn main() -> ExitCode {
         self::zone::save_state_now(&center, &zone);
     }
 
+    // Only allow other instances to start once the state has been saved.
+    drop(instance_lock);
+
     result
 }
 
+/// Read the state files of the given zones.
+///
+/// The files are read on up to `daemon.startup-parallelism` threads.  The
+/// results are returned per zone, in no particular order.
+fn read_zone_states(
+    config: &Config,
+    store: &dyn StateStore,
+    zones: foldhash::HashSet<Name<Bytes>>,
+) -> Vec<(Name<Bytes>, Result<zone_state::Spec, zone_state::LoadError>)> {
+    let parallelism = config
+        .daemon
+        .startup_parallelism
+        .or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get)
+        .clamp(1, zones.len().max(1));
+
+    debug!(
+        "Reading the state files of {} zones on {parallelism} threads",
+        zones.len()
+    );
+
+    let queue = Mutex::new(zones.into_iter());
+    let results = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..parallelism {
+            scope.spawn(|| {
+                loop {
+                    let Some(name) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    let spec = Zone::read_state(config, store, &name);
+                    results.lock().unwrap().push((name, spec));
+                }
+            });
+        }
+    });
+    results.into_inner().unwrap()
+}
+
 /// Bind to all listen addresses that are referred to our by the Cascade
 /// configuration.
 ///
@@ -371,6 +473,7 @@ fn main() -> ExitCode {
 /// bound.
 fn bind_to_listen_sockets_as_needed(config: &Config) -> Result<SocketProvider, ()> {
     let mut socket_provider = SocketProvider::new();
+    socket_provider.set_reuse_port(config.daemon.reuse_port);
     socket_provider.init_from_env(Some(MAX_SYSTEMD_FD_SOCKETS));
 
     // Convert the TCP only listen addresses used by the HTTP server into
@@ -380,7 +483,10 @@ fn bind_to_listen_sockets_as_needed(config: &Config) -> Result<SocketProvider, (
         .remote_control
         .servers
         .iter()
-        .map(|&addr| SocketConfig::TCP { addr })
+        .map(|&addr| SocketConfig::TCP {
+            addr,
+            v6_only: true,
+        })
         .collect();
 
     // Make an iterator over all of the SocketConfig instances we know about.
@@ -411,12 +517,12 @@ fn pre_bind_server_sockets_as_needed<'a, T: Iterator<Item = &'a SocketConfig>>(
     socket_configs: T,
 ) -> Result<(), PreBindError> {
     for socket_config in socket_configs {
-        match socket_config {
-            SocketConfig::UDP { addr } => socket_provider.pre_bind_udp(*addr)?,
-            SocketConfig::TCP { addr } => socket_provider.pre_bind_tcp(*addr)?,
-            SocketConfig::TCPUDP { addr } => {
-                socket_provider.pre_bind_udp(*addr)?;
-                socket_provider.pre_bind_tcp(*addr)?;
+        match *socket_config {
+            SocketConfig::UDP { addr, v6_only } => socket_provider.pre_bind_udp(addr, v6_only)?,
+            SocketConfig::TCP { addr, v6_only } => socket_provider.pre_bind_tcp(addr, v6_only)?,
+            SocketConfig::TCPUDP { addr, v6_only } => {
+                socket_provider.pre_bind_udp(addr, v6_only)?;
+                socket_provider.pre_bind_tcp(addr, v6_only)?;
             }
         }
     }
@@ -1,12 +1,14 @@
 //! Controlling the entire operation.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::center::Center;
-use crate::daemon::SocketProvider;
+use crate::daemon::{SocketProvider, notify_service_manager, watchdog_interval};
 use crate::loader::Loader;
 use crate::persistence::{Compacter, Restorer};
 use crate::server::{LoadedReviewServer, PublicationServer, SignedReviewServer};
+use crate::signer::expiry::ExpiryMonitor;
 use crate::units::http_server::HTTP_UNIT_NAME;
 use crate::units::http_server::HttpServer;
 use crate::units::key_manager::KeyManager;
@@ -15,7 +17,9 @@ use crate::util::AbortOnDrop;
 use crate::zone::{HistoricalEvent, Zone};
 use daemonbase::process::EnvSocketsError;
 use domain::base::Serial;
-use tracing::{debug, error, info};
+use tokio::sync::oneshot;
+use tokio::task::AbortHandle;
+use tracing::{debug, error, info, warn};
 
 //----------- Manager ----------------------------------------------------------
 
@@ -47,7 +51,10 @@ impl Manager {
 
         // Spawn the zone data restorer.
         debug!("Starting the zone data restorer");
-        handles.push(Restorer::run(center.clone()));
+        let (restored_tx, restored_rx) = oneshot::channel();
+        let restorer = Restorer::run(center.clone(), restored_tx);
+        let restorer_id = restorer.id();
+        handles.push(restorer);
 
         // Spawn the zone data compacter.
         debug!("Starting the zone data compacter");
@@ -69,6 +76,10 @@ impl Manager {
         debug!("Starting the zone signer");
         handles.push(ZoneSigner::run(center.clone()));
 
+        // Spawn the signature expiry monitor.
+        debug!("Starting the signature expiry monitor");
+        handles.push(ExpiryMonitor::run(center.clone()));
+
         // Spawn the signed zone review server.
         debug!("Starting the signed review server");
         handles.extend(SignedReviewServer::run(&center, &mut socket_provider)?);
@@ -105,6 +116,34 @@ impl Manager {
         debug!("Starting the HTTP remote-control server");
         let http_server = HttpServer::launch(center.clone(), http_sockets)?;
 
+        // Spawn the watchdog, if the service manager wants one.
+        if let Some(interval) = watchdog_interval() {
+            debug!("Starting the watchdog (every {interval:?})");
+            // The restorer finishes by design, so it is not monitored.
+            let units = handles
+                .iter()
+                .filter(|h| h.id() != restorer_id)
+                .map(|h| h.abort_handle())
+                .collect();
+            handles.push(tokio::spawn(watchdog(center.clone(), units, interval)).into());
+        }
+
+        debug!("All units report ready");
+
+        // Only report readiness once the zone data has been restored.
+        handles.push(
+            tokio::spawn({
+                let http_server = http_server.clone();
+                async move {
+                    if restored_rx.await.is_ok() {
+                        debug!("Zone data restored; marking Cascade as ready");
+                        http_server.mark_ready();
+                    }
+                }
+            })
+            .into(),
+        );
+
         Ok(Self {
             _center: center,
             _http_server: http_server,
@@ -113,6 +152,56 @@ impl Manager {
     }
 }
 
+/// Send keep-alives to the service manager while Cascade is healthy.
+///
+/// This runs as its own task, sending a keep-alive every `interval` (half of
+/// `$WATCHDOG_USEC`), independent of how busy the units are. A keep-alive is
+/// only sent if all units are still running, and the global state and every
+/// zone's state can be locked within half an interval. If Cascade hangs (e.g.
+/// due to a deadlock), keep-alives stop and the service manager will restart
+/// it.
+async fn watchdog(center: Arc<Center>, units: Vec<AbortHandle>, interval: Duration) {
+    let mut ticks = tokio::time::interval(interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    // A health check that has not finished yet; it is waited for again
+    // rather than piling up blocked threads.
+    let mut pending = None;
+
+    loop {
+        ticks.tick().await;
+
+        if units.iter().any(|unit| unit.is_finished()) {
+            error!("A unit has stopped unexpectedly; no longer feeding the watchdog");
+            return;
+        }
+
+        // Lock everything in a blocking task, as a deadlocked lock would
+        // otherwise stall this task forever.
+        let mut check = pending.take().unwrap_or_else(|| {
+            let center = center.clone();
+            tokio::task::spawn_blocking(move || {
+                let zones: Vec<_> = {
+                    let state = center.state.lock().unwrap();
+                    state.zones.iter().map(|z| z.0.clone()).collect()
+                };
+                for zone in zones {
+                    drop(zone.read());
+                }
+            })
+        });
+
+        match tokio::time::timeout(interval / 2, &mut check).await {
+            Ok(Ok(())) => notify_service_manager("WATCHDOG=1"),
+            Ok(Err(err)) => warn!("Watchdog health check failed: {err}"),
+            Err(_) => {
+                warn!("Watchdog health check timed out; Cascade may be deadlocked");
+                pending = Some(check);
+            }
+        }
+    }
+}
+
 pub fn record_zone_event(
     center: &Arc<Center>,
     zone: &Arc<Zone>,
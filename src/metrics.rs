@@ -9,7 +9,7 @@
 use core::sync::atomic::AtomicU64;
 use std::fmt::{self, Debug, Write};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
 use bytes::Bytes;
 use domain::base::Name;
@@ -18,10 +18,12 @@ use prometheus_client::encoding::{EncodeLabelSet, EncodeLabelValue};
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
 use prometheus_client::metrics::info::Info;
 use prometheus_client::registry::{Registry, Unit};
 
 use crate::center::Center;
+use crate::signer::expiry;
 use crate::zone::ZoneByName;
 use crate::zone::machine::ZoneStateMachine;
 
@@ -54,6 +56,9 @@ pub struct Metrics {
     /// Metrics that are available per zone.
     per_zone_metrics: PerZoneMetrics,
 
+    /// Metrics that are available per HSM (KMIP) server.
+    per_hsm_metrics: PerHsmMetrics,
+
     /// The metrics assemble time only relevant for metrics that get collected
     /// on scraping. If we remove all metrics that get built (from state) on
     /// each scrape, then this timer will be useless and should be removed.
@@ -69,6 +74,7 @@ impl Metrics {
         let mut col = Self {
             registry: Registry::with_prefix(PROMETHEUS_PREFIX),
             per_zone_metrics: Default::default(),
+            per_hsm_metrics: Default::default(),
             assemble_time_metric: Default::default(),
             state_metrics: Default::default(),
         };
@@ -106,6 +112,7 @@ impl Metrics {
 
         col.state_metrics.register_metrics(&mut col.registry);
         col.per_zone_metrics.register_metrics(&mut col.registry);
+        col.per_hsm_metrics.register_metrics(&mut col.registry);
 
         col
     }
@@ -123,6 +130,9 @@ impl Metrics {
         let mut zones_unsigned: i64 = 0;
         let mut zones_signed: i64 = 0;
         let mut zones_published: i64 = 0;
+        let mut zones_expiring: i64 = 0;
+        let expiry_window = expiry::warning_window(&center);
+        let now = SystemTime::now();
 
         // Using Family::clear() to delete all metrics and label sets
         metrics.zones_halted.clear();
@@ -145,7 +155,9 @@ impl Metrics {
                     zones_unsigned += 1;
                 } else {
                     match zone_state.machine {
-                        ZoneStateMachine::Waiting(_) | ZoneStateMachine::Loading(_) => {}
+                        ZoneStateMachine::Waiting(_)
+                        | ZoneStateMachine::Loading(_)
+                        | ZoneStateMachine::LoadingFailed(_) => {}
 
                         ZoneStateMachine::LoadedReview(_)
                         | ZoneStateMachine::HaltLoaded(_)
@@ -164,6 +176,12 @@ impl Metrics {
                     }
                 }
 
+                if let Some(window) = expiry_window
+                    && expiry::expires_within(&zone_state, window, now).is_some()
+                {
+                    zones_expiring += 1;
+                }
+
                 if zone_state.machine.is_halted() {
                     metrics
                         .zones_halted
@@ -184,6 +202,32 @@ impl Metrics {
         metrics.zones_unsigned.set(zones_unsigned);
         metrics.zones_signed.set(zones_signed);
         metrics.zones_published.set(zones_published);
+        metrics.zones_expiring.set(zones_expiring);
+
+        let queue = center.signer.queue.stats();
+        let secs = |wait: Option<Duration>| wait.unwrap_or_default().as_secs_f64();
+        // We won't have 2^63 zones in the signing queue
+        metrics.signing_queue_active.set(queue.active as i64);
+        metrics.signing_queue_pending.set(queue.pending as i64);
+        metrics
+            .signing_queue_memory_usage
+            .set(queue.memory_usage as u64);
+        metrics
+            .signing_queue_oldest_wait
+            .set(secs(queue.oldest_wait));
+        metrics
+            .signing_queue_average_wait
+            .set(secs(queue.average_wait));
+        metrics.signing_queue_max_wait.set(secs(queue.max_wait));
+
+        if let Some(rrl) = center.publication_server.rate_limiter() {
+            // The rate limiter keeps its own counts; catch up with them.
+            let catch_up = |counter: &Counter, total: u64| {
+                counter.inc_by(total.saturating_sub(counter.get()));
+            };
+            catch_up(&metrics.rrl_responses_dropped, rrl.dropped());
+            catch_up(&metrics.rrl_responses_slipped, rrl.slipped());
+        }
 
         // u64::MAX milliseconds is around 585_000_000 years
         let assemble_ms = start_time.elapsed().as_millis() as u64;
@@ -197,6 +241,13 @@ impl Metrics {
             zone_name: name.into(),
         }
     }
+
+    pub fn get_hsm_metrics(&self, server_id: &str) -> HsmMetrics {
+        HsmMetrics {
+            per_hsm_metrics: self.per_hsm_metrics.clone(),
+            server_id: server_id.into(),
+        }
+    }
 }
 
 impl TryFrom<&Metrics> for String {
@@ -257,6 +308,48 @@ pub enum HaltMode {
     HardHalt,
 }
 
+//------------ HsmLabel ------------------------------------------------------
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct HsmLabel {
+    pub server: String,
+}
+
+//------------ TsigLabels ----------------------------------------------------
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct TsigLabels {
+    pub zone: StoredName,
+    pub key: String,
+}
+
+//------------ ReviewLabels --------------------------------------------------
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+pub struct ReviewLabels {
+    pub zone: StoredName,
+    pub stage: ReviewStage,
+    pub outcome: ReviewOutcome,
+}
+
+//------------ ReviewStage ---------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum ReviewStage {
+    Unsigned,
+    Signed,
+}
+
+//------------ ReviewOutcome -------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum ReviewOutcome {
+    Approved,
+    Rejected,
+    HookFailed,
+    Superseded,
+}
+
 //------------ XfrLabels -----------------------------------------------------
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -273,6 +366,15 @@ pub enum XfrType {
     Ixfr,
 }
 
+impl From<XfrType> for crate::api::ZoneTransferType {
+    fn from(value: XfrType) -> Self {
+        match value {
+            XfrType::Axfr => Self::Axfr,
+            XfrType::Ixfr => Self::Ixfr,
+        }
+    }
+}
+
 //------------ StateMetrics --------------------------------------------------
 
 #[derive(Debug, Default)]
@@ -282,10 +384,36 @@ struct StateMetrics {
     zones_loaded: Gauge,
     zones_active: Gauge,
     zones_unsigned: Gauge,
-    // TODO: Track how many zones are waiting to be signed.
     zones_signed: Gauge,
     zones_published: Gauge,
     zones_halted: Family<ZoneHaltMode, Gauge>,
+
+    /// The number of zones whose signatures expire within the warning window.
+    zones_expiring: Gauge,
+
+    /// The number of zones being signed.
+    signing_queue_active: Gauge,
+
+    /// The number of zones waiting for signing capacity.
+    signing_queue_pending: Gauge,
+
+    /// The estimated memory used by the zones being signed.
+    signing_queue_memory_usage: Gauge<u64, AtomicU64>,
+
+    /// How long the longest-waiting pending zone has been waiting.
+    signing_queue_oldest_wait: Gauge<f64, AtomicU64>,
+
+    /// The average time zones waited for signing capacity.
+    signing_queue_average_wait: Gauge<f64, AtomicU64>,
+
+    /// The longest time a zone waited for signing capacity.
+    signing_queue_max_wait: Gauge<f64, AtomicU64>,
+
+    /// The number of responses dropped by response rate limiting.
+    rrl_responses_dropped: Counter,
+
+    /// The number of responses truncated by response rate limiting.
+    rrl_responses_slipped: Counter,
 }
 
 impl StateMetrics {
@@ -325,6 +453,55 @@ impl StateMetrics {
             "Number of halted zones",
             self.zones_halted.clone(),
         );
+        reg.register(
+            "zones_expiring",
+            "Number of zones whose signatures expire within the configured warning window",
+            self.zones_expiring.clone(),
+        );
+        reg.register(
+            "signing_queue_active",
+            "Number of zones being signed",
+            self.signing_queue_active.clone(),
+        );
+        reg.register(
+            "signing_queue_pending",
+            "Number of zones waiting for signing capacity",
+            self.signing_queue_pending.clone(),
+        );
+        reg.register_with_unit(
+            "signing_queue_memory_usage",
+            "Estimated memory used by the zones being signed",
+            Unit::Bytes,
+            self.signing_queue_memory_usage.clone(),
+        );
+        reg.register_with_unit(
+            "signing_queue_oldest_wait",
+            "Time the longest-waiting zone in the signing queue has been waiting",
+            Unit::Seconds,
+            self.signing_queue_oldest_wait.clone(),
+        );
+        reg.register_with_unit(
+            "signing_queue_average_wait",
+            "Average time zones waited for signing capacity since startup",
+            Unit::Seconds,
+            self.signing_queue_average_wait.clone(),
+        );
+        reg.register_with_unit(
+            "signing_queue_max_wait",
+            "Longest time a zone waited for signing capacity since startup",
+            Unit::Seconds,
+            self.signing_queue_max_wait.clone(),
+        );
+        reg.register(
+            "rrl_responses_dropped",
+            "Number of responses dropped by response rate limiting",
+            self.rrl_responses_dropped.clone(),
+        );
+        reg.register(
+            "rrl_responses_slipped",
+            "Number of responses sent truncated by response rate limiting",
+            self.rrl_responses_slipped.clone(),
+        );
     }
 }
 
@@ -363,6 +540,13 @@ struct PerZoneMetrics {
 
     /// Duration of the last signing operations for this zone.
     zone_last_successful_sign_duration: Family<ZoneLabel, Gauge<f64, AtomicU64>>,
+
+    /// The number of responses from the upstream and inbound NOTIFY messages
+    /// that failed TSIG verification
+    tsig_verification_failures: Family<TsigLabels, Counter>,
+
+    /// The number of reviews of this zone, by stage and outcome
+    review_outcomes: Family<ReviewLabels, Counter>,
 }
 
 impl PerZoneMetrics {
@@ -432,6 +616,18 @@ impl PerZoneMetrics {
             Unit::Seconds,
             self.zone_last_successful_sign_duration.clone(),
         );
+
+        metrics.register(
+            "tsig_verification_failures",
+            "Number of responses from the upstream primary and inbound NOTIFY messages that failed TSIG verification",
+            self.tsig_verification_failures.clone(),
+        );
+
+        metrics.register(
+            "review_outcomes",
+            "Number of reviews of unsigned and signed zones, by outcome",
+            self.review_outcomes.clone(),
+        );
     }
 }
 
@@ -536,4 +732,166 @@ impl ZoneMetrics {
             })
             .set(n);
     }
+
+    pub fn inc_tsig_verification_failures(&self, key: &impl fmt::Display) {
+        self.per_zone_metrics
+            .tsig_verification_failures
+            .get_or_create(&TsigLabels {
+                zone: self.zone_name.clone(),
+                key: key.to_string(),
+            })
+            .inc();
+    }
+
+    pub fn inc_review_outcome(&self, stage: ReviewStage, outcome: ReviewOutcome) {
+        self.per_zone_metrics
+            .review_outcomes
+            .get_or_create(&ReviewLabels {
+                zone: self.zone_name.clone(),
+                stage,
+                outcome,
+            })
+            .inc();
+    }
+}
+
+//------------ PerHsmMetrics -------------------------------------------------
+
+#[derive(Debug, Clone)]
+struct PerHsmMetrics {
+    /// The number of signing operations performed by an HSM.
+    hsm_sign_operations: Family<HsmLabel, Counter>,
+
+    /// The number of signing operations that failed at an HSM.
+    hsm_sign_failures: Family<HsmLabel, Counter>,
+
+    /// The number of signing operations currently awaiting an HSM.
+    hsm_sign_in_flight: Family<HsmLabel, Gauge>,
+
+    /// The time taken by an HSM to perform a signing operation.
+    hsm_sign_duration: Family<HsmLabel, Histogram, fn() -> Histogram>,
+}
+
+impl Default for PerHsmMetrics {
+    fn default() -> Self {
+        Self {
+            hsm_sign_operations: Default::default(),
+            hsm_sign_failures: Default::default(),
+            hsm_sign_in_flight: Default::default(),
+            // Buckets from 0.5ms up to ~8s; HSM round-trips are expected to
+            // take a few milliseconds.
+            hsm_sign_duration: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.0005, 2.0, 15))
+            }),
+        }
+    }
+}
+
+impl PerHsmMetrics {
+    fn register_metrics(&self, metrics: &mut Registry) {
+        metrics.register(
+            "hsm_sign_operations",
+            "Number of signing operations performed by an HSM",
+            self.hsm_sign_operations.clone(),
+        );
+
+        metrics.register(
+            "hsm_sign_failures",
+            "Number of signing operations that failed at an HSM",
+            self.hsm_sign_failures.clone(),
+        );
+
+        metrics.register(
+            "hsm_sign_in_flight",
+            "Number of signing operations currently awaiting an HSM",
+            self.hsm_sign_in_flight.clone(),
+        );
+
+        metrics.register_with_unit(
+            "hsm_sign_duration",
+            "Time taken by an HSM to perform a signing operation",
+            Unit::Seconds,
+            self.hsm_sign_duration.clone(),
+        );
+    }
+}
+
+//------------ HsmMetrics ----------------------------------------------------
+
+/// An instantiation of `PerHsmMetrics` for an HSM server.
+#[derive(Debug, Clone)]
+pub struct HsmMetrics {
+    per_hsm_metrics: PerHsmMetrics,
+    server_id: String,
+}
+
+impl HsmMetrics {
+    /// Measure a single signing operation at the HSM.
+    pub fn measure_sign<T, E>(&self, op: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+        let label = HsmLabel {
+            server: self.server_id.clone(),
+        };
+        let m = &self.per_hsm_metrics;
+
+        let in_flight = m.hsm_sign_in_flight.get_or_create(&label).clone();
+        in_flight.inc();
+        let start = Instant::now();
+        let res = op();
+        let elapsed = start.elapsed().as_secs_f64();
+        in_flight.dec();
+
+        m.hsm_sign_operations.get_or_create(&label).inc();
+        m.hsm_sign_duration.get_or_create(&label).observe(elapsed);
+        if res.is_err() {
+            m.hsm_sign_failures.get_or_create(&label).inc();
+        }
+
+        res
+    }
+}
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::{Metrics, ReviewOutcome, ReviewStage};
+
+    #[test]
+    fn tsig_verification_failures_are_counted_per_zone_and_key() {
+        let metrics = Metrics::new();
+        let zone = metrics.get_zone_metrics("example.org".parse().unwrap());
+        zone.inc_tsig_verification_failures(&"old-key");
+        zone.inc_tsig_verification_failures(&"old-key");
+        zone.inc_tsig_verification_failures(&"new-key");
+
+        let text = String::try_from(&metrics).unwrap();
+        assert!(text.contains(
+            r#"cascade_tsig_verification_failures_total{zone="example.org",key="old-key"} 2"#
+        ));
+        assert!(text.contains(
+            r#"cascade_tsig_verification_failures_total{zone="example.org",key="new-key"} 1"#
+        ));
+    }
+
+    #[test]
+    fn review_outcomes_are_counted_per_stage() {
+        let metrics = Metrics::new();
+        let zone = metrics.get_zone_metrics("example.org".parse().unwrap());
+        zone.inc_review_outcome(ReviewStage::Unsigned, ReviewOutcome::Approved);
+        zone.inc_review_outcome(ReviewStage::Signed, ReviewOutcome::Approved);
+        zone.inc_review_outcome(ReviewStage::Signed, ReviewOutcome::HookFailed);
+        zone.inc_review_outcome(ReviewStage::Signed, ReviewOutcome::HookFailed);
+
+        let text = String::try_from(&metrics).unwrap();
+        assert!(text.contains(
+            r#"cascade_review_outcomes_total{zone="example.org",stage="Unsigned",outcome="Approved"} 1"#
+        ));
+        assert!(text.contains(
+            r#"cascade_review_outcomes_total{zone="example.org",stage="Signed",outcome="Approved"} 1"#
+        ));
+        assert!(text.contains(
+            r#"cascade_review_outcomes_total{zone="example.org",stage="Signed",outcome="HookFailed"} 2"#
+        ));
+        assert!(!text.contains(r#"outcome="Rejected""#));
+    }
 }
@@ -189,7 +189,8 @@ use crate::{
 mod persist;
 mod restore;
 use restore::{restore_loaded, restore_signed};
-use tracing::trace;
+use tokio::sync::oneshot;
+use tracing::{debug, error, trace};
 
 pub mod zone;
 
@@ -314,8 +315,10 @@ impl Restorer {
     ///
     /// At startup, the set of zones will be traversed, and for zones that were
     /// restored from state files, restore operations for their zone data will
-    /// be initiated.
-    pub fn run(center: Arc<Center>) -> AbortOnDrop {
+    /// be initiated.  Once all of them have finished, the removal of zones that
+    /// were still in their removal grace period is resumed, and `done` is
+    /// signalled.
+    pub fn run(center: Arc<Center>, done: oneshot::Sender<()>) -> AbortOnDrop {
         AbortOnDrop::from(tokio::spawn(async move {
             // Obtain a list of all zones (that need restoring).
             let zones = {
@@ -329,7 +332,7 @@ impl Restorer {
             };
 
             // Attempt to restore data for every zone.
-            for zone in zones {
+            for zone in &zones {
                 let mut handle = zone.write_handle(&center);
 
                 // Zones that are _not_ restored from disk will move out the
@@ -340,6 +343,34 @@ impl Restorer {
 
                 handle.persistence().start_restore(restorer);
             }
+
+            // The restore operations run in the background; wait for them to
+            // finish (whether they succeed or are abandoned).
+            let mut interval = tokio::time::interval(Duration::from_millis(100));
+            while zones.iter().any(|zone| zone.read().storage.is_restoring()) {
+                interval.tick().await;
+            }
+
+            debug!("Restored the data of {} zones", zones.len());
+
+            // Resume the removal of zones that were in their removal grace
+            // period; they are only served for the remainder of it.
+            let removing_zones = {
+                let state = center.state.lock().unwrap();
+                state
+                    .removing_zones
+                    .iter()
+                    .filter(|&name| state.zones.contains(name))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            for name in removing_zones {
+                if let Err(err) = crate::center::remove_zone(&center, name.clone()) {
+                    error!("Could not resume the removal of zone '{name}': {err}");
+                }
+            }
+
+            let _ = done.send(());
         }))
     }
 }
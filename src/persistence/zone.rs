@@ -12,10 +12,12 @@ use tracing::{debug, info, trace, trace_span, warn};
 
 use crate::{
     center::Center,
+    loader::Source,
     persistence::persist::{persist_loaded, persist_signed, persist_to_file_from_parts},
     server::{LoadedReviewServer, PublicationServer, SignedReviewServer},
+    signer::ResigningTrigger,
     util::BackgroundTasks,
-    zone::{Zone, ZoneHandle, ZoneState, save_state_now},
+    zone::{HistoricalEvent, Zone, ZoneHandle, ZoneState, save_state_now},
     zonedata::{LoadedZonePersister, LoadedZoneRestorer, SignedZonePersister, SignedZoneRestorer},
 };
 
@@ -144,6 +146,18 @@ impl ZonePersistenceHandle<'_> {
 
                 handle.signer().on_restoration();
 
+                // Check that the restored data matches the recorded state. If
+                // Cascade crashed while publishing a new instance, the data on
+                // disk may not be the instance the zone state recorded.
+                let served_serial = viewer.read().map(|reader| reader.soa().rdata.serial);
+                let recorded_serial = handle
+                    .state
+                    .instances
+                    .persisted
+                    .as_ref()
+                    .map(|persisted| persisted.signed.serial());
+                let mismatch = served_serial != recorded_serial;
+
                 // Register the zone against the zone servers.
                 LoadedReviewServer::add_zone(handle.center, handle.zone.clone(), loaded_reviewer);
                 SignedReviewServer::add_zone(handle.center, handle.zone.clone(), signed_reviewer);
@@ -152,6 +166,38 @@ impl ZonePersistenceHandle<'_> {
                 // Mark restoration as complete.
                 handle.state.instances.restore();
 
+                if mismatch {
+                    // Re-sign the restored data, so that a consistent instance
+                    // is published with a newer serial.
+                    heal(
+                        &mut handle.get(),
+                        format!(
+                            "the restored signed instance has SOA serial {} but the zone state \
+                            recorded SOA serial {}; re-signing it",
+                            display_serial(served_serial),
+                            display_serial(recorded_serial),
+                        ),
+                    );
+                    handle
+                        .signer()
+                        .enqueue_resign(ResigningTrigger::SIGS_NEED_REFRESH);
+                }
+
+                // Staged and held instances are not persisted.  Rather than
+                // dropping the approved version, sign it again, so that it is
+                // reviewed and staged or held anew.
+                if let Some(what) = take_lost_review(&mut handle.state) {
+                    heal(
+                        &mut handle.get(),
+                        format!(
+                            "the {what} signed instance was not retained across the restart; re-signing it"
+                        ),
+                    );
+                    handle
+                        .signer()
+                        .enqueue_resign(ResigningTrigger::SIGS_NEED_REFRESH);
+                }
+
                 // Send a notification that the state machine is now passive.
                 handle.storage().on_passive();
 
@@ -227,6 +273,7 @@ fn abandon_loaded_restoration(
     zone: &Arc<Zone>,
     restorer: LoadedZoneRestorer,
 ) {
+    let was_published = zone.read().instances.persisted.is_some();
     reset_state_due_to_abandoned_restore(center, zone);
     let mut handle = zone.write_handle(center);
     let (loaded_reviewer, signed_reviewer, viewer) =
@@ -240,6 +287,8 @@ fn abandon_loaded_restoration(
     // Send a notification that the state machine is now passive.
     handle.storage().on_passive();
 
+    reload_after_abandoned_restore(&mut handle.get(), was_published);
+
     handle.state.persistence.ongoing.finish();
 }
 
@@ -248,6 +297,7 @@ fn abandon_signed_restoration(
     zone: &Arc<Zone>,
     restorer: SignedZoneRestorer,
 ) {
+    let was_published = zone.read().instances.persisted.is_some();
     reset_state_due_to_abandoned_restore(center, zone);
     let mut handle = zone.write_handle(center);
     let (loaded_reviewer, signed_reviewer, viewer) =
@@ -261,9 +311,67 @@ fn abandon_signed_restoration(
     // Send a notification that the state machine is now passive.
     handle.storage().on_passive();
 
+    reload_after_abandoned_restore(&mut handle.get(), was_published);
+
     handle.state.persistence.ongoing.finish();
 }
 
+/// Heal a zone whose restoration was abandoned, by loading it again.
+///
+/// If the zone was published, nothing can be served for it now.  If a signed
+/// instance of it was staged or held, that instance was not persisted.  In
+/// either case, the zone is loaded again, so that it goes through review and
+/// is published, staged or held anew.
+fn reload_after_abandoned_restore(handle: &mut ZoneHandle<'_>, was_published: bool) {
+    let lost_review = take_lost_review(handle.state);
+    let reason = if was_published {
+        "the published instance recorded in the zone state could not be \
+        restored; reloading the zone"
+            .into()
+    } else if let Some(what) = lost_review {
+        format!(
+            "the {what} signed instance was not retained across the restart; reloading the zone"
+        )
+    } else {
+        return;
+    };
+
+    heal(handle, reason);
+    if !matches!(handle.state.loader.source, Source::None) {
+        handle.loader().enqueue_refresh(true);
+    }
+}
+
+/// Report that a zone needed healing on startup.
+///
+/// The zone's served data did not match its recorded state; `reason`
+/// describes the mismatch and how it is being healed.
+fn heal(handle: &mut ZoneHandle<'_>, reason: String) {
+    warn!("Zone '{}' needed healing: {reason}", handle.zone.name);
+    handle
+        .state
+        .record_event(HistoricalEvent::Healed { reason }, None);
+}
+
+/// Forget about a staged or held signed instance lost by a restart.
+///
+/// Returns how the instance was kept back, if it was.
+fn take_lost_review(state: &mut ZoneState) -> Option<&'static str> {
+    let staged = state.staged_since.take().is_some();
+    let held = state.held_since.take().is_some();
+    if staged {
+        Some("staged")
+    } else if held {
+        Some("held")
+    } else {
+        None
+    }
+}
+
+fn display_serial(serial: Option<Serial>) -> String {
+    serial.map_or_else(|| "<none>".into(), |serial| serial.get().to_string())
+}
+
 fn reset_state_due_to_abandoned_restore(center: &Arc<Center>, zone: &Arc<Zone>) {
     PersistenceState::clear(center, zone);
     {
@@ -886,6 +994,16 @@ impl IxfrZoneDiffs {
         self.signed_diffs.len()
     }
 
+    /// All stored diffs, loaded and signed.
+    ///
+    /// This is cheap, so that the stored diffs can be inspected (e.g. to
+    /// estimate their memory usage) without holding on to the zone.
+    pub fn diffs(&self) -> Vec<Arc<DiffData>> {
+        let loaded = self.loaded_diffs.values();
+        let signed = self.signed_diffs.values().map(|related| &related.diff);
+        loaded.chain(signed).cloned().collect()
+    }
+
     pub fn store_loaded_diff(&mut self, diff: Arc<DiffData>) {
         let from_serial = diff.removed_soa.as_ref().map(|s| s.rdata.serial).unwrap();
         let to_serial = diff.added_soa.as_ref().map(|s| s.rdata.serial).unwrap();
@@ -905,6 +1023,18 @@ impl IxfrZoneDiffs {
         // necessary information to do so.
     }
 
+    /// Get the loaded diff that leads up to the specified loaded serial.
+    pub fn loaded_diff_to(&self, to_serial: Serial) -> Option<Arc<DiffData>> {
+        self.loaded_diffs
+            .values()
+            .find(|diff| {
+                diff.added_soa
+                    .as_ref()
+                    .is_some_and(|soa| soa.rdata.serial == to_serial)
+            })
+            .cloned()
+    }
+
     pub fn get(&self, from_serial: Serial) -> Vec<(Arc<DiffData>, Arc<DiffData>)> {
         let mut diffs = vec![];
 
@@ -1,10 +1,11 @@
 //! The policy file.
 
-use std::{fs, io};
+use std::{fmt, fs, io};
 
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
 
+use crate::config::file::merge_tables;
 use crate::policy::PolicyVersion;
 
 use super::Policy;
@@ -32,7 +33,6 @@ impl Spec {
     }
 
     /// Build into this specification.
-    #[expect(dead_code, reason = "We plan to support policy serialization")]
     pub fn build(policy: &Policy) -> Self {
         Self::V1(v1::Spec::build(&policy.latest))
     }
@@ -41,17 +41,132 @@ impl Spec {
 //--- Loading / Saving
 
 impl Spec {
-    /// Load and parse this specification from a file.
-    pub fn load(path: &Utf8Path) -> io::Result<Self> {
-        let text = fs::read_to_string(path)?;
-        toml::from_str(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    /// Load and parse this specification from a file, resolving `extends`.
+    ///
+    /// If the policy extends another policy, the file of that policy (in the
+    /// same directory) is loaded too, and so on. The settings of each policy
+    /// are merged over those of the policy it extends: tables are merged key
+    /// by key, while other values (including arrays) are replaced.
+    ///
+    /// `on_read` is called with the path of every file, before it is read.
+    pub fn load_extended(
+        path: &Utf8Path,
+        mut on_read: impl FnMut(&Utf8Path),
+    ) -> Result<Self, LoadError> {
+        let dir = path.parent().expect("a policy file is in a directory");
+        let name = path.file_stem().expect("a policy file has a file name");
+
+        on_read(path);
+        let mut table = read_table(path)?;
+        let mut chain: Vec<Box<str>> = vec![name.into()];
+        let mut overrides = Vec::new();
+
+        while let Some(base) = table.get("extends") {
+            // Only policies in the same directory can be extended.
+            let Some(base) = base.as_str().filter(|b| !b.is_empty() && !b.contains('/')) else {
+                let policy = chain.last().unwrap().clone();
+                return Err(LoadError::BadExtends { policy });
+            };
+            if chain.iter().any(|name| **name == *base) {
+                chain.push(base.into());
+                return Err(LoadError::Cycle(chain));
+            }
+
+            let base_path = dir.join(format!("{base}.toml"));
+            on_read(&base_path);
+            let base_table = match read_table(&base_path) {
+                Ok(table) => table,
+                Err(LoadError::Io(_, err)) if err.kind() == io::ErrorKind::NotFound => {
+                    let policy = chain.last().unwrap().clone();
+                    let base = base.into();
+                    return Err(LoadError::UnknownBase { policy, base });
+                }
+                Err(err) => return Err(err),
+            };
+
+            chain.push(base.into());
+            overrides.push(std::mem::replace(&mut table, base_table));
+        }
+
+        // Apply the overrides, starting from the most basic policy.
+        for child in overrides.into_iter().rev() {
+            merge_tables(&mut table, child);
+        }
+
+        toml::Value::Table(table).try_into().map_err(|err| {
+            LoadError::Io(path.into(), io::Error::new(io::ErrorKind::InvalidData, err))
+        })
     }
 
     /// Build and save this specification to a file.
     #[expect(dead_code, reason = "We plan to support policy serialization")]
     pub fn save(&self, path: &Utf8Path) -> io::Result<()> {
-        let text = toml::to_string_pretty(self)
+        let text = self
+            .to_toml()
             .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
         crate::util::write_file(path, text.as_bytes())
     }
+
+    /// Render this specification as the text of a policy file.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+/// Read a policy file as a TOML table.
+fn read_table(path: &Utf8Path) -> Result<toml::Table, LoadError> {
+    let io_error = |err| LoadError::Io(path.into(), err);
+    let text = fs::read_to_string(path).map_err(io_error)?;
+    toml::from_str(&text).map_err(|err| io_error(io::Error::new(io::ErrorKind::InvalidData, err)))
+}
+
+//----------- LoadError --------------------------------------------------------
+
+/// An error in loading a policy file.
+#[derive(Debug)]
+pub enum LoadError {
+    /// A policy file could not be read or parsed.
+    Io(Box<Utf8Path>, io::Error),
+
+    /// The `extends` setting of a policy is not a policy name.
+    BadExtends { policy: Box<str> },
+
+    /// A policy extends a policy that does not exist.
+    UnknownBase { policy: Box<str>, base: Box<str> },
+
+    /// A policy (indirectly) extends itself.
+    ///
+    /// The policies involved are listed in order, starting and ending with the
+    /// same policy.
+    Cycle(Vec<Box<str>>),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{path}: {err}"),
+            Self::BadExtends { policy } => {
+                write!(
+                    f,
+                    "policy '{policy}': 'extends' must be the name of a policy"
+                )
+            }
+            Self::UnknownBase { policy, base } => {
+                write!(
+                    f,
+                    "policy '{policy}' extends policy '{base}', which does not exist"
+                )
+            }
+            Self::Cycle(chain) => {
+                f.write_str("policy inheritance cycle: ")?;
+                for (i, name) in chain.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" -> ")?;
+                    }
+                    f.write_str(name)?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
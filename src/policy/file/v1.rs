@@ -19,6 +19,7 @@ use crate::{
     policy::{
         self, KeyManagerPolicy, LoaderPolicy, NameserverCommsPolicy, OutboundPolicy, PolicyVersion,
         ReviewPolicy, ServerPolicy, SignerDenialPolicy, SignerPolicy, SignerSerialPolicy,
+        StagingPolicy, TransferLimitsPolicy,
     },
 };
 
@@ -82,6 +83,13 @@ const MAX_DIFFS_SIZE: usize = 20;
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
 pub struct Spec {
+    /// The policy this policy extends, if any.
+    ///
+    /// This is resolved when the file is loaded; see
+    /// [`super::Spec::load_extended()`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<Box<str>>,
+
     /// How zones are loaded.
     pub loader: LoaderSpec,
 
@@ -112,6 +120,7 @@ impl Spec {
     /// Build into this specification.
     pub fn build(policy: &PolicyVersion) -> Self {
         Self {
+            extends: None,
             loader: LoaderSpec::build(&policy.loader),
             key_manager: KeyManagerSpec::build(&policy.key_manager),
             signer: SignerSpec::build(&policy.signer),
@@ -128,6 +137,12 @@ impl Spec {
 pub struct LoaderSpec {
     /// Reviewing loaded zones.
     pub review: Option<ReviewSpec>,
+
+    /// Limits on inbound zone transfers.
+    pub transfer: TransferLimitsSpec,
+
+    /// Whether SOA queries to primaries are sent over TCP.
+    pub soa_query_over_tcp: bool,
 }
 
 //--- Conversion
@@ -137,6 +152,8 @@ impl LoaderSpec {
     pub fn parse(self) -> LoaderPolicy {
         LoaderPolicy {
             review: self.review.map_or(Default::default(), |r| r.parse()),
+            transfer: self.transfer.parse(),
+            soa_query_over_tcp: self.soa_query_over_tcp,
         }
     }
 
@@ -144,6 +161,53 @@ impl LoaderSpec {
     pub fn build(policy: &LoaderPolicy) -> Self {
         Self {
             review: Some(ReviewSpec::build(&policy.review)),
+            transfer: TransferLimitsSpec::build(&policy.transfer),
+            soa_query_over_tcp: policy.soa_query_over_tcp,
+        }
+    }
+}
+
+//----------- TransferLimitsSpec -----------------------------------------------
+
+/// Limits on inbound zone transfers.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct TransferLimitsSpec {
+    /// The maximum number of bytes a single transfer may consist of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+
+    /// The maximum number of records a single transfer may contain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_records: Option<u64>,
+
+    /// How long a refresh of the zone from its primary may take.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<TimeSpan>,
+}
+
+//--- Conversion
+
+impl TransferLimitsSpec {
+    /// Parse from this specification.
+    pub fn parse(self) -> TransferLimitsPolicy {
+        TransferLimitsPolicy {
+            max_bytes: self.max_bytes,
+            max_records: self.max_records,
+            timeout: self
+                .timeout
+                .map(|span| Duration::from_secs(span.as_secs().into())),
+        }
+    }
+
+    /// Build into this specification.
+    pub fn build(policy: &TransferLimitsPolicy) -> Self {
+        Self {
+            max_bytes: policy.max_bytes,
+            max_records: policy.max_records,
+            timeout: policy
+                .timeout
+                .map(|time| TimeSpan::from_secs(time.as_secs() as u32)),
         }
     }
 }
@@ -175,6 +239,10 @@ pub struct KeyManagerSpec {
     /// How long to wait before removing old keys.
     pub auto_remove_delay: TimeSpan,
 
+    /// By how much key validities may be shortened, to spread the key rolls
+    /// of zones using this policy.
+    pub validity_jitter: TimeSpan,
+
     /// How special DNS records are managed.
     pub records: KeyManagerRecordsSpec,
 
@@ -283,6 +351,7 @@ impl KeyManagerSpec {
             ds_algorithm: self.ds_algorithm,
             auto_remove: self.auto_remove,
             auto_remove_delay: Duration::from_secs(self.auto_remove_delay.as_secs().into()),
+            validity_jitter: self.validity_jitter.as_secs(),
             publication_nameservers: self
                 .publication_nameservers
                 .into_iter()
@@ -320,6 +389,7 @@ impl KeyManagerSpec {
             ds_algorithm: policy.ds_algorithm.clone(),
             auto_remove: policy.auto_remove,
             auto_remove_delay: TimeSpan::from_secs(policy.auto_remove_delay.as_secs() as u32),
+            validity_jitter: TimeSpan::from_secs(policy.validity_jitter),
             publication_nameservers: policy
                 .publication_nameservers
                 .iter()
@@ -374,6 +444,7 @@ impl Default for KeyManagerSpec {
             ds_algorithm: DsAlgorithm::Sha256,
             auto_remove: true,
             auto_remove_delay: TimeSpan::from_secs(AUTO_REMOVE_DELAY),
+            validity_jitter: TimeSpan::from_secs(0),
             publication_nameservers: Default::default(),
             records: Default::default(),
             generation: Default::default(),
@@ -653,6 +724,12 @@ pub struct SignerSpec {
 
     /// Reviewing signed zones.
     pub review: ReviewSpec,
+
+    /// Whether to keep signatures made by an external signer.
+    pub preserve_external_signatures: bool,
+
+    /// Whether to refuse signing zones which already contain DNSSEC records.
+    pub refuse_dnssec_input: bool,
     //
     // TODO:
     // - Signing policy (disabled, pass-through?, enabled)
@@ -672,6 +749,8 @@ impl SignerSpec {
             key_roll_time: self.key_roll_time.as_secs(),
             denial: self.denial.parse(),
             review: self.review.parse(),
+            preserve_external_signatures: self.preserve_external_signatures,
+            refuse_dnssec_input: self.refuse_dnssec_input,
         }
     }
 
@@ -686,6 +765,8 @@ impl SignerSpec {
             key_roll_time: TimeSpan::from_secs(policy.key_roll_time),
             denial: SignerDenialSpec::build(&policy.denial),
             review: ReviewSpec::build(&policy.review),
+            preserve_external_signatures: policy.preserve_external_signatures,
+            refuse_dnssec_input: policy.refuse_dnssec_input,
         }
     }
 }
@@ -704,6 +785,10 @@ impl Default for SignerSpec {
             denial: Default::default(),
 
             review: Default::default(),
+
+            preserve_external_signatures: false,
+
+            refuse_dnssec_input: false,
         }
     }
 }
@@ -923,6 +1008,7 @@ impl ReviewSpec {
 #[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
 pub struct ServerSpec {
     outbound: OutboundSpec,
+    staging: StagingSpec,
 }
 
 //--- Conversion
@@ -932,6 +1018,7 @@ impl ServerSpec {
     pub fn parse(self) -> ServerPolicy {
         ServerPolicy {
             outbound: self.outbound.parse(),
+            staging: self.staging.parse(),
         }
     }
 
@@ -939,6 +1026,7 @@ impl ServerSpec {
     pub fn build(policy: &ServerPolicy) -> Self {
         Self {
             outbound: OutboundSpec::build(&policy.outbound),
+            staging: StagingSpec::build(&policy.staging),
         }
     }
 }
@@ -954,6 +1042,13 @@ pub struct OutboundSpec {
     #[serde(default = "empty_list")]
     pub provide_xfr_to: Vec<NameserverCommsSpec>,
 
+    /// The set of nameservers to which the review servers may provide zone
+    /// transfers of instances under review.
+    ///
+    /// If empty, instances under review will be provided to any nameserver.
+    #[serde(default = "empty_list")]
+    pub provide_review_xfr_to: Vec<NameserverCommsSpec>,
+
     /// The set of nameservers to which NOTIFY messages should be sent.
     ///
     /// If empty, no NOTIFY messages will be sent.
@@ -975,6 +1070,12 @@ pub struct OutboundSpec {
     /// served to clients.
     #[serde(default = "default_max_diffs_size")]
     max_diffs_size: usize,
+
+    /// How long a removed zone is still served.
+    ///
+    /// If not set, a zone is no longer served as soon as it is removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removal_grace_period: Option<TimeSpan>,
 }
 
 fn empty_list() -> Vec<NameserverCommsSpec> {
@@ -996,9 +1097,17 @@ impl OutboundSpec {
     pub fn parse(self) -> OutboundPolicy {
         OutboundPolicy {
             provide_xfr_to: self.provide_xfr_to.into_iter().map(|v| v.parse()).collect(),
+            provide_review_xfr_to: self
+                .provide_review_xfr_to
+                .into_iter()
+                .map(|v| v.parse())
+                .collect(),
             send_notify_to: self.send_notify_to.into_iter().map(|v| v.parse()).collect(),
             max_diffs: self.max_diffs,
             max_diffs_size: self.max_diffs_size,
+            removal_grace_period: self
+                .removal_grace_period
+                .map(|span| Duration::from_secs(span.as_secs().into())),
         }
     }
 
@@ -1010,6 +1119,11 @@ impl OutboundSpec {
                 .iter()
                 .map(NameserverCommsSpec::build)
                 .collect(),
+            provide_review_xfr_to: policy
+                .provide_review_xfr_to
+                .iter()
+                .map(NameserverCommsSpec::build)
+                .collect(),
             send_notify_to: policy
                 .send_notify_to
                 .iter()
@@ -1017,6 +1131,56 @@ impl OutboundSpec {
                 .collect(),
             max_diffs: policy.max_diffs,
             max_diffs_size: policy.max_diffs_size,
+            removal_grace_period: policy
+                .removal_grace_period
+                .map(|time| TimeSpan::from_secs(time.as_secs() as u32)),
+        }
+    }
+}
+
+//----------- StagingSpec ------------------------------------------------------
+
+/// Policy for publishing zones to staging nameservers first.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct StagingSpec {
+    /// The set of staging nameservers to which NOTIFY messages should be sent.
+    ///
+    /// If empty, staging is disabled.
+    #[serde(default = "empty_list")]
+    pub send_notify_to: Vec<NameserverCommsSpec>,
+
+    /// How long a staged zone is kept before it is promoted automatically.
+    ///
+    /// If not set, staged zones are only promoted manually.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub soak_time: Option<TimeSpan>,
+}
+
+//--- Conversion
+
+impl StagingSpec {
+    /// Parse from this specification.
+    pub fn parse(self) -> StagingPolicy {
+        StagingPolicy {
+            send_notify_to: self.send_notify_to.into_iter().map(|v| v.parse()).collect(),
+            soak_time: self
+                .soak_time
+                .map(|span| Duration::from_secs(span.as_secs().into())),
+        }
+    }
+
+    /// Build into this specification.
+    pub fn build(policy: &StagingPolicy) -> Self {
+        Self {
+            send_notify_to: policy
+                .send_notify_to
+                .iter()
+                .map(NameserverCommsSpec::build)
+                .collect(),
+            soak_time: policy
+                .soak_time
+                .map(|time| TimeSpan::from_secs(time.as_secs() as u32)),
         }
     }
 }
@@ -1130,7 +1294,9 @@ impl FromStr for SimpleNameserverCommsSpec {
 
 #[cfg(test)]
 mod tests {
-    use super::KeyValiditySpec;
+    use std::time::Duration;
+
+    use super::{KeyValiditySpec, Spec};
     use crate::common::datetime::TimeSpan;
     use serde::Deserialize;
 
@@ -1172,4 +1338,28 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn parse_staging_spec() {
+        let spec: Spec = toml::from_str(
+            r#"
+            [server.staging]
+            send-notify-to = ["127.0.0.1:5353"]
+            soak-time = "1h"
+            "#,
+        )
+        .unwrap();
+        let staging = spec.parse("staged").server.staging;
+        assert!(staging.is_enabled());
+        assert_eq!(
+            staging.send_notify_to[0].addr,
+            "127.0.0.1:5353".parse().unwrap()
+        );
+        assert_eq!(staging.soak_time, Some(Duration::from_secs(3600)));
+
+        // Staging is disabled by default.
+        let staging = Spec::default().parse("default").server.staging;
+        assert!(!staging.is_enabled());
+        assert_eq!(staging.soak_time, None);
+    }
 }
@@ -2,13 +2,14 @@
 
 use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use std::{fs, io, sync::Arc};
 
 use bytes::Bytes;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use domain::base::Name;
 use domain::base::Ttl;
+use domain::base::iana::SecurityAlgorithm;
 use domain::tsig::KeyName;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, warn};
@@ -35,6 +36,46 @@ pub struct Policy {
 
     /// The zones using this policy.
     pub zones: foldhash::HashSet<Name<Bytes>>,
+
+    /// The state of the files backing the policy when it was last loaded.
+    ///
+    /// This covers the policy's own file and the files of the policies it
+    /// extends. If empty, the state is unknown. This is not persisted, so that
+    /// all policies are re-parsed on the first reload after startup.
+    pub files: Vec<PolicyFileStamp>,
+}
+
+/// The state of a policy file, used to detect whether it has changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyFileStamp {
+    /// The path to the file.
+    pub path: Box<Utf8Path>,
+
+    /// When the file was last modified.
+    pub modified: SystemTime,
+
+    /// The size of the file, in bytes.
+    pub len: u64,
+}
+
+impl PolicyFileStamp {
+    /// Determine the state of a policy file.
+    fn of(path: &Utf8Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            path: path.into(),
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+
+    /// Whether none of the given files changed since they were stamped.
+    fn all_unchanged(stamps: &[Self]) -> bool {
+        !stamps.is_empty()
+            && stamps
+                .iter()
+                .all(|stamp| Self::of(&stamp.path).as_ref() == Some(stamp))
+    }
 }
 
 //--- Loading / Saving
@@ -51,7 +92,9 @@ pub enum PolicyChange {
 
 /// Reload all policies.
 ///
-/// Any changes are reported via the `on_change` callback.
+/// Unless `full` is set, policy files that have not been modified since they
+/// were last loaded are not re-parsed. Any changes are reported via the
+/// `on_change` callback.
 // Allow the large enum variant caused by TsigKeyName using Name<Array<255>>
 // to avoid the conversions that would be needed if Name<Bytes> were to be
 // used instead.
@@ -60,14 +103,25 @@ pub fn reload_all(
     policies: &mut foldhash::HashMap<Box<str>, Policy>,
     config: &Config,
     tsig_store: &TsigStore,
+    full: bool,
     mut on_change: impl FnMut(&Box<str>, PolicyChange),
 ) -> Result<(), PolicyReloadError> {
-    let new_versions = load_all(policies, config, tsig_store)?;
+    let new_versions = load_all(policies, config, tsig_store, full)?;
 
     let mut new_policies = foldhash::HashMap::default();
 
-    for (name, new_version) in new_versions {
+    for (name, LoadedPolicy { version, files }) in new_versions {
+        let Some(new_version) = version else {
+            // The file is unchanged, so the policy is too.
+            let pol = policies
+                .remove(&name)
+                .expect("only known policies can be unchanged");
+            new_policies.insert(name, pol);
+            continue;
+        };
+
         if let Some(mut pol) = policies.remove(&name) {
+            pol.files = files;
             if *pol.latest == new_version {
                 new_policies.insert(name, pol);
             } else {
@@ -87,6 +141,7 @@ pub fn reload_all(
                     latest: new,
                     mid_deletion: false,
                     zones: Default::default(),
+                    files,
                 },
             );
         }
@@ -119,10 +174,24 @@ pub fn reload_all(
     Ok(())
 }
 
+/// A policy file found by [`load_all()`].
+pub struct LoadedPolicy {
+    /// The parsed policy, or `None` if the file was not modified since it was
+    /// last loaded.
+    pub version: Option<PolicyVersion>,
+
+    /// The state of the files the policy was loaded from.
+    ///
+    /// See [`Policy::files`].
+    pub files: Vec<PolicyFileStamp>,
+}
+
 /// Load all the policies based on the path to the config
 ///
 /// The current policies are used for logging purposes so we can log whether
-/// a policy is new, updated, unchanged or removed.
+/// a policy is new, updated, unchanged or removed. Unless `full` is set, they
+/// are also used to skip parsing files that were not modified since they were
+/// last loaded.
 // Allow the large enum variant caused by TsigKeyName using Name<Array<255>>
 // to avoid the conversions that would be needed if Name<Bytes> were to be
 // used instead.
@@ -131,7 +200,8 @@ pub fn load_all(
     policies: &foldhash::HashMap<Box<str>, Policy>,
     config: &Config,
     tsig_store: &TsigStore,
-) -> Result<foldhash::HashMap<Box<str>, PolicyVersion>, PolicyReloadError> {
+    full: bool,
+) -> Result<foldhash::HashMap<Box<str>, LoadedPolicy>, PolicyReloadError> {
     // Write the loaded policies to a new hashmap, so policies that no longer
     // exist can be detected easily.
     let mut new_policies = foldhash::HashMap::<_, _>::default();
@@ -171,28 +241,65 @@ pub fn load_all(
             continue;
         }
 
+        let name = path
+            .file_stem()
+            .expect("this path has a file name, as it has an extension");
+
+        // Check whether the files of the policy changed since it was last
+        // loaded.
+        if !full
+            && let Some(policy) = policies.get(name)
+            && PolicyFileStamp::all_unchanged(&policy.files)
+        {
+            debug!("Policy '{name}' is unchanged since it was last loaded");
+            let loaded = LoadedPolicy {
+                version: None,
+                files: policy.files.clone(),
+            };
+            let prev = new_policies.insert(name.into(), loaded);
+            assert!(prev.is_none(), "there is at most one policy per path");
+            continue;
+        }
+
         // Try loading the file; ignore a failure if it's a directory.
         //
+        // Files are examined before they are read, so a concurrent
+        // modification will be noticed on the next reload.
+        //
         // NOTE: Checking that the object is a file, and then opening it, would
         // be vulnerable to TOCTOU.
-        let spec = match file::Spec::load(&path) {
+        let mut files = Some(Vec::new());
+        let res = file::Spec::load_extended(&path, |path| {
+            match (&mut files, PolicyFileStamp::of(path)) {
+                (Some(files), Some(stamp)) => files.push(stamp),
+                (files, _) => *files = None,
+            }
+        });
+        let files = files.unwrap_or_default();
+        let spec = match res {
             Ok(spec) => spec,
             // Ignore a directory ending in '.toml'.
-            Err(err) if err.kind() == io::ErrorKind::IsADirectory => {
+            Err(file::LoadError::Io(p, err))
+                if *p == *path && err.kind() == io::ErrorKind::IsADirectory =>
+            {
                 warn!("Ignoring potential policy '{path}'; policies must be files");
                 continue;
             }
-            Err(err) => return Err(PolicyReloadError::Io(path, err.to_string())),
+            Err(file::LoadError::Io(p, err)) => {
+                return Err(PolicyReloadError::Io(p.into_path_buf(), err.to_string()));
+            }
+            Err(file::LoadError::Cycle(chain)) => {
+                let chain = chain.into_iter().map(String::from).collect();
+                return Err(PolicyReloadError::InheritanceCycle(chain));
+            }
+            Err(err) => return Err(PolicyReloadError::BadValue(err.to_string())),
         };
 
         // Build a new policy or merge an existing one.
-        let name = path
-            .file_stem()
-            .expect("this path points to a readable file, so it must have a file name");
-
         let policy = spec.parse(name);
 
         check_policy(&policy, tsig_store)?;
+        warn_serial_policy_keep(name, &policy);
         if policies.contains_key(name) {
             info!("Reloaded policy '{name}'");
         } else {
@@ -200,13 +307,36 @@ pub fn load_all(
         }
 
         // Record the new policy.
-        let prev = new_policies.insert(name.into(), policy);
+        let loaded = LoadedPolicy {
+            version: Some(policy),
+            files,
+        };
+        let prev = new_policies.insert(name.into(), loaded);
         assert!(prev.is_none(), "there is at most one policy per path");
     }
 
     Ok(new_policies)
 }
 
+/// Warn if the policy cannot publish re-signed zones on its own.
+///
+/// With the `keep` serial policy, a re-signed zone uses the serial of the
+/// unsigned zone.  If signatures are renewed without a change to the unsigned
+/// zone, the serial cannot increase, so secondaries would never pick up the
+/// renewed signatures; such re-signs are not published.  The unsigned zone
+/// has to change before its signatures need renewing.
+fn warn_serial_policy_keep(name: &str, policy: &PolicyVersion) {
+    if let SignerSerialPolicy::Keep = policy.signer.serial_policy {
+        let interval = policy
+            .signer
+            .sig_validity_time
+            .saturating_sub(policy.signer.sig_remain_time);
+        warn!(
+            "Policy '{name}' uses serial policy 'keep': renewed signatures are only published along with a new serial of the unsigned zone, so zones using it must be updated at least every {interval} seconds"
+        );
+    }
+}
+
 /// Perform a semantic check on the loaded policy.
 // Allow the large enum variant caused by TsigKeyName using Name<Array<255>>
 // to avoid the conversions that would be needed if Name<Bytes> were to be
@@ -220,6 +350,7 @@ fn check_policy(policy: &PolicyVersion, tsig_store: &TsigStore) -> Result<(), Po
         .publication_nameservers
         .iter()
         .chain(policy.server.outbound.provide_xfr_to.iter())
+        .chain(policy.server.outbound.provide_review_xfr_to.iter())
         .chain(policy.server.outbound.send_notify_to.iter())
         .filter_map(|ns| ns.tsig_key_name.as_ref());
 
@@ -230,83 +361,10 @@ fn check_policy(policy: &PolicyVersion, tsig_store: &TsigStore) -> Result<(), Po
     }
 
     // Check signer policy.
-
-    // sig_validity_time
-    //
-    // The maximum sig_validity_time is determined by what we can put in
-    // the expiration time. Expiration time is effectively a 32-bit signed
-    // value. So sig_validity_time has to be less then 0x8000_0000. To
-    // give ourselves some headroom, set the limit to 0x4000_0000.
-    if policy.signer.sig_validity_time >= 0x4000_0000 {
-        return Err(PolicyReloadError::BadValue(format!(
-            "signature-lifetime {} too big (>= 0x4000_0000)",
-            policy.signer.sig_validity_time
-        )));
-    }
-
-    // The minimum value of sig_validity_time is bounded by sig_remain_time
-    // and signature_refresh_interval. We get to this later.
-
-    // sig_remain_time
-    //
-    // The effective lifetime of a signature is
-    // sig_validity_time - sig_remain_time. This needs to be greater than
-    // zero. So the maximum value of sig_remain_time is bounded by
-    // sig_validity_time. We will check this later.
-    //
-    // Ideally, sig_remain_time should be larger than the maximum TTL
-    // to make sure that old signatures are removed from caches before
-    // they expire. We don't have a maximum TTL value. So what the signer
-    // does is add the TTL of an RRset to sig_remain_time to determine
-    // if a signature needs to be refreshed. For this reason, the lower bound
-    // of sig_remain_time is zero. However, this does not leave any margin
-    // for error.
-
-    // signature_refresh_interval
-    //
-    // The maximum is again bounded by sig_validity_time.
-    //
-    // Each signature_refresh_interval seconds, the signer will generate a
-    // new version of the zone with some refreshed signatures. For this reason,
-    // signature_refresh_interval should not be too low. Enforce a lower
-    // bound of 60 seconds to avoid accidentally generating new zone versions
-    // at a high rate.
-    if policy.signer.signature_refresh_interval < 60 {
-        return Err(PolicyReloadError::BadValue(format!(
-            "signature-refresh-interval {} too small (< 60)",
-            policy.signer.signature_refresh_interval
-        )));
-    }
-
-    // Check if everything fits together. The effective lifetime of a
-    // signature is sig_validity_time - sig_remain_time. This needs to be
-    // greater than zero. We need to take TTL into account. Assume a reasonable
-    // TTL of one hour (3600 seconds). So now we have
-    // sig_validity_time - sig_remain_time - 3600 > 0.
-    // We sign every signature_refresh_interval so we need to take that into
-    // account. Which gives:
-    // sig_validity_time - sig_remain_time - 3600 - signature_refresh_interval > 0
-    // Which can be written as:
-    // sig_validity_time > sig_remain_time + 3600 + signature_refresh_interval
-    //
-    // If an RRset has a high TTL such that
-    // sig_remain_time + TTL + signature_refresh_interval >= sig_validity_time
-    // then the signature will be refreshed every signature_refresh_interval
-    // and an error will be logged. In extreme cases, i.e. when
-    // TTL + signature_refresh_interval > sig_validity_time
-    // then validation errors may happen due to caching. However, this only
-    // affects RRsets with too high TTLs. The rest of the zone will be
-    // unaffected.
-    if policy.signer.sig_validity_time
-        <= policy.signer.sig_remain_time + 3600 + policy.signer.signature_refresh_interval
-    {
-        return Err(PolicyReloadError::BadValue(format!(
-            "signature-lifetime ({}) too small (<= signature-remain-time ({}) + room for TTL (3600) + signature-refresh-interval ({}))",
-            policy.signer.sig_validity_time,
-            policy.signer.sig_remain_time,
-            policy.signer.signature_refresh_interval
-        )));
-    }
+    policy
+        .signer
+        .check_timing()
+        .map_err(PolicyReloadError::BadValue)?;
 
     // key_roll_time
     //
@@ -342,10 +400,37 @@ pub struct PolicyVersion {
 //----------- LoaderPolicy -----------------------------------------------------
 
 /// Policy for loading zones.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct LoaderPolicy {
     /// Reviewing loaded zones.
     pub review: ReviewPolicy,
+
+    /// Limits on inbound zone transfers.
+    pub transfer: TransferLimitsPolicy,
+
+    /// Whether SOA queries to primaries are sent over TCP.
+    ///
+    /// Responses over UDP can be spoofed by off-path attackers, who could use
+    /// them to suppress refreshes and keep the zone stale.
+    pub soa_query_over_tcp: bool,
+}
+
+//----------- TransferLimitsPolicy ---------------------------------------------
+
+/// Limits on inbound zone transfers.
+///
+/// A transfer exceeding any of these limits is aborted, and the current
+/// instance of the zone is kept.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransferLimitsPolicy {
+    /// The maximum number of bytes a single transfer may consist of.
+    pub max_bytes: Option<u64>,
+
+    /// The maximum number of records a single transfer may contain.
+    pub max_records: Option<u64>,
+
+    /// How long a refresh of the zone from its primary may take.
+    pub timeout: Option<Duration>,
 }
 
 //----------- KeyManagerPolicy -------------------------------------------------
@@ -409,10 +494,40 @@ pub struct KeyManagerPolicy {
     /// Remove keys after this amount of time.
     pub auto_remove_delay: Duration,
 
+    /// The maximum amount by which key validities are shortened, in seconds.
+    ///
+    /// See [`Self::validity_jitter_for()`].
+    pub validity_jitter: u32,
+
     /// Nameservers to check for RRSIG propagation during a key roll.
     pub publication_nameservers: Vec<NameserverCommsPolicy>,
 }
 
+impl KeyManagerPolicy {
+    /// The amount by which key validities are shortened for a zone.
+    ///
+    /// Zones sharing a policy would otherwise roll their keys at the same
+    /// time, requiring many DS updates at once. The jitter is derived from
+    /// the zone name, so it is stable across restarts and policy reloads, and
+    /// lies in `0..=validity_jitter` seconds. Validities are only ever
+    /// shortened, so keys never outlive the configured validity.
+    pub fn validity_jitter_for(&self, zone: &Name<Bytes>) -> u32 {
+        if self.validity_jitter == 0 {
+            return 0;
+        }
+
+        // FNV-1a, as 'std' hashers are not guaranteed to be stable.
+        let hash = zone
+            .to_string()
+            .to_ascii_lowercase()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |hash, b| {
+                (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+            });
+        (hash % (u64::from(self.validity_jitter) + 1)) as u32
+    }
+}
+
 //----------- SignerPolicy -----------------------------------------------------
 
 /// Policy for signing zones.
@@ -450,12 +565,111 @@ pub struct SignerPolicy {
 
     /// Reviewing signed zones.
     pub review: ReviewPolicy,
+
+    /// Whether to keep signatures made by an external signer.
+    ///
+    /// If enabled, `RRSIG` records in the loaded zone which were not made by
+    /// one of Cascade's own keys are copied into the signed zone, as long as
+    /// they have not expired.  Records covered by such signatures are still
+    /// signed by Cascade as well.
+    pub preserve_external_signatures: bool,
+
+    /// Whether to refuse signing zones which already contain DNSSEC records.
+    ///
+    /// Loaded zones are expected to be unsigned.  DNSSEC records in them are
+    /// never signed over: by default, they are stripped, and the zone is
+    /// signed in full.  If enabled, signing fails instead.  Signatures kept
+    /// due to `preserve_external_signatures` are not counted.
+    pub refuse_dnssec_input: bool,
     //
     // TODO:
     // - Signing policy (disabled, pass-through?, enabled)
     // - Support keeping unsigned vs. signed zone serials distinct
 }
 
+impl SignerPolicy {
+    /// Check that the signature timing settings fit together.
+    ///
+    /// This is used both for policies and for the one-off overrides of a
+    /// `zone resign` command.
+    pub fn check_timing(&self) -> Result<(), String> {
+        // sig_validity_time
+        //
+        // The maximum sig_validity_time is determined by what we can put in
+        // the expiration time. Expiration time is effectively a 32-bit signed
+        // value. So sig_validity_time has to be less then 0x8000_0000. To
+        // give ourselves some headroom, set the limit to 0x4000_0000.
+        if self.sig_validity_time >= 0x4000_0000 {
+            return Err(format!(
+                "signature-lifetime {} too big (>= 0x4000_0000)",
+                self.sig_validity_time
+            ));
+        }
+
+        // The minimum value of sig_validity_time is bounded by sig_remain_time
+        // and signature_refresh_interval. We get to this later.
+
+        // sig_remain_time
+        //
+        // The effective lifetime of a signature is
+        // sig_validity_time - sig_remain_time. This needs to be greater than
+        // zero. So the maximum value of sig_remain_time is bounded by
+        // sig_validity_time. We will check this later.
+        //
+        // Ideally, sig_remain_time should be larger than the maximum TTL
+        // to make sure that old signatures are removed from caches before
+        // they expire. We don't have a maximum TTL value. So what the signer
+        // does is add the TTL of an RRset to sig_remain_time to determine
+        // if a signature needs to be refreshed. For this reason, the lower bound
+        // of sig_remain_time is zero. However, this does not leave any margin
+        // for error.
+
+        // signature_refresh_interval
+        //
+        // The maximum is again bounded by sig_validity_time.
+        //
+        // Each signature_refresh_interval seconds, the signer will generate a
+        // new version of the zone with some refreshed signatures. For this reason,
+        // signature_refresh_interval should not be too low. Enforce a lower
+        // bound of 60 seconds to avoid accidentally generating new zone versions
+        // at a high rate.
+        if self.signature_refresh_interval < 60 {
+            return Err(format!(
+                "signature-refresh-interval {} too small (< 60)",
+                self.signature_refresh_interval
+            ));
+        }
+
+        // Check if everything fits together. The effective lifetime of a
+        // signature is sig_validity_time - sig_remain_time. This needs to be
+        // greater than zero. We need to take TTL into account. Assume a reasonable
+        // TTL of one hour (3600 seconds). So now we have
+        // sig_validity_time - sig_remain_time - 3600 > 0.
+        // We sign every signature_refresh_interval so we need to take that into
+        // account. Which gives:
+        // sig_validity_time - sig_remain_time - 3600 - signature_refresh_interval > 0
+        // Which can be written as:
+        // sig_validity_time > sig_remain_time + 3600 + signature_refresh_interval
+        //
+        // If an RRset has a high TTL such that
+        // sig_remain_time + TTL + signature_refresh_interval >= sig_validity_time
+        // then the signature will be refreshed every signature_refresh_interval
+        // and an error will be logged. In extreme cases, i.e. when
+        // TTL + signature_refresh_interval > sig_validity_time
+        // then validation errors may happen due to caching. However, this only
+        // affects RRsets with too high TTLs. The rest of the zone will be
+        // unaffected.
+        if self.sig_validity_time <= self.sig_remain_time + 3600 + self.signature_refresh_interval {
+            return Err(format!(
+                "signature-lifetime ({}) too small (<= signature-remain-time ({}) + room for TTL (3600) + signature-refresh-interval ({}))",
+                self.sig_validity_time, self.sig_remain_time, self.signature_refresh_interval
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 //----------- SignerSerialPolicy -----------------------------------------------
 
 /// Policy for generating serial numbers.
@@ -545,6 +759,9 @@ pub enum OnReject {
 pub struct ServerPolicy {
     /// Outbound policy.
     pub outbound: OutboundPolicy,
+
+    /// Staging policy.
+    pub staging: StagingPolicy,
 }
 
 //----------- OutboundPolicy --------------------------------------------------
@@ -557,6 +774,12 @@ pub struct OutboundPolicy {
     /// If empty, zone transfers will be provided to any nameserver.
     pub provide_xfr_to: Vec<NameserverCommsPolicy>,
 
+    /// The set of nameservers to which the review servers may provide zone
+    /// transfers of instances under review.
+    ///
+    /// If empty, instances under review will be provided to any nameserver.
+    pub provide_review_xfr_to: Vec<NameserverCommsPolicy>,
+
     /// The set of nameservers to which NOTIFY messages should be sent.
     ///
     /// If empty, no NOTIFY messages will be sent.
@@ -576,6 +799,39 @@ pub struct OutboundPolicy {
     /// published version of the zone) than this limit will be kept in-memory
     /// to to serve to IXFR clients.
     pub max_diffs_size: usize,
+
+    /// How long a removed zone is still served.
+    ///
+    /// If `None`, a zone is no longer served as soon as it is removed.
+    pub removal_grace_period: Option<Duration>,
+}
+
+//----------- StagingPolicy ---------------------------------------------------
+
+/// Policy for publishing zones to staging nameservers first.
+///
+/// While a signed zone is staged, it is served by the signed review server
+/// and the staging nameservers are notified of it.  It is only published to
+/// production once it is promoted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StagingPolicy {
+    /// The set of staging nameservers to which NOTIFY messages should be sent.
+    ///
+    /// If empty, staging is disabled and approved zones are published
+    /// immediately.
+    pub send_notify_to: Vec<NameserverCommsPolicy>,
+
+    /// How long a staged zone is kept before it is promoted automatically.
+    ///
+    /// If `None`, staged zones are only promoted with `cascade zone promote`.
+    pub soak_time: Option<Duration>,
+}
+
+impl StagingPolicy {
+    /// Whether staging is enabled.
+    pub fn is_enabled(&self) -> bool {
+        !self.send_notify_to.is_empty()
+    }
 }
 
 //----------- NameserverCommsPolicy -------------------------------------------
@@ -635,6 +891,20 @@ pub enum KeyParameters {
     Ed448,
 }
 
+impl KeyParameters {
+    /// The DNSSEC algorithm of keys generated with these parameters.
+    pub fn algorithm(&self) -> SecurityAlgorithm {
+        match self {
+            KeyParameters::RsaSha256(_) => SecurityAlgorithm::RSASHA256,
+            KeyParameters::RsaSha512(_) => SecurityAlgorithm::RSASHA512,
+            KeyParameters::EcdsaP256Sha256 => SecurityAlgorithm::ECDSAP256SHA256,
+            KeyParameters::EcdsaP384Sha384 => SecurityAlgorithm::ECDSAP384SHA384,
+            KeyParameters::Ed25519 => SecurityAlgorithm::ED25519,
+            KeyParameters::Ed448 => SecurityAlgorithm::ED448,
+        }
+    }
+}
+
 impl Display for KeyParameters {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
         match self {
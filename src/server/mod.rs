@@ -1,25 +1,34 @@
 //! Serving zone data.
 
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Duration};
 
 use domain::base::Serial;
+use domain::new::base::{RType, name::RevName};
+use domain::rdata::ZoneRecordData;
 use tracing::{debug, error, info, trace, warn};
 
 use crate::{
     center::Center,
+    config::ServerConfig,
     daemon::SocketProvider,
     manager::Terminated,
+    metrics::ReviewOutcome,
     policy::OnReject,
     units::zone_server::{Source, ZoneServer},
     util::AbortOnDrop,
     zone::{Zone, ZoneHandle, machine::ZoneStateMachine},
-    zonedata::{LoadedZoneReviewer, SignedZoneReviewer, ZoneViewer},
+    zonedata::{
+        DiffData, LoadedZoneReviewer, OldRecord, RegularRecord, SignedZoneReviewer, SoaRecord,
+        ZoneViewer,
+    },
 };
 
 mod notify;
 mod request;
+pub mod rrl;
 mod service;
 
+use rrl::RateLimiter;
 use service::{ZoneService, ZoneServiceHandle};
 
 //----------- LoadedReviewServer -----------------------------------------------
@@ -75,6 +84,7 @@ impl LoadedReviewServer {
         zone: &Arc<Zone>,
         zone_serial: Serial,
         decision: crate::api::ZoneReviewDecision,
+        reason: Option<String>,
     ) -> crate::api::ZoneReviewResult {
         let mut handle = zone.write_handle(center);
 
@@ -106,6 +116,10 @@ impl LoadedReviewServer {
             return Err(crate::api::ZoneReviewError::NoSuchZone);
         };
 
+        if let Some(reason) = &reason {
+            info!("Reason given for reviewing zone '{}': {reason}", zone.name);
+        }
+
         match decision {
             crate::api::ZoneReviewDecision::Approve => {
                 info!(
@@ -113,7 +127,7 @@ impl LoadedReviewServer {
                     zone.name, zone_serial.0
                 );
 
-                handle.get().approve_loaded();
+                handle.get().approve_loaded(reason);
             }
 
             crate::api::ZoneReviewDecision::Reject => {
@@ -124,10 +138,14 @@ impl LoadedReviewServer {
 
                 match policy.loader.review.on_reject {
                     OnReject::Discard => {
-                        handle.get().soft_reject_loaded();
+                        handle
+                            .get()
+                            .soft_reject_loaded(reason, ReviewOutcome::Rejected);
                     }
                     OnReject::Halt => {
-                        handle.get().hard_reject_loaded();
+                        handle
+                            .get()
+                            .hard_reject_loaded(reason, ReviewOutcome::Rejected);
                     }
                 }
             }
@@ -158,6 +176,53 @@ impl LoadedReviewServer {
         let handle = &center.loaded_review_server.handle;
         handle.remove_zone(zone);
     }
+
+    /// Get the viewer for this zone.
+    pub fn viewer(&self, zone: &Arc<Zone>) -> Option<Arc<tokio::sync::RwLock<LoadedZoneReviewer>>> {
+        self.handle.viewer(zone)
+    }
+
+    /// Summarize the loaded instance offered by a reviewer.
+    ///
+    /// Returns `None` if the reviewer does not offer an instance.
+    pub fn summarize(
+        zone: &Zone,
+        reviewer: &LoadedZoneReviewer,
+    ) -> Option<crate::api::ZoneReviewSummary> {
+        let reader = reviewer.read()?;
+        let soa = reader.soa();
+        Some(crate::api::ZoneReviewSummary {
+            zone: zone.name.clone(),
+            stage: crate::api::ZoneReviewStage::Unsigned,
+            serial: Serial(soa.rdata.serial.into()),
+            record_count: reader.regular_records().len(),
+            diff: reviewer
+                .loaded_diff()
+                .map(|diff| summarize_diff(diff, &soa.rname)),
+            keys: None,
+        })
+    }
+
+    /// Render the loaded instance offered by a reviewer as a zonefile.
+    ///
+    /// Returns `None` if the reviewer does not offer an instance.
+    pub fn zonefile(
+        zone: &Zone,
+        reviewer: &LoadedZoneReviewer,
+    ) -> Option<crate::api::ZoneReviewZonefile> {
+        let reader = reviewer.read()?;
+        let soa = reader.soa();
+        let records = reader
+            .regular_records()
+            .iter()
+            .filter(|&r| r.rname != soa.rname || r.rtype != soa.rtype);
+        Some(crate::api::ZoneReviewZonefile {
+            zone: zone.name.clone(),
+            stage: crate::api::ZoneReviewStage::Unsigned,
+            serial: Serial(soa.rdata.serial.into()),
+            records: zonefile_records(soa, records),
+        })
+    }
 }
 
 impl Default for LoadedReviewServer {
@@ -225,6 +290,7 @@ impl SignedReviewServer {
         zone: &Arc<Zone>,
         zone_serial: Serial,
         decision: crate::api::ZoneReviewDecision,
+        reason: Option<String>,
     ) -> crate::api::ZoneReviewResult {
         let mut handle = zone.write_handle(center);
 
@@ -256,14 +322,28 @@ impl SignedReviewServer {
             return Err(crate::api::ZoneReviewError::NoSuchZone);
         };
 
+        if let Some(reason) = &reason {
+            info!("Reason given for reviewing zone '{}': {reason}", zone.name);
+        }
+
         match decision {
+            crate::api::ZoneReviewDecision::Approve if handle.state.staged_since.is_some() => {
+                debug!("The signed instance has already been approved and staged");
+                return Err(crate::api::ZoneReviewError::NotUnderReview);
+            }
+
+            crate::api::ZoneReviewDecision::Approve if handle.state.held_since.is_some() => {
+                debug!("The signed instance has already been approved and held");
+                return Err(crate::api::ZoneReviewError::NotUnderReview);
+            }
+
             crate::api::ZoneReviewDecision::Approve => {
                 info!(
                     "The signed instance of zone '{}' (SOA serial {}) has been approved.",
                     zone.name, zone_serial.0
                 );
 
-                handle.get().approve_signed();
+                handle.get().approve_signed(reason);
             }
 
             crate::api::ZoneReviewDecision::Reject => {
@@ -274,10 +354,14 @@ impl SignedReviewServer {
 
                 match policy.signer.review.on_reject {
                     OnReject::Discard => {
-                        handle.get().soft_reject_signed();
+                        handle
+                            .get()
+                            .soft_reject_signed(reason, ReviewOutcome::Rejected);
                     }
                     OnReject::Halt => {
-                        handle.get().hard_reject_signed();
+                        handle
+                            .get()
+                            .hard_reject_signed(reason, ReviewOutcome::Rejected);
                     }
                 }
             }
@@ -286,6 +370,97 @@ impl SignedReviewServer {
         Ok(crate::api::ZoneReviewOutput {})
     }
 
+    /// Promote a staged instance to production.
+    pub fn promote(center: &Arc<Center>, zone: &Arc<Zone>) -> crate::api::ZonePromoteResult {
+        let mut handle = zone.write_handle(center);
+
+        if handle.state.staged_since.is_none() {
+            debug!("The zone has no staged instance");
+            return Err(crate::api::ZonePromoteError::NotStaged);
+        }
+
+        info!(
+            "The staged instance of zone '{}' has been promoted.",
+            zone.name
+        );
+
+        handle.get().promote_signed();
+
+        Ok(crate::api::ZonePromoteOutput {
+            zone: zone.name.clone(),
+        })
+    }
+
+    /// Take an observe-only zone live.
+    pub fn go_live(center: &Arc<Center>, zone: &Arc<Zone>) -> crate::api::ZoneGoLiveResult {
+        let mut handle = zone.write_handle(center);
+
+        if !handle.state.observe_only {
+            debug!("The zone is not observe-only");
+            return Err(crate::api::ZoneGoLiveError::NotObserveOnly);
+        }
+
+        info!("Zone '{}' is going live.", zone.name);
+
+        let released = handle.state.held_since.is_some();
+        handle.get().go_live();
+
+        Ok(crate::api::ZoneGoLiveOutput {
+            zone: zone.name.clone(),
+            released,
+        })
+    }
+
+    /// React to the staging of an instance.
+    ///
+    /// Sends NOTIFY messages to the staging name servers, so that they
+    /// transfer the staged instance from the signed review server.
+    #[tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(zone = %handle.zone.name),
+    )]
+    pub fn after_staging(handle: &mut ZoneHandle<'_>) {
+        let instance = handle
+            .state
+            .instances
+            .upcoming
+            .as_ref()
+            .and_then(|i| i.signed.as_ref())
+            .expect("A staged zone must have an upcoming signed instance");
+        let policy = handle
+            .state
+            .policy
+            .as_ref()
+            .expect("A staged zone always has a policy");
+
+        let targets = policy
+            .server
+            .staging
+            .send_notify_to
+            .iter()
+            .filter(|&s| s.addr.port() != 0)
+            .collect::<Vec<_>>();
+
+        debug!(
+            "Sending NOTIFY messages to {} staging name servers",
+            targets.len()
+        );
+
+        if targets.is_empty() {
+            return;
+        }
+
+        trace!("Target name servers: {targets:?}");
+
+        self::notify::send_notify_to_addrs(
+            handle.zone.name.clone(),
+            instance.soa.clone(),
+            targets.into_iter(),
+            handle.center,
+        );
+    }
+
     /// Register a new zone.
     pub fn add_zone(center: &Arc<Center>, zone: Arc<Zone>, viewer: SignedZoneReviewer) {
         let handle = &center.signed_review_server.handle;
@@ -308,6 +483,90 @@ impl SignedReviewServer {
         let handle = &center.signed_review_server.handle;
         handle.remove_zone(zone);
     }
+
+    /// Get the viewer for this zone.
+    pub fn viewer(&self, zone: &Arc<Zone>) -> Option<Arc<tokio::sync::RwLock<SignedZoneReviewer>>> {
+        self.handle.viewer(zone)
+    }
+
+    /// Summarize the signed instance offered by a reviewer.
+    ///
+    /// Returns `None` if the reviewer does not offer an instance.
+    pub fn summarize(
+        zone: &Zone,
+        reviewer: &SignedZoneReviewer,
+    ) -> Option<crate::api::ZoneReviewSummary> {
+        let reader = reviewer.read()?;
+        let soa = reader.soa();
+        Some(crate::api::ZoneReviewSummary {
+            zone: zone.name.clone(),
+            stage: crate::api::ZoneReviewStage::Signed,
+            serial: Serial(soa.rdata.serial.into()),
+            record_count: reader.all_records().count(),
+            diff: reviewer
+                .loaded_diff()
+                .map(|diff| summarize_diff(diff, &soa.rname)),
+            keys: reviewer.signed_diff().map(|diff| summarize_keys(diff)),
+        })
+    }
+
+    /// Render the signed instance offered by a reviewer as a zonefile.
+    ///
+    /// Returns `None` if the reviewer does not offer an instance.
+    pub fn zonefile(
+        zone: &Zone,
+        reviewer: &SignedZoneReviewer,
+    ) -> Option<crate::api::ZoneReviewZonefile> {
+        let reader = reviewer.read()?;
+        let soa = reader.soa();
+        let records = reader
+            .generated_records()
+            .iter()
+            .filter(|&r| r.rname != soa.rname || r.rtype != soa.rtype)
+            .chain(reader.loaded_records());
+        Some(crate::api::ZoneReviewZonefile {
+            zone: zone.name.clone(),
+            stage: crate::api::ZoneReviewStage::Signed,
+            serial: Serial(soa.rdata.serial.into()),
+            records: zonefile_records(soa, records),
+        })
+    }
+}
+
+/// Count the records added and removed by a diff.
+fn summarize_diff(diff: &DiffData, origin: &RevName) -> crate::api::ZoneReviewDiff {
+    crate::api::ZoneReviewDiff {
+        added: diff.added_non_soa(origin).count(),
+        removed: diff.removed_non_soa(origin).count(),
+    }
+}
+
+/// Format the records of an instance in zonefile format, SOA record first.
+fn zonefile_records<'a>(
+    soa: &SoaRecord,
+    records: impl Iterator<Item = &'a RegularRecord>,
+) -> Vec<String> {
+    std::iter::once(OldRecord::from(soa.clone()).to_string())
+        .chain(records.map(|r| OldRecord::from(r.clone()).to_string()))
+        .collect()
+}
+
+/// Collect the key tags of the DNSKEY records added and removed by a diff.
+fn summarize_keys(diff: &DiffData) -> crate::api::ZoneReviewKeyChanges {
+    let key_tags = |records: &[RegularRecord]| {
+        records
+            .iter()
+            .filter(|r| r.rtype == RType::DNSKEY)
+            .filter_map(|r| match OldRecord::from(r.clone()).data() {
+                ZoneRecordData::Dnskey(dnskey) => Some(dnskey.key_tag()),
+                _ => None,
+            })
+            .collect()
+    };
+    crate::api::ZoneReviewKeyChanges {
+        added: key_tags(&diff.added_records),
+        removed: key_tags(&diff.removed_records),
+    }
 }
 
 impl Default for SignedReviewServer {
@@ -331,13 +590,32 @@ pub struct PublicationServer {
 
     /// A handle for controlling the service.
     handle: ZoneServiceHandle<ZoneViewer>,
+
+    /// The rate limiter for responses, if enabled.
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl PublicationServer {
     /// Construct a new [`PublicationServer`].
-    pub fn new() -> Self {
-        let (service, handle) = ZoneService::new(service::ServiceMode::Publication);
-        Self { service, handle }
+    pub fn new(config: &ServerConfig) -> Self {
+        let (mut service, handle) = ZoneService::new(service::ServiceMode::Publication);
+        let rate_limiter = config
+            .rrl
+            .clone()
+            .map(|config| Arc::new(RateLimiter::new(config)));
+        if let Some(rate_limiter) = &rate_limiter {
+            service = service.with_rate_limiter(rate_limiter.clone());
+        }
+        Self {
+            service,
+            handle,
+            rate_limiter,
+        }
+    }
+
+    /// The rate limiter for responses, if enabled.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.rate_limiter.as_deref()
     }
 
     /// Drive the server.
@@ -401,9 +679,85 @@ impl PublicationServer {
         );
     }
 
+    /// Test NOTIFY messages to the downstream name servers of a zone.
+    ///
+    /// A NOTIFY for the published instance is sent to each configured name
+    /// server, and the responses are collected.  The zone is not changed.
+    pub async fn test_notify(
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+    ) -> crate::api::ZoneTestNotifyResult {
+        let (soa, targets) = {
+            let state = zone.read();
+            let soa = state
+                .instances
+                .current
+                .as_ref()
+                .map(|instance| instance.signed.soa.clone())
+                .ok_or(crate::api::ZoneTestNotifyError::NotPublished)?;
+            let targets = state
+                .policy
+                .as_ref()
+                .map(|policy| {
+                    policy
+                        .server
+                        .outbound
+                        .send_notify_to
+                        .iter()
+                        .filter(|&s| s.addr.port() != 0)
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            (soa, targets)
+        };
+
+        debug!(
+            "Sending test NOTIFY messages to {} downstream name servers",
+            targets.len()
+        );
+
+        let serial = Serial(soa.rdata.serial.into());
+        let results =
+            self::notify::test_notify_addrs(zone.name.clone(), soa, targets, center).await;
+        let targets = results
+            .into_iter()
+            .map(|(nameserver, result)| crate::api::ZoneTestNotifyTarget {
+                addr: nameserver.addr,
+                tsig_key: nameserver.tsig_key_name,
+                outcome: match result {
+                    Ok(rcode) => crate::api::NotifyOutcome::Response {
+                        rcode: rcode.to_string(),
+                    },
+                    Err(error) => crate::api::NotifyOutcome::Failed {
+                        error: error.to_string(),
+                    },
+                },
+            })
+            .collect();
+
+        Ok(crate::api::ZoneTestNotifyOutput {
+            zone: zone.name.clone(),
+            serial,
+            targets,
+        })
+    }
+
     /// Register a new zone.
+    ///
+    /// If a removed zone of the same name is still being served during its
+    /// removal grace period, it is no longer served.
     pub fn add_zone(center: &Arc<Center>, zone: Arc<Zone>, viewer: ZoneViewer) {
         let handle = &center.publication_server.handle;
+        if let Some(removed) = handle.registered_zone(&zone)
+            && !Arc::ptr_eq(&removed, &zone)
+        {
+            info!(
+                "Ending the removal grace period of zone '{}' early; it was added again",
+                zone.name
+            );
+            handle.remove_zone(&removed);
+        }
         handle.add_zone(zone, viewer)
     }
 
@@ -419,9 +773,38 @@ impl PublicationServer {
     }
 
     /// Remove a zone.
-    pub fn remove_zone(center: &Arc<Center>, zone: &Arc<Zone>) {
+    ///
+    /// If a grace period is given, the zone is still served until it has
+    /// passed (unless a zone of the same name is added in the meantime).
+    pub fn remove_zone(center: &Arc<Center>, zone: &Arc<Zone>, grace_period: Option<Duration>) {
         let handle = &center.publication_server.handle;
-        handle.remove_zone(zone);
+        let Some(grace_period) = grace_period.filter(|period| !period.is_zero()) else {
+            handle.remove_zone(zone);
+            return;
+        };
+
+        info!(
+            "Serving removed zone '{}' for another {}s",
+            zone.name,
+            grace_period.as_secs()
+        );
+        let zone = zone.clone();
+        let center = center.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(grace_period).await;
+
+            if center.publication_server.handle.try_remove_zone(&zone) {
+                info!(
+                    "The removal grace period of zone '{}' has passed; no longer serving it",
+                    zone.name
+                );
+
+                let mut state = center.state.lock().unwrap();
+                if state.removing_zones.remove(&zone.name) {
+                    state.mark_dirty(&center);
+                }
+            }
+        });
     }
 
     /// Get the viewer for this zone.
@@ -433,12 +816,6 @@ impl PublicationServer {
     }
 }
 
-impl Default for PublicationServer {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl fmt::Debug for PublicationServer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("PublicationServer").finish_non_exhaustive()
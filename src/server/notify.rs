@@ -1,21 +1,25 @@
 //! Notifying downstream servers.
 
-use std::{sync::Arc, time::Duration};
+use std::{fmt, sync::Arc, time::Duration};
 
 use bytes::Bytes;
 use cascade_zonedata::OldRecord;
 use domain::{
-    base::{MessageBuilder, Name, Rtype, iana::Opcode},
+    base::{
+        Message, MessageBuilder, Name, Rtype,
+        iana::{Opcode, Rcode},
+    },
     net::client::{
         dgram,
         protocol::UdpConnect,
-        request::{RequestMessage, SendRequest},
+        request::{self, RequestMessage, SendRequest},
         tsig,
     },
+    tsig::{Key, KeyName},
 };
-use tracing::{debug, trace, warn};
+use tracing::{debug, error, trace, warn};
 
-use crate::{center::Center, policy::NameserverCommsPolicy, zonedata::SoaRecord};
+use crate::{center::Center, policy::NameserverCommsPolicy, tsig::TsigStore, zonedata::SoaRecord};
 
 pub fn send_notify_to_addrs<'a>(
     apex_name: Name<Bytes>,
@@ -23,35 +27,13 @@ pub fn send_notify_to_addrs<'a>(
     notify_set: impl Iterator<Item = &'a NameserverCommsPolicy>,
     center: &Arc<Center>,
 ) {
-    let mut dgram_config = domain::net::client::dgram::Config::new();
-    dgram_config.set_max_parallel(1);
-    dgram_config.set_read_timeout(Duration::from_millis(1000));
-    dgram_config.set_max_retries(1);
-    dgram_config.set_udp_payload_size(Some(1400));
-
-    let mut msg = MessageBuilder::new_vec();
-    msg.header_mut().set_opcode(Opcode::NOTIFY);
-    let mut msg = msg.question();
-    msg.push((apex_name, Rtype::SOA)).unwrap();
-
-    // Include the current zone SOA as an RFC 1996 "unsecure hint" (see
-    // section 3.7) to the receiving nameserver so that it can choose to avoid
-    // sending a SOA query if it deems that it has this version of the zone
-    // already.
-    let mut msg = msg.answer();
-    msg.push(OldRecord::from(soa)).unwrap();
+    let req = notify_message(apex_name, soa);
 
     for nameserver in notify_set {
-        let dgram_config = dgram_config.clone();
-        let req = RequestMessage::new(msg.clone()).unwrap();
-
+        let req = req.clone();
         let nameserver = nameserver.clone();
         let center = center.clone();
         tokio::spawn(async move {
-            // TODO: Use the connection factory here.
-            let udp_connect = UdpConnect::new(nameserver.addr);
-            let client = dgram::Connection::with_config(udp_connect, dgram_config.clone());
-
             trace!("Sending NOTIFY to nameserver {nameserver}");
             let span = tracing::trace_span!("auth", addr = %nameserver);
             let _guard = span.enter();
@@ -65,32 +47,158 @@ pub fn send_notify_to_addrs<'a>(
             //
             // TODO: We have no retry queue at the moment. Do we need one?
 
-            let tsig_key = {
-                let state = center.state.lock().unwrap();
-                nameserver
-                    .tsig_key_name
-                    .as_ref()
-                    .and_then(|tsig_key_name| state.tsig_store.get(tsig_key_name))
-                    .map(|key| key.inner.clone())
-            };
-
-            if let Some(key) = &tsig_key {
-                debug!(
-                    "Found TSIG key '{}' (algorithm {}) for NOTIFY to {nameserver}",
-                    key.name(),
-                    key.algorithm()
-                );
-            }
-            let res = if let Some(key) = tsig_key {
-                let client = tsig::Connection::new(key.clone(), client);
-                client.send_request(req.clone()).get_response().await
-            } else {
-                client.send_request(req.clone()).get_response().await
-            };
-
-            if let Err(err) = res {
-                warn!("Unable to send NOTIFY to nameserver {nameserver}: {err}");
+            match send_notify(&nameserver, req, &center).await {
+                Ok(_) => {}
+                Err(err @ NotifyError::MissingTsigKey(_)) => {
+                    error!("Not sending NOTIFY to nameserver {nameserver}: {err}");
+                }
+                Err(err) => {
+                    warn!("Unable to send NOTIFY to nameserver {nameserver}: {err}");
+                }
             }
         });
     }
 }
+
+/// Send NOTIFY messages and wait for the responses.
+///
+/// Unlike [`send_notify_to_addrs()`], this reports the RCODE of the response
+/// from each nameserver, or the error if none was received.
+pub async fn test_notify_addrs(
+    apex_name: Name<Bytes>,
+    soa: SoaRecord,
+    notify_set: Vec<NameserverCommsPolicy>,
+    center: &Arc<Center>,
+) -> Vec<(NameserverCommsPolicy, Result<Rcode, NotifyError>)> {
+    let req = notify_message(apex_name, soa);
+
+    let notifies = notify_set.into_iter().map(|nameserver| {
+        let req = req.clone();
+        async move {
+            trace!("Sending test NOTIFY to nameserver {nameserver}");
+            let result = send_notify(&nameserver, req, center)
+                .await
+                .map(|response| response.header().rcode());
+            (nameserver, result)
+        }
+    });
+    futures_util::future::join_all(notifies).await
+}
+
+/// Prepare a NOTIFY message for a zone.
+fn notify_message(apex_name: Name<Bytes>, soa: SoaRecord) -> RequestMessage<Vec<u8>> {
+    let mut msg = MessageBuilder::new_vec();
+    msg.header_mut().set_opcode(Opcode::NOTIFY);
+    let mut msg = msg.question();
+    msg.push((apex_name, Rtype::SOA)).unwrap();
+
+    // Include the current zone SOA as an RFC 1996 "unsecure hint" (see
+    // section 3.7) to the receiving nameserver so that it can choose to avoid
+    // sending a SOA query if it deems that it has this version of the zone
+    // already.
+    let mut msg = msg.answer();
+    msg.push(OldRecord::from(soa)).unwrap();
+
+    RequestMessage::new(msg).unwrap()
+}
+
+/// An error sending a NOTIFY message.
+#[derive(Debug)]
+pub enum NotifyError {
+    /// The TSIG key configured for the nameserver does not exist.
+    ///
+    /// The NOTIFY is not sent, rather than sending it unsigned.
+    MissingTsigKey(Box<KeyName>),
+
+    /// Sending the request or receiving the response failed.
+    Request(request::Error),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingTsigKey(name) => write!(f, "TSIG key '{name}' does not exist"),
+            Self::Request(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// Look up the TSIG key to sign NOTIFY messages to a nameserver with.
+fn tsig_key_for(
+    nameserver: &NameserverCommsPolicy,
+    tsig_store: &TsigStore,
+) -> Result<Option<Arc<Key>>, NotifyError> {
+    let Some(name) = &nameserver.tsig_key_name else {
+        return Ok(None);
+    };
+    match tsig_store.get(name) {
+        Some(key) => Ok(Some(key.inner.clone())),
+        None => Err(NotifyError::MissingTsigKey(Box::new(name.clone()))),
+    }
+}
+
+/// Send a NOTIFY message to a nameserver and wait for its response.
+async fn send_notify(
+    nameserver: &NameserverCommsPolicy,
+    req: RequestMessage<Vec<u8>>,
+    center: &Center,
+) -> Result<Message<Bytes>, NotifyError> {
+    let tsig_key = tsig_key_for(nameserver, &center.state.lock().unwrap().tsig_store)?;
+
+    let mut dgram_config = domain::net::client::dgram::Config::new();
+    dgram_config.set_max_parallel(1);
+    dgram_config.set_read_timeout(Duration::from_millis(1000));
+    dgram_config.set_max_retries(1);
+    dgram_config.set_udp_payload_size(Some(1400));
+
+    // TODO: Use the connection factory here.
+    let udp_connect = UdpConnect::new(nameserver.addr);
+    let client = dgram::Connection::with_config(udp_connect, dgram_config);
+
+    if let Some(key) = &tsig_key {
+        debug!(
+            "Found TSIG key '{}' (algorithm {}) for NOTIFY to {nameserver}",
+            key.name(),
+            key.algorithm()
+        );
+    }
+    let response = if let Some(key) = tsig_key {
+        let client = tsig::Connection::new(key, client);
+        client.send_request(req).get_response().await
+    } else {
+        client.send_request(req).get_response().await
+    };
+    response.map_err(NotifyError::Request)
+}
+
+//============ Tests ===========================================================
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use domain::tsig::KeyName;
+
+    use super::{NotifyError, tsig_key_for};
+    use crate::{policy::NameserverCommsPolicy, tsig::TsigStore};
+
+    #[test]
+    fn missing_tsig_key_is_an_error() {
+        let store = TsigStore::default();
+
+        let unsigned = NameserverCommsPolicy {
+            addr: "192.0.2.1:53".parse().unwrap(),
+            tsig_key_name: None,
+        };
+        assert!(matches!(tsig_key_for(&unsigned, &store), Ok(None)));
+
+        let signed = NameserverCommsPolicy {
+            tsig_key_name: Some(KeyName::from_str("missing.example").unwrap()),
+            ..unsigned
+        };
+        assert!(matches!(
+            tsig_key_for(&signed, &store),
+            Err(NotifyError::MissingTsigKey(_))
+        ));
+    }
+}
@@ -7,7 +7,7 @@
 use domain::{
     new::{
         base::{
-            Message, MessageItem, QType, RClass, RType, Record,
+            Message, MessageItem, QType, RType, Record,
             name::{Name, RevName},
             wire,
         },
@@ -16,6 +16,8 @@ use domain::{
     utils::dst::UnsizedCopy,
 };
 
+use crate::zonedata::ZONE_CLASS;
+
 /// Parse a DNS request message into a [`Request`].
 pub fn parse(message: &Message) -> Result<Request, RequestParseError> {
     let mut parser = message.parse();
@@ -31,6 +33,11 @@ pub fn parse(message: &Message) -> Result<Request, RequestParseError> {
     };
     // TODO: Check all fields of 'question'.
 
+    // Only zones of the IN class are served.
+    if question.qclass.code != ZONE_CLASS.code {
+        return Err(RequestParseError::UnsupportedClass);
+    }
+
     match question.qtype {
         QType::SOA => {
             // This is a zone-related query for a SOA record.
@@ -65,14 +72,14 @@ pub fn parse(message: &Message) -> Result<Request, RequestParseError> {
             let Some(MessageItem::Authority(Record {
                 rname,
                 rtype: rtype @ RType::SOA,
-                rclass: rclass @ RClass::IN,
+                rclass,
                 ttl,
                 rdata: RecordData::Soa(rdata),
             })) = parser.next().transpose()?
             else {
                 return Err(RequestParseError::Wire(wire::ParseError));
             };
-            if rname != question.qname {
+            if rname != question.qname || rclass != ZONE_CLASS {
                 return Err(RequestParseError::Wire(wire::ParseError));
             }
             let known_soa = Record {
@@ -151,6 +158,9 @@ pub enum ZoneRequestKind {
 pub enum RequestParseError {
     /// A low-level wire format parsing error.
     Wire(wire::ParseError),
+
+    /// The request is for a class other than IN.
+    UnsupportedClass,
 }
 
 impl From<wire::ParseError> for RequestParseError {
@@ -0,0 +1,307 @@
+//! Response rate limiting.
+//!
+//! Responses sent over UDP can be directed at a spoofed source address, which
+//! makes a DNS server usable for reflection and amplification attacks.  The
+//! [`RateLimiter`] limits the rate of responses sent to each client network,
+//! using a token bucket per network prefix.  Responses over the limit are
+//! dropped, except that every few of them are sent back truncated ("slipped"),
+//! so that legitimate clients can retry over TCP.
+
+use std::{
+    collections::HashMap,
+    mem,
+    net::IpAddr,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::config::RrlConfig;
+
+/// The maximum number of client networks to track individually.
+const MAX_NETWORKS: usize = 100_000;
+
+/// How long a client network is remembered after its last response.
+///
+/// Buckets refill completely within a second, so idle ones carry no
+/// information after that.
+const IDLE_PERIOD: Duration = Duration::from_secs(1);
+
+//----------- RateLimiter ------------------------------------------------------
+
+/// A response rate limiter.
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// The configuration.
+    config: RrlConfig,
+
+    /// The token buckets of client networks.
+    //
+    // NOTE: The keys are chosen by (potentially malicious) clients, so the
+    // randomized default hasher is used instead of 'foldhash'.
+    buckets: Mutex<Buckets>,
+
+    /// The number of responses that were dropped.
+    dropped: AtomicU64,
+
+    /// The number of responses that were sent truncated.
+    slipped: AtomicU64,
+}
+
+/// What to do with a response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Send the response.
+    Respond,
+
+    /// Send an empty, truncated response instead.
+    Slip,
+
+    /// Do not respond at all.
+    Drop,
+}
+
+impl RateLimiter {
+    /// Construct a new [`RateLimiter`].
+    pub fn new(config: RrlConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                current: HashMap::new(),
+                previous: HashMap::new(),
+                period_start: Instant::now(),
+                overflow: None,
+            }),
+            dropped: AtomicU64::new(0),
+            slipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Decide whether to respond to a client.
+    pub fn check(&self, addr: IpAddr, now: Instant) -> Verdict {
+        let rate = self.config.responses_per_second;
+        if rate == 0 {
+            return Verdict::Respond;
+        }
+        let capacity = f64::from(rate);
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.get(self.network(addr), now, capacity);
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            bucket.limited = 0;
+            return Verdict::Respond;
+        }
+
+        bucket.limited = bucket.limited.wrapping_add(1);
+        let slip = self.config.slip;
+        if slip != 0 && bucket.limited % slip == 0 {
+            self.slipped.fetch_add(1, Ordering::Relaxed);
+            Verdict::Slip
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            Verdict::Drop
+        }
+    }
+
+    /// The number of responses that were dropped.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// The number of responses that were sent truncated.
+    pub fn slipped(&self) -> u64 {
+        self.slipped.load(Ordering::Relaxed)
+    }
+
+    /// The client network an address belongs to.
+    fn network(&self, addr: IpAddr) -> IpAddr {
+        match addr {
+            IpAddr::V4(addr) => {
+                let len = u32::from(self.config.ipv4_prefix_len);
+                let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+                IpAddr::V4((u32::from(addr) & mask).into())
+            }
+            IpAddr::V6(addr) => {
+                let len = u32::from(self.config.ipv6_prefix_len);
+                let mask = u128::MAX.checked_shl(128 - len).unwrap_or(0);
+                IpAddr::V6((u128::from(addr) & mask).into())
+            }
+        }
+    }
+}
+
+//----------- Buckets ----------------------------------------------------------
+
+/// The token buckets of client networks.
+///
+/// Instead of scanning for idle buckets, buckets are kept in two generations
+/// that are rotated every [`IDLE_PERIOD`]: a bucket that is not used during
+/// two periods is dropped along with its generation.  This keeps the cost of
+/// every check constant, however many networks are tracked.
+#[derive(Debug)]
+struct Buckets {
+    /// Buckets used during the current period.
+    current: HashMap<IpAddr, Bucket>,
+
+    /// Buckets used during the previous period, but not the current one.
+    previous: HashMap<IpAddr, Bucket>,
+
+    /// When the current period started.
+    period_start: Instant,
+
+    /// A bucket shared by all networks that do not fit in [`Self::current`].
+    ///
+    /// When [`MAX_NETWORKS`] are tracked (e.g. because of queries from many
+    /// spoofed addresses), further networks are limited together.
+    overflow: Option<Bucket>,
+}
+
+impl Buckets {
+    /// Get the bucket of a client network.
+    fn get(&mut self, network: IpAddr, now: Instant, capacity: f64) -> &mut Bucket {
+        let elapsed = now.saturating_duration_since(self.period_start);
+        if elapsed >= IDLE_PERIOD {
+            // Swap the maps instead of clearing them, which would take time
+            // proportional to their size.
+            let current = mem::take(&mut self.current);
+            self.previous = if elapsed < 2 * IDLE_PERIOD {
+                current
+            } else {
+                HashMap::new()
+            };
+            self.period_start = now;
+        }
+
+        let new = || Bucket {
+            tokens: capacity,
+            updated: now,
+            limited: 0,
+        };
+
+        if !self.current.contains_key(&network) {
+            if self.current.len() >= MAX_NETWORKS {
+                return self.overflow.get_or_insert_with(new);
+            }
+            let bucket = self.previous.remove(&network).unwrap_or_else(new);
+            self.current.insert(network, bucket);
+        }
+        self.current.get_mut(&network).unwrap()
+    }
+}
+
+//----------- Bucket -----------------------------------------------------------
+
+/// The token bucket of a client network.
+#[derive(Debug)]
+struct Bucket {
+    /// The number of responses that can currently be sent.
+    tokens: f64,
+
+    /// When the bucket was last updated.
+    updated: Instant,
+
+    /// The number of responses limited in a row.
+    limited: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::IpAddr,
+        time::{Duration, Instant},
+    };
+
+    use crate::config::RrlConfig;
+
+    use super::{IDLE_PERIOD, MAX_NETWORKS, RateLimiter, Verdict};
+
+    fn limiter(responses_per_second: u32, slip: u32) -> RateLimiter {
+        RateLimiter::new(RrlConfig {
+            responses_per_second,
+            slip,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 56,
+        })
+    }
+
+    #[test]
+    fn limits_per_network() {
+        let rrl = limiter(2, 0);
+        let now = Instant::now();
+        let a: IpAddr = "192.0.2.1".parse().unwrap();
+        let b: IpAddr = "192.0.2.200".parse().unwrap();
+        let c: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert_eq!(rrl.check(a, now), Verdict::Respond);
+        assert_eq!(rrl.check(b, now), Verdict::Respond);
+        // 'a' and 'b' share a /24, which has now used up its budget.
+        assert_eq!(rrl.check(a, now), Verdict::Drop);
+        assert_eq!(rrl.check(c, now), Verdict::Respond);
+
+        // The bucket refills over time.
+        let later = now + Duration::from_millis(500);
+        assert_eq!(rrl.check(b, later), Verdict::Respond);
+        assert_eq!(rrl.check(b, later), Verdict::Drop);
+        assert_eq!(rrl.dropped(), 2);
+    }
+
+    #[test]
+    fn slips_every_nth_response() {
+        let rrl = limiter(1, 2);
+        let now = Instant::now();
+        let addr: IpAddr = "2001:db8::1".parse().unwrap();
+
+        assert_eq!(rrl.check(addr, now), Verdict::Respond);
+        assert_eq!(rrl.check(addr, now), Verdict::Drop);
+        assert_eq!(rrl.check(addr, now), Verdict::Slip);
+        assert_eq!(rrl.check(addr, now), Verdict::Drop);
+        assert_eq!(rrl.check(addr, now), Verdict::Slip);
+        assert_eq!((rrl.dropped(), rrl.slipped()), (2, 2));
+    }
+
+    #[test]
+    fn forgets_idle_networks() {
+        let rrl = limiter(1, 0);
+        let now = Instant::now();
+        let a: IpAddr = "192.0.2.1".parse().unwrap();
+        let b: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert_eq!(rrl.check(a, now), Verdict::Respond);
+        assert_eq!(rrl.check(b, now), Verdict::Respond);
+
+        // 'a' is still remembered in the next period.
+        let later = now + IDLE_PERIOD;
+        assert_eq!(rrl.check(a, later), Verdict::Respond);
+        assert_eq!(rrl.buckets.lock().unwrap().previous.len(), 1);
+
+        // Networks idle for two periods are forgotten.
+        let much_later = later + 2 * IDLE_PERIOD;
+        assert_eq!(rrl.check(b, much_later), Verdict::Respond);
+        let buckets = rrl.buckets.lock().unwrap();
+        assert_eq!((buckets.current.len(), buckets.previous.len()), (1, 0));
+    }
+
+    #[test]
+    fn limits_untracked_networks_together() {
+        let rrl = limiter(1, 0);
+        let now = Instant::now();
+
+        let network = |i: usize| IpAddr::V4(((i as u32) << 8).into());
+        for i in 0..MAX_NETWORKS {
+            assert_eq!(rrl.check(network(i), now), Verdict::Respond);
+        }
+
+        // Further networks share a single bucket.
+        assert_eq!(rrl.check(network(MAX_NETWORKS), now), Verdict::Respond);
+        assert_eq!(rrl.check(network(MAX_NETWORKS + 1), now), Verdict::Drop);
+        assert_eq!(rrl.buckets.lock().unwrap().current.len(), MAX_NETWORKS);
+    }
+}
@@ -11,6 +11,7 @@ use domain::{
 };
 
 use crate::{
+    server::rrl::RateLimiter,
     zone::Zone,
     zonedata::{LoadedZoneReviewer, RegularRecord, SignedZoneReviewer, SoaRecord, ZoneViewer},
 };
@@ -42,6 +43,9 @@ pub struct ZoneService<V> {
 
     /// What mode of operation is intended?
     mode: ServiceMode,
+
+    /// The rate limiter for responses over UDP, if any.
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -61,10 +65,17 @@ impl<V> ZoneService<V> {
         let service = ZoneService {
             state: state.clone(),
             mode: server_mode,
+            rate_limiter: None,
         };
         let handle = ZoneServiceHandle { state };
         (service, handle)
     }
+
+    /// Limit the rate of responses over UDP.
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
 }
 
 impl<V> Clone for ZoneService<V> {
@@ -72,6 +83,7 @@ impl<V> Clone for ZoneService<V> {
         Self {
             state: self.state.clone(),
             mode: self.mode,
+            rate_limiter: self.rate_limiter.clone(),
         }
     }
 }
@@ -82,7 +94,7 @@ impl<V> Clone for ZoneService<V> {
 /// so it can use [`domain::new`] and support more functionality (e.g. handling
 /// XFRs by spawning OS threads).
 mod compat {
-    use std::{pin::Pin, sync::Arc};
+    use std::{pin::Pin, sync::Arc, time::Instant};
 
     use domain::{
         base::{Message, MessageBuilder, iana::Rcode},
@@ -102,7 +114,8 @@ mod compat {
 
     use crate::{
         server::{
-            request::{RequestKind, ZoneRequestKind},
+            request::{RequestKind, RequestParseError, ZoneRequestKind},
+            rrl::Verdict,
             service::ServiceMode,
         },
         zonedata::{DiffData, OldRecord},
@@ -119,12 +132,38 @@ mod compat {
         type Future = Response;
 
         fn call(&self, old_request: Request<Vec<u8>, Option<Arc<tsig::Key>>>) -> Response {
+            // Limit the rate of responses over UDP, which can be spoofed.
+            if let Some(rate_limiter) = &self.rate_limiter
+                && old_request.transport_ctx().is_udp()
+            {
+                let client = old_request.client_addr().ip();
+                match rate_limiter.check(client, Instant::now()) {
+                    Verdict::Respond => {}
+                    Verdict::Slip => {
+                        trace!("Rate limiting {client}: sending a truncated response");
+                        return Box::pin(std::future::ready(truncated(old_request.message())));
+                    }
+                    Verdict::Drop => {
+                        trace!("Rate limiting {client}: dropping the response");
+                        let stream = futures::stream::empty();
+                        return Box::pin(std::future::ready(Box::new(stream) as ResponseStream));
+                    }
+                }
+            }
+
             // Parse the request.
             let message = old_request.message().as_slice();
             let message = domain::new::base::Message::parse_bytes_by_ref(message)
                 .expect("'message' was already checked to be a valid DNS message");
             let request = match crate::server::request::parse(message) {
                 Ok(request) => request,
+                Err(RequestParseError::UnsupportedClass) => {
+                    // Cascade only serves zones of the IN class.
+                    return Box::pin(std::future::ready(error(
+                        old_request.message(),
+                        Rcode::REFUSED,
+                    )));
+                }
                 Err(_error) => {
                     // TODO: Generate the response using 'error'.
                     return Box::pin(std::future::ready(error(
@@ -150,7 +189,7 @@ mod compat {
                         return Box::pin(std::future::ready(error(old_request.message(), rcode)));
                     };
 
-                    if self.mode == ServiceMode::Publication && !is_permitted(zone, &old_request) {
+                    if !is_permitted(zone, &old_request, self.mode) {
                         return Box::pin(std::future::ready(error(
                             old_request.message(),
                             Rcode::REFUSED,
@@ -183,6 +222,7 @@ mod compat {
     fn is_permitted<V: Viewer>(
         zone: &ServedZone<V>,
         request: &Request<Vec<u8>, Option<Arc<tsig::Key>>>,
+        mode: ServiceMode,
     ) -> bool {
         let zone_state = zone.handle.read();
 
@@ -201,11 +241,14 @@ mod compat {
             );
         }
 
-        if let Some(acls) = zone_state
-            .policy
-            .as_ref()
-            .map(|p| &p.server.outbound.provide_xfr_to)
-        {
+        // The review servers have their own ACL, so that instances under
+        // review can be restricted separately from published instances.
+        if let Some(acls) = zone_state.policy.as_ref().map(|p| match mode {
+            ServiceMode::Publication => &p.server.outbound.provide_xfr_to,
+            ServiceMode::LoadedReview | ServiceMode::SignedReview => {
+                &p.server.outbound.provide_review_xfr_to
+            }
+        }) {
             // If at least one ACL was specified, enforce it.
             if !acls.is_empty() {
                 let wanted_tsig_key_name = request.metadata().as_ref().map(|key| key.name());
@@ -694,6 +737,17 @@ mod compat {
         trace_diff("Signed", diff_idx, signed_diff);
     }
 
+    /// Generate an empty, truncated response, asking the client to use TCP.
+    fn truncated(request: &Message<Vec<u8>>) -> ResponseStream {
+        let mut builder = MessageBuilder::new_stream_vec()
+            .start_answer(request, Rcode::NOERROR)
+            .unwrap();
+        builder.header_mut().set_tc(true);
+        let response = builder.additional();
+        let result = Ok(CallResult::new(response));
+        Box::new(futures::stream::once(std::future::ready(result))) as _
+    }
+
     fn error(request: &Message<Vec<u8>>, rcode: Rcode) -> ResponseStream {
         let response = MessageBuilder::new_stream_vec()
             .start_error(request, rcode)
@@ -845,6 +899,34 @@ impl<V> ZoneServiceHandle<V> {
         let _ = viewer;
     }
 
+    /// Remove a zone, if it is still registered.
+    ///
+    /// Unlike [`Self::remove_zone()`], nothing happens if the zone is not
+    /// known to the service, or if a distinct zone of the same name is.
+    /// Returns whether the zone was removed.
+    pub fn try_remove_zone(&self, zone: &Arc<Zone>) -> bool {
+        let mut state = self.state.write().unwrap();
+        let name = RevNameBuf::parse_bytes(zone.name.as_slice()).unwrap();
+        let registered = state
+            .zones
+            .get(&*name)
+            .is_some_and(|served| Arc::ptr_eq(&served.handle, zone));
+        if registered {
+            state.zones.remove(&*name);
+        }
+        registered
+    }
+
+    /// Get the zone registered under the same name as `zone`, if any.
+    ///
+    /// This can be a distinct zone, e.g. one that was removed but is still
+    /// being served.
+    pub fn registered_zone(&self, zone: &Zone) -> Option<Arc<Zone>> {
+        let state = self.state.read().unwrap();
+        let name = RevNameBuf::parse_bytes(zone.name.as_slice()).unwrap();
+        state.zones.get(&*name).map(|served| served.handle.clone())
+    }
+
     /// Get a viewer for a zone.
     ///
     /// If Cascade is still starting up there may not be a viewer for the zone
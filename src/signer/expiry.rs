@@ -0,0 +1,140 @@
+//! Warning about zones whose signatures are about to expire.
+//!
+//! Zones are re-signed automatically well before their signatures expire.
+//! However, if the pipeline of a zone is stuck (e.g. because it is halted),
+//! re-signing does not happen, and the published zone will eventually go
+//! bogus.  The [`ExpiryMonitor`] periodically checks for such zones, so that
+//! operators get advance warning.
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tracing::warn;
+
+use crate::{
+    center::Center,
+    util::AbortOnDrop,
+    zone::{ZoneByName, ZoneState},
+};
+
+//----------- ExpiryMonitor ----------------------------------------------------
+
+/// The signature expiry monitor.
+#[derive(Debug)]
+pub struct ExpiryMonitor {}
+
+impl ExpiryMonitor {
+    /// Drive the [`ExpiryMonitor`].
+    ///
+    /// Nothing is done if no warning window is configured.
+    pub fn run(center: Arc<Center>) -> AbortOnDrop {
+        AbortOnDrop::from(tokio::spawn(async move {
+            let Some(window) = warning_window(&center) else {
+                return;
+            };
+
+            // TODO: Make the check interval configurable?
+            let mut interval = tokio::time::interval(Duration::from_secs(60 * 60));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+
+                let now = SystemTime::now();
+                let state = center.state.lock().unwrap();
+                for ZoneByName(zone) in &state.zones {
+                    let zone_state = zone.state.read();
+                    let Some(expiration) = expires_within(&zone_state, window, now) else {
+                        continue;
+                    };
+
+                    let remaining = expiration.duration_since(now).unwrap_or_default();
+                    let reason = zone_state
+                        .halted_reason()
+                        .map_or_else(String::new, |reason| format!(" (halted: {reason})"));
+                    warn!(
+                        "Signatures of zone '{}' expire in {}s and have not been refreshed{reason}",
+                        zone.name,
+                        remaining.as_secs(),
+                    );
+                }
+            }
+        }))
+    }
+}
+
+//------------------------------------------------------------------------------
+
+/// The configured warning window, if any.
+pub fn warning_window(center: &Center) -> Option<Duration> {
+    let secs = center.config.signer.expiry_warning?;
+    Some(Duration::from_secs(secs.into()))
+}
+
+/// Whether the published signatures of a zone expire within `window`, even
+/// though they should have been refreshed already.
+///
+/// Zones are re-signed once their earliest signature expires within the
+/// `sig_remain_time` of their policy; until then, an expiration within the
+/// window is expected.  If the re-signing is overdue and the signatures
+/// expire within the window, the expiration time of the earliest expiring
+/// signature is returned.
+pub fn expires_within(state: &ZoneState, window: Duration, now: SystemTime) -> Option<SystemTime> {
+    let min_expiration = state.min_expiration.clone()?;
+    let expiration = SystemTime::UNIX_EPOCH + Duration::from_secs(min_expiration.into_int().into());
+    let remain = state.policy.as_ref().map_or(Duration::ZERO, |policy| {
+        Duration::from_secs(policy.signer.sig_remain_time.into())
+    });
+    let resign_due = expiration
+        .checked_sub(remain)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    (resign_due <= now && expiration <= now + window).then_some(expiration)
+}
+
+//============ Tests ===========================================================
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use domain::rdata::dnssec::Timestamp;
+
+    use super::expires_within;
+    use crate::policy::file::v1;
+    use crate::zone::ZoneState;
+
+    const HOUR: u32 = 3600;
+
+    #[test]
+    fn only_overdue_expirations_are_reported() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+        let window = Duration::from_secs((24 * HOUR).into());
+
+        let mut policy = v1::Spec::default().parse("default");
+        policy.signer.sig_remain_time = 6 * HOUR;
+        let mut state = ZoneState::default();
+        state.set_policy(Arc::new(policy));
+        assert_eq!(expires_within(&state, window, now), None);
+
+        // Re-signing is due in 6 hours; nothing is wrong yet.
+        state.min_expiration = Some(Timestamp::from(1_800_000_000 + 12 * HOUR));
+        assert_eq!(expires_within(&state, window, now), None);
+
+        // Re-signing was due 6 hours ago.
+        let expiration = 1_800_000_000 + 4 * HOUR;
+        state.min_expiration = Some(Timestamp::from(expiration));
+        assert_eq!(
+            expires_within(&state, window, now),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(expiration.into()))
+        );
+
+        // Re-signing is overdue, but expiry is still outside the window.
+        let mut policy = (**state.policy.as_ref().unwrap()).clone();
+        policy.signer.sig_remain_time = 72 * HOUR;
+        state.set_policy(Arc::new(policy));
+        state.min_expiration = Some(Timestamp::from(1_800_000_000 + 48 * HOUR));
+        assert_eq!(expires_within(&state, window, now), None);
+    }
+}
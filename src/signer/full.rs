@@ -3,14 +3,15 @@
 use std::{
     cmp::Ordering,
     collections::HashSet,
-    env::{self, VarError},
+    num::NonZeroUsize,
+    ops::Range,
     sync::{Arc, RwLock},
-    time::Instant,
+    time::{Duration, Instant, UNIX_EPOCH},
 };
 
 use bytes::Bytes;
 use domain::{
-    base::{CanonicalOrd, Record, Serial, name::FlattenInto},
+    base::{CanonicalOrd, Name, Record, Rtype, Serial, ToName, name::FlattenInto},
     dnssec::sign::{
         denial::{
             config::DenialConfig,
@@ -21,7 +22,7 @@ use domain::{
         records::RecordsIter,
         signatures::rrsigs::GenerateRrsigConfig,
     },
-    rdata::{Nsec3param, dnssec::Timestamp},
+    rdata::{Nsec3param, Rrsig},
     zonefile::inplace::{Entry, Zonefile},
 };
 use domain::{
@@ -46,17 +47,17 @@ use crate::{
     manager::record_zone_event,
     policy::{PolicyVersion, SignerDenialPolicy},
     signer::{
-        SigningTrigger,
+        SignatureOverrides, SigningTrigger,
         incremental::LocalState,
         keys::ZoneSigningKeys,
         status::{SigningStatusPerZone, ZoneSigningStatus},
     },
     units::{
         key_manager::mk_dnst_keyset_state_file_path,
-        zone_signer::{KeySetState, MinTimestamp, SignerError},
+        zone_signer::{KeySetState, MinTimestamp, SignerError, faketime_or_now},
     },
     zone::{HistoricalEvent, Zone},
-    zonedata::{OldRecord, RegularRecord, SignedZoneBuilder},
+    zonedata::{LoadedZoneReader, OldRecord, RegularRecord, SignedZoneBuilder, is_signing},
 };
 
 pub fn sign_zone(
@@ -64,6 +65,7 @@ pub fn sign_zone(
     zone: &Arc<Zone>,
     builder: &mut SignedZoneBuilder,
     trigger: SigningTrigger,
+    overrides: &SignatureOverrides,
     status: Arc<RwLock<SigningStatusPerZone>>,
 ) -> Result<(), SignerError> {
     let zone_name = &zone.name;
@@ -77,7 +79,7 @@ pub fn sign_zone(
         // Use a block to make sure that the lock is clearly dropped.
         let zone_state = zone.read();
 
-        zone_state.policy.clone().unwrap()
+        zone_state.policy.clone().ok_or(SignerError::NoPolicy)?
     };
     let previous_serial = local_state.previous_serial;
 
@@ -96,6 +98,7 @@ pub fn sign_zone(
         policy.signer.serial_policy,
         Serial::from(loaded_serial.0.get()),
         previous_serial,
+        local_state.serial_floor,
     )?;
 
     local_state.previous_serial = Some(serial);
@@ -108,8 +111,8 @@ pub fn sign_zone(
     new_records.push(new_soa.clone().into());
 
     info!(
-        "[ZS]: Serials for zone '{zone_name}': last signed={previous_serial:?}, current={loaded_serial}, serial policy={}, new={serial}",
-        policy.signer.serial_policy
+        "[ZS]: Serials for zone '{zone_name}': last signed={previous_serial:?}, current={loaded_serial}, serial policy={}, serial floor={:?}, new={serial}",
+        policy.signer.serial_policy, local_state.serial_floor
     );
 
     //
@@ -127,7 +130,7 @@ pub fn sign_zone(
     //
     // Create a signing configuration.
     //
-    let signing_config = signing_config(&policy)?;
+    let signing_config = signing_config(&policy, overrides)?;
     let rrsig_cfg = GenerateRrsigConfig::new(signing_config.inception, signing_config.expiration);
 
     //
@@ -143,6 +146,11 @@ pub fn sign_zone(
         .map(OldRecord::from)
         .collect::<Vec<_>>();
     records.push(new_soa.clone().into());
+    let mut external_signatures = if policy.signer.preserve_external_signatures {
+        external_signatures(&loaded, faketime_or_now())
+    } else {
+        Vec::new()
+    };
     let walk_time = walk_start.elapsed();
     let unsigned_rr_count = records.len();
 
@@ -159,9 +167,7 @@ pub fn sign_zone(
     status.write().unwrap().current_action = "Fetching apex RRs from the key manager".to_string();
     // Read the DNSKEY RRs and DNSKEY RRSIG RR from the keyset state.
     let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
-    let state = std::fs::read_to_string(&state_path)
-        .map_err(|_| SignerError::CannotReadStateFile(state_path.into_string()))?;
-    let state: KeySetState = serde_json::from_str(&state).unwrap();
+    let state = KeySetState::load(&state_path)?;
 
     local_state.apex_remove = state.apex_remove.clone();
     let mut apex_extra = state.apex_extra.clone();
@@ -199,6 +205,12 @@ pub fn sign_zone(
 
         key_tags.insert(v.key_tag());
     }
+    external_signatures.retain(|r| {
+        let RecordData::Rrsig(sig) = r.data() else {
+            unreachable!();
+        };
+        !key_tags.contains(&sig.key_tag())
+    });
     local_state.key_tags = key_tags;
     local_state.key_roll = None;
 
@@ -297,7 +309,14 @@ pub fn sign_zone(
 
     // TODO: Configure Rayon's thread pool to set the number of threads. By
     // default, it relies on 'std::thread::available_parallelism()'.
-    let parallelism = rayon::current_num_threads();
+    //
+    // Signing with locally stored keys is kept on a single thread for now.
+    // Keys stored in an HSM need a network round-trip for every signature,
+    // so signing is spread over as many threads as there are connections to
+    // the HSM.
+    let parallelism = signing_keys
+        .kmip_concurrency()
+        .map_or(1, |limit| limit.min(rayon::current_num_threads()));
 
     {
         let mut v = status.write().unwrap();
@@ -313,75 +332,59 @@ pub fn sign_zone(
     // needs a slice of references, so we need to build that here.
     let keys = signing_keys.list.iter().collect::<Vec<_>>();
 
-    // TODO: This generation code is incorrect; 'sign_sorted_zone_records'
-    // looks for zone cuts, but zone cuts may need to be detected _across_
-    // the segments we split the records into. Zone cut detection needs to
-    // be re-implemented here with parallel execution in mind. This also
-    // applies to NSEC(3) generation, but it is currently single-threaded.
-
-    // Disable parallel signing for now. This may also split RRsets.
-    let signatures = if false {
-        // Split the records into segments.
-        let segments = rayon::iter::split(0..unsigned_records.len(), |range| {
-            // Always sign at least 1024 records at a time.
-            if range.len() < 1024 {
-                return (range, None);
-            }
-
-            let midpoint = range.start + range.len() / 2;
-            let left = range.start..midpoint;
-            let right = midpoint..range.end;
-            (left, Some(right))
-        });
+    let to_regular = |s: Record<_, Rrsig<_, _>>| -> RegularRecord {
+        Record::new(
+            s.owner().clone(),
+            s.class(),
+            s.ttl(),
+            ZoneRecordData::Rrsig(s.data().clone()),
+        )
+        .into()
+    };
 
-        // Generate signatures from each segment.
-        let signatures = segments.map(|range| {
+    let signatures: Vec<RegularRecord> = if parallelism > 1 {
+        // Split the records into segments that can be signed independently
+        // and sign them concurrently.  If errors occur, one error is
+        // arbitrarily chosen and returned.
+        independent_segments(
+            &zone.name,
+            &unsigned_records,
+            parallelism,
+            center.config.signer.batch_size,
+        )
+        .into_par_iter()
+        .map(|range| {
             sign_sorted_zone_records(
                 &zone.name,
                 RecordsIter::new_from_owned(&unsigned_records[range]),
                 &keys,
                 &rrsig_cfg,
             )
-        });
-
-        // Convert the signatures into new-base types and collect them together.
-        // If errors occur, one error is arbitrarily chosen and returned.
-        signatures
-            .try_fold(Vec::new, |mut a, b| {
-                a.extend(b?.into_iter().map(|r| OldRecord::from_record(r).into()));
-                Ok::<_, SigningError>(a)
-            })
-            .try_reduce(Vec::new, |mut a, mut b| {
-                a.append(&mut b);
-                Ok(a)
-            })
-            .map_err(|err| SignerError::SigningError(err.to_string()))?
+            .map(|sigs| sigs.into_iter().map(to_regular).collect::<Vec<_>>())
+        })
+        .try_reduce(Vec::new, |mut a, mut b| {
+            a.append(&mut b);
+            Ok(a)
+        })
+        .map_err(|err| SignerError::SigningError(err.to_string()))?
     } else {
-        let signatures = sign_sorted_zone_records(
+        sign_sorted_zone_records(
             &zone.name,
             RecordsIter::new_from_owned(&unsigned_records),
             &keys,
             &rrsig_cfg,
         )
-        .map_err(|err| SignerError::SigningError(err.to_string()))?;
-        let signatures: Vec<RegularRecord> = signatures
-            .into_iter()
-            .map(|s| {
-                let r = Record::new(
-                    s.owner().clone(),
-                    s.class(),
-                    s.ttl(),
-                    ZoneRecordData::Rrsig(s.data().clone()),
-                );
-                r.into()
-            })
-            .collect();
-        signatures
+        .map_err(|err| SignerError::SigningError(err.to_string()))?
+        .into_iter()
+        .map(to_regular)
+        .collect()
     };
 
     let total_signatures = signatures.len();
+    let external_signature_count = external_signatures.len();
 
     new_records.extend(signatures);
+    new_records.extend(external_signatures);
     new_records.par_sort();
     writer.set_records(new_records).unwrap();
 
@@ -410,6 +413,14 @@ pub fn sign_zone(
             continue;
         }
 
+        // Ignore signatures made by an external signer; Cascade cannot
+        // refresh them.
+        if policy.signer.preserve_external_signatures
+            && !local_state.key_tags.contains(&sig.key_tag())
+        {
+            continue;
+        }
+
         min_expiration.add(u32::from(sig.expiration).into());
     }
     local_state.next_min_expiration = saved_min_expiration.get();
@@ -434,6 +445,7 @@ pub fn sign_zone(
         Collected {unsigned_rr_count} records in {:.1}s, sorted in {:.1}s\n\
         Generated {denial_rr_count} NSEC(3) records in {:.1}s\n\
         Generated {total_signatures} signatures in {:.1}s ({generation_rate:.0}sig/s)
+        Preserved {external_signature_count} external signatures
         Took {:.1}s in total, using {parallelism} threads",
         walk_time.as_secs_f64(),
         sort_time.as_secs_f64(),
@@ -451,16 +463,99 @@ pub fn sign_zone(
         Some(Serial(serial.into())),
     );
 
-    local_state.last_signature_refresh = UnixTime::now();
+    local_state.last_signature_refresh = faketime_or_now();
     local_state.save(center, zone);
 
     Ok(())
 }
 
+//----------- external_signatures() --------------------------------------------
+
+/// Collect the signatures in a loaded zone that are worth preserving.
+///
+/// Signatures over records that Cascade generates itself (including the SOA
+/// record, whose serial is replaced) and over delegations are left out, as are
+/// signatures that have expired by `now`.  The remaining signatures are not
+/// verified; the caller must still remove those made with Cascade's own keys.
+fn external_signatures(loaded: &LoadedZoneReader<'_>, now: UnixTime) -> Vec<RegularRecord> {
+    let apex = &*loaded.soa().rname;
+    let now = UNIX_EPOCH + Duration::from(now);
+    loaded
+        .regular_records()
+        .iter()
+        .filter(|r| {
+            let RecordData::Rrsig(sig) = r.data() else {
+                return false;
+            };
+            let at_apex = *r.rname == *apex;
+            let covered = sig.type_covered();
+            covered != RType::SOA
+                && !is_signing(covered, || at_apex)
+                && (covered != RType::NS || at_apex)
+                && sig.expiration().to_system_time(now) > now
+        })
+        .cloned()
+        .collect()
+}
+
+//----------- independent_segments() -------------------------------------------
+
+/// The minimum number of records to sign in a single segment.
+const MIN_SEGMENT_LEN: usize = 1024;
+
+/// Split sorted records into segments that can be signed independently.
+///
+/// Each segment covers a range of `records`.  If `batch_size` is set, segments
+/// hold roughly that many records each, and are distributed over the signing
+/// threads as they become available.  Otherwise, at most `max_segments`
+/// segments are returned.  Segments never split the records of a single owner
+/// name, and never split a delegation from the (occluded) records below it, so
+/// that zone cuts are detected correctly when each segment is signed on its
+/// own.
+fn independent_segments(
+    apex: &Name<Bytes>,
+    records: &[OldRecord],
+    max_segments: usize,
+    batch_size: Option<NonZeroUsize>,
+) -> Vec<Range<usize>> {
+    let target_len = match batch_size {
+        Some(batch_size) => batch_size.get(),
+        None => records
+            .len()
+            .div_ceil(max_segments.max(1))
+            .max(MIN_SEGMENT_LEN),
+    };
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut cut: Option<&Name<Bytes>> = None;
+    for (i, record) in records.iter().enumerate() {
+        let owner = record.owner();
+        if i > 0 && owner != records[i - 1].owner() {
+            if cut.is_some_and(|cut| !owner.ends_with(cut)) {
+                cut = None;
+            }
+
+            if cut.is_none() && i - start >= target_len {
+                segments.push(start..i);
+                start = i;
+            }
+        }
+
+        if cut.is_none() && record.rtype() == Rtype::NS && owner != apex {
+            cut = Some(owner);
+        }
+    }
+    segments.push(start..records.len());
+
+    segments
+}
+
 //----------- signing_config() -------------------------------------------------
 
 fn signing_config(
     policy: &PolicyVersion,
+    overrides: &SignatureOverrides,
 ) -> Result<SigningConfig<Bytes, MultiThreadedSorter>, SignerError> {
     let denial = match &policy.signer.denial {
         SignerDenialPolicy::NSec => DenialConfig::Nsec(Default::default()),
@@ -470,20 +565,9 @@ fn signing_config(
         }
     };
 
-    let now = match env::var("CASCADE_FAKETIME") {
-        Ok(val) => val
-            .parse::<u32>()
-            .map_err(|e| SignerError::InternalError(format!("cannot parse {e} as u32")))?,
-        Err(VarError::NotPresent) => Timestamp::now().into_int(),
-        Err(e) => return Err(SignerError::InternalError(e.to_string())),
-    };
-    let inception = now.wrapping_sub(policy.signer.sig_inception_offset);
-    let expiration = now.wrapping_add(policy.signer.sig_validity_time);
-    Ok(SigningConfig::new(
-        denial,
-        inception.into(),
-        expiration.into(),
-    ))
+    let (inception, expiration) =
+        super::signature_validity(&overrides.apply(&policy.signer), super::signing_time());
+    Ok(SigningConfig::new(denial, inception, expiration))
 }
 
 fn parse_nsec3_config(opt_out: bool) -> GenerateNsec3Config<Bytes, MultiThreadedSorter> {
@@ -511,3 +595,92 @@ impl domain::dnssec::sign::records::Sorter for MultiThreadedSorter {
         records.par_sort_by(compare);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::num::NonZeroUsize;
+
+    use bytes::Bytes;
+    use domain::base::iana::Class;
+    use domain::base::{Name, Record, Rtype, Ttl};
+    use domain::rdata::{A, Ns, ZoneRecordData};
+
+    use crate::zonedata::OldRecord;
+
+    use super::independent_segments;
+
+    /// Build a record of the given type.
+    fn record(owner: &str, rtype: Rtype) -> OldRecord {
+        let owner: Name<Bytes> = owner.parse().unwrap();
+        let data = match rtype {
+            Rtype::NS => ZoneRecordData::Ns(Ns::new("ns.example.net.".parse().unwrap())),
+            _ => ZoneRecordData::A(A::new(Ipv4Addr::LOCALHOST)),
+        };
+        Record::new(owner, Class::IN, Ttl::from_secs(3600), data)
+    }
+
+    fn apex() -> Name<Bytes> {
+        "example.".parse().unwrap()
+    }
+
+    /// Six owner names below the apex, with two records each.
+    fn two_per_owner() -> Vec<OldRecord> {
+        ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .flat_map(|label| {
+                let owner = format!("{label}.example.");
+                [record(&owner, Rtype::A), record(&owner, Rtype::A)]
+            })
+            .collect()
+    }
+
+    fn batch_size(n: usize) -> Option<NonZeroUsize> {
+        NonZeroUsize::new(n)
+    }
+
+    #[test]
+    fn segments_respect_batch_size() {
+        let records = two_per_owner();
+
+        let segments = independent_segments(&apex(), &records, 8, batch_size(4));
+        assert_eq!(segments, [0..4, 4..8, 8..12]);
+
+        // Without a batch size, small zones are not split at all.
+        let segments = independent_segments(&apex(), &records, 8, None);
+        assert_eq!(segments, [0..12]);
+    }
+
+    #[test]
+    fn segments_never_split_an_owner_name() {
+        let records = two_per_owner();
+
+        // Segments end at the first owner name boundary after reaching the
+        // batch size.
+        let segments = independent_segments(&apex(), &records, 8, batch_size(3));
+        assert_eq!(segments, [0..4, 4..8, 8..12]);
+        for segment in &segments[1..] {
+            assert_ne!(
+                records[segment.start].owner(),
+                records[segment.start - 1].owner()
+            );
+        }
+    }
+
+    #[test]
+    fn segments_never_split_a_delegation() {
+        let records = [
+            record("example.", Rtype::NS),
+            record("a.example.", Rtype::A),
+            record("sub.example.", Rtype::NS),
+            record("ns.sub.example.", Rtype::A),
+            record("x.sub.example.", Rtype::A),
+            record("z.example.", Rtype::A),
+        ];
+
+        // The apex NS RRset is not a delegation, but 'sub.example.' is; the
+        // records below it stay in the segment of the delegation.
+        let segments = independent_segments(&apex(), &records, 8, batch_size(1));
+        assert_eq!(segments, [0..1, 1..2, 2..5, 5..6]);
+    }
+}
@@ -57,7 +57,9 @@ use crate::units::zone_signer::{
     KeySetState, MinTimestamp, PassThroughMode, SignerError, faketime_or_now,
 };
 use crate::zone::{HistoricalEvent, Zone};
-use crate::zonedata::{DiffData, RegularRecord, SignedZonePatcher, SignedZoneReader, SoaRecord};
+use crate::zonedata::{
+    DiffData, RegularRecord, SignedZonePatcher, SignedZoneReader, SoaRecord, ZONE_CLASS,
+};
 
 pub fn sign_incrementally(
     patch: SignedZonePatcher,
@@ -82,12 +84,9 @@ pub fn sign_incrementally(
 
     let origin = &zone.name;
     let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, origin);
-    let state = std::fs::read_to_string(&state_path)
-        .map_err(|_| SignerError::CannotReadStateFile(state_path.into_string()))?;
-    let keyset_state: KeySetState = serde_json::from_str(&state)
-        .map_err(|e| SignerError::SigningError(format!("loading keyset state failed: {e}")))?;
+    let keyset_state = KeySetState::load(&state_path)?;
 
-    let policy = zone.read().policy.clone().unwrap();
+    let policy = zone.read().policy.clone().ok_or(SignerError::NoPolicy)?;
 
     let use_nsec3 = matches!(policy.signer.denial, SignerDenialPolicy::NSec3 { .. });
 
@@ -128,7 +127,9 @@ pub fn sign_incrementally(
         refresh_signatures = true;
     }
 
-    if !load_unsigned && !apex_changed && !refresh_signatures {
+    let denial_changed = ws.denial_changed();
+
+    if !load_unsigned && !apex_changed && !refresh_signatures && !denial_changed {
         // Nothing to do.
         return Err(SignerError::NothingToDo);
     }
@@ -1075,6 +1076,7 @@ impl WorkSpace<'_> {
             self.policy.signer.serial_policy,
             loaded_serial,
             previous_serial,
+            self.local_state.serial_floor,
         )?;
 
         // Save the new SOA serial.
@@ -1203,7 +1205,7 @@ impl WorkSpace<'_> {
             );
             let record = RecordFullCmp::new(
                 iss.origin.clone(),
-                Class::IN,
+                new_base_class_to_old_base(ZONE_CLASS),
                 Ttl::ZERO,
                 ZoneRecordData::Zonemd(zonemd),
             );
@@ -1268,6 +1270,34 @@ impl WorkSpace<'_> {
         Ok(())
     }
 
+    /// Whether the zone should switch between NSEC, NSEC3 and NSEC3 opt-out.
+    ///
+    /// This compares the NSEC3PARAM record at the apex of the current signed
+    /// instance, if any, against the policy.
+    pub fn denial_changed(&self) -> bool {
+        let curr = self.patch.curr();
+        let apex = &curr.soa().rname;
+        let nsec3param = curr
+            .generated_records()
+            .iter()
+            .take_while(|r| r.rname == *apex)
+            .find(|r| r.rtype == NewRtype::NSEC3PARAM);
+        let Some(nsec3param) = nsec3param else {
+            return self.use_nsec3;
+        };
+        if !self.use_nsec3 {
+            return true;
+        }
+        let NewRecordData::Nsec3Param(nsec3param) = nsec3param.data() else {
+            panic!("ZoneRecordData::Nsec3param expected");
+        };
+        let opt_out = matches!(
+            self.policy.signer.denial,
+            SignerDenialPolicy::NSec3 { opt_out: true }
+        );
+        nsec3param.opt_out_flag() != opt_out
+    }
+
     pub fn handle_nsec_nsec3(
         &mut self,
         iss: &mut IncrementalSigningState,
@@ -1372,10 +1402,8 @@ impl<'a> IncrementalSigningState<'a> {
     ) -> Result<Self, SignerError> {
         let keys = ZoneSigningKeys::load(center, zone, keyset_state, &status)?;
 
-        let now = faketime_or_now();
-        let now_u32 = Into::<Duration>::into(now.clone()).as_secs() as u32;
-        let inception = (now_u32 - policy.signer.sig_inception_offset).into();
-        let expiration = (now_u32 + policy.signer.sig_validity_time).into();
+        let (inception, expiration) =
+            super::signature_validity(&policy.signer, super::signing_time());
 
         // This is the only way to deal with opt-out. There is no data type
         // for flags or constant for opt-out. Creating an Nsec3param makes it
@@ -3222,6 +3250,7 @@ pub struct LocalState {
     pub key_tags: HashSet<u16>,
     pub key_roll: Option<UnixTime>,
     pub previous_serial: Option<Serial>,
+    pub serial_floor: Option<Serial>,
     pub next_min_expiration: Option<Timestamp>,
 }
 
@@ -3236,6 +3265,7 @@ impl LocalState {
             key_tags: zone_state.key_tags.clone(),
             key_roll: zone_state.key_roll.clone(),
             previous_serial: zone_state.previous_serial,
+            serial_floor: zone_state.serial_floor,
             next_min_expiration: zone_state.next_min_expiration,
         })
     }
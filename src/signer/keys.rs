@@ -2,12 +2,13 @@
 
 use core::fmt;
 use std::{
+    collections::HashSet,
     sync::{Arc, RwLock},
     time::Duration,
 };
 
 use bytes::Bytes;
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
 use domain::{
     base::{Name, Record, iana::SecurityAlgorithm},
     crypto::sign::{BindFormatError, SecretKeyBytes, SignError, SignRaw, Signature},
@@ -25,16 +26,27 @@ use tracing::{debug, error, warn};
 use url::Url;
 
 use crate::{
+    api,
     center::Center,
-    signer::status::SigningStatusPerZone,
+    metrics::HsmMetrics,
+    signer::status::{SigningStatusPerZone, ZoneSigningStatus},
     units::{
         http_server::KmipServerState,
-        key_manager::{KmipClientCredentialsFile, KmipServerCredentialsFileMode},
-        zone_signer::KeySetState,
+        key_manager::{
+            KmipClientCredentialsFile, KmipServerCredentialsFileMode,
+            mk_dnst_keyset_state_file_path,
+        },
+        zone_signer::{KeySetState, SignerError},
     },
     zone::Zone,
 };
 
+/// The maximum number of connections to keep open to a single KMIP server.
+///
+/// This bounds the number of concurrent signing operations that can be issued
+/// to the server.
+pub const KMIP_POOL_SIZE: u32 = 10;
+
 //----------- ZoneSigningKeys --------------------------------------------------
 
 /// A set of keys for signing a zone.
@@ -51,10 +63,6 @@ pub struct ZoneSigningKeys {
 
 impl ZoneSigningKeys {
     /// Load the keys that should be used to sign a zone.
-    ///
-    /// ## Panics
-    ///
-    /// Panics if `keyset_state` is malformed (e.g. contains invalid URLs).
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -86,8 +94,16 @@ impl ZoneSigningKeys {
                 continue;
             };
 
-            let priv_url = Url::parse(priv_key_name).expect("valid URL expected");
-            let pub_url = Url::parse(pub_key_name).expect("valid URL expected");
+            let parse_url = |url: &str| {
+                Url::parse(url).map_err(|error| {
+                    Box::new(LoadError::MalformedKeyUrl {
+                        url: url.into(),
+                        error,
+                    })
+                })
+            };
+            let priv_url = parse_url(priv_key_name)?;
+            let pub_url = parse_url(pub_key_name)?;
 
             if priv_url.scheme() != pub_url.scheme() {
                 return Err(Box::new(LoadError::MultipleSchemesInKey {
@@ -139,6 +155,18 @@ impl ZoneSigningKeys {
 
         Ok(Self { list })
     }
+
+    /// The number of concurrent signing operations these keys support.
+    ///
+    /// Returns `None` if signing is performed locally, in which case it is
+    /// limited by the available CPU cores only.  Keys backed by a KMIP server
+    /// are limited by the size of the connection pool to that server.
+    pub fn kmip_concurrency(&self) -> Option<usize> {
+        self.list
+            .iter()
+            .any(|key| matches!(key.raw_secret_key(), KeyPair::Kmip(..)))
+            .then_some(KMIP_POOL_SIZE as usize)
+    }
 }
 
 //----------- KeyPair ----------------------------------------------------------
@@ -149,8 +177,8 @@ pub enum KeyPair {
     /// A keypair provided by [`domain`].
     Domain(domain::crypto::sign::KeyPair),
 
-    /// A KMIP keypair.
-    Kmip(domain_kmip::sign::KeyPair),
+    /// A KMIP keypair, and the metrics of the server it resides on.
+    Kmip(domain_kmip::sign::KeyPair, HsmMetrics),
 }
 
 //--- Signing
@@ -159,21 +187,21 @@ impl SignRaw for KeyPair {
     fn algorithm(&self) -> SecurityAlgorithm {
         match self {
             KeyPair::Domain(k) => k.algorithm(),
-            KeyPair::Kmip(k) => k.algorithm(),
+            KeyPair::Kmip(k, _) => k.algorithm(),
         }
     }
 
     fn dnskey(&self) -> Dnskey<Vec<u8>> {
         match self {
             KeyPair::Domain(k) => k.dnskey(),
-            KeyPair::Kmip(k) => k.dnskey(),
+            KeyPair::Kmip(k, _) => k.dnskey(),
         }
     }
 
     fn sign_raw(&self, data: &[u8]) -> Result<Signature, SignError> {
         match self {
             KeyPair::Domain(k) => k.sign_raw(data),
-            KeyPair::Kmip(k) => k.sign_raw(data),
+            KeyPair::Kmip(k, metrics) => metrics.measure_sign(|| k.sign_raw(data)),
         }
     }
 }
@@ -217,7 +245,7 @@ impl KeyPair {
     }
 
     /// Load a private key from a file.
-    fn load_priv_from_file(path: &Utf8Path) -> Result<SecretKeyBytes, Box<LoadError>> {
+    pub(crate) fn load_priv_from_file(path: &Utf8Path) -> Result<SecretKeyBytes, Box<LoadError>> {
         let encoded = std::fs::read_to_string(path).map_err(|error| {
             Box::new(LoadError::UnreadableKeyFile {
                 path: path.into(),
@@ -240,7 +268,7 @@ impl KeyPair {
     }
 
     /// Load a public key from a file.
-    fn load_pub_from_file(
+    pub(crate) fn load_pub_from_file(
         path: &Utf8Path,
     ) -> Result<Record<Name<Bytes>, Dnskey<Bytes>>, Box<LoadError>> {
         let encoded = std::fs::read_to_string(path).map_err(|error| {
@@ -361,7 +389,7 @@ impl KeyPair {
                 let pool = ConnectionManager::create_connection_pool(
                     server_id.clone(),
                     Arc::new(conn_settings.clone()),
-                    10,
+                    KMIP_POOL_SIZE,
                     Some(Duration::from_secs(60)),
                     Some(Duration::from_secs(60)),
                 )
@@ -384,6 +412,8 @@ impl KeyPair {
         let priv_key_url_inner = (*priv_key_url).clone();
         let pub_key_url_inner = (*pub_key_url).clone();
 
+        let metrics = center.metrics.get_hsm_metrics(priv_key_url.server_id());
+
         let key_pair = Self::Kmip(
             domain_kmip::sign::KeyPair::from_urls(
                 priv_key_url,
@@ -397,12 +427,172 @@ impl KeyPair {
                     error: error.to_string(),
                 })
             })?,
+            metrics,
         );
 
         Ok(key_pair)
     }
 }
 
+//----------- Verification -----------------------------------------------------
+
+/// Check that the keys for signing a zone can be loaded.
+///
+/// This repeats the first steps of signing the zone: reading the key set
+/// state and loading the signing keys it refers to.  This may block on KMIP
+/// servers.
+pub fn check_signing_keys(center: &Center, zone: &Zone) -> Result<(), SignerError> {
+    // A throwaway status, as 'ZoneSigningKeys::load()' reports its progress
+    // to one.
+    let status = RwLock::new(SigningStatusPerZone {
+        current_action: "Checking signing keys".into(),
+        status: ZoneSigningStatus::new(),
+    });
+
+    let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+    let keyset_state = KeySetState::load(&state_path)?;
+    ZoneSigningKeys::load(center, zone, &keyset_state, &status)?;
+    Ok(())
+}
+
+/// Check a zone's key set state against the keys it refers to.
+///
+/// Every key in the state must be loadable, from disk or from a KMIP server.
+/// Key files for the zone in the keys directory that the state does not refer
+/// to are reported as orphans.  This may block on KMIP servers.
+pub fn verify_keyset(
+    center: &Center,
+    zone: &Zone,
+    keyset_state: &KeySetState,
+) -> api::keyset::KeyVerifyResult {
+    // A throwaway status, as 'load_kmip()' reports its progress to one.
+    let status = RwLock::new(SigningStatusPerZone {
+        current_action: "Verifying keys".into(),
+        status: ZoneSigningStatus::new(),
+    });
+
+    let mut referenced = HashSet::new();
+    let mut dangling = Vec::new();
+    let mut checked = 0;
+    for (pub_key_name, key_info) in keyset_state.keyset.keys() {
+        checked += 1;
+        let res = verify_key(
+            center,
+            zone,
+            pub_key_name,
+            key_info.privref(),
+            &status,
+            &mut referenced,
+        );
+        if let Err(error) = res {
+            dangling.push(api::keyset::KeyVerifyProblem {
+                key: pub_key_name.clone(),
+                error: error.to_string(),
+            });
+        }
+    }
+
+    // Key files use the BIND naming scheme, 'K<zone>.+<alg>+<tag>.<ext>'.
+    let prefix = if zone.name.is_root() {
+        "K.+".to_string()
+    } else {
+        format!("K{}.+", zone.name.to_string().to_lowercase())
+    };
+    let keys_dir = &center.config.keys_dir;
+    let mut orphans = Vec::new();
+    match keys_dir.read_dir_utf8() {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let is_key_file = file_name.ends_with(".key") || file_name.ends_with(".private");
+                if !file_name.starts_with(&prefix) || !is_key_file {
+                    continue;
+                }
+                let path = entry.path();
+                let canonical = path.canonicalize_utf8().unwrap_or_else(|_| path.into());
+                if !referenced.contains(&canonical) {
+                    orphans.push(path.to_string());
+                }
+            }
+            orphans.sort();
+        }
+        Err(error) => dangling.push(api::keyset::KeyVerifyProblem {
+            key: keys_dir.to_string(),
+            error: format!("cannot list the keys directory: {error}"),
+        }),
+    }
+
+    api::keyset::KeyVerifyResult {
+        checked,
+        dangling,
+        orphans,
+    }
+}
+
+/// Check that a single key from a key set state can be loaded.
+///
+/// The paths of any key files it refers to are added to `referenced`.
+fn verify_key(
+    center: &Center,
+    zone: &Zone,
+    pub_key_name: &str,
+    priv_key_name: Option<&str>,
+    status: &RwLock<SigningStatusPerZone>,
+    referenced: &mut HashSet<Utf8PathBuf>,
+) -> Result<(), Box<LoadError>> {
+    let parse_url = |url: &str| {
+        Url::parse(url).map_err(|error| {
+            Box::new(LoadError::MalformedKeyUrl {
+                url: url.into(),
+                error,
+            })
+        })
+    };
+    let pub_url = parse_url(pub_key_name)?;
+    let priv_url = priv_key_name.map(parse_url).transpose()?;
+
+    for url in std::iter::once(&pub_url).chain(priv_url.as_ref()) {
+        if url.scheme() == "file" {
+            let path = Utf8Path::new(url.path());
+            let canonical = path.canonicalize_utf8().unwrap_or_else(|_| path.into());
+            referenced.insert(canonical);
+        }
+    }
+
+    let kmip_url = |url: Url| {
+        KeyUrl::try_from(url.clone())
+            .map_err(|error| Box::new(LoadError::MalformedKmipKeyUrl { url, error }))
+    };
+
+    let scheme = pub_url.scheme().to_string();
+    match (scheme.as_str(), priv_url) {
+        ("file", None) => {
+            KeyPair::load_pub_from_file(pub_url.path().as_ref())?;
+        }
+        ("kmip", None) => {
+            // A public key alone cannot be fetched; just check the URL.
+            kmip_url(pub_url)?;
+        }
+        (_, Some(priv_url)) if priv_url.scheme() != pub_url.scheme() => {
+            return Err(Box::new(LoadError::MultipleSchemesInKey {
+                pub_url,
+                priv_url,
+            }));
+        }
+        ("file", Some(priv_url)) => {
+            KeyPair::load_from_disk(zone, priv_url.path().as_ref(), pub_url.path().as_ref())?;
+        }
+        ("kmip", Some(priv_url)) => {
+            KeyPair::load_kmip(center, kmip_url(priv_url)?, kmip_url(pub_url)?, status)?;
+        }
+        _ => {
+            return Err(Box::new(LoadError::UnsupportedScheme { url: pub_url }));
+        }
+    }
+
+    Ok(())
+}
+
 //============ Errors ==========================================================
 
 //----------- LoadError --------------------------------------------------------
@@ -422,6 +612,15 @@ pub enum LoadError {
         priv_url: Url,
     },
 
+    /// A public/private key URL in the keyset state could not be parsed.
+    MalformedKeyUrl {
+        /// The unparsed URL of the key.
+        url: Box<str>,
+
+        /// The underlying error.
+        error: url::ParseError,
+    },
+
     /// A public/private key uses an unsupported URI scheme.
     UnsupportedScheme {
         /// The URL of the key.
@@ -539,6 +738,7 @@ impl core::error::Error for LoadError {
         match self {
             Self::NoKeysFound => None,
             Self::MultipleSchemesInKey { .. } => None,
+            Self::MalformedKeyUrl { error, .. } => Some(error),
             Self::UnsupportedScheme { .. } => None,
             Self::UnreadableKeyFile { error, .. } => Some(error),
             Self::MalformedPrivateKeyFile { error, .. } => Some(error),
@@ -569,6 +769,9 @@ impl fmt::Display for LoadError {
                     as used by key '{pub_url}' are not supported"
                 )
             }
+            Self::MalformedKeyUrl { url, error } => {
+                write!(f, "The key URL '{url}' is malformed: {error}")
+            }
             Self::UnsupportedScheme { url } => {
                 let scheme = url.scheme();
                 write!(
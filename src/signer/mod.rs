@@ -18,24 +18,37 @@
 // TODO: Move 'src/units/zone_signer.rs' here.
 
 use std::{
+    collections::BTreeSet,
     ops::{BitOr, BitOrAssign},
     sync::{Arc, RwLock},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
-use domain::base::Serial;
+use domain::{
+    base::{Serial, name::FlattenInto},
+    new::base::RType,
+    rdata::{ZoneRecordData, dnssec::Timestamp},
+    zonefile::inplace::{Entry, Zonefile},
+};
 use jiff::{Timestamp as JiffTimestamp, Zoned, tz::TimeZone};
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 use crate::{
     center::Center,
-    policy::SignerSerialPolicy,
-    signer::{queue::SigningPermit, status::SigningStatusPerZone},
-    units::zone_signer::SignerError,
+    policy::{SignerPolicy, SignerSerialPolicy},
+    signer::{
+        queue::SigningPermit,
+        status::{SigningStatistics, SigningStatusPerZone},
+    },
+    units::{
+        key_manager::mk_dnst_keyset_state_file_path,
+        zone_signer::{KeySetState, SignerError, faketime_or_now},
+    },
     zone::{HistoricalEvent, Zone},
-    zonedata::SignedZoneBuilder,
+    zonedata::{OldRecord, SignedZoneBuilder, is_signing},
 };
 
+pub mod expiry;
 pub mod full;
 pub mod incremental;
 pub mod keys;
@@ -71,10 +84,72 @@ fn sign(
 ) {
     let start = Instant::now();
 
-    let result = if let Some(patcher) = builder.patch() {
+    // Overrides provided by the operator only apply to the re-sign they
+    // requested, so take them out of the zone state.
+    let overrides = match trigger {
+        SigningTrigger::Resign(ResigningTrigger { manual: true, .. }) => zone
+            .write_handle(&center)
+            .state
+            .signer
+            .resign_overrides
+            .take()
+            .unwrap_or_default(),
+        _ => SignatureOverrides::default(),
+    };
+
+    // Incremental signing does not know how to preserve external signatures,
+    // so fall back to signing the whole zone.  An operator asking for the
+    // zone to be re-signed expects all signatures to be regenerated, which
+    // incremental signing would skip if nothing appears to have changed.
+    let mut incremental = !zone
+        .read()
+        .policy
+        .as_ref()
+        .is_some_and(|policy| policy.signer.preserve_external_signatures)
+        && !matches!(
+            trigger,
+            SigningTrigger::Resign(ResigningTrigger { manual: true, .. })
+        );
+
+    // The NS RRsets can only diverge if the zone or the keys changed.
+    if let SigningTrigger::Load
+    | SigningTrigger::Resign(ResigningTrigger {
+        keys_changed: true, ..
+    }) = trigger
+    {
+        check_ns_rrset(&center, &zone, &builder);
+    }
+
+    // Only a newly loaded instance can bring in DNSSEC records.
+    let dnssec_input = match trigger {
+        SigningTrigger::Load => check_dnssec_input(&center, &zone, &builder),
+        SigningTrigger::Resign(_) => Ok(0),
+    };
+    if let Ok(records) = dnssec_input
+        && records > 0
+    {
+        // Full signing only considers the unsigned records of the loaded
+        // instance, so it strips the DNSSEC records cleanly.
+        incremental = false;
+    }
+
+    let mut signed_incrementally = false;
+    let result = if let Err(err) = check_clock(&zone) {
+        Err(err)
+    } else if let Err(err) = dnssec_input {
+        Err(err)
+    } else if incremental && let Some(patcher) = builder.patch() {
+        signed_incrementally = true;
         self::incremental::sign_incrementally(patcher, &zone, &center, trigger, status.clone())
     } else {
-        self::full::sign_zone(&center, &zone, &mut builder, trigger, status.clone())
+        self::full::sign_zone(
+            &center,
+            &zone,
+            &mut builder,
+            trigger,
+            &overrides,
+            status.clone(),
+        )
     };
 
     let end = Instant::now();
@@ -87,9 +162,23 @@ fn sign(
     center.signer.queue.finish(permit, &center);
     // TODO: Remove `status` from `handle.state.signer.active_signing_status`?
 
+    let mut statistics = None;
     match result {
         Ok(()) => {
-            let soa = builder.next_signed().unwrap().soa();
+            let signed = builder.next_signed().unwrap();
+            let soa = signed.soa();
+            if center.config.signer.statistics_dir.is_some() {
+                let trigger = match trigger {
+                    SigningTrigger::Load => "load",
+                    SigningTrigger::Resign(_) => "resign",
+                };
+                statistics = Some(SigningStatistics::new(
+                    &signed,
+                    trigger,
+                    signed_incrementally,
+                    end - start,
+                ));
+            }
 
             debug!(
                 zone = %zone.name,
@@ -101,6 +190,7 @@ fn sign(
             handle.get().finish_signing(built);
             status.status.finish(true);
             zone.metrics.last_successful_sign_duration(duration);
+            handle.signer().on_signing_success();
             status.current_action = "Finished".to_string();
         }
         Err(SignerError::NothingToDo) => {
@@ -108,11 +198,28 @@ fn sign(
             status.status.finish(true);
             status.current_action = "Nothing to do".to_string();
         }
+        Err(SignerError::NoPolicy) => {
+            // The policy was removed while the zone was being signed, e.g.
+            // because the zone is being removed. Skip the zone rather than
+            // halting it; it is signed again once it has a policy.
+            warn!("Skipping signing of zone '{}': it has no policy", zone.name);
+            handle.get().abandon_signing(builder);
+            status.status.finish(false);
+            status.current_action = "Skipped, no policy".to_string();
+
+            handle.state.record_event(
+                HistoricalEvent::SigningFailed {
+                    trigger: trigger.into(),
+                    reason: SignerError::NoPolicy.to_string(),
+                    transient: false,
+                },
+                None, // TODO
+            );
+        }
         Err(SignerError::KeepSerialPolicyViolated) => {
             // Also ignore Keep errors. We can ignore these errors for
             // a while assuming the unsigned zone gets updated regularly.
-            // TODO: But if nothing happens for too long we should warn.
-            // Something in status would be good.
+            // Policies using Keep are warned about when they are loaded.
             handle.get().abandon_signing(builder);
             status.status.finish(true);
 
@@ -120,7 +227,10 @@ fn sign(
 
             // If the sign operation was triggered by a load, the user forgot to increase the
             // serial of the zone, so we should tell them about that by emitting an error.
-            if trigger == SigningTrigger::Load {
+            // The same goes for re-signing they requested themselves.
+            if let SigningTrigger::Load
+            | SigningTrigger::Resign(ResigningTrigger { manual: true, .. }) = trigger
+            {
                 let error =
                     "serial policy is \"keep\" but the serial of the loaded zone did not increase";
 
@@ -129,16 +239,30 @@ fn sign(
                     HistoricalEvent::SigningFailed {
                         trigger: trigger.into(),
                         reason: error.to_string(),
+                        transient: false,
                     },
                     None, // TODO
                 );
+            } else if let SigningTrigger::Resign(ResigningTrigger {
+                sigs_need_refresh: true,
+                ..
+            }) = trigger
+            {
+                // Secondaries will not pick up the renewed signatures, so
+                // they will eventually serve expired ones.
+                warn!(
+                    "Renewed signatures for zone '{}' cannot be published: serial policy is \"keep\" but the serial of the loaded zone did not increase",
+                    zone.name
+                );
             } else {
                 debug!("ignoring resign because the policy is keep");
             }
         }
         Err(error) => {
+            let transient = error.is_transient();
             error!("Signing failed: {error}");
             handle.get().signing_failed(builder, error.clone());
+            handle.signer().on_signing_failure(transient);
             status.status.finish(false);
             status.current_action = "Aborted".to_string();
 
@@ -146,11 +270,275 @@ fn sign(
                 HistoricalEvent::SigningFailed {
                     trigger: trigger.into(),
                     reason: error.to_string(),
+                    transient,
                 },
                 None, // TODO
             );
+            handle.state.record_halt();
         }
     }
+
+    // Record the statistics without holding on to the zone.
+    drop(handle);
+    drop(status);
+    if let Some(statistics) = statistics
+        && let Some(dir) = &center.config.signer.statistics_dir
+        && let Err(err) = statistics.append(dir, &zone.name)
+    {
+        warn!(
+            "Could not record the signing statistics of zone '{}' in '{dir}': {err}",
+            zone.name
+        );
+    }
+}
+
+//----------- check_clock() ----------------------------------------------------
+
+/// The earliest time the system clock can plausibly show (2020-01-01).
+///
+/// A clock before this point has most likely been reset, e.g. by a machine
+/// without a (working) real-time clock that booted before NTP synchronized.
+const CLOCK_FLOOR: Duration = Duration::from_secs(1_577_836_800);
+
+/// How far the clock may go back relative to the previous signing operation.
+const CLOCK_BACKWARDS_TOLERANCE: Duration = Duration::from_secs(60 * 60);
+
+/// Check that the system clock is plausible for signing a zone.
+///
+/// Signature inception and expiration times are derived from the current
+/// time, so signing with a wrong clock can make a zone bogus.  The clock is
+/// rejected if it lies before [`CLOCK_FLOOR`], or if it is more than
+/// [`CLOCK_BACKWARDS_TOLERANCE`] before the time the zone was last signed.
+///
+/// A clock more than the signature validity period after the last signing
+/// is only warned about: it may well be right, e.g. after Cascade was
+/// stopped for a long time, and refusing to sign would keep the (already
+/// expired) zone bogus.
+pub fn check_clock(zone: &Zone) -> Result<(), SignerError> {
+    let now = faketime_or_now();
+    let now_secs: Duration = now.clone().into();
+    if now_secs < CLOCK_FLOOR {
+        return Err(SignerError::ClockSkew(format!(
+            "the current time ({now}) lies before {}",
+            JiffTimestamp::from_second(CLOCK_FLOOR.as_secs() as i64).unwrap()
+        )));
+    }
+
+    let (last, validity) = {
+        let state = zone.read();
+        let validity = state
+            .policy
+            .as_ref()
+            .map(|policy| Duration::from_secs(policy.signer.sig_validity_time.into()));
+        (state.last_signature_refresh.clone(), validity)
+    };
+    let last_secs: Duration = last.clone().into();
+    if now_secs + CLOCK_BACKWARDS_TOLERANCE < last_secs {
+        return Err(SignerError::ClockSkew(format!(
+            "the current time ({now}) is {}s before the zone was last signed ({last})",
+            (last_secs - now_secs).as_secs()
+        )));
+    }
+
+    // A zero time means the last signing time was forgotten on purpose.
+    if let Some(validity) = validity
+        && !last_secs.is_zero()
+        && now_secs > last_secs + validity
+    {
+        warn!(
+            "The current time ({now}) is {}s after zone '{}' was last signed ({last}), beyond the signature validity of {}s; check the system clock",
+            (now_secs - last_secs).as_secs(),
+            zone.name,
+            validity.as_secs()
+        );
+    }
+
+    Ok(())
+}
+
+//----------- check_dnssec_input() ---------------------------------------------
+
+/// Look for DNSSEC records in the loaded instance of a zone.
+///
+/// Loaded zones are expected to be unsigned.  DNSSEC records in them (e.g.
+/// left over from a previous signer) are never signed over; depending on the
+/// policy, they are stripped or signing is refused.  Any finding is recorded
+/// in the history of the zone.  Returns the number of DNSSEC records found.
+fn check_dnssec_input(
+    center: &Arc<Center>,
+    zone: &Arc<Zone>,
+    builder: &SignedZoneBuilder,
+) -> Result<usize, SignerError> {
+    let Some(loaded) = builder.next_loaded().or_else(|| builder.curr_loaded()) else {
+        return Ok(0);
+    };
+    let Some(policy) = zone.read().policy.clone() else {
+        return Ok(0);
+    };
+    let preserve_rrsigs = policy.signer.preserve_external_signatures;
+
+    let apex = &*loaded.soa().rname;
+    let records = loaded
+        .regular_records()
+        .iter()
+        .filter(|r| is_dnssec_input(r.rtype, || *r.rname == *apex, preserve_rrsigs))
+        .count();
+    if records == 0 {
+        return Ok(0);
+    }
+
+    let stripped = !policy.signer.refuse_dnssec_input;
+    if stripped {
+        warn!(
+            "The loaded zone '{}' contains {records} DNSSEC records; they will be stripped before signing",
+            zone.name
+        );
+    } else {
+        error!(
+            "The loaded zone '{}' contains {records} DNSSEC records; refusing to sign it",
+            zone.name
+        );
+    }
+    let serial = Serial::from(loaded.soa().rdata.serial.0.get());
+    zone.write(center).record_event(
+        HistoricalEvent::DnssecInput { records, stripped },
+        Some(serial),
+    );
+
+    if stripped {
+        Ok(records)
+    } else {
+        Err(SignerError::DnssecInput(records))
+    }
+}
+
+/// Whether a loaded record is a DNSSEC record that must not be signed over.
+///
+/// `at_apex` computes whether the record is at the apex of the zone.  RRSIG
+/// records are allowed if `preserve_rrsigs` is set.
+fn is_dnssec_input(rtype: RType, at_apex: impl FnOnce() -> bool, preserve_rrsigs: bool) -> bool {
+    // ZONEMD records are not DNSSEC records, even if they are regenerated
+    // during signing.
+    rtype != RType::ZONEMD
+        && !(preserve_rrsigs && rtype == RType::RRSIG)
+        && is_signing(rtype, at_apex)
+}
+
+//----------- check_ns_rrset() -------------------------------------------------
+
+/// Warn if the key manager's NS RRset differs from the zone's.
+///
+/// `dnst keyset` keeps its own copy of the apex NS RRset, e.g. to find the
+/// nameservers to check during key rolls.  If it drifts from the NS RRset of
+/// the zone being signed, delegation automation such as CDS publication can
+/// silently misbehave.  Only the nameserver names are compared; an empty copy
+/// is not compared at all.
+fn check_ns_rrset(center: &Center, zone: &Zone, builder: &SignedZoneBuilder) {
+    let Some(loaded) = builder.next_loaded().or_else(|| builder.curr_loaded()) else {
+        return;
+    };
+    let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+    let Ok(state) = KeySetState::load(&state_path) else {
+        // Signing itself reports why the state could not be loaded.
+        return;
+    };
+    if state.ns_rrset.is_empty() {
+        return;
+    }
+
+    let nsdname = |record: &OldRecord| match record.data() {
+        ZoneRecordData::Ns(ns) => Some(ns.nsdname().to_string().to_ascii_lowercase()),
+        _ => None,
+    };
+
+    // Records are in canonical order, so the apex records come first.
+    let soa = loaded.soa();
+    let zone_ns: BTreeSet<String> = loaded
+        .regular_records()
+        .iter()
+        .take_while(|r| r.rname == soa.rname)
+        .filter_map(|r| nsdname(&OldRecord::from(r.clone())))
+        .collect();
+
+    let keyset_ns: BTreeSet<String> = state
+        .ns_rrset
+        .iter()
+        .filter_map(|rr| {
+            let mut zonefile = Zonefile::new();
+            zonefile.extend_from_slice(rr.as_bytes());
+            zonefile.extend_from_slice(b"\n");
+            let Ok(Some(Entry::Record(rec))) = zonefile.next_entry() else {
+                return None;
+            };
+            nsdname(&rec.flatten_into())
+        })
+        .collect();
+
+    if zone_ns != keyset_ns {
+        let list = |names: Vec<&String>| {
+            names
+                .into_iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        warn!(
+            "The key manager's NS RRset for zone '{}' differs from the zone's; only in the zone: [{}], only in the key manager: [{}]",
+            zone.name,
+            list(zone_ns.difference(&keyset_ns).collect()),
+            list(keyset_ns.difference(&zone_ns).collect()),
+        );
+    }
+}
+
+//----------- signature_validity() ---------------------------------------------
+
+/// The current time for signing, in seconds since the Unix epoch.
+///
+/// This can be overridden with the `CASCADE_FAKETIME` environment variable,
+/// so that signed output is reproducible.
+pub fn signing_time() -> u32 {
+    Duration::from(faketime_or_now()).as_secs() as u32
+}
+
+/// Compute the inception and expiration times of signatures made at `now`.
+///
+/// `now` is in seconds since the Unix epoch, as returned by [`signing_time()`].
+/// Like the timestamps themselves, the computation uses serial number
+/// arithmetic, so it wraps around instead of overflowing.
+pub fn signature_validity(policy: &SignerPolicy, now: u32) -> (Timestamp, Timestamp) {
+    let inception = now.wrapping_sub(policy.sig_inception_offset);
+    let expiration = now.wrapping_add(policy.sig_validity_time);
+    (inception.into(), expiration.into())
+}
+
+//----------- SignatureOverrides -----------------------------------------------
+
+/// Overrides of the signature validity settings of a policy.
+///
+/// These are provided by an operator for a single re-signing operation; later
+/// signing operations use the values from the policy again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SignatureOverrides {
+    /// Overrides [`SignerPolicy::sig_validity_time`].
+    pub sig_validity_time: Option<u32>,
+
+    /// Overrides [`SignerPolicy::sig_inception_offset`].
+    pub sig_inception_offset: Option<u32>,
+}
+
+impl SignatureOverrides {
+    /// Apply the overrides to a signer policy.
+    pub fn apply(&self, policy: &SignerPolicy) -> SignerPolicy {
+        let mut policy = policy.clone();
+        if let Some(validity) = self.sig_validity_time {
+            policy.sig_validity_time = validity;
+        }
+        if let Some(offset) = self.sig_inception_offset {
+            policy.sig_inception_offset = offset;
+        }
+        policy
+    }
 }
 
 /// Compute the SOA serial for a signed zone.
@@ -167,12 +555,16 @@ fn sign(
 /// 4) Broken down time (YYYYMMDDnn). The current day plus a serial
 ///    number. Implies increment to generate different serial numbers
 ///    over a day.
+///
+/// Whatever the policy, the serial never goes below the zone's serial floor,
+/// if it has one.
 fn next_signed_soa_serial(
     policy: SignerSerialPolicy,
     loaded_serial: Serial,
     previous_serial: Option<Serial>,
+    serial_floor: Option<Serial>,
 ) -> Result<Serial, SignerError> {
-    match policy {
+    let serial = match policy {
         SignerSerialPolicy::Keep => {
             if let Some(previous_serial) = previous_serial
                 && loaded_serial <= previous_serial
@@ -180,13 +572,13 @@ fn next_signed_soa_serial(
                 return Err(SignerError::KeepSerialPolicyViolated);
             }
 
-            Ok(loaded_serial)
+            loaded_serial
         }
         SignerSerialPolicy::Counter => {
             // Always increment the serial number, ignore the serial
             // number in the unsigned zone.
             let previous_serial = previous_serial.unwrap_or(Serial::from(0));
-            Ok(previous_serial.add(1))
+            previous_serial.add(1)
         }
         SignerSerialPolicy::UnixTime => {
             let mut serial = Serial::now();
@@ -196,7 +588,7 @@ fn next_signed_soa_serial(
                 serial = previous_serial.add(1);
             }
 
-            Ok(serial)
+            serial
         }
         SignerSerialPolicy::DateCounter => {
             let ts = JiffTimestamp::now();
@@ -211,8 +603,13 @@ fn next_signed_soa_serial(
                 serial = previous_serial.add(1);
             }
 
-            Ok(serial)
+            serial
         }
+    };
+
+    match serial_floor {
+        Some(serial_floor) if serial < serial_floor => Ok(serial_floor),
+        _ => Ok(serial),
     }
 }
 
@@ -249,6 +646,11 @@ pub struct ResigningTrigger {
 
     /// Whether signatures need to be refreshed.
     sigs_need_refresh: bool,
+
+    /// Whether an operator requested re-signing.
+    ///
+    /// Such re-signing operations always sign the whole zone.
+    manual: bool,
 }
 
 impl ResigningTrigger {
@@ -256,12 +658,21 @@ impl ResigningTrigger {
     pub const KEYS_CHANGED: Self = Self {
         keys_changed: true,
         sigs_need_refresh: false,
+        manual: false,
     };
 
     /// Re-signing because signatures need to be refreshed.
     pub const SIGS_NEED_REFRESH: Self = Self {
         keys_changed: false,
         sigs_need_refresh: true,
+        manual: false,
+    };
+
+    /// Re-signing because an operator requested it.
+    pub const MANUAL: Self = Self {
+        keys_changed: false,
+        sigs_need_refresh: false,
+        manual: true,
     };
 }
 
@@ -279,9 +690,11 @@ impl BitOrAssign for ResigningTrigger {
         let Self {
             keys_changed,
             sigs_need_refresh,
+            manual,
         } = rhs;
         self.keys_changed |= keys_changed;
         self.sigs_need_refresh |= sigs_need_refresh;
+        self.manual |= manual;
     }
 }
 
@@ -290,10 +703,176 @@ impl From<ResigningTrigger> for crate::api::ResigningTrigger {
         let ResigningTrigger {
             keys_changed,
             sigs_need_refresh,
+            manual,
         } = value;
         Self {
             keys_changed,
             sigs_need_refresh,
+            manual,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use domain::base::Serial;
+    use domain::new::base::RType;
+
+    use crate::policy::SignerSerialPolicy;
+    use crate::policy::file::v1::SignerSpec;
+    use crate::units::zone_signer::SignerError;
+
+    use super::{
+        ResigningTrigger, SignatureOverrides, is_dnssec_input, next_signed_soa_serial,
+        signature_validity,
+    };
+
+    #[test]
+    fn signature_validity_is_relative_to_now() {
+        let mut policy = SignerSpec::default().parse();
+        policy.sig_inception_offset = 60 * 60;
+        policy.sig_validity_time = 14 * 24 * 60 * 60;
+
+        let (inception, expiration) = signature_validity(&policy, 1_700_000_000);
+        assert_eq!(inception.into_int(), 1_700_000_000 - 60 * 60);
+        assert_eq!(expiration.into_int(), 1_700_000_000 + 14 * 24 * 60 * 60);
+
+        // The same time always results in the same signature timestamps.
+        let (inception2, expiration2) = signature_validity(&policy, 1_700_000_000);
+        assert_eq!(inception, inception2);
+        assert_eq!(expiration, expiration2);
+    }
+
+    #[test]
+    fn signature_validity_wraps_around() {
+        let mut policy = SignerSpec::default().parse();
+        policy.sig_inception_offset = 10;
+        policy.sig_validity_time = 20;
+
+        let (inception, _) = signature_validity(&policy, 5);
+        assert_eq!(inception.into_int(), u32::MAX - 4);
+
+        let (_, expiration) = signature_validity(&policy, u32::MAX - 9);
+        assert_eq!(expiration.into_int(), 10);
+    }
+
+    #[test]
+    fn serial_floor_raises_counter_serials() {
+        let floor = Some(Serial(1000));
+        let serial = next_signed_soa_serial(
+            SignerSerialPolicy::Counter,
+            Serial(5),
+            Some(Serial(10)),
+            floor,
+        );
+        assert_eq!(serial.unwrap(), Serial(1000));
+
+        // Serials above the floor are left alone.
+        let serial = next_signed_soa_serial(
+            SignerSerialPolicy::Counter,
+            Serial(5),
+            Some(Serial(2000)),
+            floor,
+        );
+        assert_eq!(serial.unwrap(), Serial(2001));
+    }
+
+    #[test]
+    fn serial_floor_with_keep_policy() {
+        let floor = Some(Serial(1000));
+
+        // The first signed version takes the floor if the loaded serial is
+        // below it.
+        let serial = next_signed_soa_serial(SignerSerialPolicy::Keep, Serial(5), None, floor);
+        assert_eq!(serial.unwrap(), Serial(1000));
+
+        // The loaded serial is kept once it exceeds the floor.
+        let serial = next_signed_soa_serial(
+            SignerSerialPolicy::Keep,
+            Serial(1001),
+            Some(Serial(1000)),
+            floor,
+        );
+        assert_eq!(serial.unwrap(), Serial(1001));
+
+        // The floor does not allow the loaded serial to go back.
+        let serial = next_signed_soa_serial(
+            SignerSerialPolicy::Keep,
+            Serial(6),
+            Some(Serial(1000)),
+            floor,
+        );
+        assert!(matches!(serial, Err(SignerError::KeepSerialPolicyViolated)));
+    }
+
+    #[test]
+    fn dnssec_records_in_loaded_zones_are_detected() {
+        assert!(is_dnssec_input(RType::NSEC, || false, false));
+        assert!(is_dnssec_input(RType::RRSIG, || false, false));
+        assert!(is_dnssec_input(RType::DNSKEY, || true, false));
+        assert!(!is_dnssec_input(RType::A, || true, false));
+
+        // DNSKEY records below the apex belong to no signer of this zone.
+        assert!(!is_dnssec_input(RType::DNSKEY, || false, false));
+
+        // Preserved external signatures and ZONEMD records are fine.
+        assert!(!is_dnssec_input(RType::RRSIG, || false, true));
+        assert!(!is_dnssec_input(RType::ZONEMD, || true, false));
+    }
+
+    #[test]
+    fn manual_resigning_survives_merged_triggers() {
+        let mut trigger = ResigningTrigger::SIGS_NEED_REFRESH;
+        trigger |= ResigningTrigger::MANUAL;
+        trigger |= ResigningTrigger::KEYS_CHANGED;
+
+        let crate::api::ResigningTrigger {
+            keys_changed,
+            sigs_need_refresh,
+            manual,
+        } = trigger.into();
+        assert!(keys_changed);
+        assert!(sigs_need_refresh);
+        assert!(manual);
+    }
+
+    #[test]
+    fn resign_overrides_are_held_to_the_policy_bounds() {
+        let policy = SignerSpec::default().parse();
+        assert_eq!(policy.check_timing(), Ok(()));
+
+        // Without overrides, the policy is used as-is.
+        assert_eq!(SignatureOverrides::default().apply(&policy), policy);
+
+        let overrides = SignatureOverrides {
+            sig_validity_time: Some(30 * 24 * 60 * 60),
+            sig_inception_offset: Some(2 * 60 * 60),
+        };
+        let signer = overrides.apply(&policy);
+        assert_eq!(signer.sig_validity_time, 30 * 24 * 60 * 60);
+        assert_eq!(signer.sig_inception_offset, 2 * 60 * 60);
+        assert_eq!(signer.sig_remain_time, policy.sig_remain_time);
+        assert_eq!(signer.check_timing(), Ok(()));
+
+        let mut signer = policy.clone();
+        signer.sig_validity_time = 0x4000_0000;
+        assert!(signer.check_timing().unwrap_err().contains("too big"));
+
+        let mut signer = policy.clone();
+        signer.signature_refresh_interval = 59;
+        assert!(
+            signer
+                .check_timing()
+                .unwrap_err()
+                .starts_with("signature-refresh-interval")
+        );
+
+        // Signatures have to outlive the remain time, a TTL and a refresh.
+        let mut signer = policy.clone();
+        signer.sig_validity_time =
+            signer.sig_remain_time + 3600 + signer.signature_refresh_interval;
+        assert!(signer.check_timing().unwrap_err().contains("too small"));
+        signer.sig_validity_time += 1;
+        assert_eq!(signer.check_timing(), Ok(()));
+    }
+}
@@ -1,7 +1,7 @@
 //! Enqueueing zones for (re-)signing.
 //
-// TODO: Is there some way to unit-test this code? This is difficult because it
-// interacts with `ZoneState` directly.
+// TODO: Is there some way to unit-test initiating signing? This is difficult
+// because it interacts with `ZoneState` directly.
 
 use core::fmt;
 use std::{
@@ -9,6 +9,7 @@ use std::{
     mem::ManuallyDrop,
     num::NonZeroUsize,
     sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
 };
 
 use tracing::{debug, trace};
@@ -20,21 +21,113 @@ use crate::{center::Center, util::FmtBy, zone::Zone, zonedata::SignedZoneBuilder
 /// A queue of zones pending signing.
 ///
 /// This is a thread-safe queue of zones which need to be (re-)signed but are
-/// waiting due to a limit on the number of concurrent signing operations. This
-/// queue enforces that limit and initiates signing for zones once capacity is
-/// available.
+/// waiting due to a limit on the number of concurrent signing operations, or
+/// on the memory they may use together. This queue enforces those limits and
+/// initiates signing for zones once capacity is available.
 pub struct SigningQueue {
     /// The maximum number of concurrent operations to allow.
     concurrency_limit: NonZeroUsize,
 
+    /// The maximum estimated memory concurrent operations may use, in bytes.
+    ///
+    /// A zone is always allowed to be signed if no other zones are, even if
+    /// it exceeds the budget on its own.
+    memory_budget: Option<usize>,
+
     /// The underlying queue of zones.
     ///
-    /// The first `concurrency_limit` elements of this queue are undergoing
-    /// signing (or signing will be initiated for them shortly). The remainder
-    /// wait for the earlier ones to finish.
+    /// The first elements of this queue, up to `concurrency_limit` of them
+    /// and as many as fit within `memory_budget` (see [`Self::active()`]), are
+    /// undergoing signing (or signing will be initiated for them shortly). The
+    /// remainder wait for the earlier ones to finish; waiting zones with
+    /// [`SigningPriority::Interactive`] are kept ahead of waiting zones with
+    /// [`SigningPriority::Background`].
     ///
     /// Duplicates of the same zone must not be present.
-    zones: Mutex<VecDeque<Arc<Zone>>>,
+    zones: Mutex<VecDeque<QueueEntry>>,
+
+    /// Statistics about the time zones spent waiting for a permit.
+    waits: Mutex<WaitStats>,
+}
+
+/// An entry in the [`SigningQueue`].
+struct QueueEntry {
+    /// The zone being signed or waiting to be signed.
+    zone: Arc<Zone>,
+
+    /// When the zone was enqueued.
+    enqueued_at: Instant,
+
+    /// The priority of the zone.
+    priority: SigningPriority,
+
+    /// The estimated memory needed to sign the zone, in bytes.
+    memory: usize,
+}
+
+/// The priority of a zone in the [`SigningQueue`].
+///
+/// Zones waiting for signing capacity are served in order of priority, then
+/// in the order they were enqueued. Priority does not preempt zones that are
+/// already being signed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SigningPriority {
+    /// Signing initiated automatically, e.g. due to expiring signatures.
+    #[default]
+    Background,
+
+    /// Signing requested by an operator.
+    Interactive,
+}
+
+/// Aggregated wait times in the [`SigningQueue`].
+#[derive(Default)]
+struct WaitStats {
+    /// The number of permits handed out.
+    permits: u64,
+
+    /// The total time waited for all permits handed out.
+    total: Duration,
+
+    /// The longest time waited for any permit handed out.
+    max: Duration,
+}
+
+impl WaitStats {
+    /// Record the wait time for a permit that is being handed out.
+    fn record(&mut self, wait: Duration) {
+        self.permits += 1;
+        self.total += wait;
+        self.max = self.max.max(wait);
+    }
+}
+
+/// A snapshot of the state of the [`SigningQueue`].
+///
+/// See [`SigningQueue::stats()`].
+#[derive(Clone, Debug)]
+pub struct SigningQueueStats {
+    /// The number of zones being signed.
+    pub active: usize,
+
+    /// The number of zones waiting for signing capacity.
+    pub pending: usize,
+
+    /// The estimated memory used by the zones being signed, in bytes.
+    pub memory_usage: usize,
+
+    /// How long the longest-waiting pending zone has been waiting.
+    pub oldest_wait: Option<Duration>,
+
+    /// The average time zones waited for signing capacity.
+    ///
+    /// This is aggregated over all signing operations since Cascade started.
+    pub average_wait: Option<Duration>,
+
+    /// The longest time any zone waited for signing capacity.
+    ///
+    /// This is aggregated over all signing operations since Cascade started.
+    pub max_wait: Option<Duration>,
 }
 
 /// A lock on the [`SigningQueue`].
@@ -42,16 +135,18 @@ pub struct SigningQueue {
 /// This is sometimes passed around explicitly to prevent deadlocks.
 pub struct SigningQueueLock<'a> {
     /// The underlying queue.
-    zones: &'a mut VecDeque<Arc<Zone>>,
+    zones: &'a mut VecDeque<QueueEntry>,
 }
 
 impl SigningQueue {
     /// Construct a new [`SigningQueue`].
     #[must_use]
-    pub fn new(concurrency_limit: NonZeroUsize) -> Self {
+    pub fn new(concurrency_limit: NonZeroUsize, memory_budget: Option<usize>) -> Self {
         Self {
             concurrency_limit,
+            memory_budget,
             zones: Mutex::new(VecDeque::new()),
+            waits: Mutex::new(WaitStats::default()),
         }
     }
 
@@ -61,15 +156,81 @@ impl SigningQueue {
         self.concurrency_limit
     }
 
+    /// The set memory budget, in bytes.
+    #[must_use]
+    pub const fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    /// The number of zones at the front of the queue that are being signed.
+    ///
+    /// This is limited by [`Self::concurrency_limit()`] and by
+    /// [`Self::memory_budget()`]. At least one zone is always allowed, so that
+    /// a zone exceeding the budget on its own is not deferred forever.
+    fn active(&self, zones: &VecDeque<QueueEntry>) -> usize {
+        let Some(budget) = self.memory_budget else {
+            return zones.len().min(self.concurrency_limit.get());
+        };
+
+        let mut usage = 0usize;
+        zones
+            .iter()
+            .take(self.concurrency_limit.get())
+            .enumerate()
+            .take_while(|(i, e)| {
+                usage = usage.saturating_add(e.memory);
+                *i == 0 || usage <= budget
+            })
+            .count()
+    }
+
     /// Export a list of all zones in the queue.
     ///
     /// This can be used for reporting on Cascade's status. The first `N` zones,
-    /// where `N` is [`Self::concurrency_limit()`], are actively being signed
+    /// where `N` is [`SigningQueueStats::active`], are actively being signed
     /// (or are about to be signed); the remainder are waiting to be signed.
     #[must_use]
     pub fn export(&self) -> Vec<Arc<Zone>> {
         let zones = self.zones.lock().unwrap_or_else(handle_poison);
-        zones.iter().cloned().collect()
+        zones.iter().map(|e| e.zone.clone()).collect()
+    }
+
+    /// Summarize the current state of the queue and past wait times.
+    ///
+    /// This can be used to right-size the concurrency limit and the memory
+    /// budget: if zones spend a lot of time waiting, more concurrent signing
+    /// operations may help.
+    #[must_use]
+    pub fn stats(&self) -> SigningQueueStats {
+        let zones = self.zones.lock().unwrap_or_else(handle_poison);
+        let active = self.active(&zones);
+        let pending = zones.len() - active;
+        let memory_usage = zones.iter().take(active).map(|e| e.memory).sum();
+        // Pending zones are ordered by priority first, so the one that waited
+        // longest can be anywhere among them.
+        let oldest_wait = zones
+            .iter()
+            .skip(active)
+            .map(|e| e.enqueued_at)
+            .min()
+            .map(|enqueued_at| enqueued_at.elapsed());
+        drop(zones);
+
+        let waits = self.waits.lock().unwrap_or_else(handle_poison);
+        let average_wait = u32::try_from(waits.permits)
+            .ok()
+            .filter(|&n| n > 0)
+            .map(|n| waits.total / n);
+        let max_wait = (waits.permits > 0).then_some(waits.max);
+
+        SigningQueueStats {
+            active,
+            pending,
+            memory_usage,
+            oldest_wait,
+            average_wait,
+            max_wait,
+        }
     }
 
     /// Enqueue signing for a zone.
@@ -78,7 +239,11 @@ impl SigningQueue {
     /// Otherwise, the zone is enqueued and it must wait until capacity is
     /// available; once capacity is available, the returned [`SigningPending`]
     /// can be traded for a [`SigningPermit`]. A [`SignedZoneBuilder`] is taken
-    /// as proof that the caller intends to perform signing.
+    /// as proof that the caller intends to perform signing, and is used to
+    /// estimate the memory that signing will need.
+    ///
+    /// Zones with [`SigningPriority::Interactive`] are placed ahead of all
+    /// waiting zones with [`SigningPriority::Background`].
     ///
     /// ## Panics
     ///
@@ -88,27 +253,53 @@ impl SigningQueue {
     #[tracing::instrument(
         level = "trace",
         skip_all,
-        fields(zone = %zone.name),
+        fields(zone = %zone.name, ?priority),
     )]
     pub fn enqueue(
         &self,
         zone: Arc<Zone>,
-        _builder: &SignedZoneBuilder,
+        builder: &SignedZoneBuilder,
+        priority: SigningPriority,
     ) -> Result<SigningPermit, SigningPending> {
         trace!("Enqueueing for signing");
-        let mut zones = self.zones.lock().unwrap_or_else(handle_poison);
 
-        zones.push_back(zone.clone());
+        // This walks the whole zone, so don't hold up the queue for it.
+        let memory = estimate_memory(builder);
+
+        let mut zones = self.zones.lock().unwrap_or_else(handle_poison);
+        let pos = self.insert(
+            &mut zones,
+            QueueEntry {
+                zone: zone.clone(),
+                enqueued_at: Instant::now(),
+                priority,
+                memory,
+            },
+        );
 
-        if zones.len() <= self.concurrency_limit.get() {
-            // The inserted zone fits within the concurrency limit.
+        if pos < self.active(&zones) {
+            // The inserted zone fits within the signing capacity.
 
-            trace!("Within concurrency limit, offering permit now");
+            trace!("Within signing capacity, offering permit now");
+            self.waits
+                .lock()
+                .unwrap_or_else(handle_poison)
+                .record(Duration::ZERO);
 
             Ok(SigningPermit {
                 zone,
                 _assertion: (),
             })
+        } else if pos < self.concurrency_limit.get() {
+            debug!(
+                "Zone '{}' is ready to be signed, but is waiting for memory to become available.",
+                zone.name
+            );
+
+            Err(SigningPending {
+                zone,
+                _assertion: (),
+            })
         } else {
             debug!(
                 "Zone '{}' is ready to be signed, but is waiting for other zones to finish first.",
@@ -122,11 +313,88 @@ impl SigningQueue {
         }
     }
 
+    /// Raise the priority of a [`SigningPending`].
+    ///
+    /// If the zone is still waiting for signing capacity, it is moved ahead of
+    /// any waiting zones of lower priority. The time it has already spent
+    /// waiting is preserved.
+    ///
+    /// With a memory budget, the zone may fit within the signing capacity at
+    /// its new position. It is then offered a [`SigningPermit`] straight away,
+    /// as nothing else would pass one on to it.
+    #[tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(zone = %pending.zone.name, ?priority),
+    )]
+    pub fn prioritize(
+        &self,
+        pending: SigningPending,
+        priority: SigningPriority,
+    ) -> Result<SigningPermit, SigningPending> {
+        let mut zones = self.zones.lock().unwrap_or_else(handle_poison);
+
+        let Some(pos) = zones
+            .iter()
+            .position(|e| Arc::ptr_eq(&e.zone, &pending.zone))
+        else {
+            unreachable!(
+                "Zone '{}' has a `SigningPending` but could not be found in the signing queue",
+                pending.zone.name
+            )
+        };
+
+        // Zones within signing capacity will receive a permit shortly anyway.
+        if pos < self.active(&zones) || zones[pos].priority >= priority {
+            return Err(pending);
+        }
+
+        trace!("Moving the zone ahead in the signing queue");
+        let mut entry = zones.remove(pos).expect("the position was just found");
+        entry.priority = priority;
+        let pos = self.insert(&mut zones, entry);
+
+        if pos < self.active(&zones) {
+            // The zone fits within the signing capacity at its new position.
+
+            trace!("Within signing capacity, offering permit now");
+            let wait = zones[pos].enqueued_at.elapsed();
+            debug!(
+                "Zone '{}' waited {wait:?} for signing capacity",
+                pending.zone.name
+            );
+            self.waits.lock().unwrap_or_else(handle_poison).record(wait);
+
+            return Ok(SigningPermit {
+                zone: pending.consume(),
+                _assertion: (),
+            });
+        }
+
+        Err(pending)
+    }
+
+    /// Insert an entry into the queue, according to its priority.
+    ///
+    /// The position of the inserted entry is returned. Entries within the
+    /// signing capacity are never displaced.
+    fn insert(&self, zones: &mut VecDeque<QueueEntry>, entry: QueueEntry) -> usize {
+        let pos = zones
+            .iter()
+            .enumerate()
+            .skip(self.active(zones))
+            .find(|(_, e)| e.priority < entry.priority)
+            .map_or(zones.len(), |(pos, _)| pos);
+        zones.insert(pos, entry);
+        pos
+    }
+
     /// Abandon a [`SigningPending`].
     ///
     /// A pending permit may need to be abandoned if a re-signing operation is
     /// canceled. If this is called when the zone would have received a permit,
-    /// the permit will be passed on to the next zone in the queue.
+    /// or the zone was holding back others by exceeding the memory budget, the
+    /// permit will be passed on to the next zone(s) in the queue.
     #[tracing::instrument(
         level = "trace",
         skip_all,
@@ -140,23 +408,20 @@ impl SigningQueue {
         let zone = pending.consume();
 
         // Locate this zone in the queue and remove it.
-        let Some(pos) = zones.iter().position(|z| Arc::ptr_eq(z, &zone)) else {
+        let Some(pos) = zones.iter().position(|e| Arc::ptr_eq(&e.zone, &zone)) else {
             unreachable!(
                 "Zone '{}' has a permit but is not present in the signing queue",
                 zone.name
             )
         };
+        let prev_active = self.active(&zones);
         let _ = zones.remove(pos);
         let finished_zone = zone;
 
-        // If the zone was within signing capacity, try activating a new zone.
-        if pos >= self.concurrency_limit.get() {
-            return;
-        }
-
-        trace!("The zone was within signing capacity");
+        // Try activating zones that now fit within signing capacity.
+        let prev_active = prev_active - usize::from(pos < prev_active);
         let mut lock = SigningQueueLock { zones: &mut zones };
-        self.initiate_resigning(&finished_zone, &mut lock, center);
+        self.initiate_resigning(&finished_zone, prev_active, &mut lock, center);
     }
 
     /// Accept a signing permit.
@@ -184,20 +449,24 @@ impl SigningQueue {
         let zone = pending.consume();
 
         // Ensure the zone is within the signing capacity.
-        let pos = zones.iter().position(|z| Arc::ptr_eq(z, &zone));
+        let pos = zones.iter().position(|e| Arc::ptr_eq(&e.zone, &zone));
         let pos = pos.unwrap_or_else(|| {
             panic!(
                 "Zone '{}' has a `SigningPending` but could not be found in the signing queue",
                 zone.name
             )
         });
+        let active = self.active(zones);
         assert!(
-            pos < self.concurrency_limit.get(),
-            "Zone '{}' is not within the signing capacity (position {pos}, limit {})",
+            pos < active,
+            "Zone '{}' is not within the signing capacity (position {pos}, capacity {active})",
             zone.name,
-            self.concurrency_limit.get()
         );
 
+        let wait = zones[pos].enqueued_at.elapsed();
+        debug!("Zone '{}' waited {wait:?} for signing capacity", zone.name);
+        self.waits.lock().unwrap_or_else(handle_poison).record(wait);
+
         // We have validated the zone is now within signing capacity.
         SigningPermit {
             zone,
@@ -209,7 +478,7 @@ impl SigningQueue {
     ///
     /// This must be called for every [`SigningPermit`] provided once the
     /// associated signing operation is complete. It initiates re-signing for
-    /// the next zone in the queue.
+    /// the next zone(s) in the queue.
     #[tracing::instrument(
         level = "trace",
         skip_all,
@@ -223,33 +492,41 @@ impl SigningQueue {
         let zone = permit.consume();
 
         // Locate this zone in the queue and remove it.
-        let Some(pos) = zones.iter().position(|z| Arc::ptr_eq(z, &zone)) else {
+        let Some(pos) = zones.iter().position(|e| Arc::ptr_eq(&e.zone, &zone)) else {
             unreachable!(
                 "Zone '{}' has a permit but is not present in the signing queue",
                 zone.name
             )
         };
+        let prev_active = self.active(&zones) - 1;
         let _ = zones.remove(pos);
         let finished_zone = zone;
 
-        // Initiate re-signing for a zone that now fits within capacity.
+        // Initiate re-signing for zones that now fit within capacity.
         let mut lock = SigningQueueLock { zones: &mut zones };
-        self.initiate_resigning(&finished_zone, &mut lock, center);
+        self.initiate_resigning(&finished_zone, prev_active, &mut lock, center);
     }
 
     /// Initiate re-signing due to the freeing up of signing capacity.
+    ///
+    /// `prev_active` is the number of zones at the front of the queue that
+    /// were already being signed; any zones after them that now fit within
+    /// capacity are activated.
     fn initiate_resigning(
         &self,
         finished_zone: &Arc<Zone>,
+        prev_active: usize,
         lock: &mut SigningQueueLock<'_>,
         center: &Arc<Center>,
     ) {
-        // Look for a zone that now fits within capacity.
-        if let Some(zone) = lock.zones.get(self.concurrency_limit.get() - 1) {
+        // Look for zones that now fit within capacity.
+        for pos in prev_active..self.active(lock.zones) {
+            let zone = lock.zones[pos].zone.clone();
+
             // Make sure 'finished_zone' doesn't appear again, otherwise we
             // might cause a deadlock!
             assert!(
-                !Arc::ptr_eq(zone, finished_zone),
+                !Arc::ptr_eq(&zone, finished_zone),
                 "Zone '{}' appeared twice in the signing queue",
                 finished_zone.name
             );
@@ -262,10 +539,7 @@ impl SigningQueue {
             // will call `SigningQueue::accept()` to do so. This could easily
             // lead to a deadlock --- this is why we explicitly pass `lock` and
             // make `accept()` consume `lock`.
-            zone.clone()
-                .write_handle(center)
-                .signer()
-                .accept_queue_permit(lock);
+            zone.write_handle(center).signer().accept_queue_permit(lock);
         }
     }
 }
@@ -275,10 +549,12 @@ impl fmt::Debug for SigningQueue {
         let concurrency_limit = self.concurrency_limit.get();
         let zones = self.zones.lock().unwrap_or_else(handle_poison);
         let zones = &*zones;
-        let active = zones.iter().take(concurrency_limit);
-        let pending = zones.iter().skip(concurrency_limit);
+        let num_active = self.active(zones);
+        let active = zones.iter().take(num_active).map(|e| &e.zone);
+        let pending = zones.iter().skip(num_active).map(|e| &e.zone);
         f.debug_struct("SigningQueue")
             .field("concurrency_limit", &concurrency_limit)
+            .field("memory_budget", &self.memory_budget)
             .field(
                 "active",
                 &FmtBy(|f| f.debug_list().entries(active.clone()).finish()),
@@ -291,6 +567,16 @@ impl fmt::Debug for SigningQueue {
     }
 }
 
+/// Estimate the memory needed to sign a zone, in bytes.
+///
+/// Signing collects the records of the zone being signed and generates
+/// signatures for them, so this is roughly twice the size of the unsigned
+/// zone.
+fn estimate_memory(builder: &SignedZoneBuilder) -> usize {
+    let loaded = builder.next_loaded().or_else(|| builder.curr_loaded());
+    loaded.map_or(0, |loaded| 2 * loaded.memory_usage())
+}
+
 //----------- SigningPermit ----------------------------------------------------
 
 /// A permit from the signing queue.
@@ -388,3 +674,212 @@ impl fmt::Debug for SigningPending {
 fn handle_poison<T, R>(_: PoisonError<T>) -> R {
     panic!("The zone signing queue is poisoned because a panic occurred elsewhere")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroUsize,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use crate::{metrics::Metrics, zone::Zone};
+
+    use super::{QueueEntry, SigningPending, SigningPriority, SigningQueue};
+
+    fn entry(name: &str, priority: SigningPriority, waited: Duration) -> QueueEntry {
+        sized_entry(name, priority, waited, 1000)
+    }
+
+    fn sized_entry(
+        name: &str,
+        priority: SigningPriority,
+        waited: Duration,
+        memory: usize,
+    ) -> QueueEntry {
+        QueueEntry {
+            zone: Arc::new(Zone::new(name.parse().unwrap(), &Metrics::new())),
+            enqueued_at: Instant::now() - waited,
+            priority,
+            memory,
+        }
+    }
+
+    fn queue_of_sizes(
+        concurrency_limit: usize,
+        memory_budget: Option<usize>,
+        sizes: &[usize],
+    ) -> SigningQueue {
+        let queue = SigningQueue::new(NonZeroUsize::new(concurrency_limit).unwrap(), memory_budget);
+        {
+            let mut zones = queue.zones.lock().unwrap();
+            for (i, &memory) in sizes.iter().enumerate() {
+                let name = format!("zone{i}.example");
+                let entry = sized_entry(&name, SigningPriority::Background, Duration::ZERO, memory);
+                queue.insert(&mut zones, entry);
+            }
+        }
+        queue
+    }
+
+    fn active(queue: &SigningQueue) -> usize {
+        queue.active(&queue.zones.lock().unwrap())
+    }
+
+    fn names(queue: &SigningQueue) -> Vec<String> {
+        queue
+            .zones
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.zone.name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn interactive_zones_wait_ahead_of_background_zones() {
+        use SigningPriority::{Background, Interactive};
+
+        let queue = SigningQueue::new(NonZeroUsize::new(1).unwrap(), None);
+        {
+            let mut zones = queue.zones.lock().unwrap();
+            for (name, priority) in [
+                ("a.example", Background),
+                ("b.example", Background),
+                ("c.example", Interactive),
+                ("d.example", Interactive),
+                ("e.example", Background),
+            ] {
+                queue.insert(&mut zones, entry(name, priority, Duration::ZERO));
+            }
+        }
+
+        // The zone being signed is never displaced; waiting zones are ordered
+        // by priority, then in the order they were enqueued.
+        assert_eq!(
+            names(&queue),
+            [
+                "a.example",
+                "c.example",
+                "d.example",
+                "b.example",
+                "e.example"
+            ]
+        );
+    }
+
+    #[test]
+    fn stats_report_the_longest_waiting_pending_zone() {
+        use SigningPriority::{Background, Interactive};
+
+        let queue = SigningQueue::new(NonZeroUsize::new(1).unwrap(), None);
+        {
+            let mut zones = queue.zones.lock().unwrap();
+            for (name, priority, waited) in [
+                ("a.example", Background, 100),
+                ("b.example", Background, 20),
+                ("c.example", Interactive, 5),
+            ] {
+                let waited = Duration::from_secs(waited);
+                queue.insert(&mut zones, entry(name, priority, waited));
+            }
+        }
+        assert_eq!(names(&queue), ["a.example", "c.example", "b.example"]);
+
+        let stats = queue.stats();
+        assert_eq!(stats.active, 1);
+        assert_eq!(stats.pending, 2);
+        assert_eq!(stats.memory_usage, 1000);
+
+        // The background zone waited longest, although it is queued last; the
+        // zone being signed is not waiting any more.
+        let oldest_wait = stats.oldest_wait.unwrap();
+        assert!(oldest_wait >= Duration::from_secs(20));
+        assert!(oldest_wait < Duration::from_secs(100));
+
+        assert_eq!(stats.average_wait, None);
+        assert_eq!(stats.max_wait, None);
+    }
+
+    #[test]
+    fn stats_of_an_empty_queue() {
+        let queue = SigningQueue::new(NonZeroUsize::new(4).unwrap(), Some(1 << 20));
+        let stats = queue.stats();
+        assert_eq!(stats.active, 0);
+        assert_eq!(stats.pending, 0);
+        assert_eq!(stats.memory_usage, 0);
+        assert_eq!(stats.oldest_wait, None);
+    }
+
+    #[test]
+    fn concurrency_limit_without_memory_budget() {
+        let queue = queue_of_sizes(2, None, &[1 << 30, 1 << 30, 1 << 30]);
+        assert_eq!(active(&queue), 2);
+    }
+
+    #[test]
+    fn memory_budget_limits_concurrent_zones() {
+        // The first two zones fit within the budget together, the third does
+        // not; later zones that would fit are not let ahead of it.
+        let queue = queue_of_sizes(4, Some(1000), &[400, 500, 200, 10]);
+        assert_eq!(active(&queue), 2);
+        assert_eq!(queue.stats().memory_usage, 900);
+
+        // The concurrency limit still applies.
+        let queue = queue_of_sizes(2, Some(1000), &[10, 10, 10]);
+        assert_eq!(active(&queue), 2);
+    }
+
+    #[test]
+    fn memory_budget_always_admits_one_zone() {
+        // A zone exceeding the budget on its own is signed, but alone.
+        let queue = queue_of_sizes(4, Some(1000), &[5000, 10]);
+        assert_eq!(active(&queue), 1);
+        assert_eq!(queue.stats().pending, 1);
+
+        let queue = queue_of_sizes(4, Some(1000), &[5000]);
+        assert_eq!(active(&queue), 1);
+    }
+
+    #[test]
+    fn prioritized_zone_within_memory_budget_gets_a_permit() {
+        fn pending(queue: &SigningQueue, pos: usize) -> SigningPending {
+            SigningPending {
+                zone: queue.zones.lock().unwrap()[pos].zone.clone(),
+                _assertion: (),
+            }
+        }
+
+        // The third zone does not fit within the budget next to the first
+        // two, but the fourth one does.  Moved ahead of the third one, it
+        // must be handed a permit, as nothing else would activate it.
+        let queue = queue_of_sizes(4, Some(1000), &[400, 500, 800, 50]);
+        assert_eq!(active(&queue), 2);
+        let Ok(permit) = queue.prioritize(pending(&queue, 3), SigningPriority::Interactive) else {
+            panic!("a zone fitting within the memory budget got no permit");
+        };
+        assert_eq!(permit.zone.name.to_string(), "zone3.example");
+        permit.consume();
+        assert_eq!(active(&queue), 3);
+        assert_eq!(queue.stats().memory_usage, 950);
+        assert!(queue.stats().max_wait.is_some());
+
+        // A zone that does not fit keeps waiting.
+        let queue = queue_of_sizes(4, Some(1000), &[400, 500, 200, 800]);
+        let Err(pending) = queue.prioritize(pending(&queue, 3), SigningPriority::Interactive)
+        else {
+            panic!("a zone exceeding the memory budget got a permit");
+        };
+        pending.consume();
+        assert_eq!(active(&queue), 2);
+        assert_eq!(
+            names(&queue),
+            [
+                "zone0.example",
+                "zone1.example",
+                "zone3.example",
+                "zone2.example"
+            ]
+        );
+    }
+}
@@ -1,7 +1,13 @@
 //! Tracking the status of zone signing.
 
+use std::fs::OpenOptions;
+use std::io::{self, Write};
 use std::time::{Duration, SystemTime};
 
+use bytes::Bytes;
+use camino::Utf8Path;
+use domain::base::Name;
+use domain::new::base::RType;
 use serde::Serialize;
 use tokio::time::Instant;
 
@@ -9,9 +15,11 @@ use crate::api::{
     SigningFinishedReport, SigningInProgressReport, SigningReport, SigningRequestedReport,
     SigningStageReport,
 };
+use crate::units::zone_signer::faketime_or_now;
 use crate::util::{
     serialize_duration_as_secs, serialize_instant_as_duration_secs, serialize_opt_duration_as_secs,
 };
+use crate::zonedata::SignedZoneReader;
 
 #[derive(Debug)]
 pub struct SigningStatusPerZone {
@@ -237,3 +245,77 @@ impl FinishedStatus {
         }
     }
 }
+
+//----------- SigningStatistics ------------------------------------------------
+
+/// A compact record of a successful signing operation.
+///
+/// If `signer.statistics-dir` is configured, one is appended, as a line of
+/// JSON, to the statistics file of the zone after every signing operation, so
+/// that e.g. the growth of signing times can be analyzed over time.
+#[derive(Clone, Debug, Serialize)]
+pub struct SigningStatistics {
+    /// When signing finished, in seconds since the Unix epoch.
+    pub finished_at: u64,
+
+    /// The SOA serial of the signed instance.
+    pub serial: u32,
+
+    /// What triggered signing, i.e. `load` or `resign`.
+    pub trigger: &'static str,
+
+    /// Whether the zone was signed incrementally.
+    pub incremental: bool,
+
+    /// How long signing took, in seconds.
+    pub duration: f64,
+
+    /// The number of records in the loaded instance.
+    pub rr_count: usize,
+
+    /// The number of RRSIG records in the signed instance.
+    pub rrsig_count: usize,
+}
+
+impl SigningStatistics {
+    /// Collect the statistics of a signed instance.
+    pub fn new(
+        signed: &SignedZoneReader<'_>,
+        trigger: &'static str,
+        incremental: bool,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            finished_at: Duration::from(faketime_or_now()).as_secs(),
+            serial: signed.soa().rdata.serial.into(),
+            trigger,
+            incremental,
+            duration: duration.as_secs_f64(),
+            rr_count: signed.loaded().regular_records().len(),
+            rrsig_count: signed
+                .generated_records()
+                .iter()
+                .filter(|r| r.rtype == RType::RRSIG)
+                .count(),
+        }
+    }
+
+    /// Append this record to the statistics file of a zone.
+    ///
+    /// The file is named after the zone, e.g. `example.org.jsonl`; the root
+    /// zone uses `_root.jsonl`.
+    pub fn append(&self, dir: &Utf8Path, zone: &Name<Bytes>) -> io::Result<()> {
+        let path = if zone.is_root() {
+            dir.join("_root.jsonl")
+        } else {
+            dir.join(format!("{zone}.jsonl"))
+        };
+        let mut line = serde_json::to_vec(self)?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(&line)
+    }
+}
@@ -5,20 +5,27 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use tracing::{debug, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
 use crate::{
     center::Center,
     signer::{
-        ResigningTrigger, SigningTrigger,
-        queue::{SigningPending, SigningPermit, SigningQueueLock},
+        ResigningTrigger, SignatureOverrides, SigningTrigger,
+        queue::{SigningPending, SigningPermit, SigningPriority, SigningQueueLock},
         status::{SigningStatusPerZone, ZoneSigningStatus},
     },
-    util::BackgroundTasks,
-    zone::{Zone, ZoneByPtr, ZoneHandle, ZoneState},
+    units::zone_signer::SignerError,
+    util::{AbortOnDrop, BackgroundTasks},
+    zone::{HistoricalEvent, Zone, ZoneByPtr, ZoneHandle, ZoneState},
     zonedata::SignedZoneBuilder,
 };
 
+/// The delay before the first automatic retry of a transient signing failure.
+const SIGNING_RETRY_MIN_DELAY: Duration = Duration::from_secs(10);
+
+/// The maximum delay between automatic retries of transient signing failures.
+const SIGNING_RETRY_MAX_DELAY: Duration = Duration::from_secs(600);
+
 //----------- SignerZoneHandle -------------------------------------------------
 
 /// A handle for signer-related operations on a [`Zone`].
@@ -82,6 +89,81 @@ impl SignerZoneHandle<'_> {
         self.enqueue_resign(ResigningTrigger::SIGS_NEED_REFRESH);
     }
 
+    /// React to a failed signing operation.
+    ///
+    /// Transient failures are retried automatically, with an exponential
+    /// backoff, by resetting the zone after a while.  Permanent failures, and
+    /// transient failures once the configured maximum number of consecutive
+    /// failures is reached, leave the zone halted until the operator resets
+    /// it.
+    pub fn on_signing_failure(&mut self, transient: bool) {
+        let streak = self.state.signer.failure_streak;
+        self.state.signer.failure_streak += 1;
+        self.state.signer.enqueued_retry = None;
+
+        if !transient {
+            return;
+        }
+
+        if let Some(max) = self.center.config.signer.max_failures
+            && self.state.signer.failure_streak >= max
+        {
+            error!(
+                "Signing failed {} times in a row; not retrying any more",
+                self.state.signer.failure_streak
+            );
+            self.state.signer.retries_exhausted = true;
+            return;
+        }
+
+        let delay = SIGNING_RETRY_MIN_DELAY
+            .saturating_mul(1 << streak.min(16))
+            .min(SIGNING_RETRY_MAX_DELAY);
+
+        info!(
+            "Retrying signing in {}s (attempt {})",
+            delay.as_secs(),
+            self.state.signer.failure_streak
+        );
+
+        let zone = self.zone.clone();
+        let center = self.center.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            let mut handle = zone.write_handle(&center);
+            let Some(_) = handle
+                .state
+                .signer
+                .enqueued_retry
+                .take_if(|r| r.id() == tokio::task::id())
+            else {
+                // 'enqueued_retry' does not match what we set, so somebody
+                // else replaced or cleared it first.  Don't do anything.
+                trace!("Ignoring enqueued signing retry due to race");
+                return;
+            };
+
+            // If the operator reset the zone in the meantime, there is
+            // nothing left to retry.
+            if handle.get().try_reset().is_err() {
+                debug!("Ignoring signing retry; the zone is no longer halted");
+            } else if handle.state.instances.current.is_none() {
+                // Resetting re-signs the published instance of the zone.  If
+                // there is none, the zone has to be loaded again instead.
+                handle.loader().enqueue_refresh(true);
+            }
+        });
+        self.state.signer.enqueued_retry = Some(task.into());
+    }
+
+    /// React to a successful signing operation.
+    pub fn on_signing_success(&mut self) {
+        self.state.signer.failure_streak = 0;
+        self.state.signer.retries_exhausted = false;
+        self.state.signer.enqueued_retry = None;
+    }
+
     /// (Re-)schedule a zone for re-signing.
     ///
     /// This will recompute when the zone should be scheduled (if at all) and
@@ -151,12 +233,11 @@ impl SignerZoneHandle<'_> {
         assert!(self.state.signer.enqueued_new_sign.is_none());
 
         // Try to get a spot in the signing queue.
-        match self
-            .center
-            .signer
-            .queue
-            .enqueue(self.zone.clone(), &builder)
-        {
+        match self.center.signer.queue.enqueue(
+            self.zone.clone(),
+            &builder,
+            SigningPriority::Background,
+        ) {
             Ok(permit) => {
                 self.start_op(builder, SigningTrigger::Load, permit);
             }
@@ -178,12 +259,27 @@ impl SignerZoneHandle<'_> {
     /// have to be passed here. It does not need to be available when this
     /// method is called; it will be obtained automatically (possibly after some
     /// time, if the underlying zone storage is currently busy).
+    pub fn enqueue_resign(&mut self, trigger: ResigningTrigger) {
+        self.enqueue_resign_with_priority(trigger, SigningPriority::Background);
+    }
+
+    /// Enqueue a re-signing operation for the zone, with a certain priority.
+    ///
+    /// This is like [`Self::enqueue_resign()`], but re-signing operations
+    /// requested by an operator can use [`SigningPriority::Interactive`] to
+    /// skip ahead of background re-signing operations in the signing queue.
+    /// If a re-signing operation is already enqueued, its priority is raised
+    /// if necessary.
     #[tracing::instrument(
         level = "trace",
         skip_all,
-        fields(zone = %self.zone.name, ?trigger)
+        fields(zone = %self.zone.name, ?trigger, ?priority)
     )]
-    pub fn enqueue_resign(&mut self, trigger: ResigningTrigger) {
+    pub fn enqueue_resign_with_priority(
+        &mut self,
+        trigger: ResigningTrigger,
+        priority: SigningPriority,
+    ) {
         // TODO: The key manager can call 'enqueue_resign()' even when the zone
         // has not been signed. So we need to ignore some (but not all) calls
         // to 'enqueue_resign()'. Ideally, the key manager would check the
@@ -208,11 +304,45 @@ impl SignerZoneHandle<'_> {
             return;
         }
 
+        // A zone without a policy cannot be signed; skip it rather than
+        // letting the signing operation fail.
+        if self.state.policy.is_none() {
+            warn!("Ignoring re-signing request; the zone has no policy");
+            self.state.record_event(
+                HistoricalEvent::SigningFailed {
+                    trigger: SigningTrigger::Resign(trigger).into(),
+                    reason: SignerError::NoPolicy.to_string(),
+                    transient: false,
+                },
+                None,
+            );
+            return;
+        }
+
         info!("Enqueuing a re-sign operation");
 
         // If a re-signing operation has already been enqueued, add to it.
         if let Some(resign) = &mut self.state.signer.enqueued_resign {
             resign.trigger |= trigger;
+            if priority > resign.priority {
+                resign.priority = priority;
+                if let Some(pending) = resign.pending.take() {
+                    match self.center.signer.queue.prioritize(pending, priority) {
+                        Ok(permit) => {
+                            // The zone moved within signing capacity; start
+                            // signing immediately.
+                            let EnqueuedResign {
+                                builder, trigger, ..
+                            } = self.state.signer.enqueued_resign.take().unwrap();
+                            let Some(builder) = builder else {
+                                unreachable!("`pending` must only exist when `builder` exists");
+                            };
+                            self.start_op(builder, SigningTrigger::Resign(trigger), permit);
+                        }
+                        Err(pending) => resign.pending = Some(pending),
+                    }
+                }
+            }
             return;
         }
 
@@ -232,7 +362,7 @@ impl SignerZoneHandle<'_> {
                 .center
                 .signer
                 .queue
-                .enqueue(self.zone.clone(), &builder)
+                .enqueue(self.zone.clone(), &builder, priority)
             {
                 Ok(permit) => {
                     // Start signing immediately.
@@ -245,6 +375,7 @@ impl SignerZoneHandle<'_> {
                         builder: Some(builder),
                         pending: Some(pending),
                         trigger,
+                        priority,
                     });
                 }
             }
@@ -254,6 +385,7 @@ impl SignerZoneHandle<'_> {
                 builder: None,
                 pending: None,
                 trigger,
+                priority,
             });
         }
     }
@@ -283,6 +415,7 @@ impl SignerZoneHandle<'_> {
             builder,
             pending,
             trigger,
+            priority,
         }) = self.state.signer.enqueued_resign.take()
         else {
             // A re-sign is not enqueued, nothing to do.
@@ -321,7 +454,7 @@ impl SignerZoneHandle<'_> {
             .center
             .signer
             .queue
-            .enqueue(self.zone.clone(), &builder)
+            .enqueue(self.zone.clone(), &builder, priority)
         {
             Ok(permit) => {
                 // Start signing immediately.
@@ -333,6 +466,7 @@ impl SignerZoneHandle<'_> {
                     builder: Some(builder),
                     pending: Some(pending),
                     trigger,
+                    priority,
                 });
             }
         }
@@ -361,6 +495,7 @@ impl SignerZoneHandle<'_> {
                 builder,
                 pending,
                 trigger,
+                ..
             } = op;
 
             let Some(pending) = pending else {
@@ -442,6 +577,21 @@ pub struct SignerState {
     //
     // TODO: Embed in a state machine.
     pub active_signing_status: Option<Arc<RwLock<SigningStatusPerZone>>>,
+
+    /// The number of consecutive signing failures.
+    pub failure_streak: u32,
+
+    /// Whether automatic retries were stopped after too many failures.
+    pub retries_exhausted: bool,
+
+    /// An enqueued automatic retry of a failed signing operation, if any.
+    pub enqueued_retry: Option<AbortOnDrop>,
+
+    /// Signature validity overrides for an operator-requested re-sign.
+    ///
+    /// These are consumed by the next re-signing operation triggered by
+    /// [`ResigningTrigger::MANUAL`].
+    pub resign_overrides: Option<SignatureOverrides>,
 }
 
 //----------- EnqueuedSign -----------------------------------------------------
@@ -482,6 +632,9 @@ pub struct EnqueuedResign {
 
     /// The trigger causing this operation.
     pub trigger: ResigningTrigger,
+
+    /// The priority of this operation in the signing queue.
+    pub priority: SigningPriority,
     //
     // TODO:
     // - The ID of the signed instance to re-sign.
@@ -1,9 +1,6 @@
 //! Serializing global state.
 
-use std::{
-    fs,
-    io::{self, BufReader},
-};
+use std::io;
 
 use bytes::Bytes;
 use camino::Utf8Path;
@@ -13,6 +10,7 @@ use tracing::{debug, error};
 
 use crate::{
     center::{Center, State},
+    common::store::StateStore,
     policy::Policy,
 };
 
@@ -36,7 +34,7 @@ pub fn save_now(center: &Center) {
 
     // Save the global state.
     let path = center.config.daemon.state_file.value();
-    match spec.save(path) {
+    match spec.save(&*center.state_store, path) {
         Ok(()) => debug!("Saved the global state (to '{path}')"),
         Err(err) => {
             error!("Could not save the global state to '{path}': {err}");
@@ -59,9 +57,10 @@ pub enum Spec {
 impl Spec {
     /// Parse from this specification.
     ///
-    /// `zones` will be set to the names of zones that need to be loaded.
-    /// `policies` will be set to the set of policies from the global state
-    /// file, that need to be parsed and inserted in the state.
+    /// `zones` will be set to the names of zones that need to be loaded; this
+    /// includes zones still in their removal grace period.  `policies` will
+    /// be set to the set of policies from the global state file, that need to
+    /// be parsed and inserted in the state.
     pub fn parse(
         self,
         zones: &mut foldhash::HashSet<Name<Bytes>>,
@@ -71,6 +70,7 @@ impl Spec {
             Self::V1(mut spec) => {
                 // Extract and write out 'zones' and 'policies'.
                 *zones = std::mem::take(&mut spec.zones);
+                zones.extend(spec.removing_zones.iter().cloned());
                 *policies = std::mem::take(&mut spec.policies)
                     .into_iter()
                     .map(|(k, v)| (k, PolicySpec::V1(v)))
@@ -91,20 +91,20 @@ impl Spec {
 
 impl Spec {
     /// Load and parse this specification from a file.
-    pub fn load(path: &Utf8Path) -> io::Result<Self> {
-        let file = BufReader::new(fs::File::open(path)?);
-        serde_json::from_reader(file).map_err(|err| err.into())
+    pub fn load(store: &dyn StateStore, path: &Utf8Path) -> io::Result<Self> {
+        let text = store.read(path)?;
+        serde_json::from_slice(&text).map_err(|err| err.into())
     }
 
     /// Build and save this specification to a file.
-    pub fn save(&self, path: &Utf8Path) -> io::Result<()> {
+    pub fn save(&self, store: &dyn StateStore, path: &Utf8Path) -> io::Result<()> {
         // TODO: METRICS: set metric "state_last_saved = timestamp"?
         if path.parent().is_none() {
             return Err(io::ErrorKind::IsADirectory.into());
         }
 
         let text = serde_json::to_string(self)?;
-        crate::util::write_file(path, text.as_bytes())
+        store.write(path, text.as_bytes())
     }
 }
 
@@ -126,3 +126,81 @@ impl PolicySpec {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{io, sync::Arc};
+
+    use bytes::Bytes;
+    use camino::Utf8Path;
+    use domain::base::Name;
+
+    use super::Spec;
+    use crate::center::State;
+    use crate::policy::{Policy, file::v1};
+    use crate::tests::util::store::MemoryStateStore;
+
+    #[test]
+    fn save_and_load_in_memory() {
+        let store = MemoryStateStore::default();
+        let path = Utf8Path::new("/state.db");
+
+        let err = Spec::load(&store, path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        Spec::build(&State::default()).save(&store, path).unwrap();
+
+        let (mut zones, mut policies) = Default::default();
+        let state = Spec::load(&store, path)
+            .unwrap()
+            .parse(&mut zones, &mut policies);
+        assert!(state.zones.is_empty());
+        assert!(zones.is_empty());
+        assert!(policies.is_empty());
+    }
+
+    #[test]
+    fn removing_zones_are_restored() {
+        let store = MemoryStateStore::default();
+        let path = Utf8Path::new("/state.db");
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+
+        let mut state = State::default();
+        state.removing_zones.insert(name.clone());
+        Spec::build(&state).save(&store, path).unwrap();
+
+        let (mut zones, mut policies) = Default::default();
+        let state = Spec::load(&store, path)
+            .unwrap()
+            .parse(&mut zones, &mut policies);
+        assert!(state.removing_zones.contains(&name));
+        assert!(zones.contains(&name));
+    }
+
+    #[test]
+    fn policies_keep_their_soa_query_transport() {
+        let store = MemoryStateStore::default();
+        let path = Utf8Path::new("/state.db");
+
+        let mut latest = v1::Spec::default().parse("tcp");
+        latest.loader.soa_query_over_tcp = true;
+        let mut state = State::default();
+        state.policies.insert(
+            "tcp".into(),
+            Policy {
+                latest: Arc::new(latest),
+                mid_deletion: false,
+                zones: Default::default(),
+                files: Vec::new(),
+            },
+        );
+        Spec::build(&state).save(&store, path).unwrap();
+
+        let (mut zones, mut policies) = Default::default();
+        Spec::load(&store, path)
+            .unwrap()
+            .parse(&mut zones, &mut policies);
+        let policy = policies.remove("tcp").unwrap().parse("tcp");
+        assert!(policy.latest.loader.soa_query_over_tcp);
+    }
+}
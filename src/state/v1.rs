@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use crate::policy;
 use crate::policy::file::v1::NameserverCommsSpec;
 use crate::policy::file::v1::OutboundSpec;
+use crate::policy::file::v1::{StagingSpec, TransferLimitsSpec};
 use crate::policy::{AutoConfig, DsAlgorithm, KeyParameters};
 use crate::{
     center::State,
@@ -32,6 +33,13 @@ pub struct Spec {
     /// stored in a dedicated state file.
     pub zones: foldhash::HashSet<Name<Bytes>>,
 
+    /// Removed zones that are still within their removal grace period.
+    ///
+    /// Like [`Self::zones`], the state of each zone is stored in its own
+    /// state file.
+    #[serde(default, skip_serializing_if = "foldhash::HashSet::is_empty")]
+    pub removing_zones: foldhash::HashSet<Name<Bytes>>,
+
     /// Policies.
     pub policies: foldhash::HashMap<Box<str>, PolicySpec>,
 }
@@ -47,18 +55,23 @@ impl Spec {
         let Self {
             // The caller will extract 'zones' and 'policies' beforehand.
             zones: _,
+            removing_zones,
             policies: _,
             // TODO: More fields.
         };
 
         // TODO: Initialize fields from 'Spec'.
-        State::default()
+        State {
+            removing_zones,
+            ..State::default()
+        }
     }
 
     /// Build this state specification.
     pub fn build(state: &State) -> Self {
         Self {
             zones: state.zones.iter().map(|zone| zone.0.name.clone()).collect(),
+            removing_zones: state.removing_zones.clone(),
             policies: state
                 .policies
                 .iter()
@@ -90,6 +103,7 @@ impl PolicySpec {
             latest: Arc::new(self.latest.parse(name)),
             mid_deletion: self.mid_deletion,
             zones: Default::default(),
+            files: Vec::new(),
         }
     }
 
@@ -165,6 +179,14 @@ impl PolicyVersionSpec {
 pub struct LoaderPolicySpec {
     /// Reviewing loaded zones.
     pub review: ReviewPolicySpec,
+
+    /// Limits on inbound zone transfers.
+    #[serde(default)]
+    pub transfer: TransferLimitsSpec,
+
+    /// Whether SOA queries to primaries are sent over TCP.
+    #[serde(default)]
+    pub soa_query_over_tcp: bool,
 }
 
 //--- Conversion
@@ -174,6 +196,8 @@ impl LoaderPolicySpec {
     pub fn parse(self) -> LoaderPolicy {
         LoaderPolicy {
             review: self.review.parse(),
+            transfer: self.transfer.parse(),
+            soa_query_over_tcp: self.soa_query_over_tcp,
         }
     }
 
@@ -181,6 +205,8 @@ impl LoaderPolicySpec {
     pub fn build(policy: &LoaderPolicy) -> Self {
         Self {
             review: ReviewPolicySpec::build(&policy.review),
+            transfer: TransferLimitsSpec::build(&policy.transfer),
+            soa_query_over_tcp: policy.soa_query_over_tcp,
         }
     }
 }
@@ -247,6 +273,10 @@ pub struct KeyManagerPolicySpec {
     /// Remove old keys after this amount of time.
     auto_remove_delay: u64,
 
+    /// The maximum per-zone reduction of key validities, in seconds.
+    #[serde(default)]
+    validity_jitter: u32,
+
     /// Nameservers to check for RRSIG propagation during a key roll.
     pub publication_nameservers: Vec<NameserverCommsSpec>,
 }
@@ -277,6 +307,7 @@ impl KeyManagerPolicySpec {
             default_ttl: self.default_ttl,
             auto_remove: self.auto_remove,
             auto_remove_delay: Duration::from_secs(self.auto_remove_delay),
+            validity_jitter: self.validity_jitter,
             publication_nameservers: self
                 .publication_nameservers
                 .into_iter()
@@ -308,6 +339,7 @@ impl KeyManagerPolicySpec {
             default_ttl: policy.default_ttl,
             auto_remove: policy.auto_remove,
             auto_remove_delay: policy.auto_remove_delay.as_secs(),
+            validity_jitter: policy.validity_jitter,
             publication_nameservers: policy
                 .publication_nameservers
                 .iter()
@@ -347,6 +379,14 @@ pub struct SignerPolicySpec {
 
     /// Reviewing signed zones.
     pub review: ReviewPolicySpec,
+
+    /// Whether to keep signatures made by an external signer.
+    #[serde(default)]
+    pub preserve_external_signatures: bool,
+
+    /// Whether to refuse signing zones which already contain DNSSEC records.
+    #[serde(default)]
+    pub refuse_dnssec_input: bool,
 }
 
 //--- Conversion
@@ -363,6 +403,8 @@ impl SignerPolicySpec {
             key_roll_time: self.key_roll_time.as_secs() as u32,
             denial: self.denial.parse(),
             review: self.review.parse(),
+            preserve_external_signatures: self.preserve_external_signatures,
+            refuse_dnssec_input: self.refuse_dnssec_input,
         }
     }
 
@@ -379,6 +421,8 @@ impl SignerPolicySpec {
             key_roll_time: Duration::from_secs(policy.key_roll_time.into()),
             denial: SignerDenialPolicySpec::build(&policy.denial),
             review: ReviewPolicySpec::build(&policy.review),
+            preserve_external_signatures: policy.preserve_external_signatures,
+            refuse_dnssec_input: policy.refuse_dnssec_input,
         }
     }
 }
@@ -530,6 +574,9 @@ impl ReviewPolicySpec {
 pub struct ServerPolicySpec {
     /// Outbound policy.
     pub outbound: OutboundSpec,
+    /// Staging policy.
+    #[serde(default)]
+    pub staging: StagingSpec,
 }
 
 //--- Conversion
@@ -539,6 +586,7 @@ impl ServerPolicySpec {
     pub fn parse(self) -> ServerPolicy {
         ServerPolicy {
             outbound: self.outbound.parse(),
+            staging: self.staging.parse(),
         }
     }
 
@@ -546,6 +594,7 @@ impl ServerPolicySpec {
     pub fn build(policy: &ServerPolicy) -> Self {
         Self {
             outbound: OutboundSpec::build(&policy.outbound),
+            staging: StagingSpec::build(&policy.staging),
         }
     }
 }
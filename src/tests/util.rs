@@ -101,3 +101,38 @@ pub mod net {
         }
     }
 }
+
+#[cfg(test)]
+pub mod store {
+    use std::{collections::HashMap, io, sync::Mutex};
+
+    use camino::{Utf8Path, Utf8PathBuf};
+
+    use crate::common::store::StateStore;
+
+    /// An in-memory StateStore, allowing state files to be saved and
+    /// restored by unit tests without any disk I/O.
+    #[derive(Debug, Default)]
+    pub struct MemoryStateStore {
+        pub files: Mutex<HashMap<Utf8PathBuf, Vec<u8>>>,
+    }
+
+    impl StateStore for MemoryStateStore {
+        fn read(&self, path: &Utf8Path) -> io::Result<Vec<u8>> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::ErrorKind::NotFound.into())
+        }
+
+        fn write(&self, path: &Utf8Path, contents: &[u8]) -> io::Result<()> {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.to_owned(), contents.to_vec());
+            Ok(())
+        }
+    }
+}
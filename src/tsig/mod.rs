@@ -324,11 +324,12 @@ pub fn remove_key(center: &Arc<Center>, name: &tsig::KeyName) -> Result<(), Remo
         .zones
         .iter()
         .filter(|z| {
-            matches!(
-                z.0.state.read().loader.source,
-                crate::loader::Source::Server { tsig_key: Some(ref key), .. }
-                    if name == key.name()
-            )
+            z.0.state
+                .read()
+                .loader
+                .source
+                .tsig_keys()
+                .any(|key| name == key.name())
         })
         .inspect(|&referenced_zone| {
             let _ = unknown_refs.remove(referenced_zone);
@@ -353,6 +354,11 @@ pub fn remove_key(center: &Arc<Center>, name: &tsig::KeyName) -> Result<(), Remo
                         .provide_xfr_to
                         .iter()
                         .any(|acl| acl.tsig_key_name.as_ref() == Some(name))
+                    || p.server
+                        .outbound
+                        .provide_review_xfr_to
+                        .iter()
+                        .any(|acl| acl.tsig_key_name.as_ref() == Some(name))
                     || p.server
                         .outbound
                         .send_notify_to
@@ -3,30 +3,41 @@ use std::collections::HashSet;
 use std::collections::hash_map::Entry;
 use std::future::IntoFuture;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
 use std::time::Duration;
 use std::time::SystemTime;
 
 use axum::Json;
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::extract::Path;
 use axum::extract::Request;
 use axum::extract::State;
 use axum::http::StatusCode;
+use axum::middleware::Next;
 use axum::response::IntoResponse;
+use axum::response::Response;
 use axum::routing::get;
 use axum::routing::post;
 use bytes::Bytes;
+use camino::Utf8Path;
 use domain::base::Name;
+use domain::base::Rtype;
 use domain::base::Serial;
 use domain::dnssec::sign::keys::keyset::KeyType;
+use domain::new::base::name::{RevName, RevNameBuf};
+use domain::new::base::wire::ParseBytes;
+use domain::rdata::ZoneRecordData;
 use domain::utils::base64;
 use domain_kmip::ConnectionSettings;
 use domain_kmip::dep::kmip::client::pool::ConnectionManager;
 use serde::Deserialize;
 use serde::Serialize;
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, UnixListener};
 use tokio::task::JoinSet;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
 use tracing::{debug, error, info, warn};
 
 use crate::api;
@@ -40,10 +51,15 @@ use crate::loader;
 use crate::manager::Terminated;
 use crate::policy::AutoConfig;
 use crate::policy::SignerDenialPolicy;
+use crate::policy::SignerPolicy;
 use crate::policy::SignerSerialPolicy;
 use crate::server::LoadedReviewServer;
-use crate::server::SignedReviewServer;
+use crate::server::{PublicationServer, SignedReviewServer};
+use crate::signer::keys::KMIP_POOL_SIZE;
+use crate::signer::queue::SigningPriority;
+use crate::signer::{ResigningTrigger, SignatureOverrides};
 use crate::tsig::{self, RemoveError};
+use crate::units::key_manager::KeyManager;
 use crate::units::key_manager::KmipClientCredentials;
 use crate::units::key_manager::KmipClientCredentialsFile;
 use crate::units::key_manager::KmipServerCredentialsFileMode;
@@ -51,15 +67,35 @@ use crate::units::key_manager::mk_dnst_keyset_cfg_file_path;
 use crate::units::key_manager::mk_dnst_keyset_state_file_path;
 use crate::units::zone_signer::KeySetState;
 use crate::zone::machine::ZoneStateMachine;
-use crate::zone::{HistoricalEvent, HistoricalEventType, ZoneByName};
+use crate::zone::{HistoricalEvent, HistoricalEventType, Zone, ZoneByName};
+use crate::zonedata::{OldParsedRecord, RegularRecord, SoaRecord};
 
 pub const HTTP_UNIT_NAME: &str = "HS";
 
+/// The maximum time to spend on a single HTTP request.
+///
+/// Requests taking longer are answered with 408 Request Timeout.
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The maximum size of an HTTP request body.
+///
+/// The largest expected request is `kmip_server_add`, which may carry client
+/// certificates and keys; these are far smaller than this.
+const HTTP_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// The maximum number of HTTP requests handled concurrently.
+///
+/// Further requests wait until one of these has completed.
+const HTTP_MAX_CONCURRENT_REQUESTS: usize = 64;
+
 // NOTE: To send data back from a unit, send them an app command with
 // a transmitter they can use to send the reply
 
 pub struct HttpServer {
     pub center: Arc<Center>,
+
+    /// Whether all of Cascade's units have been started.
+    ready: AtomicBool,
 }
 
 impl HttpServer {
@@ -68,26 +104,52 @@ impl HttpServer {
         center: Arc<Center>,
         http_sockets: Vec<TcpListener>,
     ) -> Result<Arc<Self>, Terminated> {
-        let this = Arc::new(Self { center });
+        let this = Arc::new(Self {
+            center,
+            ready: AtomicBool::new(false),
+        });
 
         let app = Router::new()
             .route("/health", get(Self::health))
+            .route("/healthz", get(Self::healthz))
+            .route("/readyz", get(Self::readyz))
             .route("/info", get(Self::info))
             .route("/metrics", get(Self::metrics))
             .route("/status", get(Self::status))
             .route("/status/keys", get(Self::status_keys))
+            .route("/status/resign-schedule", get(Self::status_resign_schedule))
             .route("/debug/change-logging", post(Self::change_logging))
             .route("/tsig/", get(Self::tsig_key_list))
             .route("/tsig/add", post(Self::tsig_key_add))
             .route("/tsig/{name}/remove", post(Self::tsig_key_remove))
             .route("/zone/", get(Self::zones_list))
             .route("/zone/add", post(Self::zone_add))
+            .route("/zone/memory", get(Self::zones_memory))
             // TODO: .route("/zone/{name}/", get(Self::zone_get))
             .route("/zone/{name}/remove", post(Self::zone_remove))
             .route("/zone/{name}/reset", post(Self::zone_reset))
+            .route("/zone/{name}/resume", post(Self::zone_resume))
+            .route("/zone/{name}/set-denial", post(Self::zone_set_denial))
+            .route(
+                "/zone/{name}/set-description",
+                post(Self::zone_set_description),
+            )
+            .route(
+                "/zone/{name}/set-serial-floor",
+                post(Self::zone_set_serial_floor),
+            )
+            .route("/zone/{name}/rotate-tsig", post(Self::zone_rotate_tsig))
+            .route("/zone/{name}/advance", post(Self::zone_advance))
+            .route("/zone/{name}/promote", post(Self::zone_promote))
+            .route("/zone/{name}/resign", post(Self::zone_resign))
+            .route("/zone/{name}/go-live", post(Self::zone_go_live))
+            .route("/zone/{name}/test-notify", post(Self::zone_test_notify))
             .route("/zone/{name}/status", get(Self::zone_status))
             .route("/zone/{name}/history", get(Self::zone_history))
+            .route("/zone/{name}/memory", get(Self::zone_memory))
             .route("/zone/{name}/reload", post(Self::zone_reload))
+            .route("/zone/{name}/rollback", post(Self::zone_rollback))
+            .route("/zone/{name}/query", post(Self::zone_query))
             .route(
                 "/zone/{name}/unsigned/{serial}/approve",
                 post(Self::approve_unsigned),
@@ -109,6 +171,27 @@ impl HttpServer {
                 post(Self::reject_signed),
             )
             .route("/zone/{name}/signed/override", post(Self::override_signed))
+            .route(
+                "/zone/{name}/review/{serial}",
+                get(Self::zone_review_summary),
+            )
+            .route(
+                "/zone/{name}/unsigned/{serial}/zonefile",
+                get(Self::unsigned_review_zonefile),
+            )
+            .route(
+                "/zone/{name}/signed/{serial}/zonefile",
+                get(Self::signed_review_zonefile),
+            )
+            .route("/review/pending", get(Self::pending_reviews))
+            .route(
+                "/batch/{batch}/unsigned/approve",
+                post(Self::approve_unsigned_batch),
+            )
+            .route(
+                "/batch/{batch}/signed/approve",
+                post(Self::approve_signed_batch),
+            )
             .route(
                 "/zone/{zone}/maintenance/enable",
                 post(Self::enable_maintenance_mode),
@@ -120,14 +203,46 @@ impl HttpServer {
             .route("/policy/", get(Self::policy_list))
             .route("/policy/reload", post(Self::policy_reload))
             .route("/policy/{name}", get(Self::policy_show))
+            .route("/policy/{name}/export", get(Self::policy_export))
             .route("/kmip", get(Self::kmip_server_list))
             .route("/kmip", post(Self::kmip_server_add))
             .route("/kmip/{server_id}", get(Self::hsm_server_get))
             .route("/key/{zone}/roll", post(Self::key_roll))
             .route("/key/{zone}/remove", post(Self::key_remove))
+            .route("/key/{zone}/purge-retired", post(Self::key_purge_retired))
+            .route("/key/{zone}/auto/pause", post(Self::key_auto_pause))
+            .route("/key/{zone}/auto/resume", post(Self::key_auto_resume))
+            .route("/key/{zone}/import", post(Self::key_import))
+            .route("/key/{zone}/migrate", post(Self::key_migrate))
+            .route("/key/{zone}/verify", get(Self::key_verify))
             .route("/key/{zone}/get", post(Self::key_get))
+            .route("/key/{zone}/dnskey", get(Self::key_dnskey))
+            .route("/key/{zone}/propagation", get(Self::key_propagation))
+            .route("/key/{zone}/state", get(Self::key_state))
             .with_state(this.clone())
-            .fallback(Self::warn_route_not_found);
+            .fallback(Self::warn_route_not_found)
+            .layer(axum::middleware::from_fn(Self::with_actor))
+            // Limit the resources a slow or misbehaving client can use.
+            .layer(DefaultBodyLimit::max(HTTP_MAX_BODY_SIZE))
+            .layer(TimeoutLayer::with_status_code(
+                StatusCode::REQUEST_TIMEOUT,
+                HTTP_REQUEST_TIMEOUT,
+            ))
+            .layer(GlobalConcurrencyLimitLayer::new(
+                HTTP_MAX_CONCURRENT_REQUESTS,
+            ));
+
+        // Bind the Unix domain socket, if one is configured.
+        //
+        // This happens here, after privileges have been dropped, so that the
+        // socket file is owned by the user Cascade runs as.
+        let unix_listener = match &this.center.config.remote_control.unix_socket {
+            Some(path) => Some(Self::bind_unix_socket(path).map_err(|err| {
+                error!("Could not listen on Unix socket '{path}': {err}");
+                Terminated
+            })?),
+            None => None,
+        };
 
         // Serve at the configured endpoints.
         tokio::spawn(async move {
@@ -135,6 +250,9 @@ impl HttpServer {
             for sock in http_sockets {
                 set.spawn(axum::serve(sock, app.clone()).into_future());
             }
+            if let Some(listener) = unix_listener {
+                set.spawn(axum::serve(listener, app.clone()).into_future());
+            }
 
             // Wait for each future in the order they complete.
             while let Some(res) = set.join_next().await {
@@ -150,6 +268,39 @@ impl HttpServer {
         Ok(this)
     }
 
+    /// Mark Cascade as ready to serve.
+    ///
+    /// Until this is called, `/readyz` responds with 503 Service Unavailable.
+    pub fn mark_ready(&self) {
+        self.ready.store(true, Relaxed);
+    }
+
+    /// Bind a Unix domain socket for the HTTP API.
+    ///
+    /// A stale socket left behind by a previous instance is removed first;
+    /// the state lock guarantees that no other instance is still using it.
+    /// The socket is only accessible to its owner and group.
+    fn bind_unix_socket(path: &Utf8Path) -> std::io::Result<UnixListener> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+            Ok(_) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    "the path exists and is not a socket",
+                ));
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let listener = UnixListener::bind(path)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o660))?;
+        info!("Listening for the remote-control server on Unix socket '{path}'");
+        Ok(listener)
+    }
+
     /// Log a warning if the HTTP request does not match any route handler
     /// registered with Axum.
     ///
@@ -163,11 +314,40 @@ impl HttpServer {
         StatusCode::NOT_FOUND
     }
 
+    /// Attribute the changes made by a request to the actor it names, if any.
+    async fn with_actor(request: Request, next: Next) -> Response {
+        let actor = request
+            .headers()
+            .get(ACTOR_HEADER)
+            .and_then(|actor| actor.to_str().ok())
+            .filter(|actor| !actor.is_empty())
+            .map(String::from);
+
+        match actor {
+            Some(actor) => crate::zone::ACTOR.scope(actor, next.run(request)).await,
+            None => next.run(request).await,
+        }
+    }
+
     /// If this endpoint responds, the daemon is considered healthy.
     async fn health() -> Json<api::Health> {
         Json(Health { healthy: true })
     }
 
+    /// Liveness probe: if this endpoint responds, the process is alive.
+    async fn healthz() -> StatusCode {
+        StatusCode::OK
+    }
+
+    /// Readiness probe: responds with 503 until all units have been started.
+    async fn readyz(State(state): State<Arc<HttpServer>>) -> StatusCode {
+        if state.ready.load(Relaxed) {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+
     /// Get server info
     async fn info() -> Json<api::Info> {
         Json(Info {
@@ -226,6 +406,21 @@ impl HttpServer {
             report
         };
 
+        let signing_queue_stats = {
+            let queue = &center.signer.queue;
+            let stats = queue.stats();
+            SigningQueueStats {
+                concurrency_limit: queue.concurrency_limit().get(),
+                active: stats.active,
+                pending: stats.pending,
+                memory_usage: stats.memory_usage,
+                memory_budget: queue.memory_budget(),
+                oldest_wait: stats.oldest_wait,
+                average_wait: stats.average_wait,
+                max_wait: stats.max_wait,
+            }
+        };
+
         let f =
             |x: &Vec<crate::config::SocketConfig>| x.iter().map(|s| s.addr()).collect::<Vec<_>>();
         let loaded_review_addrs = f(&center.config.loader.review.servers);
@@ -235,6 +430,7 @@ impl HttpServer {
         Json(ServerStatusResult {
             halted_zones,
             signing_queue,
+            signing_queue_stats,
             loaded_review_addrs,
             signed_review_addrs,
             server_addrs,
@@ -282,12 +478,34 @@ impl HttpServer {
         State(state): State<Arc<HttpServer>>,
         Json(zone_register): Json<ZoneAdd>,
     ) -> Json<Result<ZoneAddResult, ZoneAddError>> {
+        if zone_register.dry_run {
+            let res = center::check_zone_add(
+                &state.center,
+                &zone_register.name,
+                &zone_register.policy,
+                &zone_register.source,
+                &zone_register.key_imports,
+            )
+            .await;
+
+            return match res {
+                Ok(actions) => Json(Ok(ZoneAddResult {
+                    name: zone_register.name,
+                    status: "Validated".to_string(),
+                    actions,
+                })),
+                Err(err) => Json(Err(err.into())),
+            };
+        }
+
         let res = center::add_zone(
             &state.center,
             zone_register.name.clone(),
             zone_register.policy.into(),
             zone_register.source,
             zone_register.key_imports,
+            zone_register.observe_only,
+            zone_register.serial_floor,
         )
         .await;
 
@@ -295,6 +513,7 @@ impl HttpServer {
             Ok(_) => Json(Ok(ZoneAddResult {
                 name: zone_register.name,
                 status: "Submitted".to_string(),
+                actions: Vec::new(),
             })),
             Err(err) => Json(Err(err.into())),
         }
@@ -331,6 +550,446 @@ impl HttpServer {
         Json(do_zone_reset())
     }
 
+    /// Resume a halted zone, after checking that the failing step succeeds.
+    ///
+    /// If signing failed, the system clock is checked and the signing keys are
+    /// loaded again.  If loading failed too many times in a row, the source of
+    /// the zone is checked to be reachable and the zone is reloaded.  With
+    /// `force`, these checks are skipped.  A zone halted by a rejected review
+    /// is never resumed.
+    async fn zone_resume(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(ZoneResume { force }): Json<ZoneResume>,
+    ) -> Json<ZoneResumeResult> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &name) else {
+            return Json(Err(ZoneResumeError::NoSuchZone));
+        };
+
+        let (halted, rejected, signing_failed, load_halted, source) = {
+            let zone_state = zone.read();
+            (
+                zone_state.machine.is_halted(),
+                zone_state.machine.is_rejected(),
+                matches!(zone_state.machine, ZoneStateMachine::SigningFailed(_)),
+                matches!(zone_state.machine, ZoneStateMachine::LoadingFailed(_)),
+                zone_state.loader.source.clone(),
+            )
+        };
+        if !halted {
+            return Json(Err(ZoneResumeError::NotHalted));
+        }
+
+        // Nothing has changed about a rejected zone; it has to be reviewed
+        // again, or thrown away.
+        if rejected {
+            return Json(Err(ZoneResumeError::RejectedReview));
+        }
+
+        if !force && signing_failed {
+            let res = tokio::task::spawn_blocking({
+                let center = center.clone();
+                let zone = zone.clone();
+                move || {
+                    crate::signer::check_clock(&zone)?;
+                    crate::signer::keys::check_signing_keys(&center, &zone)
+                }
+            })
+            .await
+            .expect("checking the signing keys should not panic");
+            if let Err(err) = res {
+                return Json(Err(ZoneResumeError::StillFailing(err.to_string())));
+            }
+        }
+
+        if !force && load_halted {
+            let res = match &source {
+                loader::Source::None => Ok(()),
+                loader::Source::Zonefile { path } => center::probe_zone_file(path)
+                    .await
+                    .map_err(|err| format!("Cannot read zone file '{path}': {err}")),
+                loader::Source::Server { addr, .. } => center::probe_zone_source(*addr).await,
+            };
+            if let Err(err) = res {
+                return Json(Err(ZoneResumeError::StillFailing(err)));
+            }
+        }
+
+        let clock_skewed = force && crate::signer::check_clock(&zone).is_err();
+
+        let mut handle = zone.write_handle(center);
+        handle.state.signer.failure_streak = 0;
+        handle.state.signer.retries_exhausted = false;
+        handle.state.signer.enqueued_retry = None;
+
+        // If the clock went back after the zone was signed with a wrong clock,
+        // the operator vouches for the current time.  Forget about the last
+        // signing time, so that all signatures are refreshed.
+        if clock_skewed {
+            handle.state.last_signature_refresh = Duration::ZERO.into();
+        }
+
+        // Resetting after a load failure loads the zone again.
+        if handle.get().try_reset().is_err() {
+            // The zone was resumed concurrently.
+            return Json(Err(ZoneResumeError::NotHalted));
+        }
+
+        // Resetting after a signing failure re-signs the published instance
+        // of the zone.  If there is none, load the zone again.
+        if signing_failed && handle.state.instances.current.is_none() {
+            handle.loader().enqueue_refresh(true);
+        }
+
+        Json(Ok(ZoneResumeOutput {
+            zone: zone.name.clone(),
+        }))
+    }
+
+    /// Set (or remove) the description of a zone.
+    async fn zone_set_description(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(ZoneSetDescription { description }): Json<ZoneSetDescription>,
+    ) -> Json<ZoneSetDescriptionResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneSetDescriptionError::NoSuchZone));
+        };
+
+        zone.write(center).description = description.filter(|d| !d.is_empty());
+
+        Json(Ok(ZoneSetDescriptionOutput { zone: name }))
+    }
+
+    /// Set (or remove) the serial floor of a zone.
+    ///
+    /// With the keep serial policy, the signed zone has to use the serial of
+    /// the loaded zone, so the floor cannot be set above it.
+    async fn zone_set_serial_floor(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(ZoneSetSerialFloor { serial_floor }): Json<ZoneSetSerialFloor>,
+    ) -> Json<ZoneSetSerialFloorResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneSetSerialFloorError::NoSuchZone));
+        };
+
+        let mut zone_state = zone.write(center);
+        let keeps_serial = zone_state
+            .policy
+            .as_ref()
+            .is_some_and(|policy| policy.signer.serial_policy == SignerSerialPolicy::Keep);
+        let loaded_serial = zone_state
+            .instances
+            .upcoming
+            .as_ref()
+            .and_then(|i| i.loaded.as_ref())
+            .map(|i| i.serial())
+            .or_else(|| {
+                zone_state
+                    .instances
+                    .current
+                    .as_ref()
+                    .map(|i| i.loaded.serial())
+            })
+            .map(|serial| Serial(serial.into()));
+        if keeps_serial
+            && let Some(serial_floor) = serial_floor
+            && let Some(loaded_serial) = loaded_serial
+            && serial_floor > loaded_serial
+        {
+            return Json(Err(ZoneSetSerialFloorError::AboveKeptSerial {
+                loaded_serial,
+            }));
+        }
+        zone_state.serial_floor = serial_floor;
+
+        Json(Ok(ZoneSetSerialFloorOutput { zone: name }))
+    }
+
+    /// Rotate the TSIG key used to load a zone from its primary.
+    async fn zone_rotate_tsig(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(rotation): Json<ZoneRotateTsig>,
+    ) -> Json<ZoneRotateTsigResult> {
+        Json(center::rotate_zone_tsig_key(&state.center, &name, rotation))
+    }
+
+    /// Promote the staged instance of a zone to production.
+    async fn zone_promote(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<ZonePromoteResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZonePromoteError::NoSuchZone));
+        };
+
+        Json(SignedReviewServer::promote(center, &zone))
+    }
+
+    /// Re-sign a zone immediately, regardless of signature expiry.
+    ///
+    /// The signature validity of the policy can be overridden for this one
+    /// re-signing operation.
+    async fn zone_resign(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        resign: Option<Json<ZoneResign>>,
+    ) -> Json<ZoneResignResult> {
+        let resign = resign.map(|Json(resign)| resign).unwrap_or_default();
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneResignError::NoSuchZone));
+        };
+
+        let mut handle = zone.write_handle(center);
+        if let Some(reason) = handle.state.halted_reason() {
+            return Json(Err(ZoneResignError::Halted(reason)));
+        }
+        let Some(policy) = handle.state.policy.as_ref() else {
+            return Json(Err(ZoneResignError::NoPolicy));
+        };
+        if handle.state.signed_metadata().is_none() {
+            return Json(Err(ZoneResignError::NotSigned));
+        }
+
+        let overrides = match Self::resign_overrides(&policy.signer, &resign) {
+            Ok(overrides) => overrides,
+            Err(reason) => return Json(Err(ZoneResignError::BadOverride(reason))),
+        };
+        handle.state.signer.resign_overrides = Some(overrides);
+
+        handle
+            .signer()
+            .enqueue_resign_with_priority(ResigningTrigger::MANUAL, SigningPriority::Interactive);
+
+        Json(Ok(ZoneResignOutput {
+            zone: zone.name.clone(),
+        }))
+    }
+
+    /// Check the signature validity overrides for a `zone resign` command.
+    ///
+    /// These are held to the same bounds as the corresponding policy settings,
+    /// so that the new signatures last until the next automatic re-sign.
+    fn resign_overrides(
+        policy: &SignerPolicy,
+        resign: &ZoneResign,
+    ) -> Result<SignatureOverrides, String> {
+        let overrides = SignatureOverrides {
+            sig_validity_time: resign
+                .sig_validity
+                .map(|d| d.as_secs().min(u32::MAX.into()) as u32),
+            sig_inception_offset: resign
+                .inception_offset
+                .map(|d| d.as_secs().min(u32::MAX.into()) as u32),
+        };
+        let signer = overrides.apply(policy);
+
+        signer.check_timing()?;
+        if signer.sig_inception_offset >= 0x4000_0000 {
+            return Err(format!(
+                "inception offset {} too big (>= 0x4000_0000)",
+                signer.sig_inception_offset
+            ));
+        }
+
+        Ok(overrides)
+    }
+
+    /// Take an observe-only zone live.
+    async fn zone_go_live(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<ZoneGoLiveResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneGoLiveError::NoSuchZone));
+        };
+
+        Json(SignedReviewServer::go_live(center, &zone))
+    }
+
+    /// Send NOTIFY messages to the downstream name servers of a zone.
+    async fn zone_test_notify(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<ZoneTestNotifyResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneTestNotifyError::NoSuchZone));
+        };
+
+        Json(PublicationServer::test_notify(center, &zone).await)
+    }
+
+    /// Override the denial-of-existence type of a single zone.
+    ///
+    /// If this changes the denial type used by the zone, it is re-signed.
+    async fn zone_set_denial(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(ZoneSetDenial { denial }): Json<ZoneSetDenial>,
+    ) -> Json<ZoneSetDenialResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneSetDenialError::NoSuchZone));
+        };
+
+        let denial_override = match denial {
+            ZoneDenial::Policy => None,
+            ZoneDenial::Nsec => Some(SignerDenialPolicy::NSec),
+            ZoneDenial::Nsec3 { opt_out } => Some(SignerDenialPolicy::NSec3 { opt_out }),
+        };
+
+        let state = center.state.lock().unwrap();
+        let mut handle = zone.write_handle(center);
+        let Some(policy_name) = handle.state.policy.as_ref().map(|p| p.name.clone()) else {
+            return Json(Err(ZoneSetDenialError::NoPolicy));
+        };
+        let latest = state
+            .policies
+            .get(&policy_name)
+            .expect("zones and policies are consistent")
+            .latest
+            .clone();
+
+        handle.state.denial_override = denial_override;
+        let changed = handle.state.set_policy(latest);
+        if changed {
+            handle
+                .state
+                .record_event(HistoricalEvent::PolicyChanged, None);
+            handle.signer().enqueue_resign_with_priority(
+                ResigningTrigger::SIGS_NEED_REFRESH,
+                SigningPriority::Interactive,
+            );
+        }
+
+        Json(Ok(ZoneSetDenialOutput {
+            zone: zone.name.clone(),
+            changed,
+        }))
+    }
+
+    /// Forcibly advance a zone through the pipeline.
+    ///
+    /// This is only meant for testing; requests must explicitly confirm that
+    /// reviews are bypassed, and every use is recorded in the zone history.
+    async fn zone_advance(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(advance): Json<ZoneAdvance>,
+    ) -> Json<ZoneAdvanceResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneAdvanceError::NoSuchZone));
+        };
+
+        if !advance.dangerous {
+            warn!(
+                "[{HTTP_UNIT_NAME}] Refusing to forcibly advance zone '{name}': bypassing reviews was not confirmed"
+            );
+            return Json(Err(ZoneAdvanceError::NotConfirmed));
+        }
+
+        // Determine the instance under review (if any), and remember the
+        // target stage so that any reviews still to come are skipped.
+        let (review, pending) = {
+            let mut state = zone.write(center);
+            let upcoming = state.instances.upcoming.as_ref();
+            let review = match &state.machine {
+                ZoneStateMachine::LoadingFailed(_)
+                | ZoneStateMachine::HaltLoaded(_)
+                | ZoneStateMachine::HaltSigned(_)
+                | ZoneStateMachine::SigningFailed(_)
+                | ZoneStateMachine::Poisoned => {
+                    return Json(Err(ZoneAdvanceError::Halted));
+                }
+                ZoneStateMachine::LoadedReview(_) => upcoming
+                    .and_then(|i| i.loaded.as_ref())
+                    .map(|i| (ZoneReviewStage::Unsigned, Serial(i.serial().get()))),
+                ZoneStateMachine::SignedReview(_) => {
+                    if advance.to == ZoneAdvanceStage::Signed {
+                        return Json(Err(ZoneAdvanceError::AlreadyAtStage));
+                    }
+                    upcoming
+                        .and_then(|i| i.signed.as_ref())
+                        .map(|i| (ZoneReviewStage::Signed, Serial(i.serial().get())))
+                }
+                ZoneStateMachine::Waiting(_)
+                | ZoneStateMachine::Loading(_)
+                | ZoneStateMachine::Signing(_) => None,
+            };
+
+            // A loaded review being approved now still leaves the signed
+            // review to skip, if the zone is to be published.
+            state.forced_advance = match review {
+                Some((ZoneReviewStage::Unsigned, _))
+                    if advance.to == ZoneAdvanceStage::Published =>
+                {
+                    Some(advance.to)
+                }
+                Some(_) => None,
+                None => Some(advance.to),
+            };
+
+            state.record_event(
+                HistoricalEvent::ForcedAdvance { to: advance.to },
+                review.map(|(_, serial)| serial),
+            );
+
+            (review, state.forced_advance)
+        };
+
+        warn!(
+            "[{HTTP_UNIT_NAME}] Forcibly advancing zone '{name}' to the {} stage, bypassing reviews",
+            advance.to
+        );
+
+        let result = match review {
+            Some((ZoneReviewStage::Unsigned, serial)) => LoadedReviewServer::process_review(
+                center,
+                &zone,
+                serial,
+                ZoneReviewDecision::Approve,
+                Some("zone forcibly advanced".into()),
+            ),
+            Some((ZoneReviewStage::Signed, serial)) => SignedReviewServer::process_review(
+                center,
+                &zone,
+                serial,
+                ZoneReviewDecision::Approve,
+                Some("zone forcibly advanced".into()),
+            ),
+            None => {
+                return Json(Ok(ZoneAdvanceOutput {
+                    zone: name,
+                    approved: false,
+                    pending,
+                }));
+            }
+        };
+
+        if let Err(err) = result {
+            // Nothing was advanced; don't skip reviews later on either.
+            warn!("[{HTTP_UNIT_NAME}] Could not forcibly advance zone '{name}': {err}");
+            zone.write(center).forced_advance = None;
+            return Json(Err(ZoneAdvanceError::Review(err)));
+        }
+
+        Json(Ok(ZoneAdvanceOutput {
+            zone: name,
+            approved: true,
+            pending,
+        }))
+    }
+
     async fn zones_list(State(http_state): State<Arc<HttpServer>>) -> Json<ZonesListResult> {
         let state = http_state.center.state.lock().unwrap();
         let zones = state
@@ -338,7 +997,18 @@ impl HttpServer {
             .iter()
             .map(|z| z.0.name.clone())
             .collect::<Vec<_>>();
-        Json(ZonesListResult { zones })
+        let descriptions = state
+            .zones
+            .iter()
+            .filter_map(|z| {
+                let description = z.0.state.read().description.clone()?;
+                Some((z.0.name.clone(), description))
+            })
+            .collect();
+        Json(ZonesListResult {
+            zones,
+            descriptions,
+        })
     }
 
     async fn zone_status(
@@ -370,6 +1040,17 @@ impl HttpServer {
         let last_published;
         let error;
         let maintenance_mode;
+        let review_batch;
+        let description;
+        let staged_since;
+        let observe_only;
+        let serial_floor;
+        let key_rolls_paused;
+        let manual_key_roll_reports;
+        let held_since;
+        let load_failure_streak;
+        let sign_failure_streak;
+        let tsig_failures;
         {
             let locked_state = state.center.state.lock().unwrap();
             let keys_dir = &state.center.config.keys_dir;
@@ -383,6 +1064,9 @@ impl HttpServer {
 
             let zone_state = zone.read();
             halted_reason = zone_state.halted_reason();
+            load_failure_streak = zone_state.loader.failure_streak;
+            sign_failure_streak = zone_state.signer.failure_streak;
+            tsig_failures = zone_state.loader.tsig_failures.clone();
 
             policy = zone_state
                 .policy
@@ -392,10 +1076,20 @@ impl HttpServer {
             source = match zone_state.loader.source.clone() {
                 loader::Source::None => api::ZoneSource::None,
                 loader::Source::Zonefile { path } => api::ZoneSource::Zonefile { path },
-                loader::Source::Server { addr, tsig_key } => {
-                    let tsig_key = tsig_key.map(|k| k.name().clone());
-                    api::ZoneSource::Server { addr, tsig_key }
-                }
+                loader::Source::Server {
+                    addr,
+                    tsig_key,
+                    next_tsig_key,
+                    fallback,
+                } => api::ZoneSource::Server {
+                    addr,
+                    tsig_key: tsig_key.map(|k| k.name().clone()),
+                    next_tsig_key: next_tsig_key.map(|k| k.name().clone()),
+                    fallback: fallback.map(|f| api::ZonefileFallback {
+                        path: f.path,
+                        timeout: f.timeout,
+                    }),
+                },
             };
             unsigned_review_addr = state
                 .center
@@ -427,7 +1121,7 @@ impl HttpServer {
             unsigned_review_status = zone_state
                 .find_last_event(HistoricalEventType::UnsignedZoneReview, None)
                 .map(|item| {
-                    let HistoricalEvent::UnsignedZoneReview { status } = item.event else {
+                    let HistoricalEvent::UnsignedZoneReview { status, .. } = item.event else {
                         unreachable!()
                     };
                     TimestampedZoneReviewStatus {
@@ -439,7 +1133,7 @@ impl HttpServer {
             signed_review_status = zone_state
                 .find_last_event(HistoricalEventType::SignedZoneReview, None)
                 .map(|item| {
-                    let HistoricalEvent::SignedZoneReview { status } = item.event else {
+                    let HistoricalEvent::SignedZoneReview { status, .. } = item.event else {
                         unreachable!()
                     };
                     TimestampedZoneReviewStatus {
@@ -477,6 +1171,7 @@ impl HttpServer {
                     }
                 }
                 ZoneStateMachine::Loading(..) => Progress::Loading,
+                ZoneStateMachine::LoadingFailed(..) => Progress::LoadingFailed,
                 ZoneStateMachine::LoadedReview(..) => Progress::LoadedReview,
                 ZoneStateMachine::HaltLoaded(..) => Progress::HaltLoaded,
                 ZoneStateMachine::Signing(..) => Progress::Signing,
@@ -520,20 +1215,28 @@ impl HttpServer {
                         found_error = Some(reason.clone());
                         break;
                     }
-                    HistoricalEvent::SigningFailed { trigger: _, reason } => {
+                    HistoricalEvent::SigningFailed { reason, .. } => {
                         found_error = Some(format!("signing failed: {reason}"));
                         break;
                     }
                     HistoricalEvent::UnsignedZoneReview {
                         status: ZoneReviewStatus::Rejected,
+                        reason,
                     } => {
-                        found_error = Some("loaded zone was rejected".into());
+                        found_error = Some(match reason {
+                            Some(reason) => format!("loaded zone was rejected: {reason}"),
+                            None => "loaded zone was rejected".into(),
+                        });
                         break;
                     }
                     HistoricalEvent::SignedZoneReview {
                         status: ZoneReviewStatus::Rejected,
+                        reason,
                     } => {
-                        found_error = Some("signed zone was rejected".into());
+                        found_error = Some(match reason {
+                            Some(reason) => format!("signed zone was rejected: {reason}"),
+                            None => "signed zone was rejected".into(),
+                        });
                         break;
                     }
                     HistoricalEvent::UnsignedHookFailed { err } => {
@@ -550,6 +1253,23 @@ impl HttpServer {
             error = found_error;
 
             maintenance_mode = zone_state.maintenance_mode;
+            review_batch = zone_state.review_batch.clone();
+            description = zone_state.description.clone();
+            staged_since = zone_state.staged_since;
+            observe_only = zone_state.observe_only;
+            serial_floor = zone_state.serial_floor;
+            key_rolls_paused = zone_state.key_rolls_paused;
+            held_since = zone_state.held_since;
+
+            // Automatic key roll steps wait by themselves; the cache expiry
+            // wait is only used for propagation steps reported by hand.
+            manual_key_roll_reports = key_rolls_paused
+                || zone_state.policy.as_ref().is_some_and(|policy| {
+                    let km = &policy.key_manager;
+                    [&km.auto_ksk, &km.auto_zsk, &km.auto_csk, &km.auto_algorithm]
+                        .iter()
+                        .any(|auto| !auto.report)
+                });
         }
 
         // Query key status
@@ -597,14 +1317,21 @@ impl HttpServer {
 
         // Query zone keys
         let mut keys = vec![];
-        match std::fs::read_to_string(&state_path) {
-            Ok(json) => {
-                let keyset_state: KeySetState = serde_json::from_str(&json).unwrap();
+        let keyset_state = tokio::task::spawn_blocking({
+            let state_path = state_path.clone();
+            move || KeySetState::load_or_last_good(&state_path)
+        })
+        .await
+        .expect("loading the keyset state should not panic");
+        match keyset_state {
+            Ok(keyset_state) => {
                 for (pubref, key) in keyset_state.keyset.keys() {
-                    let (key_type, signer) = match key.keytype() {
-                        KeyType::Ksk(s) => (api::KeyType::Ksk, s.signer()),
-                        KeyType::Zsk(s) => (api::KeyType::Zsk, s.signer()),
-                        KeyType::Csk(s1, s2) => (api::KeyType::Csk, s1.signer() || s2.signer()),
+                    let (key_type, signer, retired) = match key.keytype() {
+                        KeyType::Ksk(s) => (api::KeyType::Ksk, s.signer(), s.stale()),
+                        KeyType::Zsk(s) => (api::KeyType::Zsk, s.signer(), s.stale()),
+                        KeyType::Csk(s1, s2) => {
+                            (api::KeyType::Csk, s1.signer() || s2.signer(), s1.stale())
+                        }
                         KeyType::Include(_) => continue,
                     };
                     keys.push(KeyInfo {
@@ -612,12 +1339,13 @@ impl HttpServer {
                         key_type,
                         key_tag: key.key_tag(),
                         signer,
+                        retired,
                     });
                 }
             }
             Err(err) => {
                 error!(
-                    "Unable to read `dnst keyset` state file '{state_path}' while querying status of zone {name} for the API: {err}"
+                    "Unable to load `dnst keyset` state file '{state_path}' while querying status of zone {name} for the API: {err}"
                 );
             }
         }
@@ -634,6 +1362,7 @@ impl HttpServer {
                     byte_count: metrics.num_loaded_bytes.load(Relaxed),
                     total_byte_count: Some(metrics.num_total_bytes.load(Relaxed)),
                     record_count: metrics.num_loaded_records.load(Relaxed),
+                    transfer: metrics.transfer.lock().unwrap().map(Into::into),
                 })
                 .or_else(|| {
                     last.map(|metrics| ZoneLoaderReport {
@@ -642,75 +1371,587 @@ impl HttpServer {
                         byte_count: metrics.num_loaded_bytes,
                         total_byte_count: None,
                         record_count: metrics.num_loaded_records,
+                        transfer: metrics.transfer.map(Into::into),
                     })
                 })
         };
 
-        Ok(ZoneStatus {
-            name,
-            source,
-            policy,
-            progress,
-            maintenance_mode,
-            last_published,
-            keys,
-            key_status,
-            receipt_report,
-            unsigned_serial,
-            unsigned_review_status,
-            unsigned_review_addr,
-            signed_serial,
-            signed_review_status,
-            signed_review_addr,
-            signing_report,
-            published_serial,
-            publish_addr,
-            halted_reason,
-            error,
-        })
+        let rollover_wait = if manual_key_roll_reports {
+            KeyManager::rollover_wait(&state.center, &zone).await
+        } else {
+            None
+        };
+
+        Ok(ZoneStatus {
+            name,
+            source,
+            policy,
+            progress,
+            maintenance_mode,
+            last_published,
+            keys,
+            key_status,
+            receipt_report,
+            unsigned_serial,
+            unsigned_review_status,
+            unsigned_review_addr,
+            signed_serial,
+            signed_review_status,
+            signed_review_addr,
+            signing_report,
+            published_serial,
+            publish_addr,
+            halted_reason,
+            load_failure_streak,
+            sign_failure_streak,
+            tsig_failures,
+            review_batch,
+            description,
+            staged_since,
+            observe_only,
+            serial_floor,
+            key_rolls_paused,
+            held_since,
+            rollover_wait,
+            error,
+        })
+    }
+
+    async fn zone_history(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<Result<ZoneHistory, ZoneHistoryError>> {
+        let zone = match get_zone(&state.center, &name) {
+            Some(zone) => zone,
+            None => return Json(Err(ZoneHistoryError::ZoneDoesNotExist)),
+        };
+        Json(Ok(ZoneHistory {
+            history: zone
+                .read()
+                .history
+                .iter()
+                .map(|i| i.clone().into())
+                .collect(),
+        }))
+    }
+
+    async fn zone_memory(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<ZoneMemoryResult> {
+        let Some(zone) = get_zone(&state.center, &name) else {
+            return Json(Err(ZoneMemoryError::NoSuchZone));
+        };
+        Json(Ok(Self::zone_memory_report(&state.center, &zone).await))
+    }
+
+    async fn zones_memory(State(state): State<Arc<HttpServer>>) -> Json<ZonesMemoryResult> {
+        let zones = {
+            let state = state.center.state.lock().unwrap();
+            state.zones.iter().map(|z| z.0.clone()).collect::<Vec<_>>()
+        };
+        let mut reports = Vec::with_capacity(zones.len());
+        for zone in zones {
+            reports.push(Self::zone_memory_report(&state.center, &zone).await);
+        }
+        Json(ZonesMemoryResult { zones: reports })
+    }
+
+    /// Estimate the memory used by the instances and diffs of a zone.
+    ///
+    /// The published instance is always counted. An instance under review is
+    /// counted in addition, unless it shares its loaded part with the
+    /// published instance.
+    ///
+    /// This walks every record of the zone, so it is done on a blocking
+    /// thread, without holding the zone state lock.
+    async fn zone_memory_report(center: &Arc<Center>, zone: &Arc<Zone>) -> ZoneMemoryReport {
+        let (loaded_review, signed_review, diffs) = {
+            let state = zone.read();
+            let loaded_review = matches!(state.machine, ZoneStateMachine::LoadedReview(_));
+            let signed_review = matches!(state.machine, ZoneStateMachine::SignedReview(_));
+            (loaded_review, signed_review, state.storage.diffs.diffs())
+        };
+
+        let published = match center.publication_server.viewer(zone) {
+            Some(viewer) => Some(viewer.read_owned().await),
+            None => None,
+        };
+
+        let loaded_reviewer = match center.loaded_review_server.viewer(zone) {
+            Some(viewer) if loaded_review => Some(viewer.read_owned().await),
+            _ => None,
+        };
+        let signed_reviewer = match center.signed_review_server.viewer(zone) {
+            Some(viewer) if signed_review => Some(viewer.read_owned().await),
+            _ => None,
+        };
+
+        let name = zone.name.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut unsigned = 0;
+            let mut signed = 0;
+            let mut published_serial = None;
+
+            if let Some(reader) = published.as_ref().and_then(|viewer| viewer.read()) {
+                let loaded = reader.loaded();
+                published_serial = Some(loaded.soa().rdata.serial);
+                unsigned += loaded.memory_usage();
+                signed += reader.memory_usage();
+            }
+
+            if let Some(reader) = loaded_reviewer.as_ref().and_then(|viewer| viewer.read()) {
+                unsigned += reader.memory_usage();
+            } else if let Some(reader) = signed_reviewer.as_ref().and_then(|viewer| viewer.read()) {
+                let loaded = reader.loaded();
+                if published_serial != Some(loaded.soa().rdata.serial) {
+                    unsigned += loaded.memory_usage();
+                }
+                signed += reader.memory_usage();
+            }
+
+            ZoneMemoryReport {
+                zone: name,
+                unsigned,
+                signed,
+                diffs: diffs.iter().map(|diff| diff.memory_usage()).sum(),
+            }
+        })
+        .await
+        .expect("estimating the memory usage should not panic")
+    }
+
+    async fn zone_reload(
+        State(api_state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        reload: Option<Json<ZoneReload>>,
+    ) -> Json<Result<ZoneReloadResult, ZoneReloadError>> {
+        let batch = reload.and_then(|Json(reload)| reload.batch);
+        Json(Self::do_zone_reload(api_state, name, batch))
+    }
+
+    fn do_zone_reload(
+        api_state: Arc<HttpServer>,
+        zone_name: Name<Bytes>,
+        batch: Option<String>,
+    ) -> Result<ZoneReloadResult, ZoneReloadError> {
+        let center = &api_state.center;
+        let zone =
+            crate::center::get_zone(center, &zone_name).ok_or(ZoneReloadError::ZoneDoesNotExist)?;
+
+        // Tag the zone, so that its upcoming versions can be approved along
+        // with the rest of the batch.  This happens before the reload starts,
+        // as a quick load could otherwise reach review untagged.
+        let previous_batch = batch.map(|batch| {
+            info!("[{HTTP_UNIT_NAME}] Adding zone '{zone_name}' to review batch '{batch}'");
+            std::mem::replace(&mut zone.write(center).review_batch, Some(batch))
+        });
+
+        if let Err(err) = center.loader.on_reload_zone(center, &zone) {
+            // The zone was not reloaded; keep it in the batch it was in.
+            if let Some(previous_batch) = previous_batch {
+                zone.write(center).review_batch = previous_batch;
+            }
+            return Err(err);
+        }
+
+        Ok(ZoneReloadResult { name: zone_name })
+    }
+
+    async fn zone_rollback(
+        State(api_state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+    ) -> Json<ZoneRollbackResult> {
+        let center = &api_state.center;
+        let Some(zone) = crate::center::get_zone(center, &name) else {
+            return Json(Err(ZoneRollbackError::ZoneDoesNotExist));
+        };
+        let result = center.loader.on_rollback_zone(center, &zone);
+        Json(result.map(|serial| ZoneRollbackOutput { name, serial }))
+    }
+
+    /// Look up records in Cascade's own copy of a zone.
+    ///
+    /// For the unsigned and signed stages, the version under review is used if
+    /// there is one; otherwise, the corresponding part of the published
+    /// version of the zone is used.
+    async fn zone_query(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Name<Bytes>>,
+        Json(ZoneQuery {
+            name: qname,
+            rtype,
+            stage,
+        }): Json<ZoneQuery>,
+    ) -> Json<ZoneQueryResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &name) else {
+            return Json(Err(ZoneQueryError::NoSuchZone));
+        };
+        if !qname.ends_with(&zone.name) {
+            return Json(Err(ZoneQueryError::OutOfZone));
+        }
+        // Matching records takes a scan through the whole zone, so it is done
+        // on a blocking thread.
+        let under_review = match zone.read().machine {
+            ZoneStateMachine::LoadedReview(_) => stage == ZoneQueryStage::Unsigned,
+            ZoneStateMachine::SignedReview(_) => stage == ZoneQueryStage::Signed,
+            _ => false,
+        };
+        if under_review
+            && stage == ZoneQueryStage::Unsigned
+            && let Some(viewer) = center.loaded_review_server.viewer(&zone)
+        {
+            let viewer = viewer.read_owned().await;
+            let qname = qname.clone();
+            let output = tokio::task::spawn_blocking(move || {
+                let reader = viewer.read()?;
+                let qname = RevNameBuf::parse_bytes(qname.as_slice()).unwrap();
+                let records = reader.regular_records().iter();
+                Some(Self::query_records(
+                    reader.soa(),
+                    records,
+                    &qname,
+                    rtype,
+                    true,
+                ))
+            })
+            .await
+            .expect("querying the zone should not panic");
+            if let Some(output) = output {
+                return Json(Ok(output));
+            }
+        } else if under_review
+            && stage == ZoneQueryStage::Signed
+            && let Some(viewer) = center.signed_review_server.viewer(&zone)
+        {
+            let viewer = viewer.read_owned().await;
+            let qname = qname.clone();
+            let output = tokio::task::spawn_blocking(move || {
+                let reader = viewer.read()?;
+                let qname = RevNameBuf::parse_bytes(qname.as_slice()).unwrap();
+                Some(Self::query_records(
+                    reader.soa(),
+                    reader.all_records(),
+                    &qname,
+                    rtype,
+                    true,
+                ))
+            })
+            .await
+            .expect("querying the zone should not panic");
+            if let Some(output) = output {
+                return Json(Ok(output));
+            }
+        }
+
+        let Some(viewer) = center.publication_server.viewer(&zone) else {
+            return Json(Err(ZoneQueryError::NotAvailable));
+        };
+        let viewer = viewer.read_owned().await;
+        let output = tokio::task::spawn_blocking(move || {
+            let reader = viewer.read()?;
+            let qname = RevNameBuf::parse_bytes(qname.as_slice()).unwrap();
+            Some(match stage {
+                ZoneQueryStage::Unsigned => {
+                    let loaded = reader.loaded();
+                    let records = loaded.regular_records().iter();
+                    Self::query_records(loaded.soa(), records, &qname, rtype, false)
+                }
+                ZoneQueryStage::Signed | ZoneQueryStage::Published => {
+                    Self::query_records(reader.soa(), reader.all_records(), &qname, rtype, false)
+                }
+            })
+        })
+        .await
+        .expect("querying the zone should not panic");
+        Json(output.ok_or(ZoneQueryError::NotAvailable))
+    }
+
+    /// Collect the records of a zone that match a query.
+    ///
+    /// Signatures covering the queried type are included, after the records
+    /// themselves.
+    fn query_records<'a>(
+        soa: &SoaRecord,
+        records: impl Iterator<Item = &'a RegularRecord>,
+        qname: &RevName,
+        rtype: Rtype,
+        under_review: bool,
+    ) -> ZoneQueryOutput {
+        let mut matches = records
+            .filter(|r| *r.rname == *qname)
+            .map(|r| OldParsedRecord::from(r.clone()))
+            .filter(|r| {
+                rtype == Rtype::ANY
+                    || r.rtype() == rtype
+                    || matches!(r.data(), ZoneRecordData::Rrsig(rrsig) if rrsig.type_covered() == rtype)
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|r| r.rtype() == Rtype::RRSIG);
+
+        ZoneQueryOutput {
+            serial: Serial(soa.rdata.serial.into()),
+            under_review,
+            records: matches.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    /// List the zone versions awaiting review, across all zones.
+    async fn pending_reviews(State(state): State<Arc<HttpServer>>) -> Json<PendingReviewsResult> {
+        let zones = {
+            let state = state.center.state.lock().unwrap();
+            state
+                .zones
+                .iter()
+                .map(|ZoneByName(zone)| zone.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut reviews = Vec::new();
+        for zone in zones {
+            let state = zone.read();
+            let upcoming = state.instances.upcoming.as_ref();
+            let (stage, typ, serial) = match &state.machine {
+                ZoneStateMachine::LoadedReview(_) => (
+                    ZoneReviewStage::Unsigned,
+                    HistoricalEventType::UnsignedZoneReview,
+                    upcoming
+                        .and_then(|i| i.loaded.as_ref())
+                        .map(|i| Serial(i.serial().get())),
+                ),
+                ZoneStateMachine::SignedReview(_) => (
+                    ZoneReviewStage::Signed,
+                    HistoricalEventType::SignedZoneReview,
+                    upcoming
+                        .and_then(|i| i.signed.as_ref())
+                        .map(|i| Serial(i.serial().get())),
+                ),
+                _ => continue,
+            };
+            let Some(serial) = serial else {
+                continue;
+            };
+
+            // The review was requested when its pending event was recorded.
+            let since = state
+                .find_last_event(typ, Some(serial))
+                .map_or_else(SystemTime::now, |item| item.when);
+
+            reviews.push(PendingReview {
+                zone: zone.name.clone(),
+                stage,
+                serial,
+                since,
+                batch: state.review_batch.clone(),
+            });
+        }
+
+        reviews.sort_by_key(|review| review.since);
+        Json(PendingReviewsResult { reviews })
+    }
+
+    /// Summarize the version of a zone under review.
+    async fn zone_review_summary(
+        State(state): State<Arc<HttpServer>>,
+        Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+    ) -> Json<ZoneReviewSummaryResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &zone_name) else {
+            return Json(Err(ZoneReviewError::NoSuchZone));
+        };
+
+        let stage = match zone.read().machine {
+            ZoneStateMachine::LoadedReview(_) => ZoneReviewStage::Unsigned,
+            ZoneStateMachine::SignedReview(_) => ZoneReviewStage::Signed,
+            _ => return Json(Err(ZoneReviewError::NotUnderReview)),
+        };
+
+        let summary = match stage {
+            ZoneReviewStage::Unsigned => match center.loaded_review_server.viewer(&zone) {
+                Some(viewer) => LoadedReviewServer::summarize(&zone, &*viewer.read().await),
+                None => None,
+            },
+            ZoneReviewStage::Signed => match center.signed_review_server.viewer(&zone) {
+                Some(viewer) => SignedReviewServer::summarize(&zone, &*viewer.read().await),
+                None => None,
+            },
+        };
+
+        match summary {
+            Some(summary) if summary.serial == zone_serial => Json(Ok(summary)),
+            _ => Json(Err(ZoneReviewError::NotUnderReview)),
+        }
+    }
+
+    /// Fetch the contents of the unsigned version of a zone under review.
+    async fn unsigned_review_zonefile(
+        State(state): State<Arc<HttpServer>>,
+        Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+    ) -> Json<ZoneReviewZonefileResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &zone_name) else {
+            return Json(Err(ZoneReviewError::NoSuchZone));
+        };
+        if !matches!(zone.read().machine, ZoneStateMachine::LoadedReview(_)) {
+            return Json(Err(ZoneReviewError::NotUnderReview));
+        }
+
+        let zonefile = match center.loaded_review_server.viewer(&zone) {
+            Some(viewer) => LoadedReviewServer::zonefile(&zone, &*viewer.read().await),
+            None => None,
+        };
+
+        match zonefile {
+            Some(zonefile) if zonefile.serial == zone_serial => Json(Ok(zonefile)),
+            _ => Json(Err(ZoneReviewError::NotUnderReview)),
+        }
+    }
+
+    /// Fetch the contents of the signed version of a zone under review.
+    async fn signed_review_zonefile(
+        State(state): State<Arc<HttpServer>>,
+        Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+    ) -> Json<ZoneReviewZonefileResult> {
+        let center = &state.center;
+        let Some(zone) = get_zone(center, &zone_name) else {
+            return Json(Err(ZoneReviewError::NoSuchZone));
+        };
+        if !matches!(zone.read().machine, ZoneStateMachine::SignedReview(_)) {
+            return Json(Err(ZoneReviewError::NotUnderReview));
+        }
+
+        let zonefile = match center.signed_review_server.viewer(&zone) {
+            Some(viewer) => SignedReviewServer::zonefile(&zone, &*viewer.read().await),
+            None => None,
+        };
+
+        match zonefile {
+            Some(zonefile) if zonefile.serial == zone_serial => Json(Ok(zonefile)),
+            _ => Json(Err(ZoneReviewError::NotUnderReview)),
+        }
     }
 
-    async fn zone_history(
+    /// Approve the unsigned versions of all zones in a review batch.
+    async fn approve_unsigned_batch(
         State(state): State<Arc<HttpServer>>,
-        Path(name): Path<Name<Bytes>>,
-    ) -> Json<Result<ZoneHistory, ZoneHistoryError>> {
-        let zone = match get_zone(&state.center, &name) {
-            Some(zone) => zone,
-            None => return Json(Err(ZoneHistoryError::ZoneDoesNotExist)),
-        };
-        Json(Ok(ZoneHistory {
-            history: zone
-                .read()
-                .history
-                .iter()
-                .map(|i| i.clone().into())
-                .collect(),
-        }))
+        Path(batch): Path<String>,
+        review: Option<Json<ZoneReview>>,
+    ) -> Json<ZoneBatchReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
+        Json(Self::approve_batch(
+            &state.center,
+            &batch,
+            ZoneReviewStage::Unsigned,
+            reason,
+        ))
     }
 
-    async fn zone_reload(
-        State(api_state): State<Arc<HttpServer>>,
-        Path(name): Path<Name<Bytes>>,
-    ) -> Json<Result<ZoneReloadResult, ZoneReloadError>> {
-        Json(Self::do_zone_reload(api_state, name))
+    /// Approve the signed versions of all zones in a review batch.
+    async fn approve_signed_batch(
+        State(state): State<Arc<HttpServer>>,
+        Path(batch): Path<String>,
+        review: Option<Json<ZoneReview>>,
+    ) -> Json<ZoneBatchReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
+        Json(Self::approve_batch(
+            &state.center,
+            &batch,
+            ZoneReviewStage::Signed,
+            reason,
+        ))
     }
 
-    fn do_zone_reload(
-        api_state: Arc<HttpServer>,
-        zone_name: Name<Bytes>,
-    ) -> Result<ZoneReloadResult, ZoneReloadError> {
-        let center = &api_state.center;
-        let zone =
-            crate::center::get_zone(center, &zone_name).ok_or(ZoneReloadError::ZoneDoesNotExist)?;
-        center.loader.on_reload_zone(center, &zone)?;
-        Ok(ZoneReloadResult { name: zone_name })
+    /// Approve every zone in a review batch that is under review at `stage`.
+    ///
+    /// Zones in the batch that are not (yet) under review at `stage` are left
+    /// alone; the batch can be approved again once they get there.  The
+    /// outcome is reported for every zone that was under review, so that
+    /// partial failures are visible.
+    fn approve_batch(
+        center: &Arc<Center>,
+        batch: &str,
+        stage: ZoneReviewStage,
+        reason: Option<String>,
+    ) -> ZoneBatchReviewResult {
+        let reason = reason.or_else(|| Some(format!("approved with review batch '{batch}'")));
+
+        let zones = {
+            let state = center.state.lock().unwrap();
+            state
+                .zones
+                .iter()
+                .map(|ZoneByName(zone)| zone.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut found = false;
+        let mut reviewed = Vec::new();
+        for zone in zones {
+            let serial = {
+                let state = zone.read();
+                if state.review_batch.as_deref() != Some(batch) {
+                    continue;
+                }
+                found = true;
+
+                let upcoming = state.instances.upcoming.as_ref();
+                match (&stage, &state.machine) {
+                    (ZoneReviewStage::Unsigned, ZoneStateMachine::LoadedReview(_)) => upcoming
+                        .and_then(|i| i.loaded.as_ref())
+                        .map(|i| Serial(i.serial().get())),
+                    (ZoneReviewStage::Signed, ZoneStateMachine::SignedReview(_)) => upcoming
+                        .and_then(|i| i.signed.as_ref())
+                        .map(|i| Serial(i.serial().get())),
+                    _ => None,
+                }
+            };
+            let Some(serial) = serial else {
+                continue;
+            };
+
+            let result = match stage {
+                ZoneReviewStage::Unsigned => LoadedReviewServer::process_review(
+                    center,
+                    &zone,
+                    serial,
+                    ZoneReviewDecision::Approve,
+                    reason.clone(),
+                ),
+                ZoneReviewStage::Signed => SignedReviewServer::process_review(
+                    center,
+                    &zone,
+                    serial,
+                    ZoneReviewDecision::Approve,
+                    reason.clone(),
+                ),
+            };
+            if let Err(err) = &result {
+                warn!(
+                    "[{HTTP_UNIT_NAME}] Could not approve zone '{}' of review batch '{batch}': {err}",
+                    zone.name
+                );
+            }
+            reviewed.push(ZoneBatchReviewed {
+                zone: zone.name.clone(),
+                serial,
+                result,
+            });
+        }
+
+        if !found {
+            return Err(ZoneBatchReviewError::NoSuchBatch);
+        }
+        Ok(ZoneBatchReviewOutput { reviewed })
     }
 
     /// Approve an unsigned version of a zone.
     async fn approve_unsigned(
         State(state): State<Arc<HttpServer>>,
         Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+        review: Option<Json<ZoneReview>>,
     ) -> Json<ZoneReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
         let center = &state.center;
         let Some(zone) = get_zone(center, &zone_name) else {
             debug!(
@@ -723,6 +1964,7 @@ impl HttpServer {
             &zone,
             zone_serial,
             ZoneReviewDecision::Approve,
+            reason,
         );
 
         Json(result)
@@ -732,7 +1974,9 @@ impl HttpServer {
     async fn reject_unsigned(
         State(state): State<Arc<HttpServer>>,
         Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+        review: Option<Json<ZoneReview>>,
     ) -> Json<ZoneReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
         let center = &state.center;
         let Some(zone) = get_zone(center, &zone_name) else {
             debug!(
@@ -745,6 +1989,7 @@ impl HttpServer {
             &zone,
             zone_serial,
             ZoneReviewDecision::Reject,
+            reason,
         );
 
         Json(result)
@@ -779,7 +2024,9 @@ impl HttpServer {
     async fn approve_signed(
         State(state): State<Arc<HttpServer>>,
         Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+        review: Option<Json<ZoneReview>>,
     ) -> Json<ZoneReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
         let center = &state.center;
         let Some(zone) = get_zone(center, &zone_name) else {
             debug!(
@@ -792,6 +2039,7 @@ impl HttpServer {
             &zone,
             zone_serial,
             ZoneReviewDecision::Approve,
+            reason,
         );
 
         Json(result)
@@ -801,7 +2049,9 @@ impl HttpServer {
     async fn reject_signed(
         State(state): State<Arc<HttpServer>>,
         Path((zone_name, zone_serial)): Path<(Name<Bytes>, Serial)>,
+        review: Option<Json<ZoneReview>>,
     ) -> Json<ZoneReviewResult> {
+        let reason = review.and_then(|Json(review)| review.reason);
         let center = &state.center;
         let Some(zone) = get_zone(center, &zone_name) else {
             debug!(
@@ -814,6 +2064,7 @@ impl HttpServer {
             &zone,
             zone_serial,
             ZoneReviewDecision::Reject,
+            reason,
         );
 
         Json(result)
@@ -893,6 +2144,7 @@ impl HttpServer {
 
     async fn policy_reload(
         State(state): State<Arc<HttpServer>>,
+        Json(PolicyReload { full }): Json<PolicyReload>,
     ) -> Json<Result<PolicyChanges, PolicyReloadError>> {
         let center = &state.center;
         let mut state = state.center.state.lock().unwrap();
@@ -909,6 +2161,7 @@ impl HttpServer {
             &mut state.policies,
             &center.config,
             &state.tsig_store,
+            full,
             |name, change| {
                 changed = true;
 
@@ -953,7 +2206,7 @@ impl HttpServer {
 
                 {
                     let mut handle = zone.write_handle(center);
-                    handle.state.policy = Some(pol.latest.clone());
+                    handle.state.set_policy(pol.latest.clone());
                     handle.signer().after_policy_change();
                 }
 
@@ -974,6 +2227,24 @@ impl HttpServer {
         Json(Ok(PolicyChanges { changes }))
     }
 
+    /// Render a policy in the format of a policy file.
+    async fn policy_export(
+        State(state): State<Arc<HttpServer>>,
+        Path(name): Path<Box<str>>,
+    ) -> Json<Result<PolicyExportOutput, PolicyExportError>> {
+        let state = state.center.state.lock().unwrap();
+        let Some(policy) = state.policies.get(&name) else {
+            return Json(Err(PolicyExportError::PolicyDoesNotExist));
+        };
+
+        let spec = crate::policy::file::Spec::build(policy);
+        Json(
+            spec.to_toml()
+                .map(|toml| PolicyExportOutput { toml })
+                .map_err(|err| PolicyExportError::Serialization(err.to_string())),
+        )
+    }
+
     async fn policy_show(
         State(state): State<Arc<HttpServer>>,
         Path(name): Path<Box<str>>,
@@ -994,7 +2265,11 @@ impl HttpServer {
         } = &*p.latest;
 
         let loader = {
-            let crate::policy::LoaderPolicy { review } = loader;
+            let crate::policy::LoaderPolicy {
+                review,
+                transfer,
+                soa_query_over_tcp,
+            } = loader;
 
             LoaderPolicyInfo {
                 review: ReviewPolicyInfo {
@@ -1010,6 +2285,12 @@ impl HttpServer {
                         crate::policy::OnReject::Halt => ReviewPolicyOnReject::Halt,
                     },
                 },
+                transfer: TransferLimitsPolicyInfo {
+                    max_bytes: transfer.max_bytes,
+                    max_records: transfer.max_records,
+                    timeout: transfer.timeout,
+                },
+                soa_query_over_tcp: *soa_query_over_tcp,
             }
         };
 
@@ -1023,6 +2304,8 @@ impl HttpServer {
                 key_roll_time,
                 ref denial,
                 ref review,
+                preserve_external_signatures,
+                refuse_dnssec_input,
             } = signer;
 
             SignerPolicyInfo {
@@ -1037,6 +2320,8 @@ impl HttpServer {
                 sig_remain_time,
                 signature_refresh_interval,
                 key_roll_time,
+                preserve_external_signatures,
+                refuse_dnssec_input,
                 denial: match denial {
                     SignerDenialPolicy::NSec => SignerDenialPolicyInfo::NSec,
                     &SignerDenialPolicy::NSec3 { opt_out } => {
@@ -1081,6 +2366,7 @@ impl HttpServer {
                 default_ttl,
                 auto_remove,
                 auto_remove_delay,
+                validity_jitter,
                 ref publication_nameservers,
             } = key_manager;
 
@@ -1121,6 +2407,7 @@ impl HttpServer {
                 default_ttl: default_ttl.as_secs(),
                 auto_remove,
                 auto_remove_delay,
+                validity_jitter,
                 publication_nameservers: publication_nameservers
                     .iter()
                     .map(ToString::to_string)
@@ -1129,7 +2416,7 @@ impl HttpServer {
         };
 
         let server = {
-            let crate::policy::ServerPolicy { outbound } = server;
+            let crate::policy::ServerPolicy { outbound, staging } = server;
             ServerPolicyInfo {
                 outbound: OutboundPolicyInfo {
                     provide_xfr_to: outbound
@@ -1137,6 +2424,11 @@ impl HttpServer {
                         .iter()
                         .map(|v| NameserverCommsPolicyInfo { addr: v.addr })
                         .collect(),
+                    provide_review_xfr_to: outbound
+                        .provide_review_xfr_to
+                        .iter()
+                        .map(|v| NameserverCommsPolicyInfo { addr: v.addr })
+                        .collect(),
                     send_notify_to: outbound
                         .send_notify_to
                         .iter()
@@ -1144,6 +2436,15 @@ impl HttpServer {
                         .collect(),
                     max_diffs: outbound.max_diffs,
                     max_diffs_size: outbound.max_diffs_size,
+                    removal_grace_period: outbound.removal_grace_period,
+                },
+                staging: StagingPolicyInfo {
+                    send_notify_to: staging
+                        .send_notify_to
+                        .iter()
+                        .map(|v| NameserverCommsPolicyInfo { addr: v.addr })
+                        .collect(),
+                    soak_time: staging.soak_time,
                 },
             }
         };
@@ -1196,6 +2497,118 @@ impl HttpServer {
         Json(res)
     }
 
+    async fn key_purge_retired(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<Vec<String>, String>> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+        let res = center.key_manager.on_purge_retired(center, &zone).await;
+
+        Json(res)
+    }
+
+    async fn key_auto_pause(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<(), String>> {
+        Json(Self::key_set_auto_paused(state, zone, true).await)
+    }
+
+    async fn key_auto_resume(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<(), String>> {
+        Json(Self::key_set_auto_paused(state, zone, false).await)
+    }
+
+    async fn key_set_auto_paused(
+        state: Arc<HttpServer>,
+        zone: Name<Bytes>,
+        paused: bool,
+    ) -> Result<(), String> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Err(format!("Zone '{zone}' does not exist"));
+        };
+        center
+            .key_manager
+            .on_set_auto_paused(center, &zone, paused)
+            .await
+    }
+
+    async fn key_import(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+        Json(KeyImports { key_imports }): Json<KeyImports>,
+    ) -> Json<Result<(), String>> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+
+        // Apply the same checks as when adding a zone.
+        let Some(policy) = zone.read().policy.clone() else {
+            return Json(Err(format!("Zone '{}' has no policy", zone.name)));
+        };
+        if let Err(err) =
+            center::check_key_imports(&zone.name, &policy.key_manager.algorithm, &key_imports).await
+        {
+            return Json(Err(err.to_string()));
+        }
+
+        let res = center
+            .key_manager
+            .on_import_keys(center, &zone, &key_imports)
+            .await;
+
+        Json(res)
+    }
+
+    async fn key_migrate(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+        Json(KeyMigrate { to_hsm }): Json<KeyMigrate>,
+    ) -> Json<Result<(), String>> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+        let res = center
+            .key_manager
+            .on_migrate_keys(center, &zone, to_hsm)
+            .await;
+
+        Json(res)
+    }
+
+    async fn key_verify(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<KeyVerifyResult, String>> {
+        let center = state.center.clone();
+        let Some(zone) = center::get_zone(&center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+
+        // Reading the keyset state and loading keys from KMIP servers blocks.
+        let res = tokio::task::spawn_blocking(move || -> Result<KeyVerifyResult, String> {
+            let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+            let keyset_state = KeySetState::load(&state_path).map_err(|err| err.to_string())?;
+            Ok(crate::signer::keys::verify_keyset(
+                &center,
+                &zone,
+                &keyset_state,
+            ))
+        })
+        .await
+        .unwrap_or_else(|err| Err(format!("Key verification failed: {err}")));
+
+        Json(res)
+    }
+
     async fn key_get(
         State(state): State<Arc<HttpServer>>,
         Path(zone): Path<Name<Bytes>>,
@@ -1213,7 +2626,199 @@ impl HttpServer {
         Json(res)
     }
 
+    /// Show the DNSKEY RRset in the published instance of a zone.
+    async fn key_dnskey(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<KeyDnskeyResult, String>> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+        let not_published = || format!("Zone '{}' has not been published yet", zone.name);
+
+        let Some(viewer) = center.publication_server.viewer(&zone) else {
+            return Json(Err(not_published()));
+        };
+        let viewer = viewer.read().await;
+        let Some(reader) = viewer.read() else {
+            return Json(Err(not_published()));
+        };
+
+        let soa = reader.soa();
+        let mut keys = Vec::new();
+        let mut signatures = Vec::new();
+
+        // Records are in canonical order, so the apex records come first.
+        for record in reader
+            .generated_records()
+            .iter()
+            .take_while(|r| r.rname == soa.rname)
+        {
+            let record = OldParsedRecord::from(record.clone());
+            match record.data() {
+                ZoneRecordData::Dnskey(dnskey) => keys.push(PublishedDnskey {
+                    key_tag: dnskey.key_tag(),
+                    flags: dnskey.flags(),
+                    record: record.to_string(),
+                }),
+                ZoneRecordData::Rrsig(rrsig) if rrsig.type_covered() == Rtype::DNSKEY => signatures
+                    .push(PublishedRrsig {
+                        key_tag: rrsig.key_tag(),
+                        record: record.to_string(),
+                    }),
+                _ => {}
+            }
+        }
+
+        Json(Ok(KeyDnskeyResult {
+            serial: Serial(soa.rdata.serial.into()),
+            keys,
+            signatures,
+        }))
+    }
+
+    /// Compare the DNSKEY RRset at the publication nameservers of a zone to
+    /// the published one.
+    async fn key_propagation(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<KeyPropagationResult, String>> {
+        let center = &state.center;
+        let Some(zone) = center::get_zone(center, &zone) else {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        };
+        let not_published = || format!("Zone '{}' has not been published yet", zone.name);
+
+        let (serial, expected, mname) = {
+            let Some(viewer) = center.publication_server.viewer(&zone) else {
+                return Json(Err(not_published()));
+            };
+            let viewer = viewer.read().await;
+            let Some(reader) = viewer.read() else {
+                return Json(Err(not_published()));
+            };
+
+            let soa = reader.soa();
+            let mname = match OldParsedRecord::from(soa.clone()).data() {
+                ZoneRecordData::Soa(soa) => soa.mname().to_string(),
+                _ => unreachable!("the SOA record has SOA data"),
+            };
+
+            // Records are in canonical order, so the apex records come first.
+            let mut expected: Vec<u16> = reader
+                .generated_records()
+                .iter()
+                .take_while(|r| r.rname == soa.rname)
+                .filter_map(
+                    |record| match OldParsedRecord::from(record.clone()).data() {
+                        ZoneRecordData::Dnskey(dnskey) => Some(dnskey.key_tag()),
+                        _ => None,
+                    },
+                )
+                .collect();
+            expected.sort();
+            expected.dedup();
+
+            (Serial(soa.rdata.serial.into()), expected, mname)
+        };
+
+        let nameservers = match center
+            .key_manager
+            .on_propagation(center, &zone, &expected, mname)
+            .await
+        {
+            Ok(nameservers) => nameservers,
+            Err(err) => return Json(Err(err)),
+        };
+
+        Json(Ok(KeyPropagationResult {
+            serial,
+            expected,
+            nameservers,
+        }))
+    }
+
+    /// Dump the `dnst keyset` state of a zone, for debugging.
+    ///
+    /// This is only served if `remote-control.debug-endpoints` is enabled.
+    async fn key_state(
+        State(state): State<Arc<HttpServer>>,
+        Path(zone): Path<Name<Bytes>>,
+    ) -> Json<Result<KeySetStateDump, String>> {
+        let center = &state.center;
+        if !center.config.remote_control.debug_endpoints {
+            return Json(Err(
+                "Debugging endpoints are disabled; set 'debug-endpoints = true' \
+                 under '[remote-control]' to enable them"
+                    .into(),
+            ));
+        }
+        if center::get_zone(center, &zone).is_none() {
+            return Json(Err(format!("Zone '{zone}' does not exist")));
+        }
+        let path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone);
+
+        let text = match tokio::fs::read_to_string(&path).await {
+            Ok(text) => text,
+            Err(err) => return Json(Err(format!("Could not read '{path}': {err}"))),
+        };
+        // Parse the state, so that a corrupt file is reported as such, but
+        // keep all of its fields, including those Cascade does not use.
+        let state = match serde_json::from_str::<serde_json::Value>(&text) {
+            Ok(value) => {
+                serde_json::to_string_pretty(&value).expect("a parsed JSON value can be serialized")
+            }
+            Err(err) => return Json(Err(format!("Could not parse '{path}': {err}"))),
+        };
+
+        Json(Ok(KeySetStateDump { path, state }))
+    }
+
     async fn status_keys(State(state): State<Arc<HttpServer>>) -> Json<KeyStatusResult> {
+        // Reading and parsing the keyset files of every zone blocks, so do
+        // it off the Tokio runtime.
+        let center = state.center.clone();
+        let res = tokio::task::spawn_blocking(move || Self::collect_key_status(&center))
+            .await
+            .expect("collecting the key status should not panic");
+
+        Json(res)
+    }
+
+    /// List when each zone is scheduled to be re-signed.
+    async fn status_resign_schedule(
+        State(state): State<Arc<HttpServer>>,
+    ) -> Json<ResignScheduleResult> {
+        let state = state.center.state.lock().unwrap();
+
+        let mut zones = state
+            .zones
+            .iter()
+            .filter_map(|ZoneByName(zone)| {
+                let zone_state = zone.read();
+                let policy = zone_state.policy.as_ref()?;
+                Some(ZoneResignSchedule {
+                    zone: zone.name.clone(),
+                    scheduled: zone_state.signer.scheduled_resign_time,
+                    last_refresh: SystemTime::UNIX_EPOCH
+                        + Duration::from(zone_state.last_signature_refresh.clone()),
+                    refresh_interval: policy.signer.signature_refresh_interval,
+                    min_expiration: zone_state.min_expiration.clone().map(|expiration| {
+                        SystemTime::UNIX_EPOCH + Duration::from_secs(expiration.into_int().into())
+                    }),
+                    sig_remain_time: policy.signer.sig_remain_time,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // Unscheduled zones are listed last.
+        zones.sort_by_key(|zone| (zone.scheduled.is_none(), zone.scheduled));
+
+        Json(ResignScheduleResult { zones })
+    }
+
+    fn collect_key_status(center: &Center) -> KeyStatusResult {
         #[derive(Deserialize)]
         struct KeySetConfig {
             ksk_validity: Option<Duration>,
@@ -1222,9 +2827,9 @@ impl HttpServer {
             autoremove: bool,
         }
 
-        let keys_dir = &state.center.config.keys_dir;
+        let keys_dir = &center.config.keys_dir;
 
-        let state = state.center.state.lock().unwrap();
+        let state = center.state.lock().unwrap();
 
         let now = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
@@ -1280,7 +2885,7 @@ impl HttpServer {
                     if ksc.autoremove {
                         "stale (will be removed automatically)".into()
                     } else {
-                        "state (must be removed manually)".into()
+                        "retired (must be removed manually)".into()
                     }
                 } else if let Some(opt_validity) = validity {
                     if let Some(validity) = opt_validity {
@@ -1329,7 +2934,7 @@ impl HttpServer {
         // Sort the zones alphabetically for a predictable order
         zones.sort_by(|a, b| a.zone.cmp(&b.zone));
 
-        Json(KeyStatusResult { expirations, zones })
+        KeyStatusResult { expirations, zones }
     }
 
     async fn tsig_key_add(
@@ -1420,6 +3025,7 @@ impl HttpServer {
                 .publication_nameservers
                 .iter()
                 .chain(policy.latest.server.outbound.provide_xfr_to.iter())
+                .chain(policy.latest.server.outbound.provide_review_xfr_to.iter())
                 .chain(policy.latest.server.outbound.send_notify_to.iter())
                 .filter_map(|acl| acl.tsig_key_name.as_ref())
                 .peekable();
@@ -1572,7 +3178,7 @@ impl HttpServer {
         let pool = match ConnectionManager::create_connection_pool(
             server_id.clone(),
             Arc::new(conn_settings.clone()),
-            10,
+            KMIP_POOL_SIZE,
             Some(Duration::from_secs(60)),
             Some(Duration::from_secs(60)),
         ) {
@@ -1649,22 +3255,22 @@ impl HttpServer {
         let kmip_state = KmipServerState::from(req);
 
         info!("Writing to KMIP server file '{kmip_server_state_file}");
-        let f = match std::fs::File::create_new(kmip_server_state_file.clone()) {
-            Ok(f) => f,
+        let json = match serde_json::to_vec_pretty(&kmip_state) {
+            Ok(json) => json,
             Err(err) => {
-                return Json(Err(
-                    HsmServerAddError::KmipServerStateFileCouldNotBeCreated {
-                        path: kmip_server_state_file.into_string(),
-                        err: err.to_string(),
-                    },
-                ));
+                return Json(Err(HsmServerAddError::KmipServerStateFileCouldNotBeSaved {
+                    path: kmip_server_state_file.into_string(),
+                    err: err.to_string(),
+                }));
             }
         };
-        if let Err(err) = serde_json::to_writer_pretty(&f, &kmip_state) {
-            return Json(Err(HsmServerAddError::KmipServerStateFileCouldNotBeSaved {
-                path: kmip_server_state_file.into_string(),
-                err: err.to_string(),
-            }));
+        if let Err(err) = crate::util::write_new_file(&kmip_server_state_file, &json) {
+            return Json(Err(
+                HsmServerAddError::KmipServerStateFileCouldNotBeCreated {
+                    path: kmip_server_state_file.into_string(),
+                    err: err.to_string(),
+                },
+            ));
         }
 
         Json(Ok(HsmServerAddResult { vendor_id }))
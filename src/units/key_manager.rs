@@ -159,34 +159,42 @@
 //   PKCS#11 C_SetAttributeValue(CKA_LABEL)
 
 use crate::api;
-use crate::api::keyset::{KeyRollCommand, KeyRollVariant};
+use crate::api::keyset::{KeyRollCommand, KeyRollVariant, RolloverWait};
 use crate::api::{FileKeyImport, KeyImport, KmipKeyImport};
 use crate::center::{Center, ZoneAddError, get_zone};
 use crate::manager::record_zone_event;
-use crate::policy::{KeyParameters, PolicyVersion};
+use crate::policy::{AutoConfig, KeyManagerPolicy, KeyParameters, PolicyVersion};
 use crate::signer::ResigningTrigger;
+use crate::signer::queue::SigningPriority;
 use crate::units::http_server::KmipServerState;
+use crate::units::zone_signer::{KeySetState, faketime};
 use crate::util::AbortOnDrop;
 use crate::zone::{HistoricalEvent, Zone};
 use bytes::Bytes;
 use camino::{Utf8Path, Utf8PathBuf};
 use core::time::Duration;
-use domain::base::Name;
-use domain::dnssec::sign::keys::keyset::{KeySet, UnixTime};
-use domain::rdata::dnssec::Timestamp;
+use domain::base::iana::Rcode;
+use domain::base::{MessageBuilder, Name, Rtype, ToName};
+use domain::dnssec::sign::keys::keyset::{KeySet, KeyType, UnixTime};
+use domain::net::client::protocol::{TcpConnect, UdpConnect};
+use domain::net::client::request::{RequestMessage, SendRequest};
+use domain::net::client::{self, dgram_stream};
+use domain::rdata::Dnskey;
+use domain::tsig;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::{VarError, var};
 use std::ffi::OsStr;
 use std::fmt::Formatter;
 use std::fs::{File, OpenOptions, metadata};
-use std::io::{BufReader, BufWriter, ErrorKind, Seek, SeekFrom, Write};
+use std::io::{BufReader, ErrorKind};
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::process::Output;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::Instant;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
 //------------ KeyManager ----------------------------------------------------
 
@@ -205,17 +213,7 @@ impl KeyManager {
 
     /// Launch the key manager.
     pub fn run(center: Arc<Center>) -> AbortOnDrop {
-        let faketime = match var("CASCADE_FAKETIME") {
-            Ok(val) => {
-                let timestamp = val
-                    .parse::<Timestamp>()
-                    .map_err(|e| panic!("cannot parse {e} as u32"))
-                    .expect("should not fail");
-                Some(timestamp.into())
-            }
-            Err(VarError::NotPresent) => None,
-            Err(e) => panic!("unable to look up CASCADE_FAKETIME: {e}"),
-        };
+        let faketime = faketime();
 
         // Perform periodic ticks in the background.
         AbortOnDrop::from(tokio::task::spawn({
@@ -242,6 +240,21 @@ impl KeyManager {
 
         if let Err(err) = &res {
             error!("Registration of zone '{name}' failed: {err}");
+        } else if let Some(zone) = get_zone(&center, &name) {
+            // Imported keys may collide with each other or with the keys
+            // generated for the zone. Report this now, as the periodic check
+            // does not report collisions that exist when it first sees a
+            // zone.
+            let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &name);
+            match KeySetInfo::try_from(&state_path) {
+                Ok(new_info) => {
+                    report_key_tag_collisions(&center, &zone, None, &new_info);
+                    if let Ok(mut ks_info) = self.ks_info.try_lock() {
+                        let _ = ks_info.insert(name.clone(), new_info);
+                    }
+                }
+                Err(err) => error!("[KM]: {err}"),
+            }
         }
 
         res
@@ -250,7 +263,7 @@ impl KeyManager {
     pub async fn on_roll_key(
         &self,
         center: &Arc<Center>,
-        zone: &Zone,
+        zone: &Arc<Zone>,
         roll_variant: KeyRollVariant,
         roll_cmd: KeyRollCommand,
     ) -> Result<(), String> {
@@ -290,13 +303,84 @@ impl KeyManager {
             return Err(format_cmd_error(&err, output));
         }
 
+        self.after_operator_key_change(&center, zone).await;
+        Ok(())
+    }
+
+    /// Compute how long a key roll should wait for caches to expire.
+    ///
+    /// Resolvers may cache any record of the signed zone, including its
+    /// signatures and negative responses, for as long as its TTL allows. A
+    /// margin is added to account for clock differences and propagation
+    /// delays. The TTL of the DS RRset at the parent is not known here, so it
+    /// is not included. Returns `None` if the zone has not been published.
+    pub async fn rollover_wait(center: &Arc<Center>, zone: &Arc<Zone>) -> Option<RolloverWait> {
+        let viewer = center.publication_server.viewer(zone)?;
+        let viewer = viewer.read_owned().await;
+
+        // This walks the whole zone, so don't hold up the runtime for it.
+        tokio::task::spawn_blocking(move || {
+            let reader = viewer.read()?;
+            let max_ttl = reader
+                .all_records()
+                .map(|r| r.ttl().as_secs())
+                .chain([u32::from(reader.soa().ttl)])
+                .max()
+                .unwrap_or_default();
+            let margin = (max_ttl / 10).max(ROLLOVER_WAIT_MIN_MARGIN);
+            Some(RolloverWait { max_ttl, margin })
+        })
+        .await
+        .expect("computing the rollover wait should not panic")
+    }
+
+    /// Move the keys of a zone to a KMIP server.
+    ///
+    /// The KMIP server is made the default for new keys of the zone, and an
+    /// algorithm roll is started to replace all of the zone's keys. The roll
+    /// then proceeds as usual, eventually retiring the old keys.
+    pub async fn on_migrate_keys(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+        to_hsm: String,
+    ) -> Result<(), String> {
+        let kmip_server = Self::read_kmip_server(center, &to_hsm)?;
+
+        // The server may already be known to the key set, e.g. if the zone
+        // used it before; then selecting it below still works.
+        if let Err(err) = Self::add_kmip_server(center, zone.name.clone(), kmip_server).await {
+            warn!(
+                "Could not add KMIP server '{to_hsm}' to the key set of zone '{}': {err}",
+                zone.name
+            );
+        }
+
+        let mut cmd = Self::keyset_cmd(center, zone.name.clone(), RecordingMode::Record);
+        cmd.arg("kmip").arg("set-default-server").arg(&to_hsm);
+        if let Err(KeySetCommandError { err, output, .. }) = cmd.output().await {
+            error!("selecting the KMIP server for new keys failed: {err}");
+            return Err(format_cmd_error(&err, output));
+        }
+
+        let mut cmd = Self::keyset_cmd(center, zone.name.clone(), RecordingMode::Record);
+        cmd.arg("algorithm").arg("start-roll");
+        if let Err(KeySetCommandError { err, output, .. }) = cmd.output().await {
+            error!("starting the key roll for the migration failed: {err}");
+            return Err(format!(
+                "new keys will be generated on KMIP server '{to_hsm}', but the key roll could not be started: {}",
+                format_cmd_error(&err, output)
+            ));
+        }
+
+        self.after_operator_key_change(center, zone).await;
         Ok(())
     }
 
     pub async fn on_remove_key(
         &self,
         center: &Arc<Center>,
-        zone: &Zone,
+        zone: &Arc<Zone>,
         key: String,
         force: bool,
         continue_flag: bool,
@@ -319,9 +403,259 @@ impl KeyManager {
             return Err(format_cmd_error(&err, output));
         }
 
+        self.after_operator_key_change(&center, zone).await;
         Ok(())
     }
 
+    /// Remove all retired (stale) keys of a zone.
+    ///
+    /// Retired keys are kept if the zone's policy disables `auto-remove`, or
+    /// until `auto-remove-delay` has passed. They are no longer used for
+    /// signing. Returns the references of the removed keys.
+    pub async fn on_purge_retired(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+    ) -> Result<Vec<String>, String> {
+        let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+        let keyset_state = tokio::task::spawn_blocking(move || KeySetState::load(&state_path))
+            .await
+            .expect("loading the keyset state should not panic")
+            .map_err(|err| err.to_string())?;
+
+        let mut retired: Vec<String> = keyset_state
+            .keyset
+            .keys()
+            .iter()
+            .filter(|(_, key)| match key.keytype() {
+                KeyType::Ksk(s) | KeyType::Zsk(s) | KeyType::Include(s) => s.stale(),
+                KeyType::Csk(s, _) => s.stale(),
+            })
+            .map(|(pubref, _)| pubref.clone())
+            .collect();
+        retired.sort();
+
+        let mut removed = Vec::new();
+        for key in retired {
+            let mut cmd = Self::keyset_cmd(center, zone.name.clone(), RecordingMode::Record);
+            cmd.arg("remove-key").arg(&key);
+            if let Err(KeySetCommandError { err, output, .. }) = cmd.output().await {
+                error!("key removal command failed: {err}");
+                self.after_operator_key_change(center, zone).await;
+                return Err(format!(
+                    "removed {} of the retired keys, then failed to remove key {key}: {}",
+                    removed.len(),
+                    format_cmd_error(&err, output)
+                ));
+            }
+            removed.push(key);
+        }
+
+        self.after_operator_key_change(center, zone).await;
+        Ok(removed)
+    }
+
+    /// Pause or resume automatic key rolls for a zone.
+    ///
+    /// This overrides the `auto-*` settings of the zone's policy until key
+    /// rolls are resumed again.
+    pub async fn on_set_auto_paused(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+        paused: bool,
+    ) -> Result<(), String> {
+        let (policy, was_paused) = {
+            let state = zone.read();
+            (state.policy.clone(), state.key_rolls_paused)
+        };
+        let Some(policy) = policy else {
+            return Err(format!("Zone '{}' has no policy", zone.name));
+        };
+        if was_paused == paused {
+            return Err(match paused {
+                true => format!(
+                    "Automatic key rolls are already paused for zone '{}'",
+                    zone.name
+                ),
+                false => format!(
+                    "Automatic key rolls are not paused for zone '{}'",
+                    zone.name
+                ),
+            });
+        }
+
+        for c in auto_to_commands(&policy.key_manager, paused) {
+            let mut cmd = Self::keyset_cmd(center, zone.name.clone(), RecordingMode::Record);
+            cmd.arg("set");
+            for a in c {
+                cmd.arg(a);
+            }
+            if let Err(KeySetCommandError { err, output, .. }) = cmd.output().await {
+                let msg = format_cmd_error(&err, output);
+                error!("{msg}");
+                return Err(msg);
+            }
+        }
+
+        zone.write(center).key_rolls_paused = paused;
+        Ok(())
+    }
+
+    /// Query the publication nameservers of a zone for its DNSKEY RRset.
+    ///
+    /// Each nameserver's DNSKEY RRset is compared to the `expected` key tags.
+    /// If the policy configures no publication nameservers, the nameserver in
+    /// the SOA MNAME field is queried, like `dnst keyset` does for the report
+    /// step of a key roll.
+    pub async fn on_propagation(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+        expected: &[u16],
+        mname: String,
+    ) -> Result<Vec<api::keyset::NameserverPropagation>, String> {
+        let Some(policy) = zone.read().policy.clone() else {
+            return Err(format!("Zone '{}' has no policy", zone.name));
+        };
+
+        // Each nameserver is listed with how to query it (its address and
+        // TSIG key, if any), or with why it cannot be queried.
+        let mut nameservers = Vec::new();
+        if policy.key_manager.publication_nameservers.is_empty() {
+            match tokio::net::lookup_host((mname.as_str(), 53)).await {
+                Ok(addrs) => {
+                    nameservers.extend(addrs.map(|addr| (mname.clone(), Ok((addr, None)))));
+                }
+                Err(err) => nameservers.push((
+                    mname.clone(),
+                    Err((None, format!("cannot resolve the SOA MNAME: {err}"))),
+                )),
+            }
+        } else {
+            let state = center.state.lock().unwrap();
+            for ns in &policy.key_manager.publication_nameservers {
+                let target = match &ns.tsig_key_name {
+                    None => Ok((ns.addr, None)),
+                    Some(name) => match state.tsig_store.get(name) {
+                        Some(key) => Ok((ns.addr, Some(key.inner.clone()))),
+                        None => Err((Some(ns.addr), format!("TSIG key '{name}' does not exist"))),
+                    },
+                };
+                nameservers.push((ns.to_string(), target));
+            }
+        }
+
+        let queries = nameservers
+            .into_iter()
+            .map(|(nameserver, target)| async move {
+                let (addr, result) = match target {
+                    Ok((addr, tsig_key)) => (
+                        Some(addr),
+                        query_dnskey_tags(addr, tsig_key, &zone.name).await,
+                    ),
+                    Err((addr, err)) => (addr, Err(err)),
+                };
+                let outcome = match result {
+                    Ok(seen) => propagation_outcome(expected, &seen),
+                    Err(error) => api::keyset::PropagationOutcome::Failed { error },
+                };
+                api::keyset::NameserverPropagation {
+                    nameserver,
+                    addr,
+                    outcome,
+                }
+            });
+        Ok(futures_util::future::join_all(queries).await)
+    }
+
+    pub async fn on_import_keys(
+        &self,
+        center: &Arc<Center>,
+        zone: &Arc<Zone>,
+        key_imports: &[KeyImport],
+    ) -> Result<(), String> {
+        // Each key is imported by a separate command. Keep the key set state
+        // from before, so that a failing import does not leave the keys
+        // before it imported.
+        let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+        let before = tokio::fs::read(&state_path)
+            .await
+            .map_err(|err| format!("Unable to read key set state '{state_path}': {err}"))?;
+
+        for (i, args) in imports_to_commands(key_imports).into_iter().enumerate() {
+            let mut cmd = Self::keyset_cmd(center, zone.name.clone(), RecordingMode::Record);
+
+            for arg in args {
+                cmd.arg(arg);
+            }
+
+            if let Err(KeySetCommandError { err, output, .. }) = cmd.output().await {
+                error!("key import command failed: {err}");
+                if i > 0 {
+                    let path = state_path.clone();
+                    let res = tokio::task::spawn_blocking(move || {
+                        crate::util::write_file(&path, &before)
+                    })
+                    .await
+                    .expect("restoring the key set state should not panic");
+                    match res {
+                        Ok(()) => info!(
+                            "[KM]: Undid the {i} key import(s) before the failure for zone '{}'",
+                            zone.name
+                        ),
+                        Err(err) => error!(
+                            "[KM]: Unable to restore key set state '{state_path}' after a failed import: {err}"
+                        ),
+                    }
+                }
+                return Err(format_cmd_error(&err, output));
+            }
+        }
+
+        self.after_operator_key_change(center, zone).await;
+        Ok(())
+    }
+
+    /// React to an operator command that may have changed a zone's keys.
+    ///
+    /// If the key set state changed, the zone is re-signed with interactive
+    /// priority, so that it does not wait behind background re-signing
+    /// operations. The cached key set information is updated so that the
+    /// periodic check does not enqueue another re-sign for the same change.
+    async fn after_operator_key_change(&self, center: &Arc<Center>, zone: &Arc<Zone>) {
+        let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &zone.name);
+        let new_info = match KeySetInfo::try_from(&state_path) {
+            Ok(info) => info,
+            Err(err) => {
+                error!("[KM]: {err}");
+                return;
+            }
+        };
+
+        // If the periodic check is busy, don't wait for it; at worst, it will
+        // enqueue a redundant re-sign. It will also report any key tag
+        // collisions the command caused.
+        if let Ok(mut ks_info) = self.ks_info.try_lock() {
+            let old_info = ks_info.get(&zone.name);
+            if old_info
+                .is_some_and(|info| info.keyset_state_modified == new_info.keyset_state_modified)
+            {
+                // The command did not change the key set state.
+                return;
+            }
+            report_key_tag_collisions(center, zone, old_info, &new_info);
+            let _ = ks_info.insert(zone.name.clone(), new_info);
+        }
+
+        zone.write_handle(center)
+            .signer()
+            .enqueue_resign_with_priority(
+                ResigningTrigger::KEYS_CHANGED,
+                SigningPriority::Interactive,
+            );
+    }
+
     pub async fn on_get_key(
         &self,
         center: &Arc<Center>,
@@ -405,11 +739,12 @@ impl KeyManager {
         }
 
         let zone_name = zone.name.clone();
+        let paused = zone.read().key_rolls_paused;
 
         tokio::spawn(async move {
             // Keep it simple, just send all config items to keyset even
             // if they didn't change.
-            let config_commands = policy_to_commands(&center, &new);
+            let config_commands = policy_to_commands(&center, &zone_name, &new, paused);
             for c in config_commands {
                 let mut cmd = Self::keyset_cmd(&center, zone_name.clone(), RecordingMode::Record);
                 cmd.arg("set");
@@ -453,9 +788,6 @@ impl KeyManager {
             kmip_server_id = policy.latest.key_manager.hsm_server_id.clone();
         };
 
-        let kmip_server_state_dir = &center.config.kmip_server_state_dir;
-        let kmip_credentials_store_path = &center.config.kmip_credentials_store_path;
-
         let state_path = mk_dnst_keyset_state_file_path(&center.config.keys_dir, &name);
 
         let mut cmd = Self::keyset_cmd(center, name.clone(), RecordingMode::Record);
@@ -476,72 +808,16 @@ impl KeyManager {
         // the `dnst keyset create`d state already exists?
 
         if let Some(kmip_server_id) = kmip_server_id {
-            let kmip_server_state_path = kmip_server_state_dir.join(kmip_server_id);
-
-            debug!("Reading KMIP server state from '{kmip_server_state_path}'");
-            let f = File::open(&kmip_server_state_path)
-                .map_err(|err| ZoneAddError::Other(format!("Unable to open KMIP server state file '{kmip_server_state_path}' for reading: {err}")))?;
-            let kmip_server: KmipServerState = serde_json::from_reader(f).map_err(|err| {
-                ZoneAddError::Other(format!(
-                    "Unable to read KMIP server state from file '{kmip_server_state_path}': {err}"
-                ))
-            })?;
-
-            let KmipServerState {
-                server_id,
-                ip_host_or_fqdn,
-                port,
-                insecure,
-                connect_timeout,
-                read_timeout,
-                write_timeout,
-                max_response_bytes,
-                key_label_prefix,
-                key_label_max_bytes,
-                has_credentials,
-            } = kmip_server;
-
-            let mut cmd = Self::keyset_cmd(center, name.clone(), RecordingMode::Record);
-
-            cmd.arg("kmip")
-                .arg("add-server")
-                .arg(server_id.clone())
-                .arg(ip_host_or_fqdn)
-                .arg("--port")
-                .arg(port.to_string())
-                .arg("--connect-timeout")
-                .arg(format!("{}s", connect_timeout.as_secs()))
-                .arg("--read-timeout")
-                .arg(format!("{}s", read_timeout.as_secs()))
-                .arg("--write-timeout")
-                .arg(format!("{}s", write_timeout.as_secs()))
-                .arg("--max-response-bytes")
-                .arg(max_response_bytes.to_string())
-                .arg("--key-label-max-bytes")
-                .arg(key_label_max_bytes.to_string());
-
-            if insecure {
-                cmd.arg("--insecure");
-            }
-
-            if has_credentials {
-                cmd.arg("--credential-store")
-                    .arg(kmip_credentials_store_path.as_str());
-            }
-
-            if let Some(key_label_prefix) = key_label_prefix {
-                cmd.arg("--key-label-prefix").arg(key_label_prefix);
-            }
-
-            // TODO: --client-cert, --client-key, --server-cert and --ca-cert
-            cmd.output()
+            let kmip_server =
+                Self::read_kmip_server(center, &kmip_server_id).map_err(ZoneAddError::Other)?;
+            Self::add_kmip_server(center, name.clone(), kmip_server)
                 .await
-                .map_err(|err| ZoneAddError::Other(err.err))?;
+                .map_err(ZoneAddError::Other)?;
         }
 
         // Pass `set` and `import` commands to `dnst keyset`.
         let config_commands = imports_to_commands(key_imports).into_iter().chain(
-            policy_to_commands(center, &policy.latest)
+            policy_to_commands(center, &name, &policy.latest, false)
                 .into_iter()
                 .chain({
                     match var("CASCADE_FAKETIME") {
@@ -586,6 +862,80 @@ impl KeyManager {
         Ok(())
     }
 
+    /// Read the stored details of a KMIP server.
+    ///
+    /// These are stored by `cascade hsm add`, under the KMIP server state
+    /// directory, in a file named after the server ID.
+    fn read_kmip_server(center: &Arc<Center>, server_id: &str) -> Result<KmipServerState, String> {
+        let kmip_server_state_path = center.config.kmip_server_state_dir.join(server_id);
+
+        debug!("Reading KMIP server state from '{kmip_server_state_path}'");
+        let f = File::open(&kmip_server_state_path).map_err(|err| {
+            format!(
+                "Unable to open KMIP server state file '{kmip_server_state_path}' for reading: {err}"
+            )
+        })?;
+        serde_json::from_reader(f).map_err(|err| {
+            format!("Unable to read KMIP server state from file '{kmip_server_state_path}': {err}")
+        })
+    }
+
+    /// Make a KMIP server known to the key set of a zone.
+    async fn add_kmip_server(
+        center: &Arc<Center>,
+        name: Name<Bytes>,
+        kmip_server: KmipServerState,
+    ) -> Result<(), String> {
+        let KmipServerState {
+            server_id,
+            ip_host_or_fqdn,
+            port,
+            insecure,
+            connect_timeout,
+            read_timeout,
+            write_timeout,
+            max_response_bytes,
+            key_label_prefix,
+            key_label_max_bytes,
+            has_credentials,
+        } = kmip_server;
+
+        let mut cmd = Self::keyset_cmd(center, name, RecordingMode::Record);
+
+        cmd.arg("kmip")
+            .arg("add-server")
+            .arg(server_id.clone())
+            .arg(ip_host_or_fqdn)
+            .arg("--port")
+            .arg(port.to_string())
+            .arg("--connect-timeout")
+            .arg(format!("{}s", connect_timeout.as_secs()))
+            .arg("--read-timeout")
+            .arg(format!("{}s", read_timeout.as_secs()))
+            .arg("--write-timeout")
+            .arg(format!("{}s", write_timeout.as_secs()))
+            .arg("--max-response-bytes")
+            .arg(max_response_bytes.to_string())
+            .arg("--key-label-max-bytes")
+            .arg(key_label_max_bytes.to_string());
+
+        if insecure {
+            cmd.arg("--insecure");
+        }
+
+        if has_credentials {
+            cmd.arg("--credential-store")
+                .arg(center.config.kmip_credentials_store_path.as_str());
+        }
+
+        if let Some(key_label_prefix) = key_label_prefix {
+            cmd.arg("--key-label-prefix").arg(key_label_prefix);
+        }
+
+        // TODO: --client-cert, --client-key, --server-cert and --ca-cert
+        cmd.output().await.map(|_| ()).map_err(|err| err.err)
+    }
+
     /// Create a keyset command with the config file for the given zone.
     fn keyset_cmd(
         center: &Arc<Center>,
@@ -651,6 +1001,7 @@ impl KeyManager {
                         continue;
                     }
                 };
+                report_key_tag_collisions(center, zone, Some(&*info), &new_info);
                 let _ = ks_info.insert(zone.name.clone(), new_info);
                 zone.write_handle(center)
                     .signer()
@@ -691,6 +1042,7 @@ impl KeyManager {
                         // Something happened. Update ks_info and signal the
                         // signer.
                         // let new_info = get_keyset_info(&state_path);
+                        report_key_tag_collisions(center, zone, Some(&*info), &new_info);
                         let _ = ks_info.insert(zone.name.clone(), new_info);
                         zone.write_handle(center)
                             .signer()
@@ -755,6 +1107,9 @@ pub struct KeySetInfo {
     keyset_state_modified: UnixTime,
     cron_next: Option<UnixTime>,
     retries: u32,
+
+    /// The references of keys that share a key tag, by key tag.
+    key_tag_collisions: BTreeMap<u16, Vec<String>>,
 }
 
 impl KeySetInfo {
@@ -805,14 +1160,84 @@ impl TryFrom<&Utf8PathBuf> for KeySetInfo {
             keyset_state_modified,
             cron_next: state.cron_next,
             retries: 0,
+            key_tag_collisions: key_tag_collisions(&state.keyset),
         })
     }
 }
 
+/// Find the keys in a key set that share a key tag.
+///
+/// Stale keys are ignored, as they are no longer published. The key
+/// references are sorted, so that the result does not depend on the order in
+/// which the key set stores its keys.
+fn key_tag_collisions(keyset: &KeySet) -> BTreeMap<u16, Vec<String>> {
+    let mut by_tag = BTreeMap::<u16, Vec<String>>::new();
+    for (pubref, key) in keyset.keys() {
+        let stale = match key.keytype() {
+            KeyType::Ksk(s) | KeyType::Zsk(s) | KeyType::Include(s) => s.stale(),
+            KeyType::Csk(s, _) => s.stale(),
+        };
+        if !stale {
+            by_tag
+                .entry(key.key_tag())
+                .or_default()
+                .push(pubref.clone());
+        }
+    }
+    by_tag.retain(|_, keys| keys.len() > 1);
+    for keys in by_tag.values_mut() {
+        keys.sort();
+    }
+    by_tag
+}
+
+/// Warn about key tag collisions that are new since `old_info`.
+///
+/// A collision is new if it involves a key that did not collide before, e.g.
+/// because it was just imported or generated for a key roll. Keys with
+/// colliding tags are kept as they are: `dnst` offers no way to replace a
+/// single generated key, and imported keys are the operator's choice. The
+/// collision is logged and recorded in the zone history instead.
+fn report_key_tag_collisions(
+    center: &Arc<Center>,
+    zone: &Arc<Zone>,
+    old_info: Option<&KeySetInfo>,
+    new_info: &KeySetInfo,
+) {
+    for (key_tag, keys) in &new_info.key_tag_collisions {
+        let old_keys = old_info.and_then(|info| info.key_tag_collisions.get(key_tag));
+        if keys
+            .iter()
+            .all(|key| old_keys.is_some_and(|old_keys| old_keys.contains(key)))
+        {
+            continue;
+        }
+
+        warn!(
+            "Keys {} of zone '{}' share key tag {key_tag}; consider rolling or removing one of them",
+            keys.join(", "),
+            zone.name
+        );
+        record_zone_event(
+            center,
+            zone,
+            HistoricalEvent::KeyTagCollision {
+                key_tag: *key_tag,
+                keys: keys.clone(),
+            },
+            None,
+        );
+    }
+}
+
 // Maximum number of times to try the cron command when the state file does
 // not change.
 const CRON_MAX_RETRIES: u32 = 5;
 
+// The minimum safety margin for key roll waits, in seconds. The margin is
+// otherwise a tenth of the longest relevant TTL.
+const ROLLOVER_WAIT_MIN_MARGIN: u32 = 60;
+
 fn file_modified(filename: impl AsRef<Path>) -> Result<UnixTime, String> {
     let md = metadata(&filename).map_err(|err| {
         format!(
@@ -837,7 +1262,12 @@ macro_rules! strs {
     };
 }
 
-fn policy_to_commands(center: &Arc<Center>, policy: &PolicyVersion) -> Vec<Vec<String>> {
+fn policy_to_commands(
+    center: &Arc<Center>,
+    zone: &Name<Bytes>,
+    policy: &PolicyVersion,
+    paused: bool,
+) -> Vec<Vec<String>> {
     let km = &policy.key_manager;
 
     let mut algorithm_cmd = vec!["algorithm".to_string()];
@@ -854,8 +1284,11 @@ fn policy_to_commands(center: &Arc<Center>, policy: &PolicyVersion) -> Vec<Vec<S
         | KeyParameters::Ed448 => algorithm_cmd.push(km.algorithm.to_string()),
     };
 
-    let validity = |x| match x {
-        Some(validity) => format!("{validity}s"),
+    // Shorten validities by the zone's jitter, to spread out the key rolls
+    // of zones sharing this policy, but by at most half.
+    let jitter = km.validity_jitter_for(zone);
+    let validity = |x: Option<u32>| match x {
+        Some(validity) => format!("{}s", validity - jitter.min(validity / 2)),
         None => "off".to_string(),
     };
 
@@ -890,34 +1323,6 @@ fn policy_to_commands(center: &Arc<Center>, policy: &PolicyVersion) -> Vec<Vec<S
         strs!["ksk-validity", validity(km.ksk_validity)],
         strs!["zsk-validity", validity(km.zsk_validity)],
         strs!["csk-validity", validity(km.csk_validity)],
-        strs![
-            "auto-ksk",
-            km.auto_ksk.start,
-            km.auto_ksk.report,
-            km.auto_ksk.expire,
-            km.auto_ksk.done,
-        ],
-        strs![
-            "auto-zsk",
-            km.auto_zsk.start,
-            km.auto_zsk.report,
-            km.auto_zsk.expire,
-            km.auto_zsk.done,
-        ],
-        strs![
-            "auto-csk",
-            km.auto_csk.start,
-            km.auto_csk.report,
-            km.auto_csk.expire,
-            km.auto_csk.done,
-        ],
-        strs![
-            "auto-algorithm",
-            km.auto_algorithm.start,
-            km.auto_algorithm.report,
-            km.auto_algorithm.expire,
-            km.auto_algorithm.done,
-        ],
         strs![
             "dnskey-inception-offset",
             seconds(km.dnskey_inception_offset),
@@ -936,9 +1341,108 @@ fn policy_to_commands(center: &Arc<Center>, policy: &PolicyVersion) -> Vec<Vec<S
         ],
         publication_nameservers_cmd,
     ]);
+    cmds.extend(auto_to_commands(km, paused));
     cmds
 }
 
+/// Build the `set auto-*` commands for a key manager policy.
+///
+/// If automatic key rolls are paused for the zone, every automatic step is
+/// disabled regardless of the policy.
+fn auto_to_commands(km: &KeyManagerPolicy, paused: bool) -> Vec<Vec<String>> {
+    let auto = |name: &str, config: &AutoConfig| {
+        let enabled = |step: bool| step && !paused;
+        strs![
+            name,
+            enabled(config.start),
+            enabled(config.report),
+            enabled(config.expire),
+            enabled(config.done),
+        ]
+    };
+
+    vec![
+        auto("auto-ksk", &km.auto_ksk),
+        auto("auto-zsk", &km.auto_zsk),
+        auto("auto-csk", &km.auto_csk),
+        auto("auto-algorithm", &km.auto_algorithm),
+    ]
+}
+
+/// Compare the DNSKEY key tags served by a nameserver to the published ones.
+fn propagation_outcome(expected: &[u16], seen: &[u16]) -> api::keyset::PropagationOutcome {
+    let missing: Vec<u16> = expected
+        .iter()
+        .filter(|t| !seen.contains(t))
+        .copied()
+        .collect();
+    let unexpected: Vec<u16> = seen
+        .iter()
+        .filter(|t| !expected.contains(t))
+        .copied()
+        .collect();
+    if missing.is_empty() && unexpected.is_empty() {
+        api::keyset::PropagationOutcome::Propagated
+    } else {
+        api::keyset::PropagationOutcome::Pending {
+            missing,
+            unexpected,
+        }
+    }
+}
+
+/// Query a nameserver for the DNSKEY RRset of a zone.
+///
+/// The sorted key tags of the DNSKEY records in the response are returned.
+async fn query_dnskey_tags(
+    addr: SocketAddr,
+    tsig_key: Option<Arc<tsig::Key>>,
+    apex: &Name<Bytes>,
+) -> Result<Vec<u16>, String> {
+    const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let mut msg = MessageBuilder::new_vec();
+    msg.header_mut().set_rd(false);
+    let mut msg = msg.question();
+    msg.push((apex, Rtype::DNSKEY)).unwrap();
+    let req = RequestMessage::new(msg).unwrap();
+
+    // DNSKEY RRsets can be large; retry over TCP if the response is truncated.
+    let (client, transport) =
+        dgram_stream::Connection::new(UdpConnect::new(addr), TcpConnect::new(addr));
+    tokio::spawn(transport.run());
+    let query = async {
+        match tsig_key {
+            Some(key) => {
+                let client = client::tsig::Connection::new(key, client);
+                client.send_request(req).get_response().await
+            }
+            None => client.send_request(req).get_response().await,
+        }
+    };
+    let response = tokio::time::timeout(QUERY_TIMEOUT, query)
+        .await
+        .map_err(|_| format!("no response within {} seconds", QUERY_TIMEOUT.as_secs()))?
+        .map_err(|err| err.to_string())?;
+
+    let rcode = response.header().rcode();
+    if rcode != Rcode::NOERROR {
+        return Err(format!("the nameserver responded with {rcode}"));
+    }
+
+    let mut tags = Vec::new();
+    let answer = response.answer().map_err(|err| err.to_string())?;
+    for record in answer.limit_to::<Dnskey<_>>() {
+        let record = record.map_err(|err| err.to_string())?;
+        if record.owner().name_eq(apex) {
+            tags.push(record.data().key_tag());
+        }
+    }
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
 //============ KMIP Credential Management ====================================
 // Copied from dnst keyset. TODO: Share the code via a separate Rust crate.
 
@@ -1006,12 +1510,8 @@ impl std::fmt::Display for KmipServerCredentialsFileMode {
 /// A KMIP server credential set file.
 #[derive(Debug)]
 pub struct KmipClientCredentialsFile {
-    /// The file from which the credentials were loaded, and will be saved
+    /// The path from which the credentials were loaded, and will be saved
     /// back to.
-    file: File,
-
-    /// The path from which the file was loaded. Used for generating error
-    /// messages.
     path: PathBuf,
 
     /// The actual set of loaded credentials.
@@ -1027,7 +1527,7 @@ impl KmipClientCredentialsFile {
     ///
     /// Optionally:
     ///   - Create the file if missing.
-    ///   - Keep the file open for writing back changes. See [`Self::save()`].
+    ///   - Check that the file can be written back to. See [`Self::save()`].
     pub fn new(path: &Path, mode: KmipServerCredentialsFileMode) -> Result<Self, String> {
         let (read, write, create) = match mode {
             KmipServerCredentialsFileMode::ReadOnly => (true, false, false),
@@ -1073,11 +1573,10 @@ impl KmipClientCredentialsFile {
             KmipClientCredentialsSet::default()
         };
 
-        // Save the path for use in generating error messages.
+        // Save the path for writing back changes.
         let path = path.to_path_buf();
 
         Ok(KmipClientCredentialsFile {
-            file,
             path,
             credentials,
             mode,
@@ -1085,37 +1584,25 @@ impl KmipClientCredentialsFile {
     }
 
     /// Write the credential set back to the file it was loaded from.
+    ///
+    /// The file is replaced atomically, so a crash while saving cannot leave
+    /// a truncated credentials file behind.
     pub fn save(&mut self) -> std::io::Result<()> {
-        // Ensure that writing happens at the start of the file.
-        self.file.seek(SeekFrom::Start(0))?;
-
-        // Use a buffered writer as writing JSON to a file directly is
-        // apparently very slow, even for small files.
-        //
-        // Enclose the use of the BufWriter in a block so that it is
-        // definitely no longer using the file when we next act on it.
-        {
-            let mut writer = BufWriter::new(&self.file);
-            serde_json::to_writer_pretty(&mut writer, &self.credentials).map_err(|e| {
-                std::io::Error::other(format!(
-                    "error writing KMIP credentials file {}: {e}",
-                    self.path.display()
-                ))
-            })?;
-
-            // Ensure that the BufWriter is flushed as advised by the
-            // BufWriter docs.
-            writer.flush()?;
-        }
-
-        // Truncate the file to the length of data we just wrote..
-        let pos = self.file.stream_position()?;
-        self.file.set_len(pos)?;
+        let json = serde_json::to_vec_pretty(&self.credentials).map_err(|e| {
+            std::io::Error::other(format!(
+                "error writing KMIP credentials file {}: {e}",
+                self.path.display()
+            ))
+        })?;
 
-        // Ensure that any write buffers are flushed.
-        self.file.flush()?;
+        let path = Utf8Path::from_path(&self.path).ok_or_else(|| {
+            std::io::Error::other(format!(
+                "KMIP credentials file path {} is not valid UTF-8",
+                self.path.display()
+            ))
+        })?;
 
-        Ok(())
+        crate::util::write_file(path, &json)
     }
 
     /// Does this credential set include credentials for the specified KMIP
@@ -1155,6 +1642,13 @@ impl KmipClientCredentialsFile {
     }
 }
 
+/// The maximum time a `dnst keyset` command may take.
+///
+/// This is generous, as generating keys on a slow HSM can take a while, but
+/// ensures that a hung `dnst` cannot block the key manager (or the HTTP API)
+/// forever.  The command is killed when the timeout expires.
+const KEYSET_COMMAND_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// A process command that doesn't block and records events in history.
 struct AsyncHistoricalCommand {
     cmd: std::process::Command,
@@ -1188,9 +1682,26 @@ impl AsyncHistoricalCommand {
         // we can execute it without blocking the Tokio runtime.
         let mut cmd = tokio::process::Command::from(self.cmd);
 
+        // Kill the command if it times out.
+        cmd.kill_on_drop(true);
+
         // Execute the command.
         debug!("Executing keyset command {cmd_string}");
-        let output = cmd.output().await.map_err(|msg| {
+        let output = tokio::time::timeout(KEYSET_COMMAND_TIMEOUT, cmd.output())
+            .await
+            .map_err(|_| {
+                let err = format!(
+                    "Keyset command '{cmd_string}' did not finish within {}s and was killed",
+                    KEYSET_COMMAND_TIMEOUT.as_secs()
+                );
+                error!("{err}");
+                KeySetCommandError {
+                    cmd: cmd_string.clone(),
+                    err,
+                    output: None,
+                }
+            })?;
+        let output = output.map_err(|msg| {
             let mut err = format!("Keyset command '{cmd_string}' could not be executed: {msg}",);
             if matches!(msg.kind(), ErrorKind::NotFound) {
                 err.push_str(&format!(" [path: {binary_path}]"));
@@ -1302,13 +1813,32 @@ impl KeySetCommand {
     }
 
     pub async fn output(&mut self) -> Result<Output, KeySetCommandError> {
+        let cmd = self.cmd.take().expect("Command has already been consumed");
+
+        // Run the command, and record its outcome, in a task of its own, so
+        // that it runs to completion (or times out) even if the caller stops
+        // waiting for it, e.g. because the HTTP request that caused it was
+        // abandoned.  Killing 'dnst keyset' halfway could leave its state
+        // file half-written.
+        let task = Self::run(
+            cmd,
+            self.name.clone(),
+            self.center.clone(),
+            self.recording_mode,
+        );
+        tokio::spawn(task)
+            .await
+            .expect("running a keyset command should not panic")
+    }
+
+    async fn run(
+        cmd: AsyncHistoricalCommand,
+        name: Name<Bytes>,
+        center: Arc<Center>,
+        recording_mode: RecordingMode,
+    ) -> Result<Output, KeySetCommandError> {
         let start = Instant::now();
-        let res = self
-            .cmd
-            .take()
-            .expect("Command has already been consumed")
-            .output()
-            .await;
+        let res = cmd.output().await;
         let elapsed = Instant::now().duration_since(start);
 
         let (res, history_event) = match res {
@@ -1318,7 +1848,7 @@ impl KeySetCommand {
                 warning,
             }) => {
                 // Determine whether and what to record in zone history
-                let record = match self.recording_mode {
+                let record = match recording_mode {
                     RecordingMode::DoNotRecord => false,
                     RecordingMode::Record => true,
                     RecordingMode::RecordOnlyOnWarningOrError => warning.is_some(),
@@ -1334,14 +1864,21 @@ impl KeySetCommand {
                 let err_string = err.err.to_string();
 
                 // Determine whether and what to record in zone history
-                let record = match self.recording_mode {
+                let record = match recording_mode {
                     RecordingMode::DoNotRecord => false,
                     RecordingMode::Record => true,
                     RecordingMode::RecordOnlyOnWarningOrError => true,
                 };
+                // Include stderr, which usually explains the failure.
+                let stderr = err
+                    .output
+                    .as_ref()
+                    .map(|o| String::from_utf8_lossy(&o.stderr).trim().to_string())
+                    .filter(|stderr| !stderr.is_empty());
                 let history_event = record.then_some(HistoricalEvent::KeySetError {
                     cmd: err.cmd.clone(),
                     err: err_string,
+                    stderr,
                     elapsed,
                 });
                 (Err(err), history_event)
@@ -1350,8 +1887,8 @@ impl KeySetCommand {
 
         if let Some(history_event) = history_event {
             // Record the error in the zone history
-            let zone = get_zone(&self.center, &self.name).unwrap();
-            record_zone_event(&self.center, &zone, history_event, None);
+            let zone = get_zone(&center, &name).unwrap();
+            record_zone_event(&center, &zone, history_event, None);
         }
 
         res
@@ -1392,3 +1929,53 @@ fn imports_to_commands(key_imports: &[KeyImport]) -> Vec<Vec<String>> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::keyset::PropagationOutcome;
+    use crate::policy::AutoConfig;
+    use crate::policy::file::v1::KeyManagerSpec;
+
+    use super::{auto_to_commands, propagation_outcome};
+
+    #[test]
+    fn paused_key_rolls_disable_every_automatic_step() {
+        let mut km = KeyManagerSpec::default().parse();
+        km.auto_zsk = AutoConfig {
+            start: false,
+            report: true,
+            expire: true,
+            done: false,
+        };
+
+        let commands = auto_to_commands(&km, false);
+        assert_eq!(commands.len(), 4);
+        assert_eq!(commands[0], ["auto-ksk", "true", "true", "true", "true"]);
+        assert_eq!(commands[1], ["auto-zsk", "false", "true", "true", "false"]);
+
+        // Pausing overrides the policy; resuming restores it.
+        for command in auto_to_commands(&km, true) {
+            assert_eq!(command[1..], ["false", "false", "false", "false"]);
+        }
+        assert_eq!(auto_to_commands(&km, false), commands);
+    }
+
+    #[test]
+    fn propagation_compares_key_tags() {
+        assert!(matches!(
+            propagation_outcome(&[1234, 5678], &[1234, 5678]),
+            PropagationOutcome::Propagated
+        ));
+
+        // A nameserver still serving the previous DNSKEY RRset.
+        let PropagationOutcome::Pending {
+            missing,
+            unexpected,
+        } = propagation_outcome(&[1234, 5678], &[1234, 4321])
+        else {
+            panic!("the nameserver serves other keys");
+        };
+        assert_eq!(missing, [5678]);
+        assert_eq!(unexpected, [4321]);
+    }
+}
@@ -6,11 +6,12 @@ use std::process::Stdio;
 use std::sync::Arc;
 
 use bytes::Bytes;
-use domain::base::iana::Class;
-use domain::base::{Name, Serial, ToName};
+use domain::base::iana::{Class, Opcode};
+use domain::base::{Name, Serial, Time48, ToName};
 use domain::net::server::ConnectionConfig;
 use domain::net::server::buf::VecBufSource;
 use domain::net::server::dgram::{self, DgramServer};
+use domain::net::server::message::Request;
 use domain::net::server::middleware::cookies::CookiesMiddlewareSvc;
 use domain::net::server::middleware::edns::EdnsMiddlewareSvc;
 use domain::net::server::middleware::mandatory::MandatoryMiddlewareSvc;
@@ -18,19 +19,22 @@ use domain::net::server::middleware::notify::{Notifiable, NotifyError, NotifyMid
 use domain::net::server::middleware::tsig::TsigMiddlewareSvc;
 use domain::net::server::service::Service;
 use domain::net::server::stream::{self, StreamServer};
-use domain::tsig::{Algorithm, KeyStore};
+use domain::rdata::Tsig;
+use domain::tsig::{self, Algorithm, KeyStore};
 use tracing::{debug, error, info, warn};
 
-use crate::api::{ZoneReviewDecision, ZoneReviewStatus};
+use crate::api::{ZoneAdvanceStage, ZoneReviewDecision, ZoneReviewStatus};
 use crate::center::Center;
 use crate::config::SocketConfig;
 use crate::daemon::SocketProvider;
 use crate::manager::Terminated;
 use crate::manager::record_zone_event;
+use crate::metrics::ReviewOutcome;
 use crate::policy::ReviewMode;
 use crate::server::{LoadedReviewServer, SignedReviewServer};
 use crate::util::AbortOnDrop;
 use crate::zone::{HistoricalEvent, Zone};
+use crate::zonedata::ZONE_CLASS;
 
 /// The source of a zone server.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -50,6 +54,7 @@ fn spawn_servers<Svc>(
     source: Source,
     svc: Svc,
     servers: &[SocketConfig],
+    udp_payload_size: Option<u16>,
 ) -> Result<Vec<AbortOnDrop>, String>
 where
     Svc: Service<Vec<u8>, ()> + Clone,
@@ -57,7 +62,7 @@ where
     let mut handles = Vec::new();
 
     for sock_cfg in servers {
-        if let SocketConfig::UDP { addr } | SocketConfig::TCPUDP { addr } = sock_cfg {
+        if let SocketConfig::UDP { addr, .. } | SocketConfig::TCPUDP { addr, .. } = sock_cfg {
             info!("Obtaining UDP socket for address {addr}");
             let sock = socket_provider
                 .take_udp(addr)
@@ -66,10 +71,11 @@ where
                 svc.clone(),
                 VecBufSource,
                 sock,
+                udp_payload_size,
             ))));
         }
 
-        if let SocketConfig::TCP { addr } | SocketConfig::TCPUDP { addr } = sock_cfg {
+        if let SocketConfig::TCP { addr, .. } | SocketConfig::TCPUDP { addr, .. } = sock_cfg {
             info!("Obtaining TCP listener for address {addr}");
             let sock = socket_provider
                 .take_tcp(addr)
@@ -94,6 +100,7 @@ where
                 svc.clone(),
                 VecBufSource,
                 sock,
+                udp_payload_size,
             ))));
         }
         while let Some(sock) = socket_provider.pop_tcp() {
@@ -112,11 +119,20 @@ where
     Ok(handles)
 }
 
-async fn serve_on_udp<Svc>(svc: Svc, buf: VecBufSource, sock: tokio::net::UdpSocket)
-where
+async fn serve_on_udp<Svc>(
+    svc: Svc,
+    buf: VecBufSource,
+    sock: tokio::net::UdpSocket,
+    udp_payload_size: Option<u16>,
+) where
     Svc: Service<Vec<u8>, ()> + Clone,
 {
-    let config = dgram::Config::new();
+    // Responses that do not fit are truncated (with the TC bit set) by the
+    // mandatory middleware, so that the client retries over TCP.
+    let mut config = dgram::Config::new();
+    if let Some(size) = udp_payload_size {
+        config.set_max_response_size(Some(size));
+    }
     let srv = DgramServer::<_, _, _>::with_config(sock, buf, svc, config);
     srv.run().await;
 }
@@ -172,9 +188,24 @@ impl ZoneServer {
 
         let svc = service;
         let svc = NotifyMiddlewareSvc::new(svc, notifier);
-        let svc = CookiesMiddlewareSvc::with_random_secret(svc);
+        // Only the publication server is configurable; the review servers
+        // always use the defaults.
+        let (udp_payload_size, cookies) = match source {
+            Source::Published => (
+                center.config.server.udp_payload_size,
+                center.config.server.cookies,
+            ),
+            Source::Unsigned | Source::Signed => (None, true),
+        };
+
+        let svc = CookiesMiddlewareSvc::with_random_secret(svc).enable(cookies);
         let svc = EdnsMiddlewareSvc::new(svc);
         let svc = TsigMiddlewareSvc::new(svc, CenterKeyStore(center.clone()));
+        let svc = NotifyTsigMonitor {
+            inner: svc,
+            enabled: matches!(source, Source::Published),
+            center: center.clone(),
+        };
         let svc = MandatoryMiddlewareSvc::<_, _, ()>::new(svc);
         let svc = Arc::new(svc);
 
@@ -184,7 +215,7 @@ impl ZoneServer {
             Source::Published => &center.config.server.servers,
         };
 
-        let handles = spawn_servers(socket_provider, source, svc, servers)
+        let handles = spawn_servers(socket_provider, source, svc, servers, udp_payload_size)
             .inspect_err(|err| error!("[{unit_name}]: Spawning nameservers failed: {err}"))
             .map_err(|_| Terminated)?;
 
@@ -212,26 +243,54 @@ impl ZoneServer {
             Source::Published => unreachable!(),
         };
 
-        let review = {
-            let zone_state = zone.read();
+        let (mut review, forced) = {
+            let mut zone_state = zone.state.write_cleanly();
             let policy = zone_state.policy.as_ref().unwrap();
-            match self.source {
+            let review = match self.source {
                 Source::Unsigned => policy.loader.review.clone(),
                 Source::Signed => policy.signer.review.clone(),
                 Source::Published => unreachable!(),
-            }
+            };
+
+            // Skip the review if the zone is being forced through the
+            // pipeline, forgetting about it once the target stage is reached.
+            let forced = match (self.source, zone_state.forced_advance) {
+                (Source::Unsigned, Some(ZoneAdvanceStage::Published)) => true,
+                (Source::Unsigned, Some(ZoneAdvanceStage::Signed))
+                | (Source::Signed, Some(ZoneAdvanceStage::Published)) => {
+                    zone_state.forced_advance = None;
+                    true
+                }
+                _ => false,
+            };
+
+            (review, forced)
         };
 
+        if forced {
+            info!(
+                "[{unit_name}]: Skipping review of {zone_type} zone '{}' at serial {zone_serial} as it is being advanced forcibly.",
+                zone.name
+            );
+            review.mode = ReviewMode::Off;
+        }
+
         let (review_server, pending_event) = {
             let status = ZoneReviewStatus::Pending;
             match self.source {
                 Source::Unsigned => (
                     center.config.loader.review.servers.first().cloned(),
-                    HistoricalEvent::UnsignedZoneReview { status },
+                    HistoricalEvent::UnsignedZoneReview {
+                        status,
+                        reason: None,
+                    },
                 ),
                 Source::Signed => (
                     center.config.signer.review.servers.first().cloned(),
-                    HistoricalEvent::SignedZoneReview { status },
+                    HistoricalEvent::SignedZoneReview {
+                        status,
+                        reason: None,
+                    },
                 ),
                 Source::Published => unreachable!(),
             }
@@ -274,6 +333,22 @@ impl ZoneServer {
             }
         };
 
+        // Summarize the version under review, so that reviewers have some
+        // context. If the viewer is being updated right now, go without.
+        let summary =
+            match self.source {
+                Source::Unsigned => center.loaded_review_server.viewer(zone).and_then(|viewer| {
+                    LoadedReviewServer::summarize(zone, &*viewer.try_read().ok()?)
+                }),
+                Source::Signed => center.signed_review_server.viewer(zone).and_then(|viewer| {
+                    SignedReviewServer::summarize(zone, &*viewer.try_read().ok()?)
+                }),
+                Source::Published => unreachable!(),
+            };
+        if let Some(summary) = &summary {
+            info!("[{unit_name}]: Under review: {summary}");
+        }
+
         record_zone_event(center, zone, pending_event, Some(zone_serial));
 
         let ReviewMode::Script { hook } = review.mode else {
@@ -302,6 +377,10 @@ impl ZoneServer {
                     &*review_server.addr().port().to_string(),
                 ),
             ])
+            .env(
+                "CASCADE_REVIEW_SUMMARY",
+                summary.map(|s| s.to_string()).unwrap_or_default(),
+            )
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
@@ -339,6 +418,7 @@ impl ZoneServer {
                         true => ZoneReviewDecision::Approve,
                         false => ZoneReviewDecision::Reject,
                     };
+                    let reason = Some(format!("review hook exited with {status}"));
 
                     match source {
                         Source::Unsigned => {
@@ -347,6 +427,7 @@ impl ZoneServer {
                                 &zone,
                                 zone_serial,
                                 decision,
+                                reason,
                             );
                         }
                         Source::Signed => {
@@ -355,6 +436,7 @@ impl ZoneServer {
                                 &zone,
                                 zone_serial,
                                 decision,
+                                reason,
                             );
                         }
                         Source::Published => unreachable!(),
@@ -376,7 +458,9 @@ impl ZoneServer {
                                 },
                                 Some(zone_serial),
                             );
-                            handle.get().hard_reject_loaded();
+                            handle
+                                .get()
+                                .hard_reject_loaded(None, ReviewOutcome::HookFailed);
                         }
                         Source::Signed => {
                             handle.state.record_event(
@@ -385,7 +469,9 @@ impl ZoneServer {
                                 },
                                 Some(zone_serial),
                             );
-                            handle.get().hard_reject_signed();
+                            handle
+                                .get()
+                                .hard_reject_signed(None, ReviewOutcome::HookFailed);
                         }
                         Source::Published => unreachable!(),
                     }
@@ -402,14 +488,14 @@ impl ZoneServer {
         zone_serial: Serial,
     ) {
         let _ = zone_serial; // TODO
-        zone.write_handle(center).get().approve_loaded();
+        zone.write_handle(center).get().approve_loaded(None);
     }
 
     fn on_signed_zone_approved(&self, center: &Arc<Center>, zone: &Arc<Zone>, zone_serial: Serial) {
         let _ = zone_serial;
 
         {
-            zone.write_handle(center).get().approve_signed();
+            zone.write_handle(center).get().approve_signed(None);
         }
     }
 
@@ -478,7 +564,10 @@ impl Notifiable for LoaderNotifier {
         _source: IpAddr,
     ) -> Pin<Box<dyn Future<Output = Result<(), NotifyError>> + Sync + Send + '_>> {
         // Don't do anything if the notifier is disabled.
-        if self.enabled && class == Class::IN {
+        if self.enabled && class != Class::from(u16::from(ZONE_CLASS.code)) {
+            // Only zones of the IN class are supported.
+            warn!("Ignoring NOTIFY for zone '{apex_name}': class {class} is not supported");
+        } else if self.enabled {
             // Propagate a request for the zone refresh.
             //
             // We ignore the serial because we will just do a SOA query to our
@@ -535,3 +624,84 @@ impl Notifiable for LoaderNotifier {
         Box::pin(std::future::ready(Ok(())))
     }
 }
+
+//----------- NotifyTsigMonitor ------------------------------------------------
+
+/// A counter of inbound NOTIFY messages that fail TSIG verification.
+///
+/// Such messages are rejected by the TSIG middleware before they reach the
+/// [`LoaderNotifier`], so they are verified separately here and counted for
+/// the zone they refer to.
+#[derive(Clone)]
+struct NotifyTsigMonitor<S> {
+    /// The wrapped service.
+    inner: S,
+
+    /// Whether the monitor is enabled.
+    enabled: bool,
+
+    center: Arc<Center>,
+}
+
+impl<S> NotifyTsigMonitor<S> {
+    /// Count the request if it is a NOTIFY that fails TSIG verification.
+    fn check(&self, request: &Request<Vec<u8>, ()>) {
+        let message = request.message();
+        if !self.enabled || message.header().opcode() != Opcode::NOTIFY {
+            return;
+        }
+
+        // Unsigned NOTIFY messages are accepted or refused elsewhere.
+        let key: Option<tsig::KeyName> = message
+            .additional()
+            .ok()
+            .and_then(|section| section.limit_to::<Tsig<_, _>>().last())
+            .and_then(Result::ok)
+            .and_then(|record| record.owner().try_to_name().ok());
+        let Some(key) = key else {
+            return;
+        };
+        let Ok(question) = message.sole_question() else {
+            return;
+        };
+        let apex_name: Name<Bytes> = question.qname().to_name();
+        let Some(zone) = crate::center::get_zone(&self.center, &apex_name) else {
+            return;
+        };
+
+        let mut copy = match domain::base::Message::from_octets(message.as_slice().to_vec()) {
+            Ok(copy) => copy,
+            Err(_) => return,
+        };
+        let store = CenterKeyStore(self.center.clone());
+        if tsig::ServerTransaction::request(&store, &mut copy, Time48::now()).is_ok() {
+            return;
+        }
+
+        warn!(
+            "NOTIFY for zone '{apex_name}' from {} failed verification with TSIG key '{key}'",
+            request.client_addr()
+        );
+        zone.metrics.inc_tsig_verification_failures(&key);
+        *zone
+            .write(&self.center)
+            .loader
+            .tsig_failures
+            .entry(key)
+            .or_default() += 1;
+    }
+}
+
+impl<S> Service<Vec<u8>, ()> for NotifyTsigMonitor<S>
+where
+    S: Service<Vec<u8>, ()>,
+{
+    type Target = S::Target;
+    type Stream = S::Stream;
+    type Future = S::Future;
+
+    fn call(&self, request: Request<Vec<u8>, ()>) -> Self::Future {
+        self.check(&request);
+        self.inner.call(request)
+    }
+}
@@ -1,18 +1,22 @@
 use std::collections::{HashMap, HashSet};
 use std::env::{self, VarError};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use camino::{Utf8Path, Utf8PathBuf};
 use domain::base::Rtype;
 use domain::dnssec::sign::keys::keyset::{KeySet, UnixTime};
 use domain::rdata::dnssec::Timestamp;
 use domain_kmip::dep::kmip::client::pool::SyncConnPool;
 use domain_kmip::{self, ClientCertificate, ConnectionSettings};
 use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
 
 use crate::center::Center;
 use crate::common::scheduler::Scheduler;
+use crate::config::SignerConfig;
 use crate::signer::ResigningTrigger;
 use crate::signer::keys::LoadError;
 use crate::signer::queue::SigningQueue;
@@ -46,10 +50,12 @@ pub struct ZoneSigner {
 }
 
 impl ZoneSigner {
-    pub fn new() -> Self {
-        let max_concurrent_operations = 1;
+    pub fn new(config: &SignerConfig) -> Self {
+        let max_concurrent_operations = config
+            .max_concurrent_operations
+            .unwrap_or(NonZeroUsize::MIN);
         let resign_scheduler = Scheduler::new();
-        let queue = SigningQueue::new(max_concurrent_operations.try_into().unwrap());
+        let queue = SigningQueue::new(max_concurrent_operations, config.memory_budget);
 
         Self {
             kmip_servers: Default::default(),
@@ -88,10 +94,89 @@ pub struct KeySetState {
     pub keyset: KeySet,
 
     pub ds_rrset: Vec<String>,
+    #[serde(default)]
+    pub ns_rrset: Vec<String>,
     pub apex_remove: HashSet<Rtype>,
     pub apex_extra: Vec<String>,
 }
 
+impl KeySetState {
+    /// Load the keyset state from a file.
+    ///
+    /// The state file is written by `dnst keyset`. Whenever it is loaded
+    /// successfully, a copy of it is kept as the last known good state, for
+    /// [`Self::load_or_last_good()`].
+    pub fn load(path: &Utf8Path) -> Result<Self, SignerError> {
+        // Note the modification time before reading, so that a concurrent
+        // update is not mistaken for the version being read.
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let text = std::fs::read_to_string(path)
+            .map_err(|_| SignerError::CannotReadStateFile(path.to_string()))?;
+
+        let state = serde_json::from_str(&text).map_err(|error| {
+            error!("Could not parse keyset state file '{path}': {error}");
+            SignerError::MalformedStateFile {
+                path: path.to_string(),
+                error: error.to_string(),
+            }
+        })?;
+
+        // Only write the copy out if the state file has changed since. The
+        // copy is given the modification time of the state file it was made
+        // from, so this doesn't require reading it back.
+        let last_good_path = Self::last_good_path(path);
+        let saved = std::fs::metadata(&last_good_path)
+            .and_then(|m| m.modified())
+            .ok();
+        if modified.is_none() || saved != modified {
+            let result = crate::util::write_file(&last_good_path, text.as_bytes()).and_then(|()| {
+                match modified {
+                    Some(modified) => std::fs::File::options()
+                        .write(true)
+                        .open(&last_good_path)?
+                        .set_modified(modified),
+                    None => Ok(()),
+                }
+            });
+            if let Err(err) = result {
+                warn!("Could not save a copy of keyset state to '{last_good_path}': {err}");
+            }
+        }
+        Ok(state)
+    }
+
+    /// Load the keyset state from a file, for display only.
+    ///
+    /// If the state file cannot be loaded (e.g. because it is corrupt), the
+    /// last copy of it that could be loaded is used instead, if there is one.
+    /// This must not be used for signing, which would then silently use stale
+    /// keys.
+    pub fn load_or_last_good(path: &Utf8Path) -> Result<Self, SignerError> {
+        let error = match Self::load(path) {
+            Ok(state) => return Ok(state),
+            Err(error) => error,
+        };
+
+        let last_good_path = Self::last_good_path(path);
+        let Some(state) = std::fs::read_to_string(&last_good_path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+        else {
+            return Err(error);
+        };
+
+        warn!("Showing the last known good keyset state from '{last_good_path}' instead");
+        Ok(state)
+    }
+
+    /// The path of the last known good copy of a keyset state file.
+    fn last_good_path(path: &Utf8Path) -> Utf8PathBuf {
+        let mut path = path.as_str().to_string();
+        path.push_str(".last-good");
+        path.into()
+    }
+}
+
 pub struct MinTimestamp(Mutex<Option<Timestamp>>);
 
 impl MinTimestamp {
@@ -234,14 +319,37 @@ pub fn load_binary_file(path: &Path) -> Vec<u8> {
     bytes
 }
 
-pub fn faketime_or_now() -> UnixTime {
-    match env::var("CASCADE_FAKETIME") {
-        Ok(val) => val.parse::<Timestamp>().unwrap().into(),
-        Err(VarError::NotPresent) => UnixTime::now(),
-        Err(_e) => panic!("Cannot parse environment variable CASCADE_FAKETIME"),
+/// The time set by the `CASCADE_FAKETIME` environment variable, if any.
+///
+/// A malformed value is logged (once) and ignored, so that Cascade uses the
+/// real time instead of crashing.
+pub fn faketime() -> Option<UnixTime> {
+    static WARNED: std::sync::Once = std::sync::Once::new();
+
+    let val = match env::var("CASCADE_FAKETIME") {
+        Ok(val) => val,
+        Err(VarError::NotPresent) => return None,
+        Err(err) => {
+            WARNED.call_once(|| warn!("Ignoring environment variable CASCADE_FAKETIME: {err}"));
+            return None;
+        }
+    };
+    match val.parse::<Timestamp>() {
+        Ok(timestamp) => Some(timestamp.into()),
+        Err(err) => {
+            WARNED.call_once(|| {
+                warn!("Ignoring environment variable CASCADE_FAKETIME: cannot parse '{val}': {err}")
+            });
+            None
+        }
     }
 }
 
+/// The time set by `CASCADE_FAKETIME`, or else the current time.
+pub fn faketime_or_now() -> UnixTime {
+    faketime().unwrap_or_else(UnixTime::now)
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub enum PassThroughMode {
     /// Pass-through is disabled.
@@ -263,10 +371,17 @@ pub enum SignerError {
     InternalError(String),
     KeepSerialPolicyViolated,
     CannotReadStateFile(String),
+    MalformedStateFile { path: String, error: String },
+    NoKeys,
+    KeysUnavailable(String),
     Load(String),
+    Kmip(String),
     PatchFailed(String),
     NothingToDo,
     SigningError(String),
+    ClockSkew(String),
+    NoPolicy,
+    DnssecInput(usize),
 }
 
 impl std::fmt::Display for SignerError {
@@ -279,16 +394,62 @@ impl std::fmt::Display for SignerError {
             SignerError::CannotReadStateFile(path) => {
                 write!(f, "Failed to read state file '{path}'")
             }
+            SignerError::MalformedStateFile { path, error } => {
+                write!(f, "Failed to parse state file '{path}': {error}")
+            }
+            SignerError::NoKeys => f.write_str("No signing keys are available"),
+            SignerError::KeysUnavailable(err) => {
+                write!(f, "The signing keys are temporarily unavailable: {err}")
+            }
             SignerError::Load(err) => write!(f, "Could not load the signing keys: {err}"),
+            SignerError::Kmip(err) => {
+                write!(
+                    f,
+                    "Could not load the signing keys from a KMIP server: {err}"
+                )
+            }
             SignerError::PatchFailed(err) => write!(f, "Patch failed: {err}"),
             SignerError::NothingToDo => write!(f, "Nothing To Do"),
             SignerError::SigningError(err) => write!(f, "Signing error: {err}"),
+            SignerError::ClockSkew(err) => {
+                write!(f, "Refusing to sign, the system clock looks wrong: {err}")
+            }
+            SignerError::NoPolicy => f.write_str("The zone has no policy"),
+            SignerError::DnssecInput(records) => write!(
+                f,
+                "Refusing to sign, the loaded zone contains {records} DNSSEC records"
+            ),
         }
     }
 }
 
+impl SignerError {
+    /// Whether the failure is likely to go away by itself.
+    ///
+    /// Transient failures (e.g. an unreachable HSM or a state file that
+    /// could not be read) are retried automatically.  Other failures halt
+    /// the zone until the operator intervenes.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            SignerError::CannotReadStateFile(_) | SignerError::KeysUnavailable(_)
+        )
+    }
+}
+
 impl From<Box<LoadError>> for SignerError {
     fn from(error: Box<LoadError>) -> Self {
-        Self::Load(error.to_string())
+        match *error {
+            LoadError::NoKeysFound => Self::NoKeys,
+            LoadError::UnreadableKeyFile { .. }
+            | LoadError::UnreadableKmipServerState { .. }
+            | LoadError::KmipConnection { .. } => Self::KeysUnavailable(error.to_string()),
+            LoadError::MalformedKmipKeyUrl { .. }
+            | LoadError::MalformedKmipServerState { .. }
+            | LoadError::KmipClientCredentials { .. }
+            | LoadError::MissingKmipClientCredentials { .. }
+            | LoadError::MalformedKmipKeypair { .. } => Self::Kmip(error.to_string()),
+            _ => Self::Load(error.to_string()),
+        }
     }
 }
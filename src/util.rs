@@ -48,6 +48,11 @@ impl AbortOnDrop {
     pub fn id(&self) -> tokio::task::Id {
         self.0.id()
     }
+
+    /// Get a handle to the task which does not abort it when dropped.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.0.clone()
+    }
 }
 
 //----------- BackgroundTasks --------------------------------------------------
@@ -166,8 +171,10 @@ pub fn write_file(path: &Utf8Path, contents: &[u8]) -> io::Result<()> {
     // Obtain a temporary file in the same directory.
     let mut tmp_file = tempfile::Builder::new().tempfile_in(dir)?;
 
-    // Fill up the temporary file.
+    // Fill up the temporary file, and make sure it hits the disk before it
+    // is renamed into place.
     tmp_file.as_file_mut().write_all(contents)?;
+    tmp_file.as_file().sync_all()?;
 
     // Replace the target path with the temporary file.
     let _ = tmp_file.persist(path)?;
@@ -175,6 +182,30 @@ pub fn write_file(path: &Utf8Path, contents: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
+/// Atomically write a new file.
+///
+/// Like [`write_file()`], but fails with [`io::ErrorKind::AlreadyExists`] if
+/// `path` already exists, rather than replacing it.
+///
+/// # Panics
+///
+/// Panics if 'path' does not have a containing directory.
+pub fn write_new_file(path: &Utf8Path, contents: &[u8]) -> io::Result<()> {
+    let dir = path
+        .parent()
+        .expect("'path' must be a file, so it must have a parent");
+    fs::create_dir_all(dir)?;
+
+    let mut tmp_file = tempfile::Builder::new().tempfile_in(dir)?;
+    tmp_file.as_file_mut().write_all(contents)?;
+    tmp_file.as_file().sync_all()?;
+
+    // Move the temporary file into place, unless the target exists.
+    let _ = tmp_file.persist_noclobber(path)?;
+
+    Ok(())
+}
+
 pub fn instant_to_duration_secs(instant: Instant) -> u64 {
     match Instant::now().checked_duration_since(instant) {
         Some(d) => d.as_secs(),
@@ -1,8 +1,11 @@
+use std::time::SystemTime;
+
 use tracing::{info, trace};
 
 use crate::{
     api::ZoneReviewStatus,
-    server::PublicationServer,
+    metrics::{ReviewOutcome, ReviewStage},
+    server::{PublicationServer, SignedReviewServer},
     units::zone_signer::SignerError,
     zone::{HistoricalEvent, ZoneHandle},
     zonedata::{
@@ -24,21 +27,26 @@ use crate::{
 /// We can go from `Waiting` to `Loading` when we start a load and from `Waiting`
 /// to `Signing` when starting a resign.
 ///
-/// Then there are 3 halting states:
+/// Then there are 4 halting states:
 ///
-/// 1. `RejectLoaded`
-/// 2. `SigningFailure`
-/// 3. `RejectSigned`
+/// 1. `LoadingFailure`
+/// 2. `RejectLoaded`
+/// 3. `SigningFailure`
+/// 4. `RejectSigned`
 ///
 /// If the pipeline is ever in one of these states, it can be `reset` to the
 /// `Waiting` state. The `Reject` states are reached on a hard reject of a
 /// loaded or a signed zone. The rejection can then be overridden to continue
-/// the pipeline anyway. `SigningFailure` cannot be overridden but only `reset`.
+/// the pipeline anyway. `LoadingFailure` is reached once loading has failed
+/// too many times in a row (see the loader's `max-failures` setting). Like
+/// `SigningFailure`, it cannot be overridden but only `reset`.
 ///
 /// Here is the diagram for it:
 //
 // TODO: There is an additional transition from 'Signing' to 'Waiting', in case
 // signing is abandoned (e.g. incremental signing turns out to be a no-op).
+// 'LoadingFailure' (reached from 'Loading' on a failure, and reset to
+// 'Waiting') is not drawn either.
 ///
 /// ```text
 /// ┌──────────────────────────────────────────────────────────────────────────────────────────────────────────────────────┐
@@ -65,6 +73,7 @@ use crate::{
 pub enum ZoneStateMachine {
     Waiting(Waiting),
     Loading(Loading),
+    LoadingFailed(LoadingFailed),
     LoadedReview(LoadedReview),
     HaltLoaded(HaltLoaded),
     Signing(Signing),
@@ -86,12 +95,21 @@ impl ZoneStateMachine {
     pub fn is_halted(&self) -> bool {
         matches!(
             self,
-            Self::HaltLoaded(_) | Self::HaltSigned(_) | Self::SigningFailed(_)
+            Self::LoadingFailed(_)
+                | Self::HaltLoaded(_)
+                | Self::HaltSigned(_)
+                | Self::SigningFailed(_)
         )
     }
 
+    /// Whether the zone halted because a review rejected it.
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, Self::HaltLoaded(_) | Self::HaltSigned(_))
+    }
+
     pub fn display_halted_reason(&self) -> Option<String> {
         let s = match self {
+            Self::LoadingFailed(_) => "loading the zone failed".into(),
             Self::HaltLoaded(_) => "loaded zone was rejected".into(),
             Self::HaltSigned(_) => "signed zone was rejected".into(),
             Self::SigningFailed(SigningFailed { err }) => format!("signing the zone failed: {err}"),
@@ -193,6 +211,25 @@ impl<'a> ZoneHandle<'a> {
         self.state.instances.abandon();
     }
 
+    /// Halt the zone after a failed load.
+    ///
+    /// This is used instead of [`Self::abandon_load()`] once loading has
+    /// failed too many times in a row.
+    pub(crate) fn loading_failed(&mut self, builder: LoadedZoneBuilder) {
+        let (transition, state) = self.state.machine.transition();
+
+        let ZoneStateMachine::Loading(loading) = state else {
+            panic!("cannot fail loading in this state");
+        };
+
+        transition.move_to(ZoneStateMachine::LoadingFailed(loading.loading_failed()));
+
+        self.storage().abandon_load(builder);
+
+        // Abandon the entire upcoming instance.
+        self.state.instances.abandon();
+    }
+
     pub(crate) fn finish_load(&mut self, built: LoadedZoneBuilt) {
         let (transition, state) = self.state.machine.transition();
 
@@ -222,15 +259,19 @@ impl<'a> ZoneHandle<'a> {
 /// # Loaded Review operations
 impl<'a> ZoneHandle<'a> {
     /// Approve the loaded instance currently under review.
-    pub(crate) fn approve_loaded(&mut self) {
+    pub(crate) fn approve_loaded(&mut self, reason: Option<String>) {
         info!("The loaded instance has been approved");
 
         self.state.record_event(
             HistoricalEvent::UnsignedZoneReview {
                 status: ZoneReviewStatus::Approved,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Unsigned, ReviewOutcome::Approved);
 
         let (transition, state) = self.state.machine.transition();
         let ZoneStateMachine::LoadedReview(loaded) = state else {
@@ -244,13 +285,18 @@ impl<'a> ZoneHandle<'a> {
         self.persistence().start_loaded_persistence(persister);
     }
 
-    pub(crate) fn soft_reject_loaded(&mut self) {
+    pub(crate) fn soft_reject_loaded(&mut self, reason: Option<String>, outcome: ReviewOutcome) {
         self.state.record_event(
             HistoricalEvent::UnsignedZoneReview {
                 status: ZoneReviewStatus::Rejected,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Unsigned, outcome);
+        self.state.review_batch = None;
 
         let (transition, state) = self.state.machine.transition();
 
@@ -267,13 +313,18 @@ impl<'a> ZoneHandle<'a> {
             .start_rewinding_loaded_review(loaded_reviewer);
     }
 
-    pub(crate) fn hard_reject_loaded(&mut self) {
+    pub(crate) fn hard_reject_loaded(&mut self, reason: Option<String>, outcome: ReviewOutcome) {
         self.state.record_event(
             HistoricalEvent::UnsignedZoneReview {
                 status: ZoneReviewStatus::Rejected,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Unsigned, outcome);
+        self.state.review_batch = None;
 
         let (transition, state) = self.state.machine.transition();
 
@@ -282,6 +333,7 @@ impl<'a> ZoneHandle<'a> {
         };
 
         transition.move_to(ZoneStateMachine::HaltLoaded(loaded.hard_reject()));
+        self.state.record_halt();
     }
 }
 
@@ -351,16 +403,107 @@ impl<'a> ZoneHandle<'a> {
 
 /// # Signed Review operations
 impl<'a> ZoneHandle<'a> {
-    pub(crate) fn approve_signed(&mut self) {
-        info!("The signed instance has been approved; publishing");
-
+    pub(crate) fn approve_signed(&mut self, reason: Option<String>) {
         self.state.record_event(
             HistoricalEvent::SignedZoneReview {
                 status: ZoneReviewStatus::Approved,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Signed, ReviewOutcome::Approved);
+
+        if self.state.observe_only {
+            info!("The signed instance has been approved; holding it until the zone goes live");
+
+            // Stay in the 'SignedReview' state, so that the instance can be
+            // verified through the signed review server, but is neither
+            // published nor announced to any nameservers.
+            self.state.held_since = Some(SystemTime::now());
+            self.state.record_event(HistoricalEvent::Held, None);
+            return;
+        }
+
+        self.release_signed();
+    }
+
+    /// Take the zone out of observe-only mode.
+    ///
+    /// A signed instance held back while the zone was observe-only is
+    /// released, i.e. staged or published as the policy requires.
+    pub(crate) fn go_live(&mut self) {
+        self.state.observe_only = false;
+        self.state.record_event(HistoricalEvent::WentLive, None);
+
+        if self.state.held_since.take().is_some() {
+            info!("The zone has gone live; releasing the held signed instance");
+            self.release_signed();
+        }
+    }
+
+    /// Stage or publish an approved signed instance.
+    fn release_signed(&mut self) {
+        let staging = self
+            .state
+            .policy
+            .as_ref()
+            .map(|policy| policy.server.staging.clone())
+            .unwrap_or_default();
+
+        if !staging.is_enabled() {
+            info!("The signed instance has been approved; publishing");
+            self.publish_signed();
+            return;
+        }
 
+        info!("The signed instance has been approved; staging");
+
+        // Stay in the 'SignedReview' state, so that the instance is served to
+        // the staging nameservers by the signed review server, and can still
+        // be rejected until it is promoted.
+        let staged_since = SystemTime::now();
+        self.state.staged_since = Some(staged_since);
+        self.state.record_event(HistoricalEvent::Staged, None);
+
+        SignedReviewServer::after_staging(self);
+
+        if let Some(soak_time) = staging.soak_time {
+            let zone = self.zone.clone();
+            let center = self.center.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(soak_time).await;
+
+                let mut handle = zone.write_handle(&center);
+                // Only promote the instance this timer was started for.
+                if handle.state.staged_since != Some(staged_since)
+                    || !matches!(handle.state.machine, ZoneStateMachine::SignedReview(_))
+                {
+                    return;
+                }
+
+                info!(
+                    "The soak time of the staged instance of '{}' has elapsed",
+                    zone.name
+                );
+                handle.get().promote_signed();
+            });
+        }
+    }
+
+    /// Promote a staged signed instance to production.
+    pub(crate) fn promote_signed(&mut self) {
+        info!("The staged instance has been promoted; publishing");
+
+        self.state.staged_since = None;
+        self.state.record_event(HistoricalEvent::Promoted, None);
+
+        self.publish_signed();
+    }
+
+    /// Publish the signed instance under review.
+    fn publish_signed(&mut self) {
         // Move to the 'Waiting' state.
         let (transition, state) = self.state.machine.transition();
         let ZoneStateMachine::SignedReview(signed) = state else {
@@ -375,13 +518,18 @@ impl<'a> ZoneHandle<'a> {
         self.persistence().start_signed_persistence(persister);
     }
 
-    pub(crate) fn soft_reject_signed(&mut self) {
+    pub(crate) fn soft_reject_signed(&mut self, reason: Option<String>, outcome: ReviewOutcome) {
         self.state.record_event(
             HistoricalEvent::SignedZoneReview {
                 status: ZoneReviewStatus::Rejected,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Signed, outcome);
+        self.state.review_batch = None;
 
         self.signer().before_signed_abandonment();
 
@@ -392,6 +540,8 @@ impl<'a> ZoneHandle<'a> {
         };
 
         transition.move_to(ZoneStateMachine::Waiting(signed.soft_reject()));
+        self.state.staged_since = None;
+        self.state.held_since = None;
 
         let (loaded_reviewer, signed_reviewer) = self.storage().abandon_signed_review();
 
@@ -404,13 +554,18 @@ impl<'a> ZoneHandle<'a> {
             .start_rewinding_review(loaded_reviewer, signed_reviewer);
     }
 
-    pub(crate) fn hard_reject_signed(&mut self) {
+    pub(crate) fn hard_reject_signed(&mut self, reason: Option<String>, outcome: ReviewOutcome) {
         self.state.record_event(
             HistoricalEvent::SignedZoneReview {
                 status: ZoneReviewStatus::Rejected,
+                reason,
             },
             None, // TODO
         );
+        self.zone
+            .metrics
+            .inc_review_outcome(ReviewStage::Signed, outcome);
+        self.state.review_batch = None;
 
         let (transition, state) = self.state.machine.transition();
 
@@ -419,6 +574,9 @@ impl<'a> ZoneHandle<'a> {
         };
 
         transition.move_to(ZoneStateMachine::HaltSigned(review.hard_reject()));
+        self.state.staged_since = None;
+        self.state.held_since = None;
+        self.state.record_halt();
 
         // Abandon the entire upcoming instance.
         self.state.instances.abandon();
@@ -435,6 +593,7 @@ impl<'a> ZoneHandle<'a> {
         // TODO: Handle this with `Instances`.
         self.state.min_expiration = self.state.next_min_expiration;
         self.state.next_min_expiration = None;
+        self.state.review_batch = None;
 
         let serial = self
             .state
@@ -465,6 +624,19 @@ impl<'a> ZoneHandle<'a> {
         let (transition, state) = self.state.machine.transition();
 
         match state {
+            ZoneStateMachine::LoadingFailed(loading_failed) => {
+                let waiting = loading_failed.reset();
+                transition.move_to(ZoneStateMachine::Waiting(waiting));
+
+                // Like for 'SigningFailed', the zone data storage is already
+                // passive, and its call to `on_passive()` was ignored.
+                self.storage().on_passive();
+
+                // Try loading the zone again; a further failure halts it
+                // again straight away.
+                self.state.loader.retries_exhausted = false;
+                self.loader().enqueue_refresh(true);
+            }
             ZoneStateMachine::HaltLoaded(halt_loaded) => {
                 let waiting = halt_loaded.reset();
                 transition.move_to(ZoneStateMachine::Waiting(waiting));
@@ -574,6 +746,7 @@ impl ZoneStateMachine {
         match self {
             ZoneStateMachine::Waiting(_) => "waiting",
             ZoneStateMachine::Loading(_) => "loading",
+            ZoneStateMachine::LoadingFailed(_) => "loading failed",
             ZoneStateMachine::LoadedReview(_) => "loaded review",
             ZoneStateMachine::HaltLoaded(_) => "halt loaded",
             ZoneStateMachine::Signing(_) => "signing",
@@ -642,6 +815,19 @@ impl Loading {
     fn abandon_load(self) -> Waiting {
         Waiting {}
     }
+
+    fn loading_failed(self) -> LoadingFailed {
+        LoadingFailed {}
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadingFailed {}
+
+impl LoadingFailed {
+    fn reset(self) -> Waiting {
+        Waiting {}
+    }
 }
 
 #[derive(Debug)]
@@ -732,3 +918,21 @@ impl HaltSigned {
         Waiting {}
     }
 }
+
+//============ Tests ===========================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{HaltLoaded, HaltSigned, LoadingFailed, Waiting, ZoneStateMachine};
+
+    #[test]
+    fn only_review_halts_are_rejections() {
+        assert!(ZoneStateMachine::HaltLoaded(HaltLoaded {}).is_rejected());
+        assert!(ZoneStateMachine::HaltSigned(HaltSigned {}).is_rejected());
+
+        let loading_failed = ZoneStateMachine::LoadingFailed(LoadingFailed {});
+        assert!(loading_failed.is_halted());
+        assert!(!loading_failed.is_rejected());
+        assert!(!ZoneStateMachine::Waiting(Waiting {}).is_rejected());
+    }
+}
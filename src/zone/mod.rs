@@ -20,11 +20,12 @@ use tracing::{debug, error, trace};
 use crate::{
     api::{self, ZoneReviewStatus},
     center::Center,
+    common::store::StateStore,
     config::Config,
     loader::zone::{LoaderState, LoaderZoneHandle},
     metrics::{Metrics, ZoneMetrics},
     persistence::zone::{PersistenceState, ZonePersistenceHandle},
-    policy::{Policy, PolicyVersion},
+    policy::{Policy, PolicyVersion, SignerDenialPolicy},
     signer::zone::{SignerState, SignerZoneHandle},
     tsig::TsigStore,
     util::{deserialize_duration_from_secs, serialize_duration_as_secs},
@@ -86,10 +87,31 @@ impl Zone {
         }
     }
 
-    /// Restore a zone from a state file.
+    /// Read the state file of a zone.
     ///
-    /// A zone originating from a previous execution of Cascade is initialized,
-    /// by reading and parsing the appropriate state file.
+    /// This is the costly part of restoring a zone. It does not touch the
+    /// global state, so the state files of many zones can be read in
+    /// parallel, and then restored with [`Self::restore_from()`].
+    pub fn read_state(
+        config: &Config,
+        store: &dyn StateStore,
+        name: &Name<Bytes>,
+    ) -> Result<state::Spec, state::LoadError> {
+        let path = config.zone_state_dir.join(format!("{name}.db"));
+
+        state::Spec::load(store, &path).map_err(|error| {
+            error!("Failed to load the state of zone '{name}' from '{path}': {error}");
+            state::LoadError::Read {
+                path: path.into(),
+                error,
+            }
+        })
+    }
+
+    /// Restore a zone from a state file read by [`Self::read_state()`].
+    ///
+    /// A zone originating from a previous execution of Cascade is initialized
+    /// from the parsed state file.
     ///
     /// `policies` should contain the set of policies loaded from the global
     /// state file. If the zone uses a policy that is not present in the global
@@ -103,30 +125,28 @@ impl Zone {
         skip_all,
         fields(%name),
     )]
-    pub fn restore(
-        config: &Config,
+    pub fn restore_from(
         name: Name<Bytes>,
+        spec: state::Spec,
         policies: &mut foldhash::HashMap<Box<str>, Policy>,
         tsig_store: &TsigStore,
         metrics: &Metrics,
     ) -> Result<Self, state::LoadError> {
-        let path = config.zone_state_dir.join(format!("{name}.db"));
-
-        // Load the underlying state file.
-        let state = match state::Spec::load(&path) {
-            Ok(spec) => spec.parse(&name, policies, tsig_store)?,
-            Err(error) => {
-                error!("Failed to load the state of zone '{name}' from '{path}': {error}");
-                return Err(state::LoadError::Read {
-                    path: path.into(),
-                    error,
-                });
-            }
-        };
+        let mut state = spec.parse(&name, policies, tsig_store)?;
+
+        // A zone without a policy cannot be loaded or signed. Rather than
+        // failing on it later, keep it out of the pipeline until an operator
+        // looks at it.
+        if state.policy.is_none() {
+            error!(
+                "Zone '{name}' was restored without a policy; placing it in maintenance mode. Remove and re-add the zone to assign it a policy."
+            );
+            state.maintenance_mode = true;
+        }
 
         let metrics = metrics.get_zone_metrics(name.clone());
 
-        debug!("Restored the state of zone '{name}' (from '{path}')");
+        debug!("Restored the state of zone '{name}'");
 
         Ok(Self {
             name,
@@ -317,14 +337,71 @@ pub struct ZoneState {
     pub machine: ZoneStateMachine,
 
     /// The policy (version) used by the zone.
+    ///
+    /// This includes the zone's [`Self::denial_override`], if any; use
+    /// [`Self::set_policy()`] to change it.
     pub policy: Option<Arc<PolicyVersion>>,
 
+    /// A zone-specific override of the policy's denial-of-existence type.
+    ///
+    /// This is set by `cascade zone set-denial`.
+    pub denial_override: Option<SignerDenialPolicy>,
+
+    /// A human-readable description of the zone, if any.
+    ///
+    /// This is set by `cascade zone set-description`; Cascade itself does not
+    /// interpret it.
+    pub description: Option<String>,
+
     /// Whether the zone is in maintenance mode
     ///
     /// Maintenance mode means that Cascade won't start loading and signing
     /// operations automatically.
     pub maintenance_mode: bool,
 
+    /// Whether the zone is observe-only.
+    ///
+    /// This is set by `cascade zone add --observe`. While set, approved
+    /// signed instances are held in the signed review stage instead of being
+    /// published, until `cascade zone go-live` clears it.
+    pub observe_only: bool,
+
+    /// A forced advance through the pipeline, if any.
+    ///
+    /// This is set by `cascade zone advance`, and by `cascade zone rollback`
+    /// to skip the unsigned review of the (previously approved) restored
+    /// version. While set, reviews up to and including the specified stage
+    /// are approved without consultation. It is cleared once that stage has
+    /// been reached, and is not persisted.
+    pub forced_advance: Option<api::ZoneAdvanceStage>,
+
+    /// The review batch that upcoming versions of the zone belong to, if any.
+    ///
+    /// This is set by `cascade zone reload --batch`. Pending reviews of all
+    /// zones in a batch can be approved at once with `cascade zone approve
+    /// --batch`. It is cleared once a version of the zone is published or
+    /// rejected, or if loading the zone yields no new version. It is not
+    /// persisted.
+    pub review_batch: Option<String>,
+
+    /// When the signed instance under review was staged, if it was.
+    ///
+    /// This is set when a signed instance is approved while the policy has
+    /// staging enabled. The instance is then served to the staging
+    /// nameservers until it is promoted (or rejected), at which point this
+    /// is cleared. It is persisted, but the staged instance itself is not;
+    /// after a restart, the zone is re-signed and staged again.
+    pub staged_since: Option<SystemTime>,
+
+    /// When the signed instance under review was held back, if it was.
+    ///
+    /// This is set when a signed instance is approved while the zone is
+    /// observe-only. The instance is then only served by the signed review
+    /// server until the zone goes live (or it is rejected), at which point
+    /// this is cleared. It is persisted, but the held instance itself is not;
+    /// after a restart, the zone is re-signed and held again.
+    pub held_since: Option<SystemTime>,
+
     /// An enqueued save of this state.
     ///
     /// The enqueued save operation will persist the current state in a short
@@ -380,6 +457,27 @@ pub struct ZoneState {
     /// serial for the Increment serial policy.
     pub previous_serial: Option<Serial>,
 
+    /// The lowest SOA serial the signer may use for this zone, if any.
+    ///
+    /// This is set by `cascade zone set-serial-floor` (or `zone add
+    /// --serial-floor`), e.g. to keep serials from going backwards when
+    /// migrating a zone from another signer. It applies regardless of the
+    /// serial policy.
+    pub serial_floor: Option<Serial>,
+
+    /// Whether automatic key rolls are paused for this zone.
+    ///
+    /// This is set by `cascade keyset <zone> auto pause`. While set, the
+    /// key manager disables all automatic key roll steps for the zone,
+    /// regardless of the `auto-*` settings of its policy.
+    pub key_rolls_paused: bool,
+
+    /// When the removal grace period of this zone ends, if it was removed.
+    ///
+    /// A removed zone that was published is still served until this time.
+    /// It is persisted so that the grace period survives a restart.
+    pub removal_deadline: Option<SystemTime>,
+
     /// Instances of the zone.
     pub instances: Instances,
 
@@ -406,14 +504,63 @@ pub struct ZoneState {
 }
 
 impl ZoneState {
+    /// Switch the zone to a (new version of a) policy.
+    ///
+    /// The zone's denial-of-existence override, if any, is applied to it.
+    /// Returns whether the policy used by the zone changed.
+    pub fn set_policy(&mut self, policy: Arc<PolicyVersion>) -> bool {
+        let policy = match &self.denial_override {
+            Some(denial) if *denial != policy.signer.denial => {
+                let mut policy = (*policy).clone();
+                policy.signer.denial = denial.clone();
+                Arc::new(policy)
+            }
+            _ => policy,
+        };
+
+        let changed = self.policy.as_ref() != Some(&policy);
+        self.policy = Some(policy);
+        changed
+    }
+
     pub fn halted_reason(&self) -> Option<String> {
-        self.machine.display_halted_reason()
+        if let Some(reason) = self.machine.display_halted_reason() {
+            let streak = match &self.machine {
+                ZoneStateMachine::LoadingFailed(_) => Some(self.loader.failure_streak),
+                ZoneStateMachine::SigningFailed(_) if self.signer.retries_exhausted => {
+                    Some(self.signer.failure_streak)
+                }
+                _ => None,
+            };
+            if let Some(streak) = streak {
+                return Some(format!(
+                    "{reason} (failed {streak} times in a row, not retrying)"
+                ));
+            }
+            return Some(reason);
+        }
+
+        None
     }
 
     pub fn record_event(&mut self, event: HistoricalEvent, serial: Option<Serial>) {
         self.history.push(HistoryItem::new(event, serial));
     }
 
+    /// Record in the history that the zone has halted, and why.
+    ///
+    /// This should be called when the zone may just have halted.  Nothing is
+    /// recorded if the zone is not actually halted, or if signing failed but
+    /// will be retried automatically.
+    pub fn record_halt(&mut self) {
+        if self.signer.enqueued_retry.is_some() {
+            return;
+        }
+        if let Some(reason) = self.halted_reason() {
+            self.record_event(HistoricalEvent::Halted { reason }, None);
+        }
+    }
+
     pub fn find_last_event(
         &self,
         typ: HistoricalEventType,
@@ -444,7 +591,14 @@ impl Default for ZoneState {
         Self {
             machine: Default::default(),
             policy: Default::default(),
+            denial_override: Default::default(),
+            description: Default::default(),
             maintenance_mode: Default::default(),
+            observe_only: Default::default(),
+            forced_advance: Default::default(),
+            review_batch: Default::default(),
+            staged_since: Default::default(),
+            held_since: Default::default(),
             enqueued_save: Default::default(),
             min_expiration: Default::default(),
             next_min_expiration: Default::default(),
@@ -454,6 +608,9 @@ impl Default for ZoneState {
             key_roll: Default::default(),
             last_signature_refresh: faketime_or_now(),
             previous_serial: Default::default(),
+            serial_floor: Default::default(),
+            key_rolls_paused: Default::default(),
+            removal_deadline: Default::default(),
             instances: Default::default(),
             history: Default::default(),
             loader: Default::default(),
@@ -464,11 +621,23 @@ impl Default for ZoneState {
     }
 }
 
+tokio::task_local! {
+    /// The operator on whose behalf the current task is acting.
+    ///
+    /// This is set while handling requests to the HTTP API that name an actor,
+    /// and is recorded in the history items created in the meantime.
+    pub static ACTOR: String;
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HistoryItem {
     pub when: SystemTime,
     pub serial: Option<Serial>,
     pub event: HistoricalEvent,
+
+    /// The operator who caused the event, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
 }
 
 impl From<HistoryItem> for api::HistoryItem {
@@ -477,11 +646,13 @@ impl From<HistoryItem> for api::HistoryItem {
             when,
             serial,
             event,
+            actor,
         } = value;
         Self {
             when,
             serial,
             event: event.into(),
+            actor,
         }
     }
 }
@@ -492,6 +663,7 @@ impl HistoryItem {
             when: SystemTime::now(),
             serial,
             event,
+            actor: ACTOR.try_with(|actor| actor.clone()).ok(),
         }
     }
 }
@@ -511,8 +683,17 @@ pub enum HistoricalEventType {
     SignedZoneReview,
     UnsignedHookFailed,
     SignedHookFailed,
+    Staged,
+    Promoted,
+    Held,
+    WentLive,
+    ForcedAdvance,
+    Healed,
     KeySetCommand,
     KeySetError,
+    KeyTagCollision,
+    DnssecInput,
+    Halted,
     Error,
 }
 
@@ -528,18 +709,31 @@ pub enum HistoricalEvent {
     LoadingFailed {
         reason: String,
     },
+    /// The zone halted and will not progress until the operator intervenes.
+    Halted {
+        reason: String,
+    },
     SigningSucceeded {
         trigger: api::SigningTrigger,
     },
     SigningFailed {
         trigger: api::SigningTrigger,
         reason: String,
+        /// Whether the failure is transient and will be retried.
+        #[serde(default)]
+        transient: bool,
     },
     UnsignedZoneReview {
         status: ZoneReviewStatus,
+        /// Why the reviewer made their decision, if known.
+        #[serde(default)]
+        reason: Option<String>,
     },
     SignedZoneReview {
         status: ZoneReviewStatus,
+        /// Why the reviewer made their decision, if known.
+        #[serde(default)]
+        reason: Option<String>,
     },
     UnsignedHookFailed {
         err: String,
@@ -547,6 +741,22 @@ pub enum HistoricalEvent {
     SignedHookFailed {
         err: String,
     },
+    /// The approved signed instance was published to the staging nameservers.
+    Staged,
+    /// The staged instance was promoted to production.
+    Promoted,
+    /// The approved signed instance was held back as the zone is observe-only.
+    Held,
+    /// The zone left observe-only mode.
+    WentLive,
+    /// The zone was forcibly advanced, bypassing its reviews.
+    ForcedAdvance {
+        to: api::ZoneAdvanceStage,
+    },
+    /// The served data did not match the recorded state on startup.
+    Healed {
+        reason: String,
+    },
     KeySetCommand {
         cmd: String,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -560,12 +770,30 @@ pub enum HistoricalEvent {
     KeySetError {
         cmd: String,
         err: String,
+        /// What the command wrote to stderr, if anything.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        stderr: Option<String>,
         #[serde(
             serialize_with = "serialize_duration_as_secs",
             deserialize_with = "deserialize_duration_from_secs"
         )]
         elapsed: Duration,
     },
+    /// Keys of the zone share a DNSKEY key tag.
+    ///
+    /// The keys are kept; validators and operators have to tell them apart
+    /// by other means until one of them is rolled or removed.
+    KeyTagCollision {
+        key_tag: u16,
+        keys: Vec<String>,
+    },
+    /// The loaded zone contained DNSSEC records.
+    ///
+    /// They were either stripped before signing, or signing was refused.
+    DnssecInput {
+        records: usize,
+        stripped: bool,
+    },
 }
 
 impl HistoricalEvent {
@@ -584,8 +812,17 @@ impl HistoricalEvent {
             HistoricalEvent::SignedZoneReview { .. } => HistoricalEventType::SignedZoneReview,
             HistoricalEvent::UnsignedHookFailed { .. } => HistoricalEventType::UnsignedHookFailed,
             HistoricalEvent::SignedHookFailed { .. } => HistoricalEventType::SignedHookFailed,
+            HistoricalEvent::Staged => HistoricalEventType::Staged,
+            HistoricalEvent::Promoted => HistoricalEventType::Promoted,
+            HistoricalEvent::Held => HistoricalEventType::Held,
+            HistoricalEvent::WentLive => HistoricalEventType::WentLive,
+            HistoricalEvent::ForcedAdvance { .. } => HistoricalEventType::ForcedAdvance,
+            HistoricalEvent::Healed { .. } => HistoricalEventType::Healed,
             HistoricalEvent::KeySetCommand { .. } => HistoricalEventType::KeySetCommand,
             HistoricalEvent::KeySetError { .. } => HistoricalEventType::KeySetError,
+            HistoricalEvent::KeyTagCollision { .. } => HistoricalEventType::KeyTagCollision,
+            HistoricalEvent::DnssecInput { .. } => HistoricalEventType::DnssecInput,
+            HistoricalEvent::Halted { .. } => HistoricalEventType::Halted,
             HistoricalEvent::LoadingFailed { .. } => HistoricalEventType::Error,
         }
     }
@@ -606,13 +843,29 @@ impl From<HistoricalEvent> for api::HistoricalEvent {
             HistoricalEvent::SourceChanged => Self::SourceChanged,
             HistoricalEvent::NewVersionReceived => Self::NewVersionReceived,
             HistoricalEvent::SigningSucceeded { trigger } => Self::SigningSucceeded { trigger },
-            HistoricalEvent::SigningFailed { trigger, reason } => {
-                Self::SigningFailed { trigger, reason }
+            HistoricalEvent::SigningFailed {
+                trigger,
+                reason,
+                transient,
+            } => Self::SigningFailed {
+                trigger,
+                reason,
+                transient,
+            },
+            HistoricalEvent::UnsignedZoneReview { status, reason } => {
+                Self::UnsignedZoneReview { status, reason }
+            }
+            HistoricalEvent::SignedZoneReview { status, reason } => {
+                Self::SignedZoneReview { status, reason }
             }
-            HistoricalEvent::UnsignedZoneReview { status } => Self::UnsignedZoneReview { status },
-            HistoricalEvent::SignedZoneReview { status } => Self::SignedZoneReview { status },
             HistoricalEvent::UnsignedHookFailed { err } => Self::UnsignedHookFailed { err },
             HistoricalEvent::SignedHookFailed { err } => Self::SignedHookFailed { err },
+            HistoricalEvent::Staged => Self::Staged,
+            HistoricalEvent::Promoted => Self::Promoted,
+            HistoricalEvent::Held => Self::Held,
+            HistoricalEvent::WentLive => Self::WentLive,
+            HistoricalEvent::ForcedAdvance { to } => Self::ForcedAdvance { to },
+            HistoricalEvent::Healed { reason } => Self::Healed { reason },
             HistoricalEvent::KeySetCommand {
                 cmd,
                 warning,
@@ -622,10 +875,25 @@ impl From<HistoricalEvent> for api::HistoricalEvent {
                 warning,
                 elapsed,
             },
-            HistoricalEvent::KeySetError { cmd, err, elapsed } => {
-                Self::KeySetError { cmd, err, elapsed }
+            HistoricalEvent::KeySetError {
+                cmd,
+                err,
+                stderr,
+                elapsed,
+            } => Self::KeySetError {
+                cmd,
+                err,
+                stderr,
+                elapsed,
+            },
+            HistoricalEvent::KeyTagCollision { key_tag, keys } => {
+                Self::KeyTagCollision { key_tag, keys }
+            }
+            HistoricalEvent::DnssecInput { records, stripped } => {
+                Self::DnssecInput { records, stripped }
             }
             HistoricalEvent::LoadingFailed { reason } => Self::LoadingFailed { reason },
+            HistoricalEvent::Halted { reason } => Self::Halted { reason },
         }
     }
 }
@@ -667,7 +935,7 @@ impl Zone {
             };
 
             // Save the zone state.
-            match spec.save(&path) {
+            match spec.save(&*center.state_store, &path) {
                 Ok(()) => debug!("Saved state of zone '{name}' (to '{path}')"),
                 Err(err) => {
                     error!("Could not save state of zone '{name}' to '{path}': {err}");
@@ -699,7 +967,7 @@ pub fn save_state_now(center: &Center, zone: &Zone) {
     };
 
     // Save the global state.
-    match spec.save(&path) {
+    match spec.save(&*center.state_store, &path) {
         Ok(()) => debug!("Saved the state of zone '{name}' (to '{path}')"),
         Err(err) => {
             error!("Could not save the state of zone '{name}' to '{path}': {err}");
@@ -908,3 +1176,181 @@ impl fmt::Display for ChangeSourceError {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use bytes::Bytes;
+    use domain::base::Name;
+
+    use super::machine::{HaltSigned, ZoneStateMachine};
+    use super::{HistoricalEvent, Zone, ZoneState, state};
+    use crate::{
+        api::{self, ZoneReviewStatus},
+        common::store::StateStore,
+        config::Config,
+        metrics::Metrics,
+        tests::util::store::MemoryStateStore,
+        tsig::TsigStore,
+    };
+
+    #[test]
+    fn restore_without_policy_enables_maintenance_mode() {
+        let config = Config::default();
+        let store = MemoryStateStore::default();
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+
+        let path = config.zone_state_dir.join(format!("{name}.db"));
+        let spec = state::Spec::build(&ZoneState::default());
+        spec.save(&store, &path).unwrap();
+        assert!(store.read(&path).is_ok());
+
+        let mut policies = Default::default();
+        let spec = Zone::read_state(&config, &store, &name).unwrap();
+        let zone = Zone::restore_from(
+            name,
+            spec,
+            &mut policies,
+            &TsigStore::default(),
+            &Metrics::new(),
+        )
+        .unwrap();
+
+        let state = zone.read();
+        assert!(state.policy.is_none());
+        assert!(state.maintenance_mode);
+        assert!(policies.is_empty());
+    }
+
+    #[test]
+    fn exhausted_load_retries_survive_a_restart() {
+        let config = Config::default();
+        let store = MemoryStateStore::default();
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+
+        let mut state = ZoneState::default();
+        state.loader.failure_streak = 5;
+        state.loader.retries_exhausted = true;
+        let path = config.zone_state_dir.join(format!("{name}.db"));
+        state::Spec::build(&state).save(&store, &path).unwrap();
+
+        let spec = Zone::read_state(&config, &store, &name).unwrap();
+        let zone = Zone::restore_from(
+            name,
+            spec,
+            &mut Default::default(),
+            &TsigStore::default(),
+            &Metrics::new(),
+        )
+        .unwrap();
+
+        let state = zone.read();
+        assert_eq!(state.loader.failure_streak, 5);
+        assert!(state.loader.retries_exhausted);
+        assert!(state.machine.is_halted());
+        assert!(state.halted_reason().unwrap().contains("failed 5 times"));
+    }
+
+    #[test]
+    fn observe_only_and_held_instances_survive_a_restart() {
+        let config = Config::default();
+        let store = MemoryStateStore::default();
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+        let held_since = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+
+        let state = ZoneState {
+            observe_only: true,
+            held_since: Some(held_since),
+            ..Default::default()
+        };
+        let path = config.zone_state_dir.join(format!("{name}.db"));
+        state::Spec::build(&state).save(&store, &path).unwrap();
+
+        let spec = Zone::read_state(&config, &store, &name).unwrap();
+        let zone = Zone::restore_from(
+            name,
+            spec,
+            &mut Default::default(),
+            &TsigStore::default(),
+            &Metrics::new(),
+        )
+        .unwrap();
+
+        let state = zone.read();
+        assert!(state.observe_only);
+        assert_eq!(state.held_since, Some(held_since));
+    }
+
+    #[test]
+    fn removal_deadline_survives_a_restart() {
+        let config = Config::default();
+        let store = MemoryStateStore::default();
+        let name: Name<Bytes> = "example.org".parse().unwrap();
+        let deadline = SystemTime::UNIX_EPOCH + Duration::from_secs(1_800_000_000);
+
+        let state = ZoneState {
+            removal_deadline: Some(deadline),
+            ..Default::default()
+        };
+        let path = config.zone_state_dir.join(format!("{name}.db"));
+        state::Spec::build(&state).save(&store, &path).unwrap();
+
+        let spec = Zone::read_state(&config, &store, &name).unwrap();
+        let zone = Zone::restore_from(
+            name,
+            spec,
+            &mut Default::default(),
+            &TsigStore::default(),
+            &Metrics::new(),
+        )
+        .unwrap();
+
+        assert_eq!(zone.read().removal_deadline, Some(deadline));
+    }
+
+    #[test]
+    fn review_reasons_are_optional_in_history() {
+        // Reviews recorded before reasons were supported have none.
+        let event: HistoricalEvent =
+            serde_json::from_str(r#"{"SignedZoneReview":{"status":"Rejected"}}"#).unwrap();
+        assert_eq!(
+            event,
+            HistoricalEvent::SignedZoneReview {
+                status: ZoneReviewStatus::Rejected,
+                reason: None,
+            }
+        );
+
+        let event = HistoricalEvent::UnsignedZoneReview {
+            status: ZoneReviewStatus::Approved,
+            reason: Some("expected changes only".into()),
+        };
+        let text = serde_json::to_string(&event).unwrap();
+        assert_eq!(
+            serde_json::from_str::<HistoricalEvent>(&text).unwrap(),
+            event
+        );
+
+        let api::HistoricalEvent::UnsignedZoneReview { reason, .. } = event.into() else {
+            panic!("the event kind should be preserved");
+        };
+        assert_eq!(reason.as_deref(), Some("expected changes only"));
+    }
+
+    #[test]
+    fn only_actual_halts_are_recorded() {
+        let mut state = ZoneState::default();
+        state.record_halt();
+        assert!(state.history.is_empty());
+
+        state.machine = ZoneStateMachine::HaltSigned(HaltSigned {});
+        state.record_halt();
+        assert_eq!(
+            state.history.last().map(|item| &item.event),
+            Some(&HistoricalEvent::Halted {
+                reason: "signed zone was rejected".into()
+            })
+        );
+    }
+}
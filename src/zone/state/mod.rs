@@ -1,12 +1,6 @@
 //! Saving Cascade's zone state.
 
-use std::{
-    collections::hash_map,
-    error::Error,
-    fmt, fs,
-    io::{self, BufReader},
-    sync::Arc,
-};
+use std::{collections::hash_map, error::Error, fmt, io, sync::Arc};
 
 use bytes::Bytes;
 use camino::Utf8Path;
@@ -15,11 +9,15 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::{
+    common::store::StateStore,
     loader::zone::LoaderState,
     persistence::zone::PersistenceState,
-    policy::{Policy, PolicyVersion},
+    policy::{Policy, PolicyVersion, SignerDenialPolicy},
     tsig::TsigStore,
-    zone::ZoneState,
+    zone::{
+        ZoneState,
+        machine::{LoadingFailed, ZoneStateMachine},
+    },
 };
 
 pub mod v1;
@@ -47,14 +45,20 @@ impl Spec {
         /// Synchronize a loaded policy with global state.
         fn sync_policy(
             known_version: PolicyVersion,
+            denial_override: Option<&SignerDenialPolicy>,
             policies: &mut foldhash::HashMap<Box<str>, Policy>,
         ) -> &mut Policy {
             // Check whether a policy of this name exists.
             match policies.entry(known_version.name.clone()) {
                 hash_map::Entry::Occupied(entry) => {
-                    // A policy of this name exists.  Compare to it.
+                    // A policy of this name exists.  Compare to it, taking the
+                    // zone's denial override into account.
                     let policy = entry.into_mut();
-                    if *policy.latest != known_version {
+                    let mut latest = (*policy.latest).clone();
+                    if let Some(denial) = denial_override {
+                        latest.signer.denial = denial.clone();
+                    }
+                    if latest != known_version {
                         // TODO: Continue using the older version of the policy, and
                         // enqueue an explicit change to the zone, so that any
                         // necessary hooks (e.g. re-signing) can be activated.
@@ -77,6 +81,7 @@ impl Spec {
                         latest: Arc::new(known_version),
                         mid_deletion: false,
                         zones: Default::default(),
+                        files: Vec::new(),
                     })
                 }
             }
@@ -85,8 +90,14 @@ impl Spec {
         match self {
             Self::V1(v1::Spec {
                 policy,
+                denial_override,
+                description,
+                observe_only,
                 instances,
                 source,
+                on_fallback,
+                load_failure_streak,
+                load_retries_exhausted,
                 min_expiration,
                 next_min_expiration,
                 apex_remove,
@@ -95,6 +106,11 @@ impl Spec {
                 key_roll,
                 last_signature_refresh,
                 previous_serial,
+                serial_floor,
+                staged_since,
+                held_since,
+                key_rolls_paused,
+                removal_deadline,
                 history,
                 persisted_loaded_diffs,
                 persisted_signed_diffs,
@@ -103,11 +119,17 @@ impl Spec {
                     source: source
                         .parse(tsig_store)
                         .map_err(LoadError::MissingSourceTsigKey)?,
+                    on_fallback,
+                    failure_streak: load_failure_streak,
+                    retries_exhausted: load_retries_exhausted,
                     ..Default::default()
                 };
 
+                let denial_override = denial_override.map(|d| d.parse());
+
                 // TODO: Won't this always be a `Some`?
-                let mut policy = policy.map(|policy| sync_policy(policy.parse(), policies));
+                let mut policy = policy
+                    .map(|policy| sync_policy(policy.parse(), denial_override.as_ref(), policies));
                 if let Some(policy) = &mut policy {
                     // Register that the policy is in use by this zone. It might
                     // already be registered; that's fine.
@@ -121,8 +143,10 @@ impl Spec {
                     ..Default::default()
                 };
 
-                Ok(ZoneState {
-                    policy,
+                let mut state = ZoneState {
+                    denial_override,
+                    description,
+                    observe_only,
                     instances: instances.parse(),
                     min_expiration,
                     next_min_expiration,
@@ -132,11 +156,26 @@ impl Spec {
                     key_roll,
                     last_signature_refresh,
                     previous_serial,
+                    serial_floor,
+                    staged_since,
+                    held_since,
+                    key_rolls_paused,
+                    removal_deadline,
                     loader,
                     history,
                     persistence,
                     ..Default::default()
-                })
+                };
+                if let Some(policy) = policy {
+                    state.set_policy(policy);
+                }
+
+                // A zone halted after too many failed loads stays halted.
+                if state.loader.retries_exhausted {
+                    state.machine = ZoneStateMachine::LoadingFailed(LoadingFailed {});
+                }
+
+                Ok(state)
             }
         }
     }
@@ -151,16 +190,16 @@ impl Spec {
 
 impl Spec {
     /// Load and parse this specification from a file.
-    pub fn load(path: &Utf8Path) -> io::Result<Self> {
-        let file = BufReader::new(fs::File::open(path)?);
-        let spec = serde_json::from_reader(file)?;
+    pub fn load(store: &dyn StateStore, path: &Utf8Path) -> io::Result<Self> {
+        let text = store.read(path)?;
+        let spec = serde_json::from_slice(&text)?;
         Ok(spec)
     }
 
     /// Build and save this specification to a file.
-    pub fn save(&self, path: &Utf8Path) -> io::Result<()> {
+    pub fn save(&self, store: &dyn StateStore, path: &Utf8Path) -> io::Result<()> {
         let text = serde_json::to_string(self)?;
-        crate::util::write_file(path, text.as_bytes())
+        store.write(path, text.as_bytes())
     }
 }
 
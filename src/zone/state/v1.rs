@@ -19,11 +19,12 @@ use domain::utils::dst::UnsizedCopy;
 use domain::{base::Name, rdata::dnssec::Timestamp};
 use serde::{Deserialize, Serialize};
 
-use crate::loader::Source;
+use crate::common::datetime::TimeSpan;
+use crate::loader::{Fallback, Source};
 use crate::persistence::zone::{
     PersistedDiffFileInfo, PersistedDiffManager, PersistedDiffRecordSource,
 };
-use crate::policy::file::v1::{NameserverCommsSpec, OutboundSpec};
+use crate::policy::file::v1::{NameserverCommsSpec, OutboundSpec, StagingSpec, TransferLimitsSpec};
 use crate::policy::{AutoConfig, DsAlgorithm, KeyParameters};
 use crate::tsig::TsigStore;
 use crate::zone::instance::PersistedInstance;
@@ -50,12 +51,37 @@ pub struct Spec {
     /// version of the policy that is not yet in use.
     pub policy: Option<PolicySpec>,
 
+    /// A zone-specific override of the policy's denial-of-existence type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub denial_override: Option<SignerDenialPolicySpec>,
+
+    /// A human-readable description of the zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Whether the zone is observe-only.
+    #[serde(default)]
+    pub observe_only: bool,
+
     /// Instances of the zone.
     pub instances: InstancesSpec,
 
     /// The source of the zone.
     pub source: ZoneLoadSourceSpec,
 
+    /// Whether the zone was last loaded from the fallback zonefile of its
+    /// source.
+    #[serde(default)]
+    pub on_fallback: bool,
+
+    /// The number of consecutive failed loads.
+    #[serde(default)]
+    pub load_failure_streak: u32,
+
+    /// Whether the zone was halted after too many consecutive failed loads.
+    #[serde(default)]
+    pub load_retries_exhausted: bool,
+
     /// The minimum expiration time in the signed zone we are serving from
     /// the publication server.
     pub min_expiration: Option<Timestamp>,
@@ -104,6 +130,26 @@ pub struct Spec {
     /// serial for the Increment serial policy.
     pub previous_serial: Option<Serial>,
 
+    /// The lowest SOA serial the signer may use for this zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_floor: Option<Serial>,
+
+    /// When the signed instance under review was staged, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged_since: Option<SystemTime>,
+
+    /// When the signed instance under review was held back, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub held_since: Option<SystemTime>,
+
+    /// Whether automatic key rolls are paused for this zone.
+    #[serde(default)]
+    pub key_rolls_paused: bool,
+
+    /// When the removal grace period of this zone ends, if it was removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub removal_deadline: Option<SystemTime>,
+
     /// History of interesting events that occurred for this zone.
     pub history: Vec<HistoryItem>,
 
@@ -125,8 +171,17 @@ impl Spec {
     pub fn build(zone: &ZoneState) -> Self {
         Self {
             policy: zone.policy.as_ref().map(|p| PolicySpec::build(p)),
+            denial_override: zone
+                .denial_override
+                .as_ref()
+                .map(SignerDenialPolicySpec::build),
+            description: zone.description.clone(),
+            observe_only: zone.observe_only,
             instances: InstancesSpec::build(&zone.instances),
             source: ZoneLoadSourceSpec::build(&zone.loader.source),
+            on_fallback: zone.loader.on_fallback,
+            load_failure_streak: zone.loader.failure_streak,
+            load_retries_exhausted: zone.loader.retries_exhausted,
             min_expiration: zone.min_expiration,
             next_min_expiration: zone.next_min_expiration,
             apex_remove: zone.apex_remove.clone(),
@@ -135,6 +190,11 @@ impl Spec {
             key_roll: zone.key_roll.clone(),
             last_signature_refresh: zone.last_signature_refresh.clone(),
             previous_serial: zone.previous_serial,
+            serial_floor: zone.serial_floor,
+            staged_since: zone.staged_since,
+            held_since: zone.held_since,
+            key_rolls_paused: zone.key_rolls_paused,
+            removal_deadline: zone.removal_deadline,
             history: zone.history.clone(),
             persisted_loaded_diffs: PersistedDiffsSpec::build_loaded(
                 &zone.persistence.loaded_diffs,
@@ -202,6 +262,14 @@ impl PolicySpec {
 pub struct LoaderPolicySpec {
     /// Reviewing loaded zones.
     pub review: ReviewPolicySpec,
+
+    /// Limits on inbound zone transfers.
+    #[serde(default)]
+    pub transfer: TransferLimitsSpec,
+
+    /// Whether SOA queries to primaries are sent over TCP.
+    #[serde(default)]
+    pub soa_query_over_tcp: bool,
 }
 
 //--- Conversion
@@ -211,6 +279,8 @@ impl LoaderPolicySpec {
     pub fn parse(self) -> LoaderPolicy {
         LoaderPolicy {
             review: self.review.parse(),
+            transfer: self.transfer.parse(),
+            soa_query_over_tcp: self.soa_query_over_tcp,
         }
     }
 
@@ -218,6 +288,8 @@ impl LoaderPolicySpec {
     pub fn build(policy: &LoaderPolicy) -> Self {
         Self {
             review: ReviewPolicySpec::build(&policy.review),
+            transfer: TransferLimitsSpec::build(&policy.transfer),
+            soa_query_over_tcp: policy.soa_query_over_tcp,
         }
     }
 }
@@ -284,6 +356,10 @@ pub struct KeyManagerPolicySpec {
     /// Remove old keys after this amount of time.
     auto_remove_delay: u64,
 
+    /// The maximum per-zone reduction of key validities, in seconds.
+    #[serde(default)]
+    validity_jitter: u32,
+
     /// Nameservers to check for RRSIG propagation during a key roll.
     publication_nameservers: Vec<NameserverCommsSpec>,
 }
@@ -314,6 +390,7 @@ impl KeyManagerPolicySpec {
             default_ttl: self.default_ttl,
             auto_remove: self.auto_remove,
             auto_remove_delay: Duration::from_secs(self.auto_remove_delay),
+            validity_jitter: self.validity_jitter,
             publication_nameservers: self
                 .publication_nameservers
                 .into_iter()
@@ -345,6 +422,7 @@ impl KeyManagerPolicySpec {
             default_ttl: policy.default_ttl,
             auto_remove: policy.auto_remove,
             auto_remove_delay: policy.auto_remove_delay.as_secs(),
+            validity_jitter: policy.validity_jitter,
             publication_nameservers: policy
                 .publication_nameservers
                 .iter()
@@ -384,6 +462,14 @@ pub struct SignerPolicySpec {
 
     /// Reviewing signed zones.
     pub review: ReviewPolicySpec,
+
+    /// Whether to keep signatures made by an external signer.
+    #[serde(default)]
+    pub preserve_external_signatures: bool,
+
+    /// Whether to refuse signing zones which already contain DNSSEC records.
+    #[serde(default)]
+    pub refuse_dnssec_input: bool,
 }
 
 //--- Conversion
@@ -400,6 +486,8 @@ impl SignerPolicySpec {
             key_roll_time: self.key_roll_time,
             denial: self.denial.parse(),
             review: self.review.parse(),
+            preserve_external_signatures: self.preserve_external_signatures,
+            refuse_dnssec_input: self.refuse_dnssec_input,
         }
     }
 
@@ -414,6 +502,8 @@ impl SignerPolicySpec {
             key_roll_time: policy.key_roll_time,
             denial: SignerDenialPolicySpec::build(&policy.denial),
             review: ReviewPolicySpec::build(&policy.review),
+            preserve_external_signatures: policy.preserve_external_signatures,
+            refuse_dnssec_input: policy.refuse_dnssec_input,
         }
     }
 }
@@ -574,6 +664,9 @@ impl ReviewPolicySpec {
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct ServerPolicySpec {
     pub outbound: OutboundSpec,
+    /// Staging policy.
+    #[serde(default)]
+    pub staging: StagingSpec,
 }
 
 //--- Conversion
@@ -583,6 +676,7 @@ impl ServerPolicySpec {
     pub fn parse(self) -> ServerPolicy {
         ServerPolicy {
             outbound: self.outbound.parse(),
+            staging: self.staging.parse(),
         }
     }
 
@@ -590,6 +684,7 @@ impl ServerPolicySpec {
     pub fn build(policy: &ServerPolicy) -> Self {
         Self {
             outbound: OutboundSpec::build(&policy.outbound),
+            staging: StagingSpec::build(&policy.staging),
         }
     }
 }
@@ -816,9 +911,28 @@ pub enum ZoneLoadSourceSpec {
 
         /// The TSIG key to use, if any.
         tsig_key: Option<Box<Name<Array<255>>>>,
+
+        /// The TSIG key being rotated to, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        next_tsig_key: Option<Box<Name<Array<255>>>>,
+
+        /// A zonefile to load if the server can't be reached, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fallback: Option<FallbackSpec>,
     },
 }
 
+/// A zonefile to fall back to when the primary of a zone is unreachable.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FallbackSpec {
+    /// The path to the zonefile.
+    pub path: Box<Utf8Path>,
+
+    /// How long to wait for the primary before falling back.
+    pub timeout: TimeSpan,
+}
+
 //--- Conversion
 
 impl ZoneLoadSourceSpec {
@@ -827,18 +941,31 @@ impl ZoneLoadSourceSpec {
         match self {
             Self::None => Ok(Source::None),
             Self::Zonefile { path } => Ok(Source::Zonefile { path }),
-            Self::Server { addr, tsig_key } => {
-                // Look up the TSIG key from the key store.
-                let tsig_key = tsig_key
-                    .map(|name| {
-                        tsig_store
-                            .get(&name)
-                            .map(|key| key.inner.clone())
-                            .ok_or(MissingTsigKeyError { name })
-                    })
-                    .transpose()?;
-
-                Ok(Source::Server { addr, tsig_key })
+            Self::Server {
+                addr,
+                tsig_key,
+                next_tsig_key,
+                fallback,
+            } => {
+                // Look up the TSIG keys from the key store.
+                let lookup = |name: Box<Name<Array<255>>>| {
+                    tsig_store
+                        .get(&name)
+                        .map(|key| key.inner.clone())
+                        .ok_or(MissingTsigKeyError { name })
+                };
+                let tsig_key = tsig_key.map(lookup).transpose()?;
+                let next_tsig_key = next_tsig_key.map(lookup).transpose()?;
+
+                Ok(Source::Server {
+                    addr,
+                    tsig_key,
+                    next_tsig_key,
+                    fallback: fallback.map(|f| Fallback {
+                        path: f.path,
+                        timeout: Duration::from_secs(f.timeout.as_secs().into()),
+                    }),
+                })
             }
         }
     }
@@ -848,9 +975,19 @@ impl ZoneLoadSourceSpec {
         match source.clone() {
             Source::None => Self::None,
             Source::Zonefile { path } => Self::Zonefile { path },
-            Source::Server { addr, tsig_key } => Self::Server {
+            Source::Server {
+                addr,
+                tsig_key,
+                next_tsig_key,
+                fallback,
+            } => Self::Server {
                 addr,
                 tsig_key: tsig_key.map(|key| key.name().clone().into()),
+                next_tsig_key: next_tsig_key.map(|key| key.name().clone().into()),
+                fallback: fallback.map(|f| FallbackSpec {
+                    path: f.path,
+                    timeout: TimeSpan::from_secs(f.timeout.as_secs() as u32),
+                }),
             },
         }
     }